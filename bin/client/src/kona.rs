@@ -7,8 +7,10 @@
 
 extern crate alloc;
 
-use alloc::string::String;
+use alloc::{format, string::String};
+use kona_client::FaultProofProgramError;
 use kona_preimage::{HintWriter, OracleReader};
+use kona_proof::errors::OracleProviderError;
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use kona_std_fpvm_proc::client_entry;
 
@@ -25,7 +27,7 @@ static HINT_WRITER_PIPE: FileChannel =
 /// The global preimage oracle reader.
 static ORACLE_READER: OracleReader<FileChannel> = OracleReader::new(ORACLE_READER_PIPE);
 
-/// The global hint writer.
+/// The global hint writer. Hint batching is negotiated with the host before the program runs.
 static HINT_WRITER: HintWriter<FileChannel> = HintWriter::new(HINT_WRITER_PIPE);
 
 #[client_entry(100_000_000)]
@@ -39,9 +41,19 @@ fn main() -> Result<(), String> {
             .expect("Failed to set tracing subscriber");
     }
 
-    kona_proof::block_on(kona_client::run(
-        ORACLE_READER,
-        HINT_WRITER,
-        Some(precompiles::fpvm_handle_register),
-    ))
+    let result = kona_proof::block_on(async {
+        let hint_writer =
+            HINT_WRITER.negotiate_batching().await.map_err(OracleProviderError::Preimage)?;
+        kona_client::run(ORACLE_READER, hint_writer, Some(precompiles::fpvm_handle_register)).await
+    });
+
+    // Only an invalid claim exits with status 1. Any other error means that the claim could not be
+    // checked, and exits with status 2, as a panic does.
+    if let Err(e) = &result {
+        if !matches!(e, FaultProofProgramError::InvalidClaim(..)) {
+            kona_std_fpvm::io::print_err(&format!("Failed to check the claim: {e:?}\n"));
+            kona_std_fpvm::io::exit(2);
+        }
+    }
+    result
 }
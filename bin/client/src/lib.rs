@@ -9,7 +9,7 @@ extern crate alloc;
 
 use alloc::sync::Arc;
 use alloy_consensus::{Header, Sealed};
-use alloy_primitives::B256;
+use alloy_primitives::{hex, B256};
 use core::fmt::Debug;
 use kona_driver::{Driver, DriverError};
 use kona_executor::{ExecutorError, KonaHandleRegister, TrieDBProvider};
@@ -22,7 +22,7 @@ use kona_proof::{
     l1::{OracleBlobProvider, OracleL1ChainProvider, OraclePipeline},
     l2::OracleL2ChainProvider,
     sync::new_pipeline_cursor,
-    BootInfo, CachingOracle, HintType,
+    BootInfo, CachingOracle, Hint,
 };
 use thiserror::Error;
 use tracing::{error, info, warn};
@@ -41,6 +41,37 @@ pub enum FaultProofProgramError {
     Driver(#[from] DriverError<ExecutorError>),
 }
 
+pub use kona_driver::{DerivedBlock, DriverObserver};
+
+/// The expected [rollup_config_hash] of the rollup config of a chain outside of the superchain
+/// registry, compiled in from the `KONA_ROLLUP_CONFIG_HASH` environment variable at build time.
+///
+/// As the hash is part of the program, and so of its absolute prestate, the host cannot substitute
+/// a different config. If it is not set, the program accepts any config from the host, which is
+/// insecure in production.
+///
+/// [rollup_config_hash]: kona_proof::rollup_config::rollup_config_hash
+pub const ROLLUP_CONFIG_HASH: Option<B256> = match option_env!("KONA_ROLLUP_CONFIG_HASH") {
+    Some(hash) => match hex::const_decode_to_array(hash.as_bytes()) {
+        Ok(hash) => Some(B256::new(hash)),
+        Err(_) => panic!("KONA_ROLLUP_CONFIG_HASH must be a 32-byte hex string"),
+    },
+    None => None,
+};
+
+/// An observer of the fault proof program, notified of the [BootInfo] that it loads, in addition
+/// to the blocks that its [Driver] derives and executes.
+///
+/// Any `FnMut(DerivedBlock<'_>)` closure is a [ProgramObserver] that only observes the executed
+/// blocks.
+pub trait ProgramObserver: DriverObserver {
+    /// Called once the program has loaded its boot information, before the agreed safe head is
+    /// fetched.
+    fn on_boot(&mut self, _boot: &BootInfo) {}
+}
+
+impl<F> ProgramObserver for F where F: FnMut(DerivedBlock<'_>) {}
+
 /// Executes the fault proof program with the given [PreimageOracleClient] and [HintWriterClient].
 #[inline]
 pub async fn run<P, H>(
@@ -53,24 +84,70 @@ pub async fn run<P, H>(
         >,
     >,
 ) -> Result<(), FaultProofProgramError>
+where
+    P: PreimageOracleClient + Send + Sync + Debug + Clone,
+    H: HintWriterClient + Send + Sync + Debug + Clone,
+{
+    run_with_observer(oracle_client, hint_client, handle_register, |_: DerivedBlock<'_>| {}).await
+}
+
+/// Executes the fault proof program with the given [PreimageOracleClient] and [HintWriterClient],
+/// notifying `observer` of the [BootInfo] that it loads, and as each L2 block is derived and
+/// executed on the way to the claimed block.
+pub async fn run_with_observer<P, H>(
+    oracle_client: P,
+    hint_client: H,
+    handle_register: Option<
+        KonaHandleRegister<
+            OracleL2ChainProvider<CachingOracle<P, H>>,
+            OracleL2ChainProvider<CachingOracle<P, H>>,
+        >,
+    >,
+    observer: impl ProgramObserver + Send,
+) -> Result<(), FaultProofProgramError>
 where
     P: PreimageOracleClient + Send + Sync + Debug + Clone,
     H: HintWriterClient + Send + Sync + Debug + Clone,
 {
     const ORACLE_LRU_SIZE: usize = 1024;
 
+    let oracle = Arc::new(CachingOracle::new(ORACLE_LRU_SIZE, oracle_client, hint_client));
+    let result = run_with_oracle(oracle.clone(), handle_register, observer).await;
+
+    // Write the hints that were queued after the last preimage request, so that the host receives
+    // every hint before the program exits.
+    let flushed = oracle.flush_hints().await.map_err(OracleProviderError::Preimage);
+    result.and(flushed.map_err(Into::into))
+}
+
+/// Executes the fault proof program with the given [CachingOracle], as in [run_with_observer].
+async fn run_with_oracle<P, H>(
+    oracle: Arc<CachingOracle<P, H>>,
+    handle_register: Option<
+        KonaHandleRegister<
+            OracleL2ChainProvider<CachingOracle<P, H>>,
+            OracleL2ChainProvider<CachingOracle<P, H>>,
+        >,
+    >,
+    mut observer: impl ProgramObserver + Send,
+) -> Result<(), FaultProofProgramError>
+where
+    P: PreimageOracleClient + Send + Sync + Debug + Clone,
+    H: HintWriterClient + Send + Sync + Debug + Clone,
+{
     ////////////////////////////////////////////////////////////////
     //                          PROLOGUE                          //
     ////////////////////////////////////////////////////////////////
 
-    let oracle = Arc::new(CachingOracle::new(ORACLE_LRU_SIZE, oracle_client, hint_client));
-    let boot = match BootInfo::load(oracle.as_ref()).await {
-        Ok(boot) => Arc::new(boot),
-        Err(e) => {
-            error!(target: "client", "Failed to load boot info: {:?}", e);
-            return Err(e.into());
-        }
-    };
+    let boot =
+        match BootInfo::load_with_rollup_config_hash(oracle.as_ref(), ROLLUP_CONFIG_HASH).await {
+            Ok(boot) => Arc::new(boot),
+            Err(e) => {
+                error!(target: "client", "Failed to load boot info: {:?}", e);
+                return Err(e.into());
+            }
+        };
+    observer.on_boot(&boot);
     let mut l1_provider = OracleL1ChainProvider::new(boot.clone(), oracle.clone());
     let mut l2_provider = OracleL2ChainProvider::new(boot.clone(), oracle.clone());
     let beacon = OracleBlobProvider::new(oracle.clone());
@@ -98,6 +175,7 @@ where
             target: "client",
             "Trace extension detected. State transition is already agreed upon.",
         );
+        observer.on_finish(safe_head.number, boot.agreed_l2_output_root);
         return Ok(());
     }
 
@@ -105,6 +183,9 @@ where
     //                   DERIVATION & EXECUTION                   //
     ////////////////////////////////////////////////////////////////
 
+    #[cfg(feature = "client-profiling")]
+    kona_std_fpvm::profile::checkpoint("prologue");
+
     // Create a new derivation driver with the given boot information and oracle.
     let cursor = new_pipeline_cursor(&boot, safe_head, &mut l1_provider, &mut l2_provider).await?;
     let cfg = Arc::new(boot.rollup_config.clone());
@@ -121,13 +202,17 @@ where
 
     // Run the derivation pipeline until we are able to produce the output root of the claimed
     // L2 block.
-    let (number, output_root) =
-        driver.advance_to_target(&boot.rollup_config, Some(boot.claimed_l2_block_number)).await?;
+    let (number, output_root) = driver
+        .advance_to_target_with(&boot.rollup_config, Some(boot.claimed_l2_block_number), observer)
+        .await?;
 
     ////////////////////////////////////////////////////////////////
     //                          EPILOGUE                          //
     ////////////////////////////////////////////////////////////////
 
+    #[cfg(feature = "client-profiling")]
+    kona_std_fpvm::profile::checkpoint("derivation");
+
     if output_root != boot.claimed_l2_output_root {
         error!(
             target: "client",
@@ -156,10 +241,10 @@ async fn fetch_safe_head<O>(
     l2_chain_provider: &mut OracleL2ChainProvider<O>,
 ) -> Result<Sealed<Header>, OracleProviderError>
 where
-    O: CommsClient,
+    O: CommsClient + Sync,
 {
     caching_oracle
-        .write(&HintType::StartingL2Output.encode_with(&[boot_info.agreed_l2_output_root.as_ref()]))
+        .write(&Hint::StartingL2Output(boot_info.agreed_l2_output_root).encode())
         .await
         .map_err(OracleProviderError::Preimage)?;
     let mut output_preimage = [0u8; 128];
@@ -1,13 +1,13 @@
 //! Contains the accelerated version of the `ecPairing` precompile.
 
 use crate::{HINT_WRITER, ORACLE_READER};
-use alloc::{string::ToString, vec::Vec};
+use alloc::string::ToString;
 use alloy_primitives::{keccak256, Address, Bytes};
 use kona_preimage::{
     errors::PreimageOracleError, HintWriterClient, PreimageKey, PreimageKeyType,
     PreimageOracleClient,
 };
-use kona_proof::{errors::OracleProviderError, HintType};
+use kona_proof::{errors::OracleProviderError, Hint};
 use revm::{
     precompile::{
         bn128::pair::{ISTANBUL_PAIR_BASE, ISTANBUL_PAIR_PER_POINT},
@@ -40,15 +40,11 @@ fn fpvm_ecpairing(input: &Bytes, gas_limit: u64) -> PrecompileResult {
 
     let result_data = kona_proof::block_on(async move {
         // Write the hint for the ecrecover precompile run.
-        let hint_data = &[ECPAIRING_ADDRESS.as_ref(), input.as_ref()];
-        HINT_WRITER
-            .write(&HintType::L1Precompile.encode_with(hint_data))
-            .await
-            .map_err(OracleProviderError::Preimage)?;
+        let hint = Hint::L1Precompile { address: ECPAIRING_ADDRESS, input: input.clone() };
+        HINT_WRITER.write(&hint.encode()).await.map_err(OracleProviderError::Preimage)?;
 
         // Construct the key hash for the ecrecover precompile run.
-        let raw_key_data = hint_data.iter().copied().flatten().copied().collect::<Vec<u8>>();
-        let key_hash = keccak256(&raw_key_data);
+        let key_hash = keccak256(hint.payload());
 
         // Fetch the result of the ecrecover precompile run from the host.
         let result_data = ORACLE_READER
@@ -0,0 +1,242 @@
+//! This module contains the bisection mode of the host, which finds the first L2 block whose output
+//! root, as computed by the client program, diverges from a reference rollup node.
+
+use crate::{
+    cli::HostCli,
+    discovery::{fetch_block, fetch_output_at_block},
+    start_server_and_observed_native_client,
+    transport::HostProvider,
+};
+use alloy_consensus::{Header, Sealed};
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use kona_client::DerivedBlock;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+
+/// An L2 block that was derived and executed by the client program.
+#[derive(Debug, Clone)]
+pub struct ObservedBlock {
+    /// The payload attributes that the block was built from.
+    pub attributes: OpPayloadAttributes,
+    /// The header of the executed block.
+    pub header: Sealed<Header>,
+    /// The output root of the block.
+    pub output_root: B256,
+}
+
+/// The first L2 block whose output root, as computed by the client program, diverges from the
+/// reference rollup node.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// The block, as derived and executed by the client program.
+    pub block: ObservedBlock,
+    /// The output root of the block on the reference rollup node.
+    pub expected_output_root: B256,
+    /// The header of the block on the L2 node.
+    pub expected_header: Header,
+}
+
+impl Divergence {
+    /// Returns the names of the header fields that differ between the computed and the expected
+    /// header.
+    pub fn header_diff(&self) -> Vec<String> {
+        let computed = serde_json::to_value(self.block.header.inner()).unwrap_or_default();
+        let expected = serde_json::to_value(&self.expected_header).unwrap_or_default();
+        let (Value::Object(computed), Value::Object(expected)) = (computed, expected) else {
+            return Vec::new();
+        };
+
+        let mut fields = computed
+            .keys()
+            .chain(expected.keys())
+            .filter(|field| computed.get(*field) != expected.get(*field))
+            .cloned()
+            .collect::<Vec<_>>();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
+
+    /// Logs the divergent block, with its payload attributes and the computed and expected
+    /// headers.
+    pub fn report(&self) {
+        let number = self.block.header.number;
+        error!(
+            target: "bisect",
+            "L2 block #{number} diverges: computed output root {}, expected {}",
+            self.block.output_root,
+            self.expected_output_root
+        );
+        error!(target: "bisect", "Differing header fields: {}", self.header_diff().join(", "));
+        let to_json = |value: Result<String, serde_json::Error>| value.unwrap_or_default();
+        info!(
+            target: "bisect",
+            "Payload attributes of L2 block #{number}:\n{}",
+            to_json(serde_json::to_string_pretty(&self.block.attributes))
+        );
+        info!(
+            target: "bisect",
+            "Computed header of L2 block #{number}:\n{}",
+            to_json(serde_json::to_string_pretty(self.block.header.inner()))
+        );
+        info!(
+            target: "bisect",
+            "Expected header of L2 block #{number}:\n{}",
+            to_json(serde_json::to_string_pretty(&self.expected_header))
+        );
+    }
+}
+
+/// Runs the client program natively, as in
+/// [start_server_and_native_client](crate::start_server_and_native_client), recording the output
+/// root of each L2 block that it derives. Afterwards, bisects the recorded blocks against the
+/// reference rollup node at `cfg.l2_rollup_node_address` to find the first divergent block, and
+/// reports it.
+///
+/// Every derived block is held in memory until the client program exits, so bisection is meant for
+/// the block ranges of a single claim.
+///
+/// ## Returns
+/// - `Ok(0)` if no derived block diverges and the client program validated the claim.
+/// - `Ok(1)` if a derived block diverges, or the client program found the claim to be invalid.
+/// - `Err(_)` if the client program could not be run or failed before it could check the claim, or
+///   the reference could not be fetched. The derived blocks are bisected even if the client program
+///   failed.
+pub async fn start_server_and_bisect(cfg: HostCli) -> Result<i32> {
+    let rollup_provider = HostCli::http_provider(
+        cfg.l2_rollup_node_address
+            .as_ref()
+            .ok_or_else(|| anyhow!("Rollup node address must be set"))?,
+    )?;
+    let l2_provider = HostCli::http_provider(
+        cfg.l2_node_address.as_ref().ok_or_else(|| anyhow!("L2 node address must be set"))?,
+    )?;
+
+    let blocks = Arc::new(Mutex::new(Vec::new()));
+    let observed = Arc::clone(&blocks);
+    let status = start_server_and_observed_native_client(cfg, move |block: DerivedBlock<'_>| {
+        observed.lock().unwrap_or_else(|e| e.into_inner()).push(ObservedBlock {
+            attributes: block.attributes.clone(),
+            header: block.header.clone(),
+            output_root: block.output_root,
+        });
+    })
+    .await;
+
+    let blocks = std::mem::take(&mut *blocks.lock().unwrap_or_else(|e| e.into_inner()));
+    info!(target: "bisect", "Client program derived {} L2 blocks, bisecting", blocks.len());
+    if let Some(divergence) = find_divergence(&rollup_provider, &l2_provider, blocks).await? {
+        divergence.report();
+        return Ok(1);
+    }
+
+    let status = status?;
+    if status != 0 {
+        warn!(
+            target: "bisect",
+            "No derived L2 block diverges from the reference, but the claim is invalid"
+        );
+    } else {
+        info!(target: "bisect", "No derived L2 block diverges from the reference");
+    }
+    Ok(status)
+}
+
+/// Finds the first of the given blocks, in order of derivation, whose output root diverges from
+/// the reference rollup node.
+///
+/// Each output root commits to the state after all prior blocks, so once a block diverges, all
+/// later blocks diverge as well. This allows binary search over the blocks, fetching the expected
+/// output roots of only a logarithmic number of blocks.
+pub async fn find_divergence(
+    rollup_provider: &HostProvider,
+    l2_provider: &HostProvider,
+    mut blocks: Vec<ObservedBlock>,
+) -> Result<Option<Divergence>> {
+    let (mut low, mut high) = (0, blocks.len());
+    let mut divergent_output_root = None;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let number = blocks[mid].header.number;
+        let expected_output_root = fetch_output_at_block(rollup_provider, number).await?;
+        if blocks[mid].output_root == expected_output_root {
+            low = mid + 1;
+        } else {
+            high = mid;
+            divergent_output_root = Some(expected_output_root);
+        }
+    }
+
+    let Some(expected_output_root) = divergent_output_root else {
+        return Ok(None);
+    };
+    let block = blocks.swap_remove(low);
+    let expected_header = fetch_block(l2_provider, block.header.number).await?.header.inner;
+    Ok(Some(Divergence { block, expected_output_root, expected_header }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{mock_header, mock_output_root, parse_quantity, MockRpc};
+    use alloy_primitives::Sealable;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Returns the blocks from 101 to 120 of the mock chain, as derived by a client program that
+    /// diverges from block `divergent` on.
+    fn observed_blocks(divergent: u64) -> Vec<ObservedBlock> {
+        (101..=120)
+            .map(|number| {
+                let (mut header, _) = mock_header(number);
+                let mut output_root = mock_output_root(number);
+                if number >= divergent {
+                    header.gas_used = 1;
+                    output_root = B256::repeat_byte(number as u8);
+                }
+                ObservedBlock {
+                    attributes: Default::default(),
+                    header: header.seal_slow(),
+                    output_root,
+                }
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_find_divergence() {
+        let l2_node = MockRpc::l2_node().await;
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let rollup_node = MockRpc::serve(move |method, params| match method {
+            "optimism_outputAtBlock" => {
+                counter.fetch_add(1, Ordering::Relaxed);
+                let number = parse_quantity(&params[0]);
+                Ok(json!({ "version": B256::ZERO, "outputRoot": mock_output_root(number) }))
+            }
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await;
+        let rollup_provider = HostCli::http_provider(&rollup_node.url).unwrap();
+        let l2_provider = HostCli::http_provider(&l2_node.url).unwrap();
+
+        let divergence =
+            find_divergence(&rollup_provider, &l2_provider, observed_blocks(107)).await.unwrap();
+        let divergence = divergence.unwrap();
+        assert_eq!(divergence.block.header.number, 107);
+        assert_eq!(divergence.expected_output_root, mock_output_root(107));
+        assert_eq!(divergence.expected_header, mock_header(107).0);
+        assert_eq!(divergence.header_diff(), vec!["gasUsed".to_string()]);
+        assert!(requests.load(Ordering::Relaxed) <= 5);
+
+        let divergence =
+            find_divergence(&rollup_provider, &l2_provider, observed_blocks(101)).await.unwrap();
+        assert_eq!(divergence.unwrap().block.header.number, 101);
+        let divergence =
+            find_divergence(&rollup_provider, &l2_provider, observed_blocks(121)).await.unwrap();
+        assert!(divergence.is_none());
+    }
+}
@@ -1,11 +1,21 @@
 //! Contains an online implementation of the `BlobProvider` trait.
 
-use alloy_eips::eip4844::{Blob, BlobTransactionSidecarItem, IndexedBlobHash};
+use alloy_eips::eip4844::{Blob, BlobTransactionSidecarItem, Bytes48, IndexedBlobHash};
 use alloy_rpc_types_beacon::sidecar::{BeaconBlobBundle, BlobData};
 use async_trait::async_trait;
 use kona_derive::{errors::BlobProviderError, traits::BlobProvider};
 use op_alloy_protocol::BlockInfo;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
+use std::{
+    fmt,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tracing::warn;
 
 /// The config spec engine api method.
 const SPEC_METHOD: &str = "eth/v1/config/spec";
@@ -62,6 +72,43 @@ impl APIGenesisResponse {
     }
 }
 
+/// A blob sidecar stored in a [BlobSource::Directory]. Further fields, such as those of the
+/// sidecars served by the beacon API, are ignored.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct StoredSidecar {
+    /// The blob.
+    blob: Box<Blob>,
+    /// The KZG commitment to the blob.
+    kzg_commitment: Bytes48,
+    /// The KZG proof of the blob.
+    kzg_proof: Bytes48,
+}
+
+/// A source of blob sidecars that the [OnlineBlobProvider] falls back to for the blobs that its
+/// beacon node does not serve, such as those past the beacon node's retention window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlobSource {
+    /// A secondary beacon node, queried for the blob sidecars of a slot.
+    Beacon(String),
+    /// A blob archiver that serves the blob sidecars endpoint of the beacon API for every slot,
+    /// such as op-blob-archiver.
+    Archiver(String),
+    /// A local directory with one blob sidecar per file, named by the versioned hash of the blob
+    /// as `<versioned hash>.json`. Each file holds a JSON object with the `blob`, `kzg_commitment`
+    /// and `kzg_proof` of the sidecar, as served by the beacon API.
+    Directory(PathBuf),
+}
+
+impl fmt::Display for BlobSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Beacon(base) => write!(f, "beacon node {base}"),
+            Self::Archiver(base) => write!(f, "blob archiver {base}"),
+            Self::Directory(dir) => write!(f, "blob directory {}", dir.display()),
+        }
+    }
+}
+
 /// An online implementation of the [BlobProvider] trait.
 #[derive(Debug, Clone)]
 pub struct OnlineBlobProvider {
@@ -73,6 +120,10 @@ pub struct OnlineBlobProvider {
     genesis_time: u64,
     /// The slot interval.
     slot_interval: u64,
+    /// The sources queried, in order, for the blobs that the beacon node does not serve.
+    fallbacks: Vec<BlobSource>,
+    /// The number of HTTP requests sent, shared by the clones of the provider.
+    requests: Arc<AtomicU64>,
 }
 
 impl OnlineBlobProvider {
@@ -105,22 +156,50 @@ impl OnlineBlobProvider {
             .map_err(|e| BlobProviderError::Backend(e.to_string()))?
             .data
             .seconds_per_slot;
-        Ok(Self { base, inner, genesis_time, slot_interval })
+        Ok(Self {
+            base,
+            inner,
+            genesis_time,
+            slot_interval,
+            fallbacks: Vec::new(),
+            // The genesis and spec requests above.
+            requests: Arc::new(AtomicU64::new(2)),
+        })
+    }
+
+    /// Sets the [BlobSource]s that are queried, in order, for the blobs that the beacon node does
+    /// not serve.
+    pub fn with_fallbacks(mut self, fallbacks: Vec<BlobSource>) -> Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Returns the number of HTTP requests that the provider has sent, to the beacon node and its
+    /// fallback sources.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Creates a GET request to `url`, counting it in [Self::requests].
+    fn get(&self, url: String) -> RequestBuilder {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.inner.get(url)
     }
 
     /// Fetches blob sidecars that were confirmed in the specified L1 block with the given indexed
-    /// hashes. Order of the returned sidecars is guaranteed to be that of the hashes. Blob data is
-    /// not checked for validity.
+    /// hashes from the beacon API at `base`. Order of the returned sidecars is guaranteed to be
+    /// that of the hashes. Blob data is not checked for validity.
     async fn beacon_blob_side_cars(
         &self,
+        base: &str,
         slot: u64,
         hashes: &[IndexedBlobHash],
     ) -> Result<Vec<BlobData>, reqwest::Error> {
         let raw_response = self
-            .inner
-            .get(format!("{}/{}/{}", self.base, SIDECARS_METHOD_PREFIX, slot))
+            .get(format!("{}/{}/{}", base, SIDECARS_METHOD_PREFIX, slot))
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
         let raw_response = raw_response.json::<BeaconBlobBundle>().await?;
 
         // Filter the sidecars by the hashes, in-order.
@@ -136,17 +215,80 @@ impl OnlineBlobProvider {
         Ok(sidecars)
     }
 
+    /// Checks that the beacon node serves blob sidecars, by fetching the sidecars of the head slot.
+    pub async fn probe_blob_sidecars(&self) -> Result<(), BlobProviderError> {
+        self.get(format!("{}/{}/head", self.base, SIDECARS_METHOD_PREFIX))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| BlobProviderError::Backend(e.to_string()))?
+            .json::<BeaconBlobBundle>()
+            .await
+            .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
     /// Fetches blob sidecars for the given slot and blob hashes.
     pub async fn fetch_sidecars(
         &self,
         slot: u64,
         hashes: &[IndexedBlobHash],
     ) -> Result<Vec<BlobData>, BlobProviderError> {
-        self.beacon_blob_side_cars(slot, hashes)
+        self.beacon_blob_side_cars(&self.base, slot, hashes)
             .await
             .map_err(|e| BlobProviderError::Backend(e.to_string()))
     }
 
+    /// Fetches the blob sidecars for the given slot and blob hashes from the given [BlobSource].
+    /// Sidecars that the source does not hold are omitted. Blob data is not checked for validity.
+    async fn fetch_sidecars_from(
+        &self,
+        source: &BlobSource,
+        slot: u64,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<BlobTransactionSidecarItem>, BlobProviderError> {
+        let dir = match source {
+            BlobSource::Beacon(base) | BlobSource::Archiver(base) => {
+                let sidecars = self
+                    .beacon_blob_side_cars(base, slot, hashes)
+                    .await
+                    .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
+                return Ok(sidecars
+                    .into_iter()
+                    .map(|s| BlobTransactionSidecarItem {
+                        index: s.index,
+                        blob: s.blob,
+                        kzg_commitment: s.kzg_commitment,
+                        kzg_proof: s.kzg_proof,
+                    })
+                    .collect());
+            }
+            BlobSource::Directory(dir) => dir,
+        };
+
+        let mut sidecars = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let path = dir.join(format!("{}.json", hash.hash));
+            let raw = match tokio::fs::read(&path).await {
+                Ok(raw) => raw,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(BlobProviderError::Backend(e.to_string())),
+            };
+            let sidecar = serde_json::from_slice::<StoredSidecar>(&raw).map_err(|e| {
+                BlobProviderError::Backend(format!("Invalid sidecar {}: {e}", path.display()))
+            })?;
+
+            // Files are keyed by versioned hash alone, so the sidecar takes the requested index.
+            sidecars.push(BlobTransactionSidecarItem {
+                index: hash.index,
+                blob: sidecar.blob,
+                kzg_commitment: sidecar.kzg_commitment,
+                kzg_proof: sidecar.kzg_proof,
+            });
+        }
+        Ok(sidecars)
+    }
+
     /// Computes the slot for the given timestamp.
     pub const fn slot(
         genesis: u64,
@@ -159,7 +301,12 @@ impl OnlineBlobProvider {
         Ok((timestamp - genesis) / slot_time)
     }
 
-    /// Fetches blob sidecars for the given block reference and blob hashes.
+    /// Fetches blob sidecars for the given block reference and blob hashes, in the order of the
+    /// hashes.
+    ///
+    /// Each sidecar is fetched from the beacon node, or else from the first fallback [BlobSource]
+    /// that serves it, and is only accepted once its blob is verified against its versioned hash
+    /// and KZG commitment.
     pub async fn fetch_filtered_sidecars(
         &self,
         block_ref: &BlockInfo,
@@ -172,30 +319,57 @@ impl OnlineBlobProvider {
         // Calculate the slot for the given timestamp.
         let slot = Self::slot(self.genesis_time, self.slot_interval, block_ref.timestamp)?;
 
-        // Fetch blob sidecars for the slot using the given blob hashes.
-        let sidecars = self.fetch_sidecars(slot, blob_hashes).await?;
-
-        // Filter blob sidecars that match the indicies in the specified list.
-        let blob_hash_indicies = blob_hashes.iter().map(|b| b.index).collect::<Vec<u64>>();
-        let filtered = sidecars
-            .into_iter()
-            .filter(|s| blob_hash_indicies.contains(&s.index))
-            .collect::<Vec<_>>();
+        let primary = BlobSource::Beacon(self.base.clone());
+        let mut sidecars = vec![None; blob_hashes.len()];
+        for source in core::iter::once(&primary).chain(&self.fallbacks) {
+            let missing = blob_hashes
+                .iter()
+                .zip(&sidecars)
+                .filter(|(_, sidecar)| sidecar.is_none())
+                .map(|(hash, _)| hash.clone())
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                break;
+            }
 
-        // Validate the correct number of blob sidecars were retrieved.
-        if blob_hashes.len() != filtered.len() {
-            return Err(BlobProviderError::SidecarLengthMismatch(blob_hashes.len(), filtered.len()));
+            let fetched = match self.fetch_sidecars_from(source, slot, &missing).await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    warn!(target: "blob_provider", "Failed to fetch blobs from {source}: {e}");
+                    continue;
+                }
+            };
+            for sidecar in fetched {
+                let Some(i) = blob_hashes.iter().position(|hash| hash.index == sidecar.index)
+                else {
+                    continue;
+                };
+                if sidecars[i].is_some() {
+                    continue;
+                }
+                match sidecar.verify_blob(&blob_hashes[i]) {
+                    Ok(()) => sidecars[i] = Some(sidecar),
+                    Err(e) => warn!(
+                        target: "blob_provider",
+                        "Rejecting blob {} from {source}: {e}",
+                        blob_hashes[i].hash
+                    ),
+                }
+            }
         }
 
-        Ok(filtered
+        sidecars
             .into_iter()
-            .map(|s| BlobTransactionSidecarItem {
-                index: s.index,
-                blob: s.blob,
-                kzg_commitment: s.kzg_commitment,
-                kzg_proof: s.kzg_proof,
+            .zip(blob_hashes)
+            .map(|(sidecar, hash)| {
+                sidecar.ok_or_else(|| {
+                    BlobProviderError::Backend(format!(
+                        "Blob {} is not available from any blob source",
+                        hash.hash
+                    ))
+                })
             })
-            .collect::<Vec<BlobTransactionSidecarItem>>())
+            .collect()
     }
 }
 
@@ -204,34 +378,102 @@ impl BlobProvider for OnlineBlobProvider {
     type Error = BlobProviderError;
 
     /// Fetches blob sidecars that were confirmed in the specified L1 block with the given indexed
-    /// hashes. The blobs are validated for their index and hashes using the specified
-    /// [IndexedBlobHash].
+    /// hashes. The blobs are validated for their index, hashes and KZG commitments using the
+    /// specified [IndexedBlobHash].
     async fn get_blobs(
         &mut self,
         block_ref: &BlockInfo,
         blob_hashes: &[IndexedBlobHash],
     ) -> Result<Vec<Box<Blob>>, Self::Error> {
-        // Fetch the blob sidecars for the given block reference and blob hashes.
+        // Fetch the blob sidecars for the given block reference and blob hashes, which are
+        // validated as they are fetched.
         let sidecars = self.fetch_filtered_sidecars(block_ref, blob_hashes).await?;
+        let blobs = sidecars.into_iter().map(|sidecar| sidecar.blob).collect::<Vec<_>>();
+        Ok(blobs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::MockRpc;
+    use serde_json::json;
+
+    /// Returns a sidecar of the zero blob, whose KZG commitment and proof are both the point at
+    /// infinity.
+    fn zero_sidecar(index: u64) -> BlobTransactionSidecarItem {
+        let mut infinity = Bytes48::ZERO;
+        infinity[0] = 0xc0;
+        BlobTransactionSidecarItem {
+            index,
+            blob: Box::default(),
+            kzg_commitment: infinity,
+            kzg_proof: infinity,
+        }
+    }
 
-        // Validate the blob sidecars straight away with the num hashes.
-        let blobs = sidecars
+    /// Serves a beacon node with 12 second slots from genesis at time 0, which serves the given
+    /// sidecars for slot 1.
+    async fn mock_beacon(sidecars: Vec<BlobTransactionSidecarItem>) -> MockRpc {
+        let sidecars = sidecars
             .into_iter()
-            .enumerate()
-            .map(|(i, sidecar)| {
-                let hash = blob_hashes
-                    .get(i)
-                    .ok_or(BlobProviderError::Backend("Missing blob hash".to_string()))?;
-                match sidecar.verify_blob(&alloy_eips::eip4844::IndexedBlobHash {
-                    hash: hash.hash,
-                    index: hash.index,
-                }) {
-                    Ok(_) => Ok(sidecar.blob),
-                    Err(e) => Err(BlobProviderError::Backend(e.to_string())),
-                }
+            .map(|s| BlobData {
+                index: s.index,
+                blob: s.blob,
+                kzg_commitment: s.kzg_commitment,
+                kzg_proof: s.kzg_proof,
+                signed_block_header: Default::default(),
+                kzg_commitment_inclusion_proof: Vec::new(),
             })
-            .collect::<Result<Vec<Box<Blob>>, BlobProviderError>>()
-            .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
-        Ok(blobs)
+            .collect::<Vec<_>>();
+        let bundle = json!({ "data": sidecars });
+        MockRpc::serve(move |path, _| match path {
+            "/eth/v1/beacon/genesis" => {
+                Ok(serde_json::to_value(APIGenesisResponse::new(0)).unwrap())
+            }
+            "/eth/v1/config/spec" => Ok(serde_json::to_value(APIConfigResponse::new(12)).unwrap()),
+            "/eth/v1/beacon/blob_sidecars/1" => Ok(bundle.clone()),
+            _ => Err(format!("unknown path {path}")),
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_filtered_sidecars_fallbacks() {
+        let hash = zero_sidecar(0).to_kzg_versioned_hash().into();
+        let hashes = [IndexedBlobHash { index: 0, hash }, IndexedBlobHash { index: 1, hash }];
+        let block_ref = BlockInfo { timestamp: 12, ..Default::default() };
+
+        // The beacon node serves a blob that does not match its commitment, and the archiver
+        // serves nothing.
+        let mut invalid = zero_sidecar(0);
+        invalid.blob[31] = 1;
+        let beacon = mock_beacon(vec![invalid, zero_sidecar(1)]).await;
+        let archiver = MockRpc::serve(|path, _| Err(format!("unknown path {path}"))).await;
+        let provider = OnlineBlobProvider::new_http(beacon.url.clone())
+            .await
+            .unwrap()
+            .with_fallbacks(vec![BlobSource::Archiver(archiver.url.clone())]);
+        let err = provider.fetch_filtered_sidecars(&block_ref, &hashes).await.unwrap_err();
+        assert!(err.to_string().contains("not available from any blob source"));
+
+        // The blob directory serves the valid blob.
+        let dir = std::env::temp_dir().join(format!("kona-blob-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sidecar = zero_sidecar(0);
+        let stored = json!({
+            "blob": sidecar.blob,
+            "kzg_commitment": sidecar.kzg_commitment,
+            "kzg_proof": sidecar.kzg_proof,
+        });
+        std::fs::write(dir.join(format!("{hash}.json")), stored.to_string()).unwrap();
+        let provider = provider.with_fallbacks(vec![
+            BlobSource::Archiver(archiver.url.clone()),
+            BlobSource::Directory(dir.clone()),
+        ]);
+        let sidecars = provider.fetch_filtered_sidecars(&block_ref, &hashes).await.unwrap();
+        assert_eq!(sidecars, vec![zero_sidecar(0), zero_sidecar(1)]);
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -0,0 +1,62 @@
+//! This module contains the subcommands of the host binary, which run instead of one of the modes
+//! of [HostCli](super::HostCli).
+
+use super::cli_styles;
+use crate::validate::validate_rollup_config;
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use std::path::PathBuf;
+
+/// A subcommand of the host binary.
+#[derive(Parser, Clone, Debug, PartialEq, Eq)]
+#[command(name = "kona-host", version, styles = cli_styles())]
+pub enum HostCommand {
+    /// Check a rollup config for internal consistency, and diff it against the config of the same
+    /// chain in the superchain registry if there is one. Exits with status 1 if the config has
+    /// problems or differs from the registry.
+    ValidateRollupConfig {
+        /// Path to the rollup config.
+        path: PathBuf,
+        /// The L2 chain ID of the superchain registry config to diff against. Defaults to the
+        /// `l2_chain_id` of the rollup config.
+        #[clap(long)]
+        l2_chain_id: Option<u64>,
+    },
+}
+
+impl HostCommand {
+    /// Parses a [HostCommand] from the command line arguments if the first argument names one,
+    /// exiting on invalid arguments as [clap::Parser::parse] does. Returns [None] otherwise, to
+    /// parse the arguments as a [HostCli](super::HostCli).
+    pub fn parse_subcommand() -> Option<Self> {
+        let name = std::env::args_os().nth(1)?;
+        Self::command().find_subcommand(name)?;
+        Some(Self::parse())
+    }
+
+    /// Runs the [HostCommand], returning its exit status.
+    pub fn run(self) -> Result<i32> {
+        match self {
+            Self::ValidateRollupConfig { path, l2_chain_id } => {
+                validate_rollup_config(&path, l2_chain_id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_host_command() {
+        let command =
+            HostCommand::try_parse_from(["kona-host", "validate-rollup-config", "rollup.json"])
+                .unwrap();
+        assert_eq!(
+            command,
+            HostCommand::ValidateRollupConfig { path: "rollup.json".into(), l2_chain_id: None }
+        );
+        assert!(HostCommand::command().find_subcommand("--native").is_none());
+    }
+}
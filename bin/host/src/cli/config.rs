@@ -0,0 +1,343 @@
+//! Contains the config file of the host, which holds default values for the [HostCli] arguments,
+//! with named profiles that override them.
+
+use super::HostCli;
+use anyhow::{anyhow, bail, Result};
+use clap::{
+    error::ErrorKind, parser::ValueSource, Arg, ArgAction, ArgMatches, Command, CommandFactory,
+    FromArgMatches,
+};
+use serde_json::{Map, Value};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item, Table};
+
+/// The IDs of the arguments that select and print the config file, which cannot be set within it.
+const CONFIG_ARGS: [&str; 3] = ["config", "profile", "print_config"];
+
+/// The settings of a config file, keyed by the long names of the [HostCli] arguments they set.
+pub type ConfigSettings = Map<String, Value>;
+
+impl HostCli {
+    /// Parses the [HostCli] from the command line, as [clap::Parser::parse] does, with the settings
+    /// of the config file selected by `--config` and `--profile` as defaults. Exits on error.
+    ///
+    /// If `--print-config` is given, prints the effective configuration and exits.
+    pub fn parse_with_config() -> Self {
+        let parse = || {
+            let matches = Self::try_get_matches_with_config(std::env::args_os())?;
+            Self::from_arg_matches(&matches).map(|cfg| (cfg, matches))
+        };
+        let (cfg, matches) = parse().unwrap_or_else(|e| e.exit());
+        if cfg.print_config {
+            match cfg
+                .effective_config(&matches)
+                .and_then(|settings| serde_json::to_string_pretty(&settings).map_err(Into::into))
+            {
+                Ok(settings) => println!("{settings}"),
+                Err(e) => Self::command().error(ErrorKind::Io, e).exit(),
+            }
+            std::process::exit(0);
+        }
+        cfg
+    }
+
+    /// Parses the [HostCli] from the given arguments, as [clap::Parser::try_parse_from] does, with
+    /// the settings of the config file selected by `--config` and `--profile` as defaults.
+    pub fn try_parse_with_config<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Self::from_arg_matches(&Self::try_get_matches_with_config(args)?)
+    }
+
+    /// Matches the given arguments against the [HostCli] command, with the settings of the config
+    /// file selected by `--config` and `--profile` as defaults.
+    ///
+    /// An argument given on the command line or through its environment variable overrides the
+    /// config file, and so does any argument that conflicts with it.
+    pub fn try_get_matches_with_config<I, T>(args: I) -> Result<ArgMatches, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args = args.into_iter().map(Into::into).collect::<Vec<OsString>>();
+        let mut command = Self::command();
+        let config = command.clone().ignore_errors(true).try_get_matches_from(&args).ok().and_then(
+            |matches| {
+                let path = matches.get_one::<PathBuf>("config")?.clone();
+                Some((matches, path))
+            },
+        );
+        let Some((matches, path)) = config else {
+            return command.try_get_matches_from(args);
+        };
+
+        let profile = matches.get_one::<String>("profile").map(String::as_str);
+        let config_args = load_config(&path, profile)
+            .and_then(|settings| config_args(&command, &matches, &settings))
+            .map_err(|e| command.error(ErrorKind::InvalidValue, e))?;
+        let (bin, rest) =
+            args.split_first().map_or((None, &[][..]), |(bin, rest)| (Some(bin), rest));
+        command.try_get_matches_from(bin.into_iter().chain(&config_args).chain(rest))
+    }
+
+    /// Returns the settings of this configuration that were given by the config file, the
+    /// environment or the command line, as recorded in its [ArgMatches], keyed by the long names
+    /// of their arguments. The settings can be saved as a JSON config file.
+    pub fn effective_config(&self, matches: &ArgMatches) -> Result<ConfigSettings> {
+        let Value::Object(values) = serde_json::to_value(self)? else {
+            bail!("Host configuration is not a table of settings");
+        };
+
+        let command = Self::command();
+        let mut settings = ConfigSettings::new();
+        for (id, value) in values {
+            let given = matches!(
+                matches.value_source(&id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            );
+            if !given || CONFIG_ARGS.contains(&id.as_str()) {
+                continue;
+            }
+            let long = command.get_arguments().find(|arg| arg.get_id() == id.as_str());
+            if let Some(long) = long.and_then(Arg::get_long) {
+                settings.insert(long.to_string(), value);
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Loads the settings of the config file at the given path, with the settings of the given profile
+/// applied over the defaults.
+///
+/// The file is parsed as JSON if its extension is `json`, and as TOML otherwise. Its top level
+/// holds the default settings, and its `profiles` table holds a table of settings per profile.
+pub fn load_config(path: &Path, profile: Option<&str>) -> Result<ConfigSettings> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read config file {}: {e}", path.display()))?;
+    let config = if path.extension().is_some_and(|extension| extension == "json") {
+        serde_json::from_str(&raw)?
+    } else {
+        toml_table_to_json(raw.parse::<DocumentMut>()?.as_table())
+    };
+    let Value::Object(mut settings) = config else {
+        bail!("Config file {} must hold a table of settings", path.display());
+    };
+
+    let profiles = match settings.remove("profiles") {
+        Some(Value::Object(profiles)) => profiles,
+        None => Map::new(),
+        Some(_) => bail!("`profiles` must be a table of profiles"),
+    };
+    if let Some(name) = profile {
+        match profiles.get(name) {
+            Some(Value::Object(overrides)) => settings.extend(overrides.clone()),
+            Some(_) => bail!("Profile {name:?} must be a table of settings"),
+            None => bail!(
+                "Profile {name:?} is not in {}; available profiles: {}",
+                path.display(),
+                profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+    Ok(settings)
+}
+
+/// Converts config settings into arguments of the given [Command], skipping the settings of the
+/// arguments that were given in the [ArgMatches], and of the arguments that conflict with them.
+fn config_args(
+    command: &Command,
+    matches: &ArgMatches,
+    settings: &ConfigSettings,
+) -> Result<Vec<OsString>> {
+    let given = command
+        .get_arguments()
+        .filter(|arg| {
+            matches!(
+                matches.value_source(arg.get_id().as_str()),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut args = Vec::new();
+    for (key, value) in settings {
+        let arg = command
+            .get_arguments()
+            .find(|arg| {
+                arg.get_long() == Some(key.as_str()) &&
+                    !CONFIG_ARGS.contains(&arg.get_id().as_str())
+            })
+            .ok_or_else(|| anyhow!("Unknown setting {key:?}"))?;
+        let overridden = given.iter().any(|other| {
+            other.get_id() == arg.get_id() ||
+                command.get_arg_conflicts_with(arg).contains(other) ||
+                command.get_arg_conflicts_with(other).contains(&arg)
+        });
+        if overridden {
+            continue;
+        }
+
+        let flag = format!("--{key}");
+        match (arg.get_action(), value) {
+            (_, Value::Null) | (ArgAction::SetTrue, Value::Bool(false)) => {}
+            (ArgAction::SetTrue, Value::Bool(true)) => args.push(flag.into()),
+            (ArgAction::Count, Value::Number(count)) => {
+                let count = count.as_u64().ok_or_else(|| anyhow!("Invalid count for {key:?}"))?;
+                args.extend((0..count).map(|_| OsString::from(&flag)));
+            }
+            (action, Value::String(value)) if action.takes_values() => {
+                args.push(format!("{flag}={value}").into())
+            }
+            (action, Value::Number(value)) if action.takes_values() => {
+                args.push(format!("{flag}={value}").into())
+            }
+            _ => bail!("Invalid value for setting {key:?}: {value}"),
+        }
+    }
+    Ok(args)
+}
+
+/// Converts a TOML table into a JSON object.
+fn toml_table_to_json(table: &Table) -> Value {
+    Value::Object(
+        table.iter().map(|(key, item)| (key.to_string(), toml_item_to_json(item))).collect(),
+    )
+}
+
+/// Converts a TOML item into a JSON value.
+fn toml_item_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => toml_table_to_json(table),
+        Item::ArrayOfTables(tables) => {
+            Value::Array(tables.iter().map(toml_table_to_json).collect())
+        }
+    }
+}
+
+/// Converts a TOML value into a JSON value.
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(value) => Value::String(value.value().clone()),
+        toml_edit::Value::Integer(value) => Value::from(*value.value()),
+        toml_edit::Value::Float(value) => Value::from(*value.value()),
+        toml_edit::Value::Boolean(value) => Value::Bool(*value.value()),
+        toml_edit::Value::Datetime(value) => Value::String(value.value().to_string()),
+        toml_edit::Value::Array(values) => {
+            Value::Array(values.iter().map(toml_value_to_json).collect())
+        }
+        toml_edit::Value::InlineTable(table) => Value::Object(
+            table.iter().map(|(key, value)| (key.to_string(), toml_value_to_json(value))).collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::B256;
+    use serde_json::json;
+
+    /// Writes a config file with the given name and contents to a fresh temporary directory.
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kona-host-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_with_config() {
+        let zero_hash = B256::ZERO.to_string();
+        let path = write_config(
+            "kona.toml",
+            &format!(
+                r#"
+                native = true
+                l1-head = "{zero_hash}"
+                agreed-l2-head-hash = "{zero_hash}"
+                agreed-l2-output-root = "{zero_hash}"
+                claimed-l2-output-root = "{zero_hash}"
+                claimed-l2-block-number = 100
+                data-dir = "/tmp/kona"
+                fetch-retries = 3
+                v = 2
+
+                [profiles.op-mainnet]
+                l2-chain-id = 10
+                l1-node-address = "http://l1:8545"
+                l2-node-address = "http://l2:8545"
+                l1-beacon-address = "http://beacon:5052"
+
+                [profiles.devnet]
+                rollup-config-path = "devnet.json"
+                "#
+            ),
+        );
+        let config = path.to_str().unwrap();
+
+        // The profile's settings apply over the defaults.
+        let cfg =
+            HostCli::try_parse_with_config(["host", "--config", config, "--profile", "op-mainnet"])
+                .unwrap();
+        assert!(cfg.native);
+        assert_eq!(cfg.v, 2);
+        assert_eq!(cfg.claimed_l2_block_number, 100);
+        assert_eq!(cfg.fetch_retries, Some(3));
+        assert_eq!(cfg.l2_chain_id, Some(10));
+        assert_eq!(cfg.l1_beacon_address.as_deref(), Some("http://beacon:5052"));
+
+        // Command line arguments override the config file, including those that conflict with it.
+        let cfg = HostCli::try_parse_with_config([
+            "host",
+            "--config",
+            config,
+            "--profile",
+            "op-mainnet",
+            "--claimed-l2-block-number",
+            "120",
+            "--rollup-config-path",
+            "rollup.json",
+        ])
+        .unwrap();
+        assert_eq!(cfg.claimed_l2_block_number, 120);
+        assert_eq!(cfg.l2_chain_id, None);
+        assert_eq!(cfg.rollup_config_path, Some(PathBuf::from("rollup.json")));
+
+        // The effective configuration can be loaded as a config file.
+        let args =
+            ["host", "--config", config, "--profile", "op-mainnet", "--l2-block-number", "120"];
+        let matches = HostCli::try_get_matches_with_config(args).unwrap();
+        let cfg = HostCli::from_arg_matches(&matches).unwrap();
+        let settings = cfg.effective_config(&matches).unwrap();
+        assert_eq!(settings["claimed-l2-block-number"], 120);
+        assert_eq!(settings["l1-head"], json!(zero_hash));
+        assert!(!settings.contains_key("claim-index") && !settings.contains_key("config"));
+        let json = write_config("effective.json", &Value::Object(settings.clone()).to_string());
+        let args = ["host", "--config", json.to_str().unwrap()];
+        let matches = HostCli::try_get_matches_with_config(args).unwrap();
+        let reloaded = HostCli::from_arg_matches(&matches).unwrap();
+        assert_eq!(reloaded.effective_config(&matches).unwrap(), settings);
+
+        // Unknown profiles and settings are rejected.
+        let args = ["host", "--config", config, "--profile", "op-sepolia"];
+        let err = HostCli::try_parse_with_config(args).unwrap_err();
+        assert!(
+            err.to_string().contains("available profiles") && err.to_string().contains("devnet")
+        );
+        let typo = write_config("typo.toml", "l1-nod-address = \"http://l1:8545\"");
+        let args = ["host", "--config", typo.to_str().unwrap()];
+        let err = HostCli::try_parse_with_config(args).unwrap_err();
+        assert!(err.to_string().contains("Unknown setting \"l1-nod-address\""));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,26 +1,36 @@
 //! This module contains all CLI-specific code for the host binary.
 
 use crate::{
-    blobs::OnlineBlobProvider,
+    blobs::{BlobSource, OnlineBlobProvider},
     kv::{
-        DiskKeyValueStore, LocalKeyValueStore, MemoryKeyValueStore, SharedKeyValueStore,
-        SplitKeyValueStore,
+        ConcurrentKeyValueStore, DiskKeyValueStore, KeyValueStore, LocalKeyValueStore,
+        MemoryKeyValueStore, SharedKeyValueStore, SplitKeyValueStore,
     },
+    preflight::StateStrategy,
+    transport::{CountingTransport, HostProvider},
 };
-use alloy_primitives::B256;
-use alloy_provider::ReqwestProvider;
+use alloy_primitives::{Address, B256};
 use alloy_rpc_client::RpcClient;
 use alloy_transport_http::Http;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use clap::{
-    builder::styling::{AnsiColor, Color, Style},
+    builder::{
+        styling::{AnsiColor, Color, Style},
+        RangedU64ValueParser,
+    },
     ArgAction, Parser,
 };
 use op_alloy_genesis::RollupConfig;
 use reqwest::Client;
 use serde::Serialize;
 use std::{path::PathBuf, sync::Arc};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
+
+mod command;
+pub use command::HostCommand;
+
+mod config;
+pub use config::{load_config, ConfigSettings};
 
 mod parser;
 pub(crate) use parser::parse_b256;
@@ -30,10 +40,17 @@ pub use tracing_util::init_tracing_subscriber;
 
 const ABOUT: &str = "
 kona-host is a CLI application that runs the Kona pre-image server and client program. The host
-can run in two modes: server mode and native mode. In server mode, the host runs the pre-image
-server and waits for the client program in the parent process to request pre-images. In native
-mode, the host runs the client program in a separate thread with the pre-image server in the
-primary thread.
+can run in five modes: server mode, native mode, emulated mode, listen mode and bisection mode. In
+server mode, the host runs the pre-image server and waits for the client program in the parent
+process to request pre-images. In native mode, the host runs the client program in a separate
+thread with the pre-image server in the primary thread. In emulated mode, the host runs a client
+program ELF built for an FPVM in an in-process emulator, serving its pre-image requests. In listen
+mode, the host serves many concurrent client sessions over a Unix domain socket, sharing fetched
+pre-images between them. In bisection mode, the host runs the client program natively and finds
+the first derived L2 block whose output root diverges from a reference rollup node.
+
+The `validate-rollup-config` subcommand checks a rollup config for internal consistency, and diffs
+it against the superchain registry.
 ";
 
 /// The host binary CLI application arguments.
@@ -43,21 +60,106 @@ pub struct HostCli {
     /// Verbosity level (0-2)
     #[arg(long, short, action = ArgAction::Count)]
     pub v: u8,
+    /// Path of a config file that holds default values for these arguments, keyed by their long
+    /// names, with named profiles in its `profiles` table. Parsed as JSON if its extension is
+    /// `json`, and as TOML otherwise. Arguments given on the command line or through the
+    /// environment override the config file.
+    #[clap(long, env = "KONA_HOST_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Name of the profile in the config file whose settings override the defaults of the file.
+    #[clap(long, requires = "config", env = "KONA_HOST_PROFILE")]
+    pub profile: Option<String>,
+    /// Print the effective configuration, merged from the config file, the environment and the
+    /// command line, as a JSON config file, and exit.
+    #[clap(long)]
+    pub print_config: bool,
     /// Hash of the L1 head block. Derivation stops after this block is processed.
-    #[clap(long, value_parser = parse_b256, env)]
+    #[clap(
+        long,
+        value_parser = parse_b256,
+        required_unless_present_any = ["listen", "agreed_l2_block_number", "dispute_game"],
+        default_value_t,
+        env
+    )]
     pub l1_head: B256,
     /// Hash of the agreed upon safe L2 block committed to by `--agreed-l2-output-root`.
-    #[clap(long, visible_alias = "l2-head", value_parser = parse_b256, env)]
+    #[clap(
+        long,
+        visible_alias = "l2-head",
+        value_parser = parse_b256,
+        required_unless_present_any = ["listen", "agreed_l2_block_number", "dispute_game"],
+        default_value_t,
+        env
+    )]
     pub agreed_l2_head_hash: B256,
     /// Agreed safe L2 Output Root to start derivation from.
-    #[clap(long, visible_alias = "l2-output-root", value_parser = parse_b256, env)]
+    #[clap(
+        long,
+        visible_alias = "l2-output-root",
+        value_parser = parse_b256,
+        required_unless_present_any = ["listen", "agreed_l2_block_number", "dispute_game"],
+        default_value_t,
+        env
+    )]
     pub agreed_l2_output_root: B256,
     /// Claimed L2 output root at block # `--claimed-l2-block-number` to validate.
-    #[clap(long, visible_alias = "l2-claim", value_parser = parse_b256, env)]
+    #[clap(
+        long,
+        visible_alias = "l2-claim",
+        value_parser = parse_b256,
+        required_unless_present_any = ["listen", "agreed_l2_block_number", "dispute_game"],
+        default_value_t,
+        env
+    )]
     pub claimed_l2_output_root: B256,
     /// Number of the L2 block that the claimed output root commits to.
-    #[clap(long, visible_alias = "l2-block-number", env)]
+    #[clap(
+        long,
+        visible_alias = "l2-block-number",
+        required_unless_present_any = ["listen", "dispute_game"],
+        default_value_t,
+        env
+    )]
     pub claimed_l2_block_number: u64,
+    /// Number of the agreed upon safe L2 block. If provided, the L1 head and the agreed and
+    /// claimed output roots are discovered from the L1 and L2 nodes for the L2 blocks from
+    /// `--agreed-l2-block-number` to `--claimed-l2-block-number`, rather than passed explicitly.
+    #[clap(
+        long,
+        requires = "l2_node_address",
+        conflicts_with_all = [
+            "l1_head",
+            "agreed_l2_head_hash",
+            "agreed_l2_output_root",
+            "claimed_l2_output_root",
+            "listen"
+        ],
+        env
+    )]
+    pub agreed_l2_block_number: Option<u64>,
+    /// Address of a `FaultDisputeGame` on L1. If provided, the claim at `--claim-index` in the
+    /// game is checked, and the L1 head and the agreed and claimed output roots are read from the
+    /// game and its anchor state registry, rather than passed explicitly.
+    #[clap(
+        long,
+        requires = "l1_node_address",
+        conflicts_with_all = [
+            "l1_head",
+            "agreed_l2_head_hash",
+            "agreed_l2_output_root",
+            "claimed_l2_output_root",
+            "claimed_l2_block_number",
+            "agreed_l2_block_number",
+            "listen",
+            "server"
+        ],
+        env
+    )]
+    pub dispute_game: Option<Address>,
+    /// Index of the claim to check in the dispute game at `--dispute-game`. The claim must be an
+    /// output root claim, at or above the split depth of the game.
+    #[clap(long, default_value_t, requires = "dispute_game", env)]
+    pub claim_index: u64,
     /// Address of L2 JSON-RPC endpoint to use (eth and debug namespace required).
     #[clap(
         long,
@@ -85,6 +187,26 @@ pub struct HostCli {
         env
     )]
     pub l1_beacon_address: Option<String>,
+    /// Address of a secondary L1 Beacon API endpoint, queried for the blobs that the primary
+    /// beacon node does not serve.
+    #[clap(long, visible_alias = "beacon-fallback", requires = "l1_beacon_address", env)]
+    pub l1_beacon_fallback_address: Option<String>,
+    /// Address of a blob archiver serving the blob sidecars endpoint of the Beacon API, queried
+    /// for the blobs that neither beacon node serves, such as those past their retention window.
+    #[clap(long, requires = "l1_beacon_address", env)]
+    pub blob_archiver_address: Option<String>,
+    /// Directory of blob sidecars, queried last for the blobs that no other blob source serves.
+    /// Each sidecar is stored as `<versioned hash>.json`, holding the `blob`, `kzg_commitment`
+    /// and `kzg_proof` of the sidecar as served by the Beacon API.
+    #[clap(long, requires = "l1_beacon_address", env)]
+    pub blob_dir: Option<PathBuf>,
+    /// Address of the L2 rollup node JSON-RPC endpoint to use (optimism namespace required). If
+    /// provided, claim discovery fetches the claimed output root from the rollup node rather than
+    /// computing it from the L2 node, and dispute game discovery checks the anchor state against
+    /// the L2 safe head at the L1 head of the game. Required in bisection mode, where it serves
+    /// the reference output roots.
+    #[clap(long, visible_alias = "rollup-node", env)]
+    pub l2_rollup_node_address: Option<String>,
     /// The Data Directory for preimage data storage. Optional if running in online mode,
     /// required if running in offline mode.
     #[clap(
@@ -94,13 +216,72 @@ pub struct HostCli {
         env
     )]
     pub data_dir: Option<PathBuf>,
+    /// Directory of preimages for global generic preimage keys, laid out as
+    /// `<namespace>/<commitment>`, with both components hex encoded with a `0x` prefix. The host
+    /// serves these preimages as-is; the client program verifies them against their commitments.
+    #[clap(long, env)]
+    pub generic_preimage_dir: Option<PathBuf>,
+    /// The strategy used to fetch code and state trie nodes from the L2 node. If not provided,
+    /// the first strategy that the L2 node supports is selected by the preflight checks, or
+    /// `db-get` if they are skipped.
+    #[clap(long, value_enum, env)]
+    pub l2_state_strategy: Option<StateStrategy>,
+    /// Skip the preflight checks of the L1, L2 and beacon endpoints in online mode. The host then
+    /// assumes that every RPC method it uses is supported.
+    #[clap(long, env)]
+    pub skip_preflight: bool,
+    /// Number of times that a failed hint fetch is retried before the preimage request fails. If
+    /// not provided, failed fetches are retried until they succeed.
+    #[clap(long, env)]
+    pub fetch_retries: Option<u32>,
+    /// Delay between the retries of a failed hint fetch, in milliseconds.
+    #[clap(long, default_value_t, env)]
+    pub fetch_retry_delay_ms: u64,
+    /// Maximum number of hints that are fetched at once, across all client sessions in listen
+    /// mode. Must be at least 1. Unlimited if not provided.
+    #[clap(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..), env)]
+    pub max_concurrent_fetches: Option<usize>,
+    /// Size of the block cache of the preimage database in `--data-dir`, in megabytes. If not
+    /// provided, the database uses its default block cache.
+    #[clap(long, requires = "data_dir", env)]
+    pub db_cache_size_mb: Option<usize>,
     /// Run the client program natively.
-    #[clap(long, conflicts_with = "server", required_unless_present = "server")]
+    #[clap(
+        long,
+        conflicts_with_all = ["server", "emulate", "listen", "bisect"],
+        required_unless_present_any = ["server", "emulate", "listen", "bisect"]
+    )]
     pub native: bool,
     /// Run in pre-image server mode without executing any client program. If not provided, the
     /// host will run the client program in the host process.
-    #[clap(long, conflicts_with = "native", required_unless_present = "native")]
+    #[clap(
+        long,
+        conflicts_with_all = ["native", "emulate", "listen", "bisect"],
+        required_unless_present_any = ["native", "emulate", "listen", "bisect"]
+    )]
     pub server: bool,
+    /// Path to a client program ELF built for Cannon (MIPS32) or Asterisc (RV64) to run in the
+    /// in-process FPVM emulator.
+    #[clap(long, conflicts_with_all = ["native", "server", "listen", "bisect"])]
+    pub emulate: Option<PathBuf>,
+    /// Path of a Unix domain socket to serve concurrent client sessions on, without executing any
+    /// client program. Each session provides its own boot information, and the sessions share
+    /// the pre-image store and in-flight fetches.
+    #[clap(long, conflicts_with_all = ["native", "server", "emulate", "bisect"])]
+    pub listen: Option<PathBuf>,
+    /// Run the client program natively, and find the first L2 block whose output root diverges
+    /// from the rollup node at `--l2-rollup-node-address`.
+    #[clap(
+        long,
+        conflicts_with_all = ["native", "server", "emulate", "listen"],
+        requires = "l2_rollup_node_address",
+        requires = "l2_node_address"
+    )]
+    pub bisect: bool,
+    /// Path to write a JSON report of the run to once the client program exits, for the client
+    /// program run natively.
+    #[clap(long, conflicts_with_all = ["server", "emulate", "listen", "bisect"])]
+    pub report: Option<PathBuf>,
     /// The L2 chain ID of a supported chain. If provided, the host will look for the corresponding
     /// rollup config in the superchain registry.
     #[clap(
@@ -131,43 +312,80 @@ impl HostCli {
     }
 
     /// Returns an HTTP provider for the given URL.
-    fn http_provider(url: &str) -> ReqwestProvider {
-        let url = url.parse().unwrap();
-        let http = Http::<Client>::new(url);
-        ReqwestProvider::new(RpcClient::new(http, true))
+    pub(crate) fn http_provider(url: &str) -> Result<HostProvider> {
+        let url = url.parse().map_err(|e| anyhow!("Invalid RPC URL {url:?}: {e}"))?;
+        let http = CountingTransport::new(Http::<Client>::new(url));
+        Ok(HostProvider::new(RpcClient::new(http, true)))
     }
 
     /// Creates the providers associated with the [HostCli] configuration.
     ///
     /// ## Returns
-    /// - A [ReqwestProvider] for the L1 node.
+    /// - A [HostProvider] for the L1 node.
     /// - An [OnlineBlobProvider] for the L1 beacon node.
-    /// - A [ReqwestProvider] for the L2 node.
+    /// - A [HostProvider] for the L2 node.
     pub async fn create_providers(
         &self,
-    ) -> Result<(ReqwestProvider, OnlineBlobProvider, ReqwestProvider)> {
-        let blob_provider = OnlineBlobProvider::new_http(
-            self.l1_beacon_address.clone().ok_or(anyhow!("Beacon API URL must be set"))?,
-        )
-        .await
-        .map_err(|e| anyhow!("Failed to load blob provider configuration: {e}"))?;
+    ) -> Result<(HostProvider, OnlineBlobProvider, HostProvider)> {
+        let beacon_address =
+            self.l1_beacon_address.clone().ok_or(anyhow!("Beacon API URL must be set"))?;
+        reqwest::Url::parse(&beacon_address)
+            .map_err(|e| anyhow!("Invalid beacon API URL {beacon_address:?}: {e}"))?;
+        let blob_provider = OnlineBlobProvider::new_http(beacon_address)
+            .await
+            .map_err(|e| anyhow!("Failed to load blob provider configuration: {e}"))?
+            .with_fallbacks(self.blob_sources()?);
         let l1_provider = Self::http_provider(
             self.l1_node_address.as_ref().ok_or(anyhow!("Provider must be set"))?,
-        );
+        )?;
         let l2_provider = Self::http_provider(
             self.l2_node_address.as_ref().ok_or(anyhow!("L2 node address must be set"))?,
-        );
+        )?;
 
         Ok((l1_provider, blob_provider, l2_provider))
     }
 
+    /// Returns the semaphore that limits the number of hints fetched at once, to
+    /// `--max-concurrent-fetches`. Shared by all [Fetcher]s of the host.
+    ///
+    /// [Fetcher]: crate::fetcher::Fetcher
+    pub fn fetch_permits(&self) -> Arc<Semaphore> {
+        Arc::new(Semaphore::new(self.max_concurrent_fetches.unwrap_or(Semaphore::MAX_PERMITS)))
+    }
+
+    /// Returns the [BlobSource]s that the blob provider falls back to, in the order that they are
+    /// queried: the secondary beacon node, the blob archiver, and the blob directory.
+    pub fn blob_sources(&self) -> Result<Vec<BlobSource>> {
+        let mut sources = Vec::new();
+        if let Some(url) = &self.l1_beacon_fallback_address {
+            reqwest::Url::parse(url)
+                .map_err(|e| anyhow!("Invalid fallback beacon API URL {url:?}: {e}"))?;
+            sources.push(BlobSource::Beacon(url.clone()));
+        }
+        if let Some(url) = &self.blob_archiver_address {
+            reqwest::Url::parse(url)
+                .map_err(|e| anyhow!("Invalid blob archiver URL {url:?}: {e}"))?;
+            sources.push(BlobSource::Archiver(url.clone()));
+        }
+        if let Some(dir) = &self.blob_dir {
+            if !dir.is_dir() {
+                bail!("Blob directory {} does not exist", dir.display());
+            }
+            sources.push(BlobSource::Directory(dir.clone()));
+        }
+        Ok(sources)
+    }
+
     /// Parses the CLI arguments and returns a new instance of a [SharedKeyValueStore], as it is
     /// configured to be created.
     pub fn construct_kv_store(&self) -> SharedKeyValueStore {
         let local_kv_store = LocalKeyValueStore::new(self.clone());
 
         let kv_store: SharedKeyValueStore = if let Some(ref data_dir) = self.data_dir {
-            let disk_kv_store = DiskKeyValueStore::new(data_dir.clone());
+            let disk_kv_store = DiskKeyValueStore::with_cache_size(
+                data_dir.clone(),
+                self.db_cache_size_mb.map(|size| size << 20),
+            );
             let split_kv_store = SplitKeyValueStore::new(local_kv_store, disk_kv_store);
             Arc::new(RwLock::new(split_kv_store))
         } else {
@@ -179,6 +397,25 @@ impl HostCli {
         kv_store
     }
 
+    /// Parses the CLI arguments and returns a new instance of a [ConcurrentKeyValueStore] for
+    /// remote preimages, without the local boot information, as it is configured to be created.
+    /// Clones of the store may be shared between client sessions.
+    pub fn construct_remote_kv_store(
+        &self,
+    ) -> ConcurrentKeyValueStore<dyn KeyValueStore + Send + Sync> {
+        let store: Arc<std::sync::RwLock<dyn KeyValueStore + Send + Sync>> =
+            if let Some(ref data_dir) = self.data_dir {
+                Arc::new(std::sync::RwLock::new(DiskKeyValueStore::with_cache_size(
+                    data_dir.clone(),
+                    self.db_cache_size_mb.map(|size| size << 20),
+                )))
+            } else {
+                Arc::new(std::sync::RwLock::new(MemoryKeyValueStore::new()))
+            };
+
+        ConcurrentKeyValueStore::new(store)
+    }
+
     /// Reads the [RollupConfig] from the file system and returns it as a string.
     pub fn read_rollup_config(&self) -> Result<RollupConfig> {
         let path = self.rollup_config_path.as_ref().ok_or_else(|| {
@@ -211,8 +448,8 @@ const fn cli_styles() -> clap::builder::Styles {
 
 #[cfg(test)]
 mod test {
-    use crate::HostCli;
-    use alloy_primitives::B256;
+    use crate::{blobs::BlobSource, HostCli};
+    use alloy_primitives::{Address, B256};
     use clap::Parser;
 
     #[test]
@@ -238,6 +475,10 @@ mod test {
             (["--server", "--rollup-config-path", "dummy", "--data-dir", "dummy"].as_slice(), true),
             (["--native", "--l2-chain-id", "0", "--data-dir", "dummy"].as_slice(), true),
             (["--native", "--rollup-config-path", "dummy", "--data-dir", "dummy"].as_slice(), true),
+            (
+                ["--emulate", "kona.elf", "--l2-chain-id", "0", "--data-dir", "dummy"].as_slice(),
+                true,
+            ),
             (
                 [
                     "--l1-node-address",
@@ -253,8 +494,14 @@ mod test {
                 .as_slice(),
                 true,
             ),
+            (
+                ["--listen", "kona.sock", "--l2-chain-id", "0", "--data-dir", "dummy"].as_slice(),
+                true,
+            ),
             // invalid
             (["--server", "--native", "--l2-chain-id", "0"].as_slice(), false),
+            (["--native", "--emulate", "kona.elf", "--l2-chain-id", "0"].as_slice(), false),
+            (["--listen", "kona.sock", "--server", "--l2-chain-id", "0"].as_slice(), false),
             (["--l2-chain-id", "0", "--rollup-config-path", "dummy", "--server"].as_slice(), false),
             (["--server"].as_slice(), false),
             (["--native"].as_slice(), false),
@@ -272,5 +519,75 @@ mod test {
             let parsed = HostCli::try_parse_from(args);
             assert_eq!(parsed.is_ok(), valid);
         }
+
+        // In listen mode, the boot information is provided by each client session instead.
+        let listen = ["host", "--listen", "kona.sock", "--l2-chain-id", "0", "--data-dir", "dummy"];
+        assert!(HostCli::try_parse_from(listen).is_ok());
+        let server = ["host", "--server", "--l2-chain-id", "0", "--data-dir", "dummy"];
+        assert!(HostCli::try_parse_from(server).is_err());
+
+        // With an agreed L2 block number, the output roots and L1 head are discovered instead.
+        let rpcs = ["--l1", "dummy", "--l2", "dummy", "--beacon", "dummy"];
+        let discover = ["host", "--native", "--l2-chain-id", "10", "--l2-block-number", "120"];
+        let discover = [discover.as_slice(), &rpcs, &["--agreed-l2-block-number", "100"]].concat();
+        assert!(HostCli::try_parse_from(&discover).is_ok());
+        let explicit = [discover.as_slice(), &["--l1-head", zero_hash_str]].concat();
+        assert!(HostCli::try_parse_from(explicit).is_err());
+        let offline = [&discover[..6], &["--agreed-l2-block-number", "100"]].concat();
+        assert!(HostCli::try_parse_from(offline).is_err());
+
+        // With a dispute game, the boot information is read from the game instead.
+        let game = Address::repeat_byte(0xaa).to_string();
+        let dispute = ["host", "--native", "--l2-chain-id", "10", "--dispute-game", &game];
+        let dispute = [dispute.as_slice(), &rpcs, &["--claim-index", "3"]].concat();
+        assert!(HostCli::try_parse_from(&dispute).is_ok());
+        let explicit = [dispute.as_slice(), &["--l2-block-number", "120"]].concat();
+        assert!(HostCli::try_parse_from(explicit).is_err());
+        let server = [dispute.as_slice(), &["--server"]].concat();
+        assert!(HostCli::try_parse_from(&server[..]).is_err());
+        let offline = [&dispute[..6], &["--claim-index", "3"]].concat();
+        assert!(HostCli::try_parse_from(offline).is_err());
+        let no_game = ["host", "--native", "--l2-chain-id", "10", "--claim-index", "3"];
+        assert!(HostCli::try_parse_from(no_game).is_err());
+
+        // Bisection needs a reference rollup node, and the L2 node to fetch headers from.
+        let bisect =
+            [default_flags.as_slice(), &rpcs, &["--bisect", "--l2-chain-id", "10"]].concat();
+        assert!(HostCli::try_parse_from(&bisect).is_err());
+        let bisect = [bisect.as_slice(), &["--rollup-node", "dummy"]].concat();
+        assert!(HostCli::try_parse_from(&bisect).is_ok());
+        let native = [bisect.as_slice(), &["--native"]].concat();
+        assert!(HostCli::try_parse_from(native).is_err());
+
+        // A fetch limit of zero would never grant a permit.
+        let native = ["host", "--native", "--l2-chain-id", "0", "--data-dir", "dummy"];
+        let limit = [default_flags.as_slice(), &native[1..], &["--max-concurrent-fetches", "1"]];
+        assert!(HostCli::try_parse_from(limit.concat()).is_ok());
+        let zero = [default_flags.as_slice(), &native[1..], &["--max-concurrent-fetches", "0"]];
+        assert!(HostCli::try_parse_from(zero.concat()).is_err());
+    }
+    #[test]
+    fn test_blob_sources() {
+        let dir = std::env::temp_dir();
+        let cfg = HostCli {
+            l1_beacon_fallback_address: Some("http://beacon:5052".to_string()),
+            blob_archiver_address: Some("http://archiver:8000".to_string()),
+            blob_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        assert_eq!(
+            cfg.blob_sources().unwrap(),
+            vec![
+                BlobSource::Beacon("http://beacon:5052".to_string()),
+                BlobSource::Archiver("http://archiver:8000".to_string()),
+                BlobSource::Directory(dir.clone()),
+            ]
+        );
+
+        let invalid =
+            HostCli { blob_archiver_address: Some("archiver".to_string()), ..cfg.clone() };
+        assert!(invalid.blob_sources().is_err());
+        let missing = HostCli { blob_dir: Some(dir.join("kona-missing-blob-dir")), ..cfg };
+        assert!(missing.blob_sources().is_err());
     }
 }
@@ -0,0 +1,228 @@
+//! This module contains the discovery of a claim's boot information from the L1 and L2 nodes, for
+//! a range of L2 blocks.
+
+use crate::{cli::HostCli, fetcher::fetch_output_root_preimage, transport::HostProvider};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{Block, BlockNumberOrTag, BlockTransactionsKind};
+use anyhow::{anyhow, bail, Result};
+use op_alloy_consensus::OpTxEnvelope;
+use op_alloy_genesis::RollupConfig;
+use op_alloy_protocol::L1BlockInfoTx;
+use op_alloy_registry::ROLLUP_CONFIGS;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// The response of the `optimism_outputAtBlock` method of a rollup node, with only the fields
+/// that are used by discovery.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputAtBlockResponse {
+    /// The output root of the block.
+    output_root: B256,
+}
+
+/// Discovers the boot information of the claim over the L2 blocks from
+/// `cfg.agreed_l2_block_number` to `cfg.claimed_l2_block_number`, and fills it into `cfg`.
+///
+/// - The agreed L2 head and output root are those of the agreed L2 block. The output root is
+///   computed from the L2 node, as it is for the `StartingL2Output` hint.
+/// - The claimed output root is fetched from the rollup node with `optimism_outputAtBlock` if
+///   `cfg.l2_rollup_node_address` is set, and computed from the L2 node otherwise.
+/// - The L1 head is the L1 block one sequencing window after the L1 origin of the claimed L2 block,
+///   by which all batch data for the claimed block must have been posted, or the latest L1 block if
+///   that block does not exist yet.
+pub async fn discover_claim(cfg: &mut HostCli) -> Result<()> {
+    let agreed_number = cfg
+        .agreed_l2_block_number
+        .ok_or_else(|| anyhow!("No agreed L2 block number to discover the claim from"))?;
+    let claimed_number = cfg.claimed_l2_block_number;
+    if claimed_number <= agreed_number {
+        bail!(
+            "Claimed L2 block #{claimed_number} must come after agreed L2 block #{agreed_number}"
+        );
+    }
+
+    let l1_provider = HostCli::http_provider(
+        cfg.l1_node_address.as_ref().ok_or(anyhow!("L1 node address must be set"))?,
+    )?;
+    let l2_provider = HostCli::http_provider(
+        cfg.l2_node_address.as_ref().ok_or(anyhow!("L2 node address must be set"))?,
+    )?;
+    let rollup_config = discovery_rollup_config(cfg)?;
+
+    // The agreed L2 head and output root.
+    let agreed_block = fetch_block(&l2_provider, agreed_number).await?;
+    let agreed_l2_head_hash = agreed_block.header.hash;
+    let agreed_l2_output_root =
+        keccak256(fetch_output_root_preimage(&l2_provider, agreed_l2_head_hash).await?);
+
+    // The claimed output root.
+    let claimed_block = fetch_block(&l2_provider, claimed_number).await?;
+    let claimed_l2_output_root = match cfg.l2_rollup_node_address {
+        Some(ref address) => {
+            fetch_output_at_block(&HostCli::http_provider(address)?, claimed_number).await?
+        }
+        None => {
+            keccak256(fetch_output_root_preimage(&l2_provider, claimed_block.header.hash).await?)
+        }
+    };
+
+    // The L1 head, one sequencing window after the L1 origin of the claimed block.
+    let l1_origin = fetch_l1_origin(&l2_provider, &claimed_block).await?;
+    let latest_l1_number = l1_provider
+        .get_block_number()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch L1 block number: {e}"))?;
+    let l1_head_number = l1_head_number(l1_origin, rollup_config.seq_window_size, latest_l1_number);
+    if l1_head_number < l1_origin + rollup_config.seq_window_size {
+        warn!(
+            target: "discovery",
+            "The sequencing window of the claimed block has not elapsed; using the latest L1 block \
+             #{latest_l1_number} as the L1 head"
+        );
+    }
+    let l1_head = fetch_block(&l1_provider, l1_head_number).await?.header.hash;
+
+    info!(
+        target: "discovery",
+        "Discovered claim: l1_head = {l1_head} (#{l1_head_number}), agreed_l2_head_hash = \
+         {agreed_l2_head_hash}, agreed_l2_output_root = {agreed_l2_output_root}, \
+         claimed_l2_output_root = {claimed_l2_output_root} (#{claimed_number})"
+    );
+    cfg.l1_head = l1_head;
+    cfg.agreed_l2_head_hash = agreed_l2_head_hash;
+    cfg.agreed_l2_output_root = agreed_l2_output_root;
+    cfg.claimed_l2_output_root = claimed_l2_output_root;
+
+    Ok(())
+}
+
+/// Fetches the output root of the L2 block with the given number from a rollup node, with
+/// `optimism_outputAtBlock`.
+pub(crate) async fn fetch_output_at_block(
+    rollup_provider: &HostProvider,
+    number: u64,
+) -> Result<B256> {
+    let output: OutputAtBlockResponse = rollup_provider
+        .client()
+        .request("optimism_outputAtBlock", [BlockNumberOrTag::Number(number)])
+        .await
+        .map_err(|e| anyhow!("Failed to fetch output root of L2 block #{number}: {e}"))?;
+    Ok(output.output_root)
+}
+
+/// Returns the number of the L1 head for a claim whose L1 origin is `l1_origin`, given the
+/// sequencing window size of the chain and the number of the latest L1 block.
+const fn l1_head_number(l1_origin: u64, seq_window_size: u64, latest_l1_number: u64) -> u64 {
+    let end_of_window = l1_origin.saturating_add(seq_window_size);
+    if end_of_window < latest_l1_number {
+        end_of_window
+    } else {
+        latest_l1_number
+    }
+}
+
+/// Returns the rollup config used for discovery, from the superchain registry or from the rollup
+/// config file.
+pub(crate) fn discovery_rollup_config(cfg: &HostCli) -> Result<RollupConfig> {
+    cfg.l2_chain_id.map_or_else(
+        || cfg.read_rollup_config(),
+        |chain_id| {
+            ROLLUP_CONFIGS.get(&chain_id).cloned().ok_or_else(|| {
+                anyhow!("No rollup config in the superchain registry for {chain_id}")
+            })
+        },
+    )
+}
+
+/// Fetches the block with the given number, with only the hashes of its transactions.
+pub(crate) async fn fetch_block(provider: &HostProvider, number: u64) -> Result<Block> {
+    provider
+        .get_block_by_number(BlockNumberOrTag::Number(number), BlockTransactionsKind::Hashes)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch block #{number}: {e}"))?
+        .ok_or_else(|| anyhow!("Block #{number} not found"))
+}
+
+/// Fetches the number of the L1 origin of an L2 block, from the L1 info deposit transaction at the
+/// start of the block.
+async fn fetch_l1_origin(l2_provider: &HostProvider, block: &Block) -> Result<u64> {
+    let tx_hash =
+        block.transactions.hashes().next().ok_or_else(|| {
+            anyhow!("L2 block #{} has no L1 info transaction", block.header.number)
+        })?;
+    let raw_tx: Bytes = l2_provider
+        .client()
+        .request("debug_getRawTransaction", [tx_hash])
+        .await
+        .map_err(|e| anyhow!("Failed to fetch L1 info transaction: {e}"))?;
+
+    let OpTxEnvelope::Deposit(deposit) = OpTxEnvelope::decode_2718(&mut raw_tx.as_ref())
+        .map_err(|e| anyhow!("Failed to decode L1 info transaction: {e}"))?
+    else {
+        bail!("The first transaction of L2 block #{} is not a deposit", block.header.number);
+    };
+    let l1_info = L1BlockInfoTx::decode_calldata(deposit.input.as_ref())
+        .map_err(|e| anyhow!("Failed to decode L1 info transaction: {e}"))?;
+    Ok(l1_info.id().number)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{mock_block, mock_header, mock_output_root, parse_quantity, MockRpc};
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_discover_claim() {
+        let l2_node = MockRpc::l2_node().await;
+        let l1_node = MockRpc::serve(|method, params| match method {
+            "eth_blockNumber" => Ok(json!("0x2000")),
+            "eth_getBlockByNumber" => Ok(mock_block(parse_quantity(&params[0]))),
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await;
+
+        let mut cfg = HostCli {
+            l1_node_address: Some(l1_node.url.clone()),
+            l2_node_address: Some(l2_node.url.clone()),
+            agreed_l2_block_number: Some(100),
+            claimed_l2_block_number: 120,
+            l2_chain_id: Some(10),
+            ..Default::default()
+        };
+        discover_claim(&mut cfg).await.unwrap();
+
+        // OP Mainnet has a sequencing window of 3600 L1 blocks, and the L1 origin of block #120
+        // is #60.
+        assert_eq!(cfg.l1_head, mock_header(3660).1);
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(100).1);
+        assert_eq!(cfg.agreed_l2_output_root, mock_output_root(100));
+        assert_eq!(cfg.claimed_l2_output_root, mock_output_root(120));
+
+        // The claimed output root is taken from the rollup node, if there is one.
+        let claimed_output_root = B256::repeat_byte(0xcc);
+        let rollup_node = MockRpc::serve(move |method, params| match method {
+            "optimism_outputAtBlock" if params[0] == "0x78" => {
+                Ok(json!({ "version": B256::ZERO, "outputRoot": claimed_output_root }))
+            }
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await;
+        cfg.l2_rollup_node_address = Some(rollup_node.url.clone());
+        discover_claim(&mut cfg).await.unwrap();
+        assert_eq!(cfg.claimed_l2_output_root, claimed_output_root);
+
+        cfg.agreed_l2_block_number = Some(120);
+        assert!(discover_claim(&mut cfg).await.is_err());
+    }
+
+    #[test]
+    fn test_l1_head_number() {
+        assert_eq!(l1_head_number(100, 3600, 10_000), 3700);
+        assert_eq!(l1_head_number(100, 3600, 3000), 3000);
+        assert_eq!(l1_head_number(u64::MAX, 3600, 3000), 3000);
+    }
+}
@@ -0,0 +1,449 @@
+//! This module contains the discovery of a claim's boot information from the on-chain state of a
+//! `FaultDisputeGame` and its `AnchorStateRegistry`.
+
+use crate::{cli::HostCli, fetcher::fetch_output_root_preimage, transport::HostProvider};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind, TransactionRequest};
+use alloy_sol_types::{sol, SolCall};
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+sol! {
+    /// The subset of the `FaultDisputeGame` interface that is used to read claims.
+    #[allow(missing_docs)]
+    interface IFaultDisputeGame {
+        function gameType() external view returns (uint32);
+        function l1Head() external view returns (bytes32);
+        function l2BlockNumber() external view returns (uint256);
+        function splitDepth() external view returns (uint256);
+        function startingOutputRoot() external view returns (bytes32 root, uint256 l2BlockNumber);
+        function anchorStateRegistry() external view returns (address);
+        function claimDataLen() external view returns (uint256);
+        function claimData(uint256 index) external view returns (
+            uint32 parentIndex,
+            address counteredBy,
+            address claimant,
+            uint128 bond,
+            bytes32 claim,
+            uint128 position,
+            uint128 clock
+        );
+    }
+
+    /// The subset of the `AnchorStateRegistry` interface that is used to read anchor states.
+    #[allow(missing_docs)]
+    interface IAnchorStateRegistry {
+        function anchors(uint32 gameType) external view returns (bytes32 root, uint256 l2BlockNumber);
+    }
+}
+
+/// The response of the `optimism_safeHeadAtL1Block` method of a rollup node, with only the fields
+/// that are used by claim discovery.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeHeadAtL1BlockResponse {
+    /// The L2 safe head as of the L1 block.
+    safe_head: SafeHead,
+}
+
+/// The L2 safe head in a [SafeHeadAtL1BlockResponse].
+#[derive(Debug, Deserialize)]
+struct SafeHead {
+    /// The number of the L2 safe head.
+    number: u64,
+}
+
+/// An output root claim of a dispute game, checked by the client program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameClaim {
+    /// The address of the dispute game.
+    pub game: Address,
+    /// The index of the claim in the game.
+    pub index: u64,
+    /// The output root of the claim.
+    pub output_root: B256,
+    /// The L2 block number that the output root of the claim commits to.
+    pub l2_block_number: u64,
+}
+
+impl GameClaim {
+    /// Reports whether the claim is honest, given the exit status of the client program. Only an
+    /// exit status of 1, for an invalid claim, makes the claim dishonest.
+    pub fn report(&self, status: i32) {
+        let verdict = match status {
+            0 => "honest",
+            1 => "dishonest",
+            _ => {
+                warn!(
+                    target: "dispute_game",
+                    "Claim #{} of dispute game {} could not be checked: the client program exited \
+                     with status {status}",
+                    self.index,
+                    self.game
+                );
+                return;
+            }
+        };
+        info!(
+            target: "dispute_game",
+            "Claim #{} of dispute game {} ({} at L2 block #{}) is {verdict}",
+            self.index,
+            self.game,
+            self.output_root,
+            self.l2_block_number
+        );
+    }
+}
+
+/// Discovers the boot information of the claim at `cfg.claim_index` in the dispute game at
+/// `cfg.dispute_game`, and fills it into `cfg`.
+///
+/// - The L1 head is the L1 head of the game.
+/// - The claimed output root and L2 block number are those of the claim, which must be an output
+///   root claim at or above the split depth of the game.
+/// - The agreed output root is the current anchor state of the game type in the anchor state
+///   registry if its L2 block is before the claimed block and at or below the L2 safe head at the
+///   L1 head of the game, as reported by the rollup node at `cfg.l2_rollup_node_address`.
+///   Otherwise, or without a rollup node, it is the starting output root of the game. The agreed L2
+///   head is fetched from the L2 node, and its output root checked against the agreed output root.
+pub async fn discover_game_claim(cfg: &mut HostCli) -> Result<GameClaim> {
+    let game = cfg.dispute_game.ok_or_else(|| anyhow!("No dispute game to read the claim from"))?;
+    let index = cfg.claim_index;
+    let l1_provider = HostCli::http_provider(
+        cfg.l1_node_address.as_ref().ok_or(anyhow!("L1 node address must be set"))?,
+    )?;
+    let l2_provider = HostCli::http_provider(
+        cfg.l2_node_address.as_ref().ok_or(anyhow!("L2 node address must be set"))?,
+    )?;
+
+    // Read the claim from the game.
+    let claims =
+        to_u64(call(&l1_provider, game, IFaultDisputeGame::claimDataLenCall {}).await?._0)?;
+    if index >= claims {
+        bail!("Dispute game {game} has {claims} claims, no claim #{index}");
+    }
+    let claim =
+        call(&l1_provider, game, IFaultDisputeGame::claimDataCall { index: U256::from(index) })
+            .await?;
+    let l1_head = call(&l1_provider, game, IFaultDisputeGame::l1HeadCall {}).await?._0;
+    let split_depth =
+        to_u64(call(&l1_provider, game, IFaultDisputeGame::splitDepthCall {}).await?._0)?;
+    let game_block_number =
+        to_u64(call(&l1_provider, game, IFaultDisputeGame::l2BlockNumberCall {}).await?._0)?;
+    let starting = call(&l1_provider, game, IFaultDisputeGame::startingOutputRootCall {}).await?;
+    let starting_block_number = to_u64(starting.l2BlockNumber)?;
+    let claimed_block_number =
+        claim_block_number(claim.position, split_depth, starting_block_number, game_block_number)?;
+
+    // Start from the anchor state if it is before the claimed block, and derivable from the L1
+    // head of the game.
+    let game_type = call(&l1_provider, game, IFaultDisputeGame::gameTypeCall {}).await?._0;
+    let registry =
+        call(&l1_provider, game, IFaultDisputeGame::anchorStateRegistryCall {}).await?._0;
+    let anchor =
+        call(&l1_provider, registry, IAnchorStateRegistry::anchorsCall { gameType: game_type })
+            .await?;
+    let anchor_block_number = to_u64(anchor.l2BlockNumber)?;
+    let anchor_usable = if anchor_block_number >= claimed_block_number {
+        warn!(
+            target: "dispute_game",
+            "Anchor state at L2 block #{anchor_block_number} is past the claim; starting from the \
+             starting output root of the game"
+        );
+        false
+    } else if let Some(ref address) = cfg.l2_rollup_node_address {
+        let safe_head =
+            fetch_safe_head_at(&l1_provider, &HostCli::http_provider(address)?, l1_head).await?;
+        if anchor_block_number > safe_head {
+            warn!(
+                target: "dispute_game",
+                "Anchor state at L2 block #{anchor_block_number} is past the L2 safe head \
+                 #{safe_head} at the L1 head of the game; starting from the starting output root \
+                 of the game"
+            );
+        }
+        anchor_block_number <= safe_head
+    } else {
+        warn!(
+            target: "dispute_game",
+            "No rollup node to check the anchor state against the L2 safe head at the L1 head of \
+             the game; starting from the starting output root of the game"
+        );
+        false
+    };
+    let (agreed_output_root, agreed_block_number) = if anchor_usable {
+        (anchor.root, anchor_block_number)
+    } else {
+        (starting.root, starting_block_number)
+    };
+
+    // Find the agreed L2 head, and check it against the agreed output root.
+    let agreed_l2_head_hash = l2_provider
+        .get_block_by_number(
+            BlockNumberOrTag::Number(agreed_block_number),
+            BlockTransactionsKind::Hashes,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to fetch L2 block #{agreed_block_number}: {e}"))?
+        .ok_or_else(|| anyhow!("L2 block #{agreed_block_number} not found"))?
+        .header
+        .hash;
+    let computed_output_root =
+        keccak256(fetch_output_root_preimage(&l2_provider, agreed_l2_head_hash).await?);
+    if computed_output_root != agreed_output_root {
+        bail!(
+            "Output root of L2 block #{agreed_block_number} is {computed_output_root}, but the \
+             agreed output root is {agreed_output_root}"
+        );
+    }
+
+    info!(
+        target: "dispute_game",
+        "Checking claim #{index} of dispute game {game}: {} at L2 block #{claimed_block_number}, \
+         from {agreed_output_root} at L2 block #{agreed_block_number}",
+        claim.claim
+    );
+    cfg.l1_head = l1_head;
+    cfg.agreed_l2_head_hash = agreed_l2_head_hash;
+    cfg.agreed_l2_output_root = agreed_output_root;
+    cfg.claimed_l2_output_root = claim.claim;
+    cfg.claimed_l2_block_number = claimed_block_number;
+
+    Ok(GameClaim { game, index, output_root: claim.claim, l2_block_number: claimed_block_number })
+}
+
+/// Returns the L2 block number that an output root claim at the given position commits to.
+///
+/// Claims at or above the split depth bisect the output roots after the starting block of the
+/// game. The claim at `position` commits to the output root at the rightmost trace index below
+/// it at the split depth, clamped to the L2 block number of the game.
+fn claim_block_number(
+    position: u128,
+    split_depth: u64,
+    starting_block_number: u64,
+    game_block_number: u64,
+) -> Result<u64> {
+    if position == 0 {
+        bail!("Invalid claim position 0");
+    }
+    let depth = (127 - position.leading_zeros()) as u64;
+    if depth > split_depth || split_depth >= 64 {
+        bail!("Claim at depth {depth} is not an output root claim (split depth {split_depth})");
+    }
+
+    let index_at_depth = (position - (1 << depth)) as u64;
+    let trace_index = ((index_at_depth + 1) << (split_depth - depth)) - 1;
+    Ok(starting_block_number.saturating_add(trace_index + 1).min(game_block_number))
+}
+
+/// Fetches the number of the L2 safe head as of the L1 block `l1_head` from a rollup node, with
+/// `optimism_safeHeadAtL1Block`.
+async fn fetch_safe_head_at(
+    l1_provider: &HostProvider,
+    rollup_provider: &HostProvider,
+    l1_head: B256,
+) -> Result<u64> {
+    let l1_head_number = l1_provider
+        .get_block_by_hash(l1_head, BlockTransactionsKind::Hashes)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch L1 block {l1_head}: {e}"))?
+        .ok_or_else(|| anyhow!("L1 block {l1_head} not found"))?
+        .header
+        .number;
+    let response: SafeHeadAtL1BlockResponse = rollup_provider
+        .client()
+        .request("optimism_safeHeadAtL1Block", [BlockNumberOrTag::Number(l1_head_number)])
+        .await
+        .map_err(|e| anyhow!("Failed to fetch L2 safe head at L1 block #{l1_head_number}: {e}"))?;
+    Ok(response.safe_head.number)
+}
+
+/// Calls a view function of the contract at `to`.
+async fn call<C: SolCall>(provider: &HostProvider, to: Address, call: C) -> Result<C::Return> {
+    let tx = TransactionRequest::default().to(to).input(call.abi_encode().into());
+    let output =
+        provider.call(&tx).await.map_err(|e| anyhow!("Failed to call {}: {e}", C::SIGNATURE))?;
+    C::abi_decode_returns(&output, true)
+        .map_err(|e| anyhow!("Failed to decode {}: {e}", C::SIGNATURE))
+}
+
+/// Converts a [U256] returned by a contract to a [u64].
+fn to_u64(value: U256) -> Result<u64> {
+    u64::try_from(value).map_err(|_| anyhow!("Value {value} does not fit in a u64"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{mock_block, mock_header, mock_output_root, parse_quantity, MockRpc};
+    use alloy_primitives::{address, Bytes};
+    use alloy_sol_types::SolValue;
+    use serde_json::{json, Value};
+
+    const GAME: Address = address!("00000000000000000000000000000000000000aa");
+    const REGISTRY: Address = address!("00000000000000000000000000000000000000bb");
+
+    /// The number of the L1 head of the game.
+    const L1_HEAD_NUMBER: u64 = 50;
+
+    /// Serves the `eth_call`s of a game with a split depth of 4 over the L2 blocks 100 to 110,
+    /// whose anchor state is at `anchor_block`, and whose claims are the root claim and a claim at
+    /// position 4 (the leftmost claim at depth 2). The L1 head of the game is the mock block
+    /// #[L1_HEAD_NUMBER].
+    async fn mock_l1_node(anchor_block: u64) -> MockRpc {
+        MockRpc::serve(move |method, params| {
+            if method == "eth_getBlockByHash" && params[0] == json!(mock_header(L1_HEAD_NUMBER).1) {
+                return Ok(mock_block(L1_HEAD_NUMBER));
+            }
+            if method != "eth_call" {
+                return Err(format!("unsupported method {method}"));
+            }
+            let tx = &params[0];
+            let to: Address = serde_json::from_value(tx["to"].clone()).unwrap();
+            let input = tx.get("input").or_else(|| tx.get("data")).cloned().unwrap_or(Value::Null);
+            let input: Bytes = serde_json::from_value(input).unwrap();
+            let selector: [u8; 4] = input[..4].try_into().unwrap();
+
+            let output = match (to, selector) {
+                (GAME, IFaultDisputeGame::gameTypeCall::SELECTOR) => 1u32.abi_encode(),
+                (GAME, IFaultDisputeGame::l1HeadCall::SELECTOR) => {
+                    mock_header(L1_HEAD_NUMBER).1.abi_encode()
+                }
+                (GAME, IFaultDisputeGame::l2BlockNumberCall::SELECTOR) => {
+                    U256::from(110).abi_encode()
+                }
+                (GAME, IFaultDisputeGame::splitDepthCall::SELECTOR) => U256::from(4).abi_encode(),
+                (GAME, IFaultDisputeGame::startingOutputRootCall::SELECTOR) => {
+                    (mock_output_root(100), U256::from(100)).abi_encode_params()
+                }
+                (GAME, IFaultDisputeGame::anchorStateRegistryCall::SELECTOR) => {
+                    REGISTRY.abi_encode()
+                }
+                (GAME, IFaultDisputeGame::claimDataLenCall::SELECTOR) => U256::from(2).abi_encode(),
+                (GAME, IFaultDisputeGame::claimDataCall::SELECTOR) => {
+                    let call = IFaultDisputeGame::claimDataCall::abi_decode(&input, true).unwrap();
+                    let (claim, position) = match call.index.to::<u64>() {
+                        0 => (B256::repeat_byte(0xcc), 1u128),
+                        _ => (mock_output_root(104), 4u128),
+                    };
+                    let parent = if position == 1 { u32::MAX } else { 0 };
+                    (parent, Address::ZERO, Address::ZERO, 0u128, claim, position, 0u128)
+                        .abi_encode_params()
+                }
+                (REGISTRY, IAnchorStateRegistry::anchorsCall::SELECTOR) => {
+                    (mock_output_root(anchor_block), U256::from(anchor_block)).abi_encode_params()
+                }
+                _ => return Err(format!("unsupported call to {to}")),
+            };
+            Ok(json!(Bytes::from(output)))
+        })
+        .await
+    }
+
+    /// Serves a rollup node whose L2 safe head at the L1 head of the game is at `safe_head`.
+    async fn mock_rollup_node(safe_head: u64) -> MockRpc {
+        MockRpc::serve(move |method, params| match method {
+            "optimism_safeHeadAtL1Block" if parse_quantity(&params[0]) == L1_HEAD_NUMBER => {
+                Ok(json!({
+                    "l1Block": { "hash": mock_header(L1_HEAD_NUMBER).1, "number": L1_HEAD_NUMBER },
+                    "safeHead": { "hash": mock_header(safe_head).1, "number": safe_head },
+                }))
+            }
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_discover_game_claim() {
+        let l2_node = MockRpc::l2_node().await;
+        let l1_node = mock_l1_node(102).await;
+        let rollup_node = mock_rollup_node(105).await;
+        let mut cfg = HostCli {
+            l1_node_address: Some(l1_node.url.clone()),
+            l2_node_address: Some(l2_node.url.clone()),
+            l2_rollup_node_address: Some(rollup_node.url.clone()),
+            dispute_game: Some(GAME),
+            claim_index: 0,
+            ..Default::default()
+        };
+
+        // The root claim commits to the L2 block number of the game.
+        let claim = discover_game_claim(&mut cfg).await.unwrap();
+        assert_eq!(
+            claim,
+            GameClaim {
+                game: GAME,
+                index: 0,
+                output_root: B256::repeat_byte(0xcc),
+                l2_block_number: 110
+            }
+        );
+        assert_eq!(cfg.l1_head, mock_header(L1_HEAD_NUMBER).1);
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(102).1);
+        assert_eq!(cfg.agreed_l2_output_root, mock_output_root(102));
+        assert_eq!(cfg.claimed_l2_output_root, B256::repeat_byte(0xcc));
+        assert_eq!(cfg.claimed_l2_block_number, 110);
+
+        // The claim at position 4 commits to the fourth block after the starting block, which
+        // the anchor state has advanced past.
+        let l1_node = mock_l1_node(106).await;
+        cfg.l1_node_address = Some(l1_node.url.clone());
+        cfg.claim_index = 1;
+        let claim = discover_game_claim(&mut cfg).await.unwrap();
+        assert_eq!(claim.l2_block_number, 104);
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(100).1);
+        assert_eq!(cfg.agreed_l2_output_root, mock_output_root(100));
+        assert_eq!(cfg.claimed_l2_output_root, mock_output_root(104));
+
+        cfg.claim_index = 2;
+        assert!(discover_game_claim(&mut cfg).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_discover_game_claim_anchor_past_safe_head() {
+        let l2_node = MockRpc::l2_node().await;
+        let l1_node = mock_l1_node(102).await;
+        let rollup_node = mock_rollup_node(101).await;
+        let mut cfg = HostCli {
+            l1_node_address: Some(l1_node.url.clone()),
+            l2_node_address: Some(l2_node.url.clone()),
+            l2_rollup_node_address: Some(rollup_node.url.clone()),
+            dispute_game: Some(GAME),
+            claim_index: 0,
+            ..Default::default()
+        };
+
+        // The anchor state is before the claim, but was not yet safe at the L1 head of the game.
+        discover_game_claim(&mut cfg).await.unwrap();
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(100).1);
+        assert_eq!(cfg.agreed_l2_output_root, mock_output_root(100));
+
+        // The anchor state at the safe head is usable.
+        let rollup_node = mock_rollup_node(102).await;
+        cfg.l2_rollup_node_address = Some(rollup_node.url.clone());
+        discover_game_claim(&mut cfg).await.unwrap();
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(102).1);
+        assert_eq!(cfg.agreed_l2_output_root, mock_output_root(102));
+
+        // Without a rollup node, the safe head is unknown.
+        cfg.l2_rollup_node_address = None;
+        discover_game_claim(&mut cfg).await.unwrap();
+        assert_eq!(cfg.agreed_l2_head_hash, mock_header(100).1);
+    }
+
+    #[test]
+    fn test_claim_block_number() {
+        // Split depth 4, starting at block 100, with the game at block 110.
+        assert_eq!(claim_block_number(1, 4, 100, 110).unwrap(), 110);
+        assert_eq!(claim_block_number(2, 4, 100, 110).unwrap(), 108);
+        assert_eq!(claim_block_number(3, 4, 100, 110).unwrap(), 110);
+        assert_eq!(claim_block_number(4, 4, 100, 110).unwrap(), 104);
+        assert_eq!(claim_block_number(16, 4, 100, 110).unwrap(), 101);
+        assert_eq!(claim_block_number(17, 4, 100, 110).unwrap(), 102);
+        assert!(claim_block_number(32, 4, 100, 110).is_err());
+        assert!(claim_block_number(0, 4, 100, 110).is_err());
+    }
+}
@@ -1,31 +1,59 @@
 //! This module contains the [Fetcher] struct, which is responsible for fetching preimages from a
 //! remote source.
 
-use crate::{blobs::OnlineBlobProvider, kv::KeyValueStore};
-use alloy_consensus::{Header, TxEnvelope, EMPTY_ROOT_HASH};
+use crate::{
+    blobs::OnlineBlobProvider,
+    kv::KeyValueStore,
+    preflight::{RpcCapabilities, StateStrategy},
+    report::RpcRequests,
+    transport::HostProvider,
+};
+use alloy_consensus::{
+    constants::KECCAK_EMPTY, Header, Receipt, ReceiptEnvelope, ReceiptWithBloom, TxEnvelope,
+    EMPTY_ROOT_HASH,
+};
 use alloy_eips::{
     eip2718::Encodable2718,
     eip4844::{IndexedBlobHash, FIELD_ELEMENTS_PER_BLOB},
     BlockId,
 };
-use alloy_primitives::{address, keccak256, map::HashMap, Address, Bytes, B256};
-use alloy_provider::{Provider, ReqwestProvider};
+use alloy_primitives::{
+    address, hex, keccak256,
+    map::{HashMap, HashSet},
+    Address, Bytes, B256,
+};
+use alloy_provider::Provider;
 use alloy_rlp::{Decodable, EMPTY_STRING_CODE};
 use alloy_rpc_types::{
     debug::ExecutionWitness, Block, BlockNumberOrTag, BlockTransactions, BlockTransactionsKind,
-    Transaction,
+    EIP1186AccountProofResponse, Transaction, TransactionReceipt,
 };
 use anyhow::{anyhow, Result};
+use kona_mpt::{Nibbles, TrieNode};
 use kona_preimage::{PreimageKey, PreimageKeyType};
-use kona_proof::{Hint, HintType};
+use kona_proof::Hint;
 use op_alloy_protocol::BlockInfo;
 use op_alloy_rpc_types_engine::OpPayloadAttributes;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex as StdMutex, PoisonError,
+    },
+    thread,
+    time::Duration,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock, Semaphore};
 use tracing::{error, trace, warn};
 
 mod precompiles;
 
+/// The longest path, in nibbles, of a trie node that can be reconstructed with the
+/// [StateStrategy::Proofs] strategy. Finding a key under a path of `n` nibbles takes `16^n` hashes
+/// on average.
+const MAX_RECONSTRUCTED_PATH: usize = 7;
+
 /// The [Fetcher] struct is responsible for fetching preimages from a remote source.
 #[derive(Debug)]
 pub struct Fetcher<KV>
@@ -35,13 +63,30 @@ where
     /// Key-value store for preimages.
     kv_store: Arc<RwLock<KV>>,
     /// L1 chain provider.
-    l1_provider: ReqwestProvider,
+    l1_provider: HostProvider,
     /// The blob provider
     blob_provider: OnlineBlobProvider,
     /// L2 chain provider.
-    l2_provider: ReqwestProvider,
+    l2_provider: HostProvider,
     /// L2 head
     l2_head: B256,
+    /// The directory that global generic preimages are served from, if any.
+    generic_preimage_dir: Option<PathBuf>,
+    /// The hints that are being prefetched, shared with other [Fetcher]s that write to the same
+    /// key-value store.
+    in_flight: Arc<InFlightHints>,
+    /// The capabilities of the providers, which select the methods used to fetch preimages.
+    capabilities: RpcCapabilities,
+    /// The state learned from previous hints, used to fetch state without `debug_dbGet`.
+    state_index: StdMutex<StateIndex>,
+    /// The permits to fetch hints, which limit the number of hints fetched at once. Shared with
+    /// other [Fetcher]s of the host.
+    fetch_permits: Arc<Semaphore>,
+    /// The number of times that a failed hint fetch is retried. If [None], failed fetches are
+    /// retried until they succeed.
+    max_retries: Option<u32>,
+    /// The delay between the retries of a failed hint fetch.
+    retry_delay: Duration,
     /// The last hint that was received. [None] if no hint has been received yet.
     last_hint: Option<String>,
 }
@@ -51,19 +96,94 @@ where
     KV: KeyValueStore + ?Sized,
 {
     /// Create a new [Fetcher] with the given [KeyValueStore].
-    pub const fn new(
+    pub fn new(
         kv_store: Arc<RwLock<KV>>,
-        l1_provider: ReqwestProvider,
+        l1_provider: HostProvider,
         blob_provider: OnlineBlobProvider,
-        l2_provider: ReqwestProvider,
+        l2_provider: HostProvider,
         l2_head: B256,
     ) -> Self {
-        Self { kv_store, l1_provider, blob_provider, l2_provider, l2_head, last_hint: None }
+        Self {
+            kv_store,
+            l1_provider,
+            blob_provider,
+            l2_provider,
+            l2_head,
+            generic_preimage_dir: None,
+            in_flight: Default::default(),
+            capabilities: Default::default(),
+            state_index: Default::default(),
+            fetch_permits: Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)),
+            max_retries: None,
+            retry_delay: Duration::ZERO,
+            last_hint: None,
+        }
+    }
+
+    /// Sets the directory that global generic preimages are served from. The preimage of a
+    /// commitment is read from `<dir>/<namespace>/<commitment>`.
+    pub fn with_generic_preimage_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.generic_preimage_dir = dir;
+        self
+    }
+
+    /// Shares the set of in-flight hints with other [Fetcher]s that write to the same key-value
+    /// store, so that a hint received by several of them at once is only fetched once.
+    pub fn with_in_flight_hints(mut self, in_flight: Arc<InFlightHints>) -> Self {
+        self.in_flight = in_flight;
+        self
+    }
+
+    /// Sets the capabilities of the providers, as probed by the
+    /// [preflight](crate::preflight::preflight) checks, so that the [Fetcher] falls back to
+    /// alternate methods for the ones that are missing.
+    pub const fn with_capabilities(mut self, capabilities: RpcCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Shares the permits to fetch hints with other [Fetcher]s, limiting the number of hints that
+    /// are fetched at once to the number of permits.
+    pub fn with_fetch_permits(mut self, fetch_permits: Arc<Semaphore>) -> Self {
+        self.fetch_permits = fetch_permits;
+        self
+    }
+
+    /// Sets the number of times that a failed hint fetch is retried, after the given delay,
+    /// before the preimage request fails. If `max_retries` is [None], failed fetches are retried
+    /// until they succeed.
+    pub const fn with_retries(mut self, max_retries: Option<u32>, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Returns the number of requests that the providers of the [Fetcher] have sent, including
+    /// those of the preflight checks that share them.
+    pub fn rpc_requests(&self) -> RpcRequests {
+        let sent = |provider: &HostProvider| provider.client().transport().requests();
+        RpcRequests {
+            l1: sent(&self.l1_provider),
+            l2: sent(&self.l2_provider),
+            beacon: self.blob_provider.requests(),
+        }
     }
 
     /// Set the last hint to be received.
+    ///
+    /// The client program hints an account proof at the parent block before it reads any state of
+    /// the L2 block that it executes, so the block of the most recent proof hint is recorded as the
+    /// parent of the block being executed, whether or not the proof has to be fetched.
     pub fn hint(&mut self, hint: &str) {
         trace!(target: "fetcher", "Received hint: {hint}");
+        if let Ok(
+            Hint::L2AccountProof { block_number, .. } |
+            Hint::L2AccountStorageProof { block_number, .. },
+        ) = Hint::parse(hint)
+        {
+            self.state_index.lock().unwrap_or_else(PoisonError::into_inner).parent_block_number =
+                Some(block_number);
+        }
         self.last_hint = Some(hint.to_string());
     }
 
@@ -79,12 +199,34 @@ where
         drop(kv_lock);
 
         // Use a loop to keep retrying the prefetch as long as the key is not found
-        while preimage.is_none() && self.last_hint.is_some() {
-            let hint = self.last_hint.as_ref().expect("Cannot be None");
+        let mut retries = 0;
+        while preimage.is_none() {
+            let Some(hint) = self.last_hint.as_ref() else {
+                break;
+            };
+
+            // Wait for any other fetcher that is prefetching the same hint, and skip the prefetch
+            // if it has already stored the preimage.
+            let _in_flight = self.in_flight.acquire(hint).await;
+            if let Some(value) = self.kv_store.read().await.get(key) {
+                preimage = Some(value);
+                break;
+            }
 
-            if let Err(e) = self.prefetch(hint).await {
+            let permit = self.fetch_permits.acquire().await?;
+            let result = self.prefetch(hint).await;
+            drop(permit);
+            if let Err(e) = result {
                 error!(target: "fetcher", "Failed to prefetch hint: {e}");
+                if e.is::<UnservableHint>() {
+                    return Err(e);
+                }
+                if self.max_retries.is_some_and(|max_retries| retries >= max_retries) {
+                    return Err(e.context(format!("Failed to fetch hint after {retries} retries")));
+                }
+                retries += 1;
                 warn!(target: "fetcher", "Retrying hint fetch: {hint}");
+                tokio::time::sleep(self.retry_delay).await;
                 continue;
             }
 
@@ -98,21 +240,11 @@ where
     /// Fetch the preimage for the given hint and insert it into the key-value store.
     async fn prefetch(&self, hint: &str) -> Result<()> {
         let hint = Hint::parse(hint)?;
-        let (hint_type, hint_data) = hint.split();
-        trace!(target: "fetcher", "Fetching hint: {hint_type} {hint_data}");
-
-        match hint_type {
-            HintType::L1BlockHeader => {
-                // Validate the hint data length.
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
+        trace!(target: "fetcher", "Fetching hint: {hint}");
 
+        match hint {
+            Hint::L1BlockHeader(hash) => {
                 // Fetch the raw header from the L1 chain provider.
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
                 let raw_header: Bytes = self
                     .l1_provider
                     .client()
@@ -127,18 +259,9 @@ where
                     raw_header.into(),
                 )?;
             }
-            HintType::L1Transactions => {
-                // Validate the hint data length.
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
+            Hint::L1Transactions(hash) => {
                 // Fetch the block from the L1 chain provider and store the transactions within its
                 // body in the key-value store.
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
                 let Block { transactions, .. } = self
                     .l1_provider
                     .get_block_by_hash(hash, BlockTransactionsKind::Full)
@@ -147,45 +270,27 @@ where
                     .ok_or(anyhow!("Block not found."))?;
                 self.store_transactions(transactions).await?;
             }
-            HintType::L1Receipts => {
-                // Validate the hint data length.
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
+            Hint::L1Receipts(hash) => {
                 // Fetch the receipts from the L1 chain provider and store the receipts within the
                 // key-value store.
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
-                let raw_receipts: Vec<Bytes> = self
-                    .l1_provider
-                    .client()
-                    .request("debug_getRawReceipts", [hash])
-                    .await
-                    .map_err(|e| anyhow!(e))?;
+                let raw_receipts: Vec<Bytes> = if self.capabilities.l1_raw_receipts {
+                    self.l1_provider
+                        .client()
+                        .request("debug_getRawReceipts", [hash])
+                        .await
+                        .map_err(|e| anyhow!(e))?
+                } else {
+                    let receipts: Vec<TransactionReceipt> = self
+                        .l1_provider
+                        .client()
+                        .request("eth_getBlockReceipts", [hash])
+                        .await
+                        .map_err(|e| anyhow!("Failed to fetch receipts: {e}"))?;
+                    receipts.into_iter().map(encode_receipt).collect::<Result<_>>()?
+                };
                 self.store_trie_nodes(raw_receipts.as_slice()).await?;
             }
-            HintType::L1Blob => {
-                if hint_data.len() != 48 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
-                let hash_data_bytes: [u8; 32] = hint_data[0..32]
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
-                let index_data_bytes: [u8; 8] = hint_data[32..40]
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to u64: {e}"))?;
-                let timestamp_data_bytes: [u8; 8] = hint_data[40..48]
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to u64: {e}"))?;
-
-                let hash: B256 = hash_data_bytes.into();
-                let index = u64::from_be_bytes(index_data_bytes);
-                let timestamp = u64::from_be_bytes(timestamp_data_bytes);
-
+            Hint::L1Blob { hash, index, timestamp } => {
                 let partial_block_ref = BlockInfo { timestamp, ..Default::default() };
                 let indexed_hash = IndexedBlobHash { index, hash };
 
@@ -241,50 +346,33 @@ where
                     sidecar.kzg_proof.to_vec(),
                 )?;
             }
-            HintType::L1Precompile => {
-                // Validate the hint data length.
-                if hint_data.len() < 20 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
-                // Fetch the precompile address from the hint data.
-                let precompile_address = Address::from_slice(&hint_data.as_ref()[..20]);
-                let precompile_input = hint_data[20..].to_vec();
-                let input_hash = keccak256(hint_data.as_ref());
-
-                let result = precompiles::execute(precompile_address, precompile_input)
-                    .map_or_else(
-                        |_| vec![0u8; 1],
-                        |raw_res| {
-                            let mut res = Vec::with_capacity(1 + raw_res.len());
-                            res.push(0x01);
-                            res.extend_from_slice(&raw_res);
-                            res
-                        },
-                    );
+            Hint::L1Precompile { address, ref input } => {
+                let hint_data = hint.payload();
+                let input_hash = keccak256(&hint_data);
+
+                let result = precompiles::execute(address, input.to_vec()).map_or_else(
+                    |_| vec![0u8; 1],
+                    |raw_res| {
+                        let mut res = Vec::with_capacity(1 + raw_res.len());
+                        res.push(0x01);
+                        res.extend_from_slice(&raw_res);
+                        res
+                    },
+                );
 
                 // Acquire a lock on the key-value store and set the preimages.
                 let mut kv_lock = self.kv_store.write().await;
                 kv_lock.set(
                     PreimageKey::new(*input_hash, PreimageKeyType::Keccak256).into(),
-                    hint_data.into(),
+                    hint_data,
                 )?;
                 kv_lock.set(
                     PreimageKey::new(*input_hash, PreimageKeyType::Precompile).into(),
                     result,
                 )?;
             }
-            HintType::L2BlockHeader => {
-                // Validate the hint data length.
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
+            Hint::L2BlockHeader(hash) => {
                 // Fetch the raw header from the L2 chain provider.
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
                 let raw_header: Bytes = self
                     .l2_provider
                     .client()
@@ -299,18 +387,9 @@ where
                     raw_header.into(),
                 )?;
             }
-            HintType::L2Transactions => {
-                // Validate the hint data length.
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
+            Hint::L2Transactions(hash) => {
                 // Fetch the block from the L2 chain provider and store the transactions within its
                 // body in the key-value store.
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
                 let Block { transactions, .. } = self
                     .l2_provider
                     .get_block_by_hash(hash, BlockTransactionsKind::Hashes)
@@ -336,78 +415,25 @@ where
                     _ => anyhow::bail!("Only BlockTransactions::Hashes are supported."),
                 };
             }
-            HintType::L2Code => {
-                // geth hashdb scheme code hash key prefix
-                const CODE_PREFIX: u8 = b'c';
-
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
-
-                // Attempt to fetch the code from the L2 chain provider.
-                let code_hash = [&[CODE_PREFIX], hash.as_slice()].concat();
-                let code = self
-                    .l2_provider
-                    .client()
-                    .request::<&[Bytes; 1], Bytes>("debug_dbGet", &[code_hash.into()])
-                    .await;
-
-                // Check if the first attempt to fetch the code failed. If it did, try fetching the
-                // code hash preimage without the geth hashdb scheme prefix.
-                let code = match code {
-                    Ok(code) => code,
-                    Err(_) => self
-                        .l2_provider
-                        .client()
-                        .request::<&[B256; 1], Bytes>("debug_dbGet", &[hash])
-                        .await
-                        .map_err(|e| anyhow!("Error fetching code hash preimage: {e}"))?,
+            Hint::L2Code(hash) => {
+                let code = match self.capabilities.l2_state {
+                    StateStrategy::DbGet => Some(self.db_get_code(hash).await?),
+                    _ => self.fetch_indexed_code(hash).await?,
+                };
+                let Some(code) = code else {
+                    return self.fetch_state_by_hash(hash).await;
                 };
 
                 let mut kv_write_lock = self.kv_store.write().await;
                 kv_write_lock
                     .set(PreimageKey::new(*hash, PreimageKeyType::Keccak256).into(), code.into())?;
             }
-            HintType::StartingL2Output => {
-                const OUTPUT_ROOT_VERSION: u8 = 0;
-                const L2_TO_L1_MESSAGE_PASSER_ADDRESS: Address =
-                    address!("4200000000000000000000000000000000000016");
-
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-
-                // Fetch the header for the L2 head block.
-                let raw_header: Bytes = self
-                    .l2_provider
-                    .client()
-                    .request("debug_getRawHeader", &[self.l2_head])
-                    .await
-                    .map_err(|e| anyhow!("Failed to fetch header RLP: {e}"))?;
-                let header = Header::decode(&mut raw_header.as_ref())
-                    .map_err(|e| anyhow!("Failed to decode header: {e}"))?;
-
-                // Fetch the storage root for the L2 head block.
-                let l2_to_l1_message_passer = self
-                    .l2_provider
-                    .get_proof(L2_TO_L1_MESSAGE_PASSER_ADDRESS, Default::default())
-                    .block_id(BlockId::Hash(self.l2_head.into()))
-                    .await
-                    .map_err(|e| anyhow!("Failed to fetch account proof: {e}"))?;
-
-                let mut raw_output = [0u8; 128];
-                raw_output[31] = OUTPUT_ROOT_VERSION;
-                raw_output[32..64].copy_from_slice(header.state_root.as_ref());
-                raw_output[64..96].copy_from_slice(l2_to_l1_message_passer.storage_hash.as_ref());
-                raw_output[96..128].copy_from_slice(self.l2_head.as_ref());
+            Hint::StartingL2Output(hinted_output_root) => {
+                let raw_output =
+                    fetch_output_root_preimage(&self.l2_provider, self.l2_head).await?;
                 let output_root = keccak256(raw_output);
 
-                if output_root.as_slice() != hint_data.as_ref() {
+                if output_root != hinted_output_root {
                     anyhow::bail!("Output root does not match L2 head.");
                 }
 
@@ -417,16 +443,11 @@ where
                     raw_output.into(),
                 )?;
             }
-            HintType::L2StateNode => {
-                if hint_data.len() != 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
+            Hint::L2StateNode(hash) => {
+                if self.capabilities.l2_state != StateStrategy::DbGet {
+                    return self.fetch_state_by_hash(hash).await;
                 }
 
-                let hash: B256 = hint_data
-                    .as_ref()
-                    .try_into()
-                    .map_err(|e| anyhow!("Failed to convert bytes to B256: {e}"))?;
-
                 // Fetch the preimage from the L2 chain provider.
                 let preimage: Bytes = self
                     .l2_provider
@@ -441,108 +462,296 @@ where
                     preimage.into(),
                 )?;
             }
-            HintType::L2AccountProof => {
-                if hint_data.len() != 8 + 20 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
+            Hint::L2AccountProof { block_number, address } => {
+                self.fetch_proof(block_number, address, Vec::new()).await?;
+            }
+            Hint::L2AccountStorageProof { block_number, address, slot } => {
+                self.fetch_proof(block_number, address, vec![slot]).await?;
+            }
+            Hint::L2PayloadWitness { parent_block_hash, payload_attributes } => {
+                if !self.capabilities.l2_execute_payload {
+                    warn!(
+                        target: "fetcher",
+                        "Ignoring payload witness hint; debug_executePayload is not supported"
+                    );
+                    return Ok(());
                 }
 
-                let block_number = u64::from_be_bytes(
-                    hint_data.as_ref()[..8]
-                        .try_into()
-                        .map_err(|e| anyhow!("Error converting hint data to u64: {e}"))?,
-                );
-                let address = Address::from_slice(&hint_data.as_ref()[8..28]);
-
-                let proof_response = self
+                let execute_payload_response: ExecutionWitness = self
                     .l2_provider
-                    .get_proof(address, Default::default())
-                    .block_id(BlockId::Number(BlockNumberOrTag::Number(block_number)))
+                    .client()
+                    .request::<(B256, OpPayloadAttributes), ExecutionWitness>(
+                        "debug_executePayload",
+                        (parent_block_hash, *payload_attributes),
+                    )
                     .await
-                    .map_err(|e| anyhow!("Failed to fetch account proof: {e}"))?;
+                    .map_err(|e| anyhow!("Failed to fetch preimage: {e}"))?;
+
+                self.store_execution_witness(execute_payload_response).await?;
+            }
+            Hint::GlobalGeneric { namespace, commitment } => {
+                // The host does not know the commitment schemes of global generic preimages, so
+                // they are served from a local directory rather than fetched from a remote source.
+                let dir = self
+                    .generic_preimage_dir
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No global generic preimage directory configured"))?;
+                let path = dir.join(namespace.to_string()).join(hex::encode_prefixed(&commitment));
+                let preimage = tokio::fs::read(&path).await.map_err(|e| {
+                    anyhow!("Failed to read global generic preimage {}: {e}", path.display())
+                })?;
 
                 let mut kv_write_lock = self.kv_store.write().await;
+                kv_write_lock.set(
+                    PreimageKey::new_global_generic(namespace, &commitment).into(),
+                    preimage,
+                )?;
+            }
+        }
 
-                // Write the account proof nodes to the key-value store.
-                proof_response.account_proof.into_iter().try_for_each(|node| {
-                    let node_hash = keccak256(node.as_ref());
-                    let key = PreimageKey::new(*node_hash, PreimageKeyType::Keccak256);
-                    kv_write_lock.set(key.into(), node.into())?;
-                    Ok::<(), anyhow::Error>(())
-                })?;
+        Ok(())
+    }
+
+    /// Fetches the code with the given hash with geth's `debug_dbGet`.
+    async fn db_get_code(&self, hash: B256) -> Result<Bytes> {
+        // geth hashdb scheme code hash key prefix
+        const CODE_PREFIX: u8 = b'c';
+
+        // Attempt to fetch the code from the L2 chain provider.
+        let code_hash = [&[CODE_PREFIX], hash.as_slice()].concat();
+        let code = self
+            .l2_provider
+            .client()
+            .request::<&[Bytes; 1], Bytes>("debug_dbGet", &[code_hash.into()])
+            .await;
+
+        // Check if the first attempt to fetch the code failed. If it did, try fetching the
+        // code hash preimage without the geth hashdb scheme prefix.
+        match code {
+            Ok(code) => Ok(code),
+            Err(_) => self
+                .l2_provider
+                .client()
+                .request::<&[B256; 1], Bytes>("debug_dbGet", &[hash])
+                .await
+                .map_err(|e| anyhow!("Error fetching code hash preimage: {e}")),
+        }
+    }
+
+    /// Fetches the proof of an account, and of the given storage slots of the account, at the given
+    /// L2 block, and stores its trie nodes in the key-value store.
+    async fn fetch_proof(
+        &self,
+        block_number: u64,
+        address: Address,
+        slots: Vec<B256>,
+    ) -> Result<()> {
+        let proof_response = self
+            .l2_provider
+            .get_proof(address, slots)
+            .block_id(BlockId::Number(BlockNumberOrTag::Number(block_number)))
+            .await
+            .map_err(|e| anyhow!("Failed to fetch account proof: {e}"))?;
+        self.store_proof(block_number, address, proof_response).await
+    }
+
+    /// Stores the account and storage proof nodes of an `eth_getProof` response for the given L2
+    /// block in the key-value store, and indexes the account and trie nodes in the [StateIndex].
+    async fn store_proof(
+        &self,
+        block_number: u64,
+        address: Address,
+        proof: EIP1186AccountProofResponse,
+    ) -> Result<()> {
+        {
+            let mut index = self.state_index.lock().unwrap_or_else(PoisonError::into_inner);
+            if proof.code_hash != KECCAK_EMPTY {
+                index.code.insert(proof.code_hash, (address, block_number));
             }
-            HintType::L2AccountStorageProof => {
-                if hint_data.len() != 8 + 20 + 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
+            if self.capabilities.l2_state == StateStrategy::Proofs {
+                index.index_proof(block_number, None, &proof.account_proof);
+                for storage_proof in proof.storage_proof.iter() {
+                    index.index_proof(block_number, Some(address), &storage_proof.proof);
                 }
+            }
+        }
 
-                let block_number = u64::from_be_bytes(
-                    hint_data.as_ref()[..8]
-                        .try_into()
-                        .map_err(|e| anyhow!("Error converting hint data to u64: {e}"))?,
-                );
-                let address = Address::from_slice(&hint_data.as_ref()[8..28]);
-                let slot = B256::from_slice(&hint_data.as_ref()[28..]);
+        let mut kv_write_lock = self.kv_store.write().await;
+        let storage_nodes = proof.storage_proof.into_iter().flat_map(|proof| proof.proof);
+        for node in proof.account_proof.into_iter().chain(storage_nodes) {
+            let key = PreimageKey::new(*keccak256(node.as_ref()), PreimageKeyType::Keccak256);
+            kv_write_lock.set(key.into(), node.into())?;
+        }
+        Ok(())
+    }
 
-                let mut proof_response = self
-                    .l2_provider
-                    .get_proof(address, vec![slot])
-                    .block_id(BlockId::Number(BlockNumberOrTag::Number(block_number)))
-                    .await
-                    .map_err(|e| anyhow!("Failed to fetch account proof: {e}"))?;
+    /// Fetches the code with the given hash with `eth_getCode`, from an account whose proof was
+    /// fetched before. Returns [None] if no such account is known.
+    async fn fetch_indexed_code(&self, hash: B256) -> Result<Option<Bytes>> {
+        let account = self
+            .state_index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .code
+            .get(&hash)
+            .copied();
+        let Some((address, block_number)) = account else {
+            return Ok(None);
+        };
+
+        let code = self
+            .l2_provider
+            .get_code_at(address)
+            .block_id(BlockId::Number(BlockNumberOrTag::Number(block_number)))
+            .await
+            .map_err(|e| anyhow!("Failed to fetch code of {address}: {e}"))?;
+        if keccak256(&code) != hash {
+            anyhow::bail!("Code of {address} at L2 block #{block_number} does not match {hash}");
+        }
+        Ok(Some(code))
+    }
 
-                let mut kv_write_lock = self.kv_store.write().await;
+    /// Fetches the preimage of a trie node or code hash without `debug_dbGet`:
+    ///
+    /// - With the [StateStrategy::ExecutionWitness] strategy, the execution witness of the L2 block
+    ///   being executed is fetched and stored.
+    /// - With the [StateStrategy::Proofs] strategy, a trie node is reconstructed by proving a key
+    ///   under its path, in a trie that it was referenced from by an earlier proof.
+    ///
+    /// Preimages that the strategy cannot fetch fail with an [UnservableHint], which is not
+    /// retried.
+    async fn fetch_state_by_hash(&self, hash: B256) -> Result<()> {
+        match self.capabilities.l2_state {
+            StateStrategy::ExecutionWitness => self.fetch_witness_state(hash).await,
+            StateStrategy::Proofs => self.reconstruct_trie_node(hash).await,
+            StateStrategy::DbGet => Err(UnservableHint(format!(
+                "Cannot fetch preimage {hash} by hash with the DbGet state strategy"
+            ))
+            .into()),
+        }
+    }
 
-                // Write the account proof nodes to the key-value store.
-                proof_response.account_proof.into_iter().try_for_each(|node| {
-                    let node_hash = keccak256(node.as_ref());
-                    let key = PreimageKey::new(*node_hash, PreimageKeyType::Keccak256);
-                    kv_write_lock.set(key.into(), node.into())?;
-                    Ok::<(), anyhow::Error>(())
-                })?;
+    /// Fetches and stores the execution witness of the L2 block being executed, which must hold
+    /// the preimage of `hash`.
+    async fn fetch_witness_state(&self, hash: B256) -> Result<()> {
+        let block_number = self.executing_block_number().await?;
+        let fetched = self
+            .state_index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .witness_blocks
+            .contains(&block_number);
+        if !fetched {
+            let witness: ExecutionWitness = self
+                .l2_provider
+                .client()
+                .request("debug_executionWitness", [BlockNumberOrTag::Number(block_number)])
+                .await
+                .map_err(|e| anyhow!("Failed to fetch execution witness: {e}"))?;
+            self.store_execution_witness(witness).await?;
+            self.state_index
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .witness_blocks
+                .insert(block_number);
+        }
 
-                // Write the storage proof nodes to the key-value store.
-                let storage_proof = proof_response.storage_proof.remove(0);
-                storage_proof.proof.into_iter().try_for_each(|node| {
-                    let node_hash = keccak256(node.as_ref());
-                    let key = PreimageKey::new(*node_hash, PreimageKeyType::Keccak256);
-                    kv_write_lock.set(key.into(), node.into())?;
-                    Ok::<(), anyhow::Error>(())
-                })?;
-            }
-            HintType::L2PayloadWitness => {
-                if hint_data.len() < 32 {
-                    anyhow::bail!("Invalid hint data length: {}", hint_data.len());
-                }
-                let parent_block_hash = B256::from_slice(&hint_data.as_ref()[..32]);
-                let payload_attributes: OpPayloadAttributes =
-                    serde_json::from_slice(&hint_data[32..])?;
+        let key = PreimageKey::new(*hash, PreimageKeyType::Keccak256).into();
+        if self.kv_store.read().await.get(key).is_none() {
+            return Err(UnservableHint(format!(
+                "Preimage {hash} is not in the execution witness of L2 block #{block_number}"
+            ))
+            .into());
+        }
+        Ok(())
+    }
 
-                let execute_payload_response: ExecutionWitness = self
-                    .l2_provider
-                    .client()
-                    .request::<(B256, OpPayloadAttributes), ExecutionWitness>(
-                        "debug_executePayload",
-                        (parent_block_hash, payload_attributes),
-                    )
-                    .await
-                    .map_err(|e| anyhow!("Failed to fetch preimage: {e}"))?;
+    /// Reconstructs the trie node with the given hash from `eth_getProof`, by proving a key whose
+    /// path passes through the node, at the L2 block of the proof that the node was referenced
+    /// from.
+    async fn reconstruct_trie_node(&self, hash: B256) -> Result<()> {
+        let origin = {
+            let index = self.state_index.lock().unwrap_or_else(PoisonError::into_inner);
+            index.nodes.get(&hash).and_then(|(root, path)| {
+                index.roots.get(root).map(|(block, account)| (*block, *account, path.clone()))
+            })
+        };
+        let Some((block_number, account, path)) = origin else {
+            return Err(UnservableHint(format!(
+                "Cannot reconstruct preimage {hash}; it is not referenced by any proven trie node"
+            ))
+            .into());
+        };
+        if path.len() > MAX_RECONSTRUCTED_PATH {
+            return Err(UnservableHint(format!(
+                "Cannot reconstruct trie node {hash}; its path of {} nibbles is longer than {} \
+                 nibbles",
+                path.len(),
+                MAX_RECONSTRUCTED_PATH
+            ))
+            .into());
+        }
 
-                let mut merged = HashMap::<B256, Bytes>::default();
-                merged.extend(execute_payload_response.state);
-                merged.extend(execute_payload_response.codes);
-                merged.extend(execute_payload_response.keys);
+        // Storage slots and account addresses are hashed into their paths.
+        let (address, slots) = match account {
+            Some(address) => {
+                let slot = tokio::task::spawn_blocking(move || grind_key(&path, 32)).await?;
+                (address, vec![B256::from_slice(&slot)])
+            }
+            None => {
+                let address = tokio::task::spawn_blocking(move || grind_key(&path, 20)).await?;
+                (Address::from_slice(&address), Vec::new())
+            }
+        };
+        self.fetch_proof(block_number, address, slots).await?;
 
-                let mut kv_write_lock = self.kv_store.write().await;
-                for (hash, preimage) in merged.into_iter() {
-                    let computed_hash = keccak256(preimage.as_ref());
-                    assert_eq!(computed_hash, hash, "Preimage hash does not match expected hash");
+        let key = PreimageKey::new(*hash, PreimageKeyType::Keccak256).into();
+        if self.kv_store.read().await.get(key).is_none() {
+            anyhow::bail!("Trie node {hash} is not in the proof of {address} at #{block_number}");
+        }
+        Ok(())
+    }
 
-                    let key = PreimageKey::new(*hash, PreimageKeyType::Keccak256);
-                    kv_write_lock.set(key.into(), preimage.into())?;
-                }
+    /// Returns the number of the L2 block that the client program is executing: the block after
+    /// the parent block of the most recent proof hint, or after the agreed L2 head if the client
+    /// program has not hinted a proof yet.
+    async fn executing_block_number(&self) -> Result<u64> {
+        let parent =
+            self.state_index.lock().unwrap_or_else(PoisonError::into_inner).parent_block_number;
+        let parent = match parent {
+            Some(number) => number,
+            None => {
+                self.l2_provider
+                    .get_block_by_hash(self.l2_head, BlockTransactionsKind::Hashes)
+                    .await
+                    .map_err(|e| anyhow!("Failed to fetch L2 head: {e}"))?
+                    .ok_or(anyhow!("L2 head not found."))?
+                    .header
+                    .number
             }
+        };
+        Ok(parent + 1)
+    }
+
+    /// Stores the trie nodes, codes and keys of an [ExecutionWitness] in the key-value store. Fails
+    /// without storing anything if any preimage does not match its hash.
+    async fn store_execution_witness(&self, witness: ExecutionWitness) -> Result<()> {
+        let mut merged = HashMap::<B256, Bytes>::default();
+        merged.extend(witness.state);
+        merged.extend(witness.codes);
+        merged.extend(witness.keys);
+
+        if let Some((hash, _)) =
+            merged.iter().find(|(hash, preimage)| keccak256(preimage) != **hash)
+        {
+            anyhow::bail!("Preimage of {hash} in the execution witness does not match its hash");
         }
 
+        let mut kv_write_lock = self.kv_store.write().await;
+        for (hash, preimage) in merged.into_iter() {
+            let key = PreimageKey::new(*hash, PreimageKeyType::Keccak256);
+            kv_write_lock.set(key.into(), preimage.into())?;
+        }
         Ok(())
     }
 
@@ -594,3 +803,537 @@ where
         Ok(())
     }
 }
+
+/// Fetches the preimage of the output root of the L2 block with the given hash from the L2 chain
+/// provider, which is `version ++ state_root ++ message_passer_storage_root ++ block_hash`.
+pub(crate) async fn fetch_output_root_preimage(
+    l2_provider: &HostProvider,
+    block_hash: B256,
+) -> Result<[u8; 128]> {
+    const OUTPUT_ROOT_VERSION: u8 = 0;
+    const L2_TO_L1_MESSAGE_PASSER_ADDRESS: Address =
+        address!("4200000000000000000000000000000000000016");
+
+    // Fetch the header for the L2 block.
+    let raw_header: Bytes = l2_provider
+        .client()
+        .request("debug_getRawHeader", &[block_hash])
+        .await
+        .map_err(|e| anyhow!("Failed to fetch header RLP: {e}"))?;
+    let header = Header::decode(&mut raw_header.as_ref())
+        .map_err(|e| anyhow!("Failed to decode header: {e}"))?;
+
+    // Fetch the storage root of the message passer for the L2 block.
+    let l2_to_l1_message_passer = l2_provider
+        .get_proof(L2_TO_L1_MESSAGE_PASSER_ADDRESS, Default::default())
+        .block_id(BlockId::Hash(block_hash.into()))
+        .await
+        .map_err(|e| anyhow!("Failed to fetch account proof: {e}"))?;
+
+    let mut raw_output = [0u8; 128];
+    raw_output[31] = OUTPUT_ROOT_VERSION;
+    raw_output[32..64].copy_from_slice(header.state_root.as_ref());
+    raw_output[64..96].copy_from_slice(l2_to_l1_message_passer.storage_hash.as_ref());
+    raw_output[96..128].copy_from_slice(block_hash.as_ref());
+    Ok(raw_output)
+}
+
+/// The state learned by a [Fetcher] from previous hints, used to fetch code and trie nodes without
+/// `debug_dbGet`.
+#[derive(Debug, Default)]
+struct StateIndex {
+    /// The account and L2 block number to fetch each code hash from, by code hash.
+    code: HashMap<B256, (Address, u64)>,
+    /// The number of the parent of the L2 block being executed, from the most recent proof hint.
+    parent_block_number: Option<u64>,
+    /// The L2 blocks whose execution witnesses have been stored.
+    witness_blocks: HashSet<u64>,
+    /// The root of the trie and the path within it of each trie node that a proven trie node
+    /// references, by hash. Only kept with the [StateStrategy::Proofs] strategy.
+    nodes: HashMap<B256, (B256, Nibbles)>,
+    /// The L2 block number of each proven trie root, and the account that it is the storage trie
+    /// of, or [None] for the account trie, by root hash.
+    roots: HashMap<B256, (u64, Option<Address>)>,
+}
+
+impl StateIndex {
+    /// Indexes the nodes of an account proof, or of a storage proof of `account`, at the given L2
+    /// block. The nodes are in order from the root of the trie.
+    fn index_proof(&mut self, block_number: u64, account: Option<Address>, proof: &[Bytes]) {
+        let Some(root) = proof.first().map(keccak256) else {
+            return;
+        };
+        self.roots.insert(root, (block_number, account));
+        self.nodes.insert(root, (root, Nibbles::default()));
+
+        for node in proof {
+            let Some((root, path)) = self.nodes.get(&keccak256(node)).cloned() else {
+                continue;
+            };
+            if let Ok(node) = TrieNode::decode(&mut node.as_ref()) {
+                self.index_children(root, &path, &node);
+            }
+        }
+    }
+
+    /// Indexes the blinded children of a trie node at `path` in the trie with the given root.
+    fn index_children(&mut self, root: B256, path: &Nibbles, node: &TrieNode) {
+        let mut index_child = |path: Nibbles, child: &TrieNode| match child {
+            TrieNode::Blinded { commitment } => {
+                self.nodes.entry(*commitment).or_insert((root, path));
+            }
+            child => self.index_children(root, &path, child),
+        };
+        match node {
+            TrieNode::Branch { stack } => {
+                for (nibble, child) in stack.iter().take(16).enumerate() {
+                    let mut child_path = path.clone();
+                    child_path.push(nibble as u8);
+                    index_child(child_path, child);
+                }
+            }
+            TrieNode::Extension { prefix, node } => index_child(path.join(prefix), node),
+            TrieNode::Empty | TrieNode::Leaf { .. } | TrieNode::Blinded { .. } => {}
+        }
+    }
+}
+
+/// Returns a key of `len` bytes whose hash begins with the given path, searching on all available
+/// threads.
+fn grind_key(path: &Nibbles, len: usize) -> Vec<u8> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let found = AtomicBool::new(false);
+    let matches = |hash: B256| {
+        path.iter().enumerate().all(|(i, nibble)| {
+            let byte = hash[i / 2];
+            (if i % 2 == 0 { byte >> 4 } else { byte & 0x0f }) == *nibble
+        })
+    };
+
+    thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|start| {
+                let (found, matches) = (&found, &matches);
+                scope.spawn(move || {
+                    let mut key = vec![0u8; len];
+                    for counter in (start as u64..).step_by(threads) {
+                        if found.load(Ordering::Relaxed) {
+                            return None;
+                        }
+                        key[len - 8..].copy_from_slice(&counter.to_be_bytes());
+                        if matches(keccak256(&key)) {
+                            found.store(true, Ordering::Relaxed);
+                            return Some(key);
+                        }
+                    }
+                    None
+                })
+            })
+            .collect::<Vec<_>>();
+        workers.into_iter().filter_map(|worker| worker.join().ok().flatten()).next()
+    })
+    .expect("a key exists under every path")
+}
+
+/// An error for a hint that cannot be fetched with the capabilities of the providers. Unlike other
+/// errors, it is not retried.
+#[derive(Debug)]
+struct UnservableHint(String);
+
+impl fmt::Display for UnservableHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnservableHint {}
+
+/// Encodes a receipt returned by `eth_getBlockReceipts` in its EIP-2718 encoding, as returned by
+/// `debug_getRawReceipts`.
+fn encode_receipt(receipt: TransactionReceipt) -> Result<Bytes> {
+    let convert = |receipt: ReceiptWithBloom<Receipt<alloy_rpc_types::Log>>| ReceiptWithBloom {
+        receipt: Receipt {
+            status: receipt.receipt.status,
+            cumulative_gas_used: receipt.receipt.cumulative_gas_used,
+            logs: receipt.receipt.logs.into_iter().map(|log| log.inner).collect(),
+        },
+        logs_bloom: receipt.logs_bloom,
+    };
+    let envelope = match receipt.inner {
+        ReceiptEnvelope::Legacy(receipt) => ReceiptEnvelope::Legacy(convert(receipt)),
+        ReceiptEnvelope::Eip2930(receipt) => ReceiptEnvelope::Eip2930(convert(receipt)),
+        ReceiptEnvelope::Eip1559(receipt) => ReceiptEnvelope::Eip1559(convert(receipt)),
+        ReceiptEnvelope::Eip4844(receipt) => ReceiptEnvelope::Eip4844(convert(receipt)),
+        ReceiptEnvelope::Eip7702(receipt) => ReceiptEnvelope::Eip7702(convert(receipt)),
+        receipt => anyhow::bail!("Unsupported receipt type {}", receipt.tx_type()),
+    };
+    Ok(envelope.encoded_2718().into())
+}
+
+/// The set of hints that are being prefetched by a group of [Fetcher]s that share a key-value
+/// store. Only one of the fetchers prefetches a given hint at a time, and the others wait for it
+/// to finish rather than sending the same requests to the remote source.
+#[derive(Debug, Default)]
+pub struct InFlightHints {
+    hints: StdMutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl InFlightHints {
+    /// Waits until no other fetcher is prefetching `hint`, and marks it as in-flight until the
+    /// returned guard is dropped.
+    async fn acquire(&self, hint: &str) -> InFlightGuard<'_> {
+        let lock = Arc::clone(
+            self.hints
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(hint.to_string())
+                .or_default(),
+        );
+        let guard = Arc::clone(&lock).lock_owned().await;
+        InFlightGuard { hints: self, hint: hint.to_string(), lock, guard: Some(guard) }
+    }
+
+    /// Returns the number of hints that are in-flight or being waited on.
+    pub fn len(&self) -> usize {
+        self.hints.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Returns `true` if no hints are in-flight.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Marks a hint as in-flight in an [InFlightHints] set until it is dropped.
+#[derive(Debug)]
+struct InFlightGuard<'a> {
+    hints: &'a InFlightHints,
+    hint: String,
+    lock: Arc<Mutex<()>>,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+
+        // Forget the hint once no other fetcher is waiting on it. New waiters can only clone the
+        // lock while holding the map, so the count cannot change underneath us.
+        let mut hints = self.hints.hints.lock().unwrap_or_else(PoisonError::into_inner);
+        if Arc::strong_count(&self.lock) == 2 {
+            hints.remove(&self.hint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        blobs::{APIConfigResponse, APIGenesisResponse},
+        cli::HostCli,
+        kv::MemoryKeyValueStore,
+        test_utils::{mock_block, mock_header, MockRpc},
+    };
+    use alloy_rlp::Encodable;
+    use serde_json::{json, Value};
+    use std::{sync::atomic::AtomicUsize, time::Duration};
+
+    const ACCOUNT: Address = address!("00000000000000000000000000000000000000aa");
+
+    /// Starts a [MockRpc] that serves the genesis and spec of a beacon node, and every other
+    /// request with the given handler.
+    async fn mock_node(
+        handler: impl Fn(&str, &Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> MockRpc {
+        MockRpc::serve(move |method, params| match method {
+            "/eth/v1/beacon/genesis" => {
+                Ok(serde_json::to_value(APIGenesisResponse::new(0)).unwrap())
+            }
+            "/eth/v1/config/spec" => Ok(serde_json::to_value(APIConfigResponse::new(12)).unwrap()),
+            _ => handler(method, params),
+        })
+        .await
+    }
+
+    /// Creates a [Fetcher] over an in-memory key-value store whose providers are all served by
+    /// `node`, with the agreed L2 head at block 10 of the mock chain.
+    async fn mock_fetcher(node: &MockRpc, l2_state: StateStrategy) -> Fetcher<MemoryKeyValueStore> {
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        let blob_provider = OnlineBlobProvider::new_http(node.url.clone()).await.unwrap();
+        Fetcher::new(
+            Arc::new(RwLock::new(MemoryKeyValueStore::new())),
+            provider.clone(),
+            blob_provider,
+            provider,
+            mock_header(10).1,
+        )
+        .with_capabilities(RpcCapabilities { l2_state, ..Default::default() })
+    }
+
+    /// Returns the preimage of `hash` in the key-value store of the [Fetcher], if any.
+    async fn stored(fetcher: &Fetcher<MemoryKeyValueStore>, hash: B256) -> Option<Vec<u8>> {
+        fetcher
+            .kv_store
+            .read()
+            .await
+            .get(PreimageKey::new(*hash, PreimageKeyType::Keccak256).into())
+    }
+
+    /// Returns the RLP encoding of a [TrieNode].
+    fn encode_node(node: &TrieNode) -> Bytes {
+        let mut buf = Vec::new();
+        node.encode(&mut buf);
+        buf.into()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_indexed_code() {
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let node = mock_node({
+            let code = code.clone();
+            move |method, params| match method {
+                "eth_getCode" if params[1] == json!("0x7") => Ok(json!(code)),
+                _ => Err(format!("unsupported method {method}")),
+            }
+        })
+        .await;
+        let fetcher = mock_fetcher(&node, StateStrategy::ExecutionWitness).await;
+
+        // Code is fetched from an account proven at some block with the same code hash.
+        let code_hash = keccak256(&code);
+        assert!(fetcher.fetch_indexed_code(code_hash).await.unwrap().is_none());
+        let proof = EIP1186AccountProofResponse { code_hash, ..Default::default() };
+        fetcher.store_proof(7, ACCOUNT, proof).await.unwrap();
+        assert_eq!(fetcher.fetch_indexed_code(code_hash).await.unwrap(), Some(code));
+
+        // Code that does not match the hash is rejected.
+        let code_hash = B256::repeat_byte(1);
+        let proof = EIP1186AccountProofResponse { code_hash, ..Default::default() };
+        fetcher.store_proof(7, ACCOUNT, proof).await.unwrap();
+        assert!(fetcher.fetch_indexed_code(code_hash).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_executing_block_number() {
+        let node = mock_node(|method, params| match method {
+            "eth_getBlockByHash" if params[0] == json!(mock_header(10).1) => Ok(mock_block(10)),
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await;
+        let mut fetcher = mock_fetcher(&node, StateStrategy::ExecutionWitness).await;
+
+        // Before any proof hint, the client program executes the block after the agreed L2 head.
+        assert_eq!(fetcher.executing_block_number().await.unwrap(), 11);
+
+        // Proof hints move to the block after their parent block, even if their preimages are
+        // never fetched.
+        fetcher.hint(&Hint::L2AccountProof { block_number: 20, address: ACCOUNT }.encode());
+        assert_eq!(fetcher.executing_block_number().await.unwrap(), 21);
+        fetcher.hint(&Hint::L2BlockHeader(B256::ZERO).encode());
+        assert_eq!(fetcher.executing_block_number().await.unwrap(), 21);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_execution_witness() {
+        let node = mock_node(|method, _| Err(format!("unsupported method {method}"))).await;
+        let fetcher = mock_fetcher(&node, StateStrategy::ExecutionWitness).await;
+
+        let trie_node = Bytes::from_static(b"trie node");
+        let mut witness = ExecutionWitness::default();
+        witness.state.insert(keccak256(&trie_node), trie_node.clone());
+        witness.codes.insert(B256::repeat_byte(1), Bytes::from_static(b"not the preimage"));
+
+        // Nothing is stored if any preimage does not match its hash.
+        assert!(fetcher.store_execution_witness(witness.clone()).await.is_err());
+        assert!(stored(&fetcher, keccak256(&trie_node)).await.is_none());
+
+        witness.codes.clear();
+        fetcher.store_execution_witness(witness).await.unwrap();
+        assert_eq!(stored(&fetcher, keccak256(&trie_node)).await.unwrap(), trie_node);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_state_by_hash_witness() {
+        let trie_node = Bytes::from_static(b"trie node");
+        let hash = keccak256(&trie_node);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let node = mock_node({
+            let (trie_node, requests) = (trie_node.clone(), Arc::clone(&requests));
+            move |method, params| match method {
+                "debug_executionWitness" if params[0] == json!("0x15") => {
+                    // The first request fails.
+                    if requests.fetch_add(1, Ordering::Relaxed) == 0 {
+                        return Err("unavailable".to_string());
+                    }
+                    let mut witness = ExecutionWitness::default();
+                    witness.state.insert(keccak256(&trie_node), trie_node.clone());
+                    Ok(serde_json::to_value(witness).unwrap())
+                }
+                _ => Err(format!("unsupported method {method}")),
+            }
+        })
+        .await;
+        let mut fetcher = mock_fetcher(&node, StateStrategy::ExecutionWitness).await;
+        fetcher.hint(&Hint::L2AccountProof { block_number: 20, address: ACCOUNT }.encode());
+
+        // A failed fetch of the witness is fetched again.
+        assert!(fetcher.fetch_state_by_hash(hash).await.is_err());
+        fetcher.fetch_state_by_hash(hash).await.unwrap();
+        assert_eq!(stored(&fetcher, hash).await.unwrap(), trie_node);
+
+        // A stored witness is not fetched again, and preimages that are not in it are not retried.
+        let err = fetcher.fetch_state_by_hash(B256::repeat_byte(1)).await.unwrap_err();
+        assert!(err.is::<UnservableHint>());
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+
+        let fetcher = mock_fetcher(&node, StateStrategy::DbGet).await;
+        let err = fetcher.fetch_state_by_hash(hash).await.unwrap_err();
+        assert!(err.is::<UnservableHint>());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_state_by_hash_proofs() {
+        // An account trie whose root branches into two leaves, at nibbles 1 and 2.
+        let leaf = |nibble: u8| {
+            encode_node(&TrieNode::Leaf {
+                prefix: Nibbles::from_nibbles_unchecked([nibble; 63]),
+                value: vec![nibble; 40].into(),
+            })
+        };
+        let leaves = [leaf(1), leaf(2)];
+        let mut stack = vec![TrieNode::Empty; 17];
+        stack[1] = TrieNode::new_blinded(keccak256(&leaves[0]));
+        stack[2] = TrieNode::new_blinded(keccak256(&leaves[1]));
+        let root = encode_node(&TrieNode::Branch { stack });
+
+        let node = mock_node({
+            let (root, leaves) = (root.clone(), leaves.clone());
+            move |method, params| match method {
+                "eth_getProof" if params[2] == json!("0x7") => {
+                    let address: Address = serde_json::from_value(params[0].clone()).unwrap();
+                    let mut account_proof = vec![root.clone()];
+                    let nibble = keccak256(address)[0] >> 4;
+                    if let 1 | 2 = nibble {
+                        account_proof.push(leaves[nibble as usize - 1].clone());
+                    }
+                    let proof = EIP1186AccountProofResponse {
+                        address,
+                        account_proof,
+                        ..Default::default()
+                    };
+                    Ok(serde_json::to_value(proof).unwrap())
+                }
+                _ => Err(format!("unsupported method {method}")),
+            }
+        })
+        .await;
+        let fetcher = mock_fetcher(&node, StateStrategy::Proofs).await;
+
+        // The client program proves an account under the first leaf, and then needs the second.
+        let proof = EIP1186AccountProofResponse {
+            account_proof: vec![root, leaves[0].clone()],
+            ..Default::default()
+        };
+        fetcher.store_proof(7, ACCOUNT, proof).await.unwrap();
+        let hash = keccak256(&leaves[1]);
+        assert!(stored(&fetcher, hash).await.is_none());
+        fetcher.fetch_state_by_hash(hash).await.unwrap();
+        assert_eq!(stored(&fetcher, hash).await.unwrap(), leaves[1]);
+
+        // Nodes that no proven node references cannot be reconstructed.
+        let err = fetcher.fetch_state_by_hash(B256::repeat_byte(1)).await.unwrap_err();
+        assert!(err.is::<UnservableHint>());
+    }
+
+    #[test]
+    fn test_grind_key() {
+        let path = Nibbles::from_nibbles_unchecked([0xa, 0xb, 0xc]);
+        let key = grind_key(&path, 20);
+        assert_eq!(key.len(), 20);
+        assert_eq!(Nibbles::unpack(keccak256(&key))[..3], path[..]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_in_flight_hints() {
+        let in_flight = Arc::new(InFlightHints::default());
+        let fetches = Arc::new(Mutex::new(Vec::new()));
+
+        // Three fetchers receive the same hint at once. Each only fetches if the hint has not
+        // been fetched by the time it is its turn.
+        let tasks = (0..3)
+            .map(|i| {
+                let (in_flight, fetches) = (Arc::clone(&in_flight), Arc::clone(&fetches));
+                tokio::spawn(async move {
+                    let _guard = in_flight.acquire("l1-block-header 0x01").await;
+                    let mut fetches = fetches.lock().await;
+                    if fetches.is_empty() {
+                        fetches.push(i);
+                        drop(fetches);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(fetches.lock().await.len(), 1);
+        assert!(in_flight.is_empty());
+
+        // Distinct hints do not wait on each other.
+        let a = in_flight.acquire("a").await;
+        let b = in_flight.acquire("b").await;
+        assert_eq!(in_flight.len(), 2);
+        drop((a, b));
+        assert!(in_flight.is_empty());
+    }
+
+    #[test]
+    fn test_encode_receipt() {
+        let log = alloy_primitives::Log::new_unchecked(
+            address!("00000000000000000000000000000000000000aa"),
+            vec![B256::repeat_byte(1)],
+            Bytes::from_static(&[0xbe, 0xef]),
+        );
+        let expected = ReceiptEnvelope::Eip1559(ReceiptWithBloom {
+            receipt: Receipt {
+                status: true.into(),
+                cumulative_gas_used: 21_000,
+                logs: vec![log.clone()],
+            },
+            logs_bloom: Default::default(),
+        });
+
+        // The receipt as returned by `eth_getBlockReceipts`, with the RPC fields of its logs.
+        let rpc_receipt = serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logsBloom": alloy_primitives::Bloom::default(),
+            "logs": [{
+                "address": log.address,
+                "topics": log.topics(),
+                "data": log.data.data,
+                "blockHash": B256::repeat_byte(2),
+                "blockNumber": "0x1",
+                "transactionHash": B256::repeat_byte(3),
+                "transactionIndex": "0x0",
+                "logIndex": "0x0",
+                "removed": false
+            }],
+            "transactionHash": B256::repeat_byte(3),
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(2),
+            "blockNumber": "0x1",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "from": Address::ZERO,
+            "to": Address::ZERO,
+            "contractAddress": null
+        });
+        let receipt: TransactionReceipt = serde_json::from_value(rpc_receipt).unwrap();
+        assert_eq!(encode_receipt(receipt).unwrap(), Bytes::from(expected.encoded_2718()));
+    }
+}
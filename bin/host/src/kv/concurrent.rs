@@ -0,0 +1,61 @@
+//! Contains a concrete implementation of the [KeyValueStore] trait that can be shared between
+//! several owners.
+
+use super::KeyValueStore;
+use alloy_primitives::B256;
+use anyhow::Result;
+use std::{
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+/// A [KeyValueStore] handle that can be cloned and shared between several owners, such as the
+/// key-value stores of concurrent client sessions. Clones read from and write to the same
+/// underlying store.
+pub struct ConcurrentKeyValueStore<KV>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    store: Arc<RwLock<KV>>,
+}
+
+impl<KV> ConcurrentKeyValueStore<KV>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    /// Create a new [ConcurrentKeyValueStore] around the given store.
+    pub const fn new(store: Arc<RwLock<KV>>) -> Self {
+        Self { store }
+    }
+}
+
+impl<KV> fmt::Debug for ConcurrentKeyValueStore<KV>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentKeyValueStore").finish_non_exhaustive()
+    }
+}
+
+impl<KV> Clone for ConcurrentKeyValueStore<KV>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self { store: Arc::clone(&self.store) }
+    }
+}
+
+impl<KV> KeyValueStore for ConcurrentKeyValueStore<KV>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    fn get(&self, key: B256) -> Option<Vec<u8>> {
+        self.store.read().unwrap_or_else(PoisonError::into_inner).get(key)
+    }
+
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+        self.store.write().unwrap_or_else(PoisonError::into_inner).set(key, value)
+    }
+}
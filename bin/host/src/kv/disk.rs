@@ -4,7 +4,7 @@
 use super::{KeyValueStore, MemoryKeyValueStore};
 use alloy_primitives::B256;
 use anyhow::{anyhow, Result};
-use rocksdb::{Options, DB};
+use rocksdb::{BlockBasedOptions, Cache, Options, DB};
 use std::path::PathBuf;
 
 /// A simple, synchronous key-value store that stores data on disk.
@@ -17,7 +17,20 @@ pub struct DiskKeyValueStore {
 impl DiskKeyValueStore {
     /// Create a new [DiskKeyValueStore] with the given data directory.
     pub fn new(data_directory: PathBuf) -> Self {
-        let db = DB::open(&Self::get_db_options(), data_directory.as_path())
+        Self::with_cache_size(data_directory, None)
+    }
+
+    /// Create a new [DiskKeyValueStore] with the given data directory, whose database has a block
+    /// cache of `cache_size` bytes if given, rather than the default block cache.
+    pub fn with_cache_size(data_directory: PathBuf, cache_size: Option<usize>) -> Self {
+        let mut options = Self::get_db_options();
+        if let Some(cache_size) = cache_size {
+            let mut table_options = BlockBasedOptions::default();
+            table_options.set_block_cache(&Cache::new_lru_cache(cache_size));
+            options.set_block_based_table_factory(&table_options);
+        }
+
+        let db = DB::open(&options, data_directory.as_path())
             .unwrap_or_else(|e| panic!("Failed to open database at {data_directory:?}: {e}"));
 
         Self { data_directory, db }
@@ -17,6 +17,9 @@ pub use split::SplitKeyValueStore;
 mod local;
 pub use local::LocalKeyValueStore;
 
+mod concurrent;
+pub use concurrent::ConcurrentKeyValueStore;
+
 /// A type alias for a shared key-value store.
 pub type SharedKeyValueStore = Arc<RwLock<dyn KeyValueStore + Send + Sync>>;
 
@@ -2,25 +2,53 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod bisect;
+pub use bisect::start_server_and_bisect;
+
 pub mod blobs;
 pub mod cli;
-pub use cli::{init_tracing_subscriber, HostCli};
+pub use cli::{init_tracing_subscriber, HostCli, HostCommand};
+
+pub mod discovery;
+pub use discovery::discover_claim;
+
+pub mod dispute_game;
+pub use dispute_game::{discover_game_claim, GameClaim};
 
 pub mod fetcher;
 pub mod kv;
+pub mod preflight;
+pub use preflight::{preflight, RpcCapabilities, StateStrategy};
+
 pub mod preimage;
+
+pub mod report;
+pub use report::start_server_and_reported_native_client;
+
 pub mod server;
+pub mod session;
+pub mod transport;
+pub mod validate;
+
+#[cfg(test)]
+mod test_utils;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use fetcher::Fetcher;
+use kona_client::{DerivedBlock, FaultProofProgramError, ProgramObserver};
+use kona_fpvm_emulator::{pipe, Emulator, EmulatorReport};
 use kona_preimage::{
-    BidirectionalChannel, HintReader, HintWriter, NativeChannel, OracleReader, OracleServer,
+    BidirectionalChannel, Channel, HintReader, HintWriter, OracleReader, OracleServer,
+    UnixChannelListener,
 };
+use kona_proof::errors::OracleProviderError;
 use kona_std_fpvm::{FileChannel, FileDescriptor};
-use kv::KeyValueStore;
+use kv::{KeyValueStore, SharedKeyValueStore};
+use report::{Phase, RpcRequests, SharedRunStats};
 use server::PreimageServer;
-use std::sync::Arc;
-use tokio::{sync::RwLock, task};
+use session::SessionServer;
+use std::{io::Write, sync::Arc, time::Duration};
+use tokio::{runtime::Handle, sync::RwLock, task};
 use tracing::info;
 
 /// Starts the [PreimageServer] in the primary thread. In this mode, the host program has been
@@ -33,18 +61,7 @@ pub async fn start_server(cfg: HostCli) -> Result<()> {
     let oracle_server = OracleServer::new(preimage_chan);
     let hint_reader = HintReader::new(hint_chan);
     let kv_store = cfg.construct_kv_store();
-    let fetcher = if !cfg.is_offline() {
-        let (l1_provider, blob_provider, l2_provider) = cfg.create_providers().await?;
-        Some(Arc::new(RwLock::new(Fetcher::new(
-            kv_store.clone(),
-            l1_provider,
-            blob_provider,
-            l2_provider,
-            cfg.agreed_l2_head_hash,
-        ))))
-    } else {
-        None
-    };
+    let fetcher = create_fetcher(&cfg, kv_store.clone()).await?;
 
     // Start the server and wait for it to complete.
     info!("Starting preimage server.");
@@ -54,6 +71,16 @@ pub async fn start_server(cfg: HostCli) -> Result<()> {
     Ok(())
 }
 
+/// Starts the [SessionServer] in the primary thread. In this mode, the host serves the client
+/// sessions that connect to the Unix domain socket at `cfg.listen`, until it is interrupted.
+pub async fn start_session_server(cfg: HostCli) -> Result<()> {
+    let path = cfg.listen.as_ref().ok_or_else(|| anyhow!("No socket to listen on"))?;
+    let listener = UnixChannelListener::bind(path)?;
+    info!("Listening for client sessions on {}", path.display());
+
+    SessionServer::new(cfg, listener).await?.start().await
+}
+
 /// Starts the [PreimageServer] and the client program in separate threads. The client program is
 /// ran natively in this mode.
 ///
@@ -61,62 +88,322 @@ pub async fn start_server(cfg: HostCli) -> Result<()> {
 /// - `cfg`: The host configuration.
 ///
 /// ## Returns
-/// - `Ok(exit_code)` if the client program exits successfully.
-/// - `Err(_)` if the client program failed to execute, was killed by a signal, or the host program
-///   exited first.
+/// - `Ok(0)` if the client program validated the claim.
+/// - `Ok(1)` if the client program found the claim to be invalid.
+/// - `Err(_)` if the client program failed before it could check the claim, was killed by a signal,
+///   or the host program exited first.
 pub async fn start_server_and_native_client(cfg: HostCli) -> Result<i32> {
+    start_server_and_observed_native_client(cfg, |_: DerivedBlock<'_>| {}).await
+}
+
+/// Starts the [PreimageServer] and the client program in separate threads, as in
+/// [start_server_and_native_client], notifying `observer` of the boot information that the client
+/// program loads, and as it derives and executes each L2 block.
+pub async fn start_server_and_observed_native_client(
+    cfg: HostCli,
+    observer: impl ProgramObserver + Send + 'static,
+) -> Result<i32> {
+    let kv_store = cfg.construct_kv_store();
+    let client_result = run_native_client(cfg, kv_store, observer, None).await?;
+    client_exit_status(client_result)
+}
+
+/// Returns the exit status of the client program for its `result`. Only an invalid claim fails
+/// the claim; any other error means that the claim could not be checked.
+///
+/// ## Returns
+/// - `Ok(0)` if the claim is valid.
+/// - `Ok(1)` if the claim is invalid.
+/// - `Err(_)` if the client program failed before it could check the claim.
+pub(crate) fn client_exit_status(result: Result<(), FaultProofProgramError>) -> Result<i32> {
+    match result {
+        Ok(()) => Ok(0),
+        Err(FaultProofProgramError::InvalidClaim(..)) => Ok(1),
+        Err(e) => Err(anyhow!("Client program failed: {e}")),
+    }
+}
+
+/// Runs the [PreimageServer] over `kv_store` and the client program natively, as in
+/// [start_server_and_observed_native_client], recording the statistics of the run into `stats` if
+/// it is set.
+///
+/// ## Returns
+/// - `Ok(result)` with the result of the client program, if it exits.
+/// - `Err(_)` if the client program was killed by a signal, or the host program exited first.
+pub(crate) async fn run_native_client(
+    cfg: HostCli,
+    kv_store: SharedKeyValueStore,
+    observer: impl ProgramObserver + Send + 'static,
+    stats: Option<SharedRunStats>,
+) -> Result<Result<(), FaultProofProgramError>> {
     let hint_chan = BidirectionalChannel::new()?;
     let preimage_chan = BidirectionalChannel::new()?;
-    let kv_store = cfg.construct_kv_store();
-    let fetcher = if !cfg.is_offline() {
-        let (l1_provider, blob_provider, l2_provider) = cfg.create_providers().await?;
-        Some(Arc::new(RwLock::new(Fetcher::new(
-            kv_store.clone(),
-            l1_provider,
-            blob_provider,
-            l2_provider,
-            cfg.agreed_l2_head_hash,
-        ))))
-    } else {
-        None
-    };
+    let fetcher = create_fetcher(&cfg, kv_store.clone()).await?;
 
     // Create the server and start it.
-    let server_task = task::spawn(start_native_preimage_server(
+    let server = PreimageServer::new(
+        OracleServer::new(preimage_chan.host),
+        HintReader::new(hint_chan.host),
         kv_store,
-        fetcher,
-        hint_chan.host,
-        preimage_chan.host,
-    ));
+        fetcher.clone(),
+    )
+    .with_stats(stats.clone());
+    let server_task = task::spawn(server.start());
 
     // Start the client program in a separate child process.
-    let program_task = task::spawn(kona_client::run(
-        OracleReader::new(preimage_chan.client),
-        HintWriter::new(hint_chan.client),
-        None,
-    ));
+    if let Some(stats) = stats.as_ref() {
+        report::lock(stats).enter(Some(Phase::Prologue));
+    }
+    let program_task = task::spawn(async move {
+        let hint_writer = HintWriter::new(hint_chan.client)
+            .negotiate_batching()
+            .await
+            .map_err(OracleProviderError::Preimage)?;
+        kona_client::run_with_observer(
+            OracleReader::new(preimage_chan.client),
+            hint_writer,
+            None,
+            observer,
+        )
+        .await
+    });
 
     // Execute both tasks and wait for them to complete.
     info!("Starting preimage server and client program.");
     let (_, client_result) = tokio::try_join!(server_task, program_task,)?;
     info!(target: "kona_host", "Preimage server and client program have joined.");
 
-    Ok(client_result.is_err() as i32)
+    if let Some(stats) = stats.as_ref() {
+        let rpc_requests = match fetcher.as_ref() {
+            Some(fetcher) => fetcher.read().await.rpc_requests(),
+            None => RpcRequests::default(),
+        };
+        let mut stats = report::lock(stats);
+        stats.enter(None);
+        stats.record_rpc_requests(rpc_requests);
+    }
+
+    Ok(client_result)
+}
+
+/// Starts the [PreimageServer] and the client program in separate threads. The client program is
+/// a Fault Proof VM ELF, and is ran in the in-process emulator in this mode.
+///
+/// ## Takes
+/// - `cfg`: The host configuration. `cfg.emulate` must point to the client program ELF.
+///
+/// ## Returns
+/// - `Ok(0)` if the client program validated the claim.
+/// - `Ok(1)` if the client program found the claim to be invalid.
+/// - `Err(_)` if the client program could not be loaded, halted the emulator, or exited with any
+///   other code because it failed before it could check the claim.
+pub async fn start_server_and_emulated_client(cfg: HostCli) -> Result<i32> {
+    let kv_store = cfg.construct_kv_store();
+    let report = run_emulated_client(&cfg, kv_store).await?;
+    info!(
+        target: "kona_host",
+        "Emulated client program exited with code {} after {} instructions and {} syscalls, \
+         using at most {} bytes of memory.",
+        report.exit_code,
+        report.instructions,
+        report.syscalls,
+        report.peak_memory
+    );
+
+    // Forward the output of the client program.
+    std::io::stdout().write_all(&report.stdout)?;
+    std::io::stderr().write_all(&report.stderr)?;
+
+    match report.exit_code {
+        0 | 1 => Ok(report.exit_code as i32),
+        code => Err(anyhow!("Client program failed with exit code {code}")),
+    }
+}
+
+/// Runs the [PreimageServer] over `kv_store` and the client program ELF at `cfg.emulate` in the
+/// emulator, as in [start_server_and_emulated_client].
+///
+/// ## Returns
+/// - `Ok(report)` with the [EmulatorReport] of the client program, if it ran to completion.
+/// - `Err(_)` if the client program could not be loaded, or halted the emulator.
+pub(crate) async fn run_emulated_client(
+    cfg: &HostCli,
+    kv_store: SharedKeyValueStore,
+) -> Result<EmulatorReport> {
+    let program_path =
+        cfg.emulate.as_ref().ok_or_else(|| anyhow!("No client program to emulate"))?;
+    let elf = tokio::fs::read(program_path).await?;
+
+    let (hint_host, hint_client) = pipe();
+    let (preimage_host, preimage_client) = pipe();
+    let emulator = Emulator::new(&elf, hint_client, preimage_client)?;
+    info!(
+        target: "kona_host",
+        "Loaded {:?} client program from {}",
+        emulator.arch(),
+        program_path.display()
+    );
+
+    let fetcher = create_fetcher(cfg, kv_store.clone()).await?;
+
+    // Create the server and start it.
+    let server_task =
+        task::spawn(start_native_preimage_server(kv_store, fetcher, hint_host, preimage_host));
+
+    // Start the client program in the emulator. The emulator is CPU-bound, so it runs on a
+    // blocking thread rather than stalling a worker of the runtime that serves its preimages.
+    let runtime = Handle::current();
+    let program_task = task::spawn_blocking(move || runtime.block_on(emulator.run()));
+
+    // Execute both tasks and wait for them to complete.
+    info!("Starting preimage server and emulated client program.");
+    let (_, report) = tokio::try_join!(server_task, program_task)?;
+    Ok(report?)
+}
+
+/// Creates the [Fetcher] of the host in online mode, after running the [preflight] checks against
+/// its providers. Returns [None] in offline mode.
+async fn create_fetcher<KV>(
+    cfg: &HostCli,
+    kv_store: Arc<RwLock<KV>>,
+) -> Result<Option<Arc<RwLock<Fetcher<KV>>>>>
+where
+    KV: KeyValueStore + ?Sized,
+{
+    if cfg.is_offline() {
+        return Ok(None);
+    }
+
+    let (l1_provider, blob_provider, l2_provider) = cfg.create_providers().await?;
+    let capabilities = preflight(cfg, &l1_provider, &blob_provider, &l2_provider).await?;
+    Ok(Some(Arc::new(RwLock::new(
+        Fetcher::new(kv_store, l1_provider, blob_provider, l2_provider, cfg.agreed_l2_head_hash)
+            .with_generic_preimage_dir(cfg.generic_preimage_dir.clone())
+            .with_capabilities(capabilities)
+            .with_fetch_permits(cfg.fetch_permits())
+            .with_retries(cfg.fetch_retries, Duration::from_millis(cfg.fetch_retry_delay_ms)),
+    ))))
 }
 
-/// Starts the preimage server in a separate thread. The client program is ran natively in this
-/// mode.
-pub async fn start_native_preimage_server<KV>(
+/// Starts the preimage server in a separate thread, serving the client program over the given
+/// channels. The client program is ran natively or in the emulator in this mode.
+pub async fn start_native_preimage_server<KV, C>(
     kv_store: Arc<RwLock<KV>>,
     fetcher: Option<Arc<RwLock<Fetcher<KV>>>>,
-    hint_chan: NativeChannel,
-    preimage_chan: NativeChannel,
+    hint_chan: C,
+    preimage_chan: C,
 ) -> Result<()>
 where
     KV: KeyValueStore + Send + Sync + ?Sized + 'static,
+    C: Channel + Send + Sync + 'static,
 {
     let hint_reader = HintReader::new(hint_chan);
     let oracle_server = OracleServer::new(preimage_chan);
 
     PreimageServer::new(oracle_server, hint_reader, kv_store, fetcher).start().await
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        kv::{LocalKeyValueStore, MemoryKeyValueStore, SplitKeyValueStore},
+        report::{ReportObserver, RunReport},
+    };
+    use alloy_consensus::Header;
+    use alloy_primitives::{keccak256, B256};
+    use alloy_rlp::Encodable;
+    use kona_preimage::{PreimageKey, PreimageKeyType};
+    use kona_proof::errors::OracleProviderError;
+
+    /// The block number of the agreed L2 safe head in [offline_run].
+    const SAFE_HEAD_NUMBER: u64 = 5;
+
+    /// Returns a [HostCli] for an offline run on OP Mainnet whose agreed L2 output root commits to
+    /// a safe head at block [SAFE_HEAD_NUMBER], and the claim is the agreed output root at
+    /// `claimed_l2_block_number`. The returned store holds the output root and safe head
+    /// preimages that the client program loads in its prologue.
+    fn offline_run(claimed_l2_block_number: u64) -> (HostCli, SharedKeyValueStore) {
+        let header = Header { number: SAFE_HEAD_NUMBER, ..Default::default() };
+        let mut header_rlp = Vec::new();
+        header.encode(&mut header_rlp);
+        let block_hash = keccak256(&header_rlp);
+        let output = [B256::ZERO, B256::repeat_byte(2), B256::repeat_byte(3), block_hash].concat();
+        let output_root = keccak256(&output);
+
+        let cfg = HostCli {
+            l1_head: B256::repeat_byte(1),
+            agreed_l2_head_hash: block_hash,
+            agreed_l2_output_root: output_root,
+            claimed_l2_output_root: output_root,
+            claimed_l2_block_number,
+            l2_chain_id: Some(10),
+            ..Default::default()
+        };
+
+        let mut mem_kv_store = MemoryKeyValueStore::new();
+        for preimage in [output, header_rlp] {
+            let key = PreimageKey::new(*keccak256(&preimage), PreimageKeyType::Keccak256);
+            mem_kv_store.set(key.into(), preimage).unwrap();
+        }
+        let kv_store = SplitKeyValueStore::new(LocalKeyValueStore::new(cfg.clone()), mem_kv_store);
+        (cfg, Arc::new(RwLock::new(kv_store)))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_native_client_offline() {
+        let (cfg, kv_store) = offline_run(SAFE_HEAD_NUMBER);
+        let result = run_native_client(cfg, kv_store, |_: DerivedBlock<'_>| {}, None).await;
+        assert!(matches!(result, Ok(Ok(()))));
+
+        // The report records the boot information that the client program loaded, and the agreed
+        // output root that it finished at without executing a block.
+        let (cfg, kv_store) = offline_run(SAFE_HEAD_NUMBER);
+        let stats = SharedRunStats::default();
+        let observer = ReportObserver::new(Arc::clone(&stats));
+        let result = run_native_client(cfg.clone(), kv_store, observer, Some(Arc::clone(&stats)));
+        let result = result.await;
+        assert!(matches!(result, Ok(Ok(()))));
+        let report = RunReport::new(&result, &report::lock(&stats), Duration::ZERO);
+        let boot_info = report.boot_info.unwrap();
+        assert_eq!(boot_info.agreed_l2_output_root, cfg.agreed_l2_output_root);
+        assert_eq!(boot_info.chain_id, 10);
+        assert_eq!(report.output_root, Some(cfg.agreed_l2_output_root));
+        assert_eq!(report.blocks, 0);
+
+        let (cfg, kv_store) = offline_run(SAFE_HEAD_NUMBER - 1);
+        let result = run_native_client(cfg, kv_store, |_: DerivedBlock<'_>| {}, None).await;
+        assert!(matches!(result, Ok(Err(FaultProofProgramError::InvalidClaim(..)))));
+    }
+
+    #[test]
+    fn test_client_exit_status() {
+        assert_eq!(client_exit_status(Ok(())).unwrap(), 0);
+        let invalid = FaultProofProgramError::InvalidClaim(B256::ZERO, B256::with_last_byte(1));
+        assert_eq!(client_exit_status(Err(invalid)).unwrap(), 1);
+
+        // Failing to check the claim does not make it invalid.
+        let failed = FaultProofProgramError::OracleProviderError(
+            OracleProviderError::BlockNumberPastHead(2, 1),
+        );
+        assert!(client_exit_status(Err(failed)).is_err());
+    }
+
+    /// Runs the client program ELF at `KONA_CLIENT_ELF` in the emulator against the same preimages
+    /// as [test_native_client_offline]. `just emulator-tests` builds the ELF for Asterisc and runs
+    /// this test, as the `emulator-tests` job of the client and host CI workflow does.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "requires a client program ELF at KONA_CLIENT_ELF"]
+    async fn test_emulated_client_offline() {
+        let elf = std::env::var_os("KONA_CLIENT_ELF").expect("KONA_CLIENT_ELF is not set");
+
+        for (claimed_l2_block_number, exit_code) in
+            [(SAFE_HEAD_NUMBER, 0), (SAFE_HEAD_NUMBER - 1, 1)]
+        {
+            let (cfg, kv_store) = offline_run(claimed_l2_block_number);
+            let cfg = HostCli { emulate: Some(elf.clone().into()), ..cfg };
+            let report = run_emulated_client(&cfg, kv_store).await.unwrap();
+            assert_eq!(report.exit_code, exit_code);
+        }
+    }
+}
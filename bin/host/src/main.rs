@@ -1,19 +1,43 @@
 //! Main entrypoint for the host binary.
 
 use anyhow::Result;
-use clap::Parser;
-use kona_host::{init_tracing_subscriber, start_server, start_server_and_native_client, HostCli};
+use kona_host::{
+    discover_claim, discover_game_claim, init_tracing_subscriber, start_server,
+    start_server_and_bisect, start_server_and_emulated_client, start_server_and_native_client,
+    start_server_and_reported_native_client, start_session_server, HostCli, HostCommand,
+};
 use tracing::{error, info};
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<()> {
-    let cfg = HostCli::parse();
+    if let Some(command) = HostCommand::parse_subcommand() {
+        std::process::exit(command.run()?);
+    }
+
+    let mut cfg = HostCli::parse_with_config();
     init_tracing_subscriber(cfg.v)?;
 
+    if cfg.agreed_l2_block_number.is_some() {
+        discover_claim(&mut cfg).await?;
+    }
+    let game_claim =
+        if cfg.dispute_game.is_some() { Some(discover_game_claim(&mut cfg).await?) } else { None };
+
     if cfg.server {
         start_server(cfg).await?;
+    } else if cfg.listen.is_some() {
+        start_session_server(cfg).await?;
     } else {
-        let status = match start_server_and_native_client(cfg).await {
+        let result = if cfg.bisect {
+            start_server_and_bisect(cfg).await
+        } else if cfg.emulate.is_some() {
+            start_server_and_emulated_client(cfg).await
+        } else if cfg.report.is_some() {
+            start_server_and_reported_native_client(cfg).await
+        } else {
+            start_server_and_native_client(cfg).await
+        };
+        let status = match result {
             Ok(status) => status,
             Err(e) => {
                 error!(target: "kona_host", "Exited with an error: {:?}", e);
@@ -21,6 +45,10 @@ async fn main() -> Result<()> {
             }
         };
 
+        if let Some(claim) = game_claim {
+            claim.report(status as i32);
+        }
+
         // Bubble up the exit status of the client program.
         std::process::exit(status as i32);
     }
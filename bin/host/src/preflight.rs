@@ -0,0 +1,408 @@
+//! This module contains the preflight checks of the host, which validate the L1, L2 and beacon
+//! endpoints before the host starts serving the client program.
+
+use crate::{
+    blobs::OnlineBlobProvider, cli::HostCli, discovery::discovery_rollup_config,
+    fetcher::fetch_output_root_preimage, transport::HostProvider,
+};
+use alloy_consensus::Header;
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_rlp::Decodable;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind};
+use anyhow::{anyhow, bail, Result};
+use clap::ValueEnum;
+use op_alloy_genesis::RollupConfig;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+/// The JSON-RPC error code of a method that does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The strategy used to fetch the code and state trie nodes of L2 blocks by hash.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StateStrategy {
+    /// Fetch preimages from the database of the L2 node with geth's `debug_dbGet`.
+    #[default]
+    DbGet,
+    /// Fetch code by the address of an account proven with `eth_getProof`, and trie nodes from the
+    /// `debug_executionWitness` of the L2 block being executed, as supported by op-reth.
+    ExecutionWitness,
+    /// Fetch code by the address of an account proven with `eth_getProof`, and trie nodes from
+    /// account and storage proofs. Trie nodes that are not covered by the proofs that the client
+    /// program hints are reconstructed by proving a key under their path, which is only feasible
+    /// for nodes that are at most a few nibbles deep.
+    Proofs,
+}
+
+/// The optional capabilities of the configured endpoints, as probed by [preflight]. The [Fetcher]
+/// falls back to alternate methods for the capabilities that are missing.
+///
+/// [Fetcher]: crate::fetcher::Fetcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcCapabilities {
+    /// Whether the L1 node supports `debug_getRawReceipts`. If not, receipts are fetched with
+    /// `eth_getBlockReceipts` and encoded by the host.
+    pub l1_raw_receipts: bool,
+    /// Whether the L2 node supports `debug_executePayload`. If not, `L2PayloadWitness` hints are
+    /// ignored, and the client program's state is fetched node by node.
+    pub l2_execute_payload: bool,
+    /// The strategy used to fetch code and state trie nodes from the L2 node.
+    pub l2_state: StateStrategy,
+}
+
+impl Default for RpcCapabilities {
+    fn default() -> Self {
+        Self { l1_raw_receipts: true, l2_execute_payload: true, l2_state: StateStrategy::DbGet }
+    }
+}
+
+/// Checks that the configured endpoints can serve the client program, before the host starts:
+///
+/// - The chain IDs of the L1 and L2 nodes match the rollup config.
+/// - The L1 node serves raw headers, and raw receipts or `eth_getBlockReceipts`.
+/// - The L2 node serves raw headers, and still holds the state of the agreed L2 block, matching the
+///   agreed output root.
+/// - The L2 node supports the state strategy in `cfg.l2_state_strategy`, or else the first
+///   [StateStrategy] that it supports is selected.
+/// - The beacon node serves blob sidecars.
+///
+/// Every problem found is reported at once, rather than surfacing mid-run as retried hints. The
+/// checks are skipped if `cfg.skip_preflight` is set.
+///
+/// ## Returns
+/// - `Ok(capabilities)` with the optional capabilities of the endpoints, if all required
+///   capabilities are present.
+/// - `Err(_)` listing the missing capabilities otherwise.
+pub async fn preflight(
+    cfg: &HostCli,
+    l1_provider: &HostProvider,
+    blob_provider: &OnlineBlobProvider,
+    l2_provider: &HostProvider,
+) -> Result<RpcCapabilities> {
+    if cfg.skip_preflight {
+        warn!(target: "preflight", "Skipping preflight checks");
+        return Ok(RpcCapabilities {
+            l2_state: cfg.l2_state_strategy.unwrap_or_default(),
+            ..Default::default()
+        });
+    }
+
+    let rollup_config = discovery_rollup_config(cfg)?;
+    let mut capabilities = RpcCapabilities::default();
+    let mut problems = Vec::new();
+
+    if let Err(e) = check_l1(cfg, l1_provider, &rollup_config, &mut capabilities).await {
+        problems.push(format!("L1 node: {e}"));
+    }
+    if let Err(e) = check_l2(cfg, l2_provider, &rollup_config, &mut capabilities).await {
+        problems.push(format!("L2 node: {e}"));
+    }
+    if let Err(e) = blob_provider.probe_blob_sidecars().await {
+        problems.push(format!("Beacon node: blob sidecars are not available: {e}"));
+    }
+
+    if !problems.is_empty() {
+        bail!("Preflight checks failed:\n  - {}", problems.join("\n  - "));
+    }
+    info!(target: "preflight", "Preflight checks passed: {capabilities:?}");
+    Ok(capabilities)
+}
+
+/// Checks the L1 node, recording whether it supports `debug_getRawReceipts`.
+async fn check_l1(
+    cfg: &HostCli,
+    l1_provider: &HostProvider,
+    rollup_config: &RollupConfig,
+    capabilities: &mut RpcCapabilities,
+) -> Result<()> {
+    check_chain_id(l1_provider, rollup_config.l1_chain_id).await?;
+
+    // The L1 head must be known to the L1 node.
+    let l1_head = if cfg.l1_head.is_zero() { latest_hash(l1_provider).await? } else { cfg.l1_head };
+    fetch_raw_header(l1_provider, l1_head).await?;
+
+    capabilities.l1_raw_receipts =
+        supports_method(l1_provider, "debug_getRawReceipts", [l1_head]).await?;
+    if !capabilities.l1_raw_receipts {
+        if !supports_method(l1_provider, "eth_getBlockReceipts", [l1_head]).await? {
+            bail!("neither debug_getRawReceipts nor eth_getBlockReceipts is supported");
+        }
+        warn!(
+            target: "preflight",
+            "L1 node does not support debug_getRawReceipts; falling back to eth_getBlockReceipts"
+        );
+    }
+    Ok(())
+}
+
+/// Checks the L2 node, recording its state strategy and whether it supports
+/// `debug_executePayload`.
+async fn check_l2(
+    cfg: &HostCli,
+    l2_provider: &HostProvider,
+    rollup_config: &RollupConfig,
+    capabilities: &mut RpcCapabilities,
+) -> Result<()> {
+    check_chain_id(l2_provider, rollup_config.l2_chain_id).await?;
+
+    // The L2 node must serve the state of the agreed L2 block, which it only keeps for recent
+    // blocks unless it is an archive node.
+    let agreed_l2_head = if cfg.agreed_l2_head_hash.is_zero() {
+        latest_hash(l2_provider).await?
+    } else {
+        cfg.agreed_l2_head_hash
+    };
+    let header = fetch_raw_header(l2_provider, agreed_l2_head).await?;
+    if !cfg.agreed_l2_head_hash.is_zero() {
+        let output_root = keccak256(
+            fetch_output_root_preimage(l2_provider, agreed_l2_head)
+                .await
+                .map_err(|e| anyhow!("state of agreed L2 block is not available: {e}"))?,
+        );
+        if output_root != cfg.agreed_l2_output_root {
+            bail!(
+                "output root of the agreed L2 block is {output_root}, but the agreed output root \
+                 is {}",
+                cfg.agreed_l2_output_root
+            );
+        }
+    }
+
+    capabilities.l2_state = match cfg.l2_state_strategy {
+        Some(strategy) => {
+            probe_state_strategy(l2_provider, &header, strategy).await?;
+            strategy
+        }
+        None => detect_state_strategy(l2_provider, &header).await?,
+    };
+
+    capabilities.l2_execute_payload =
+        supports_method(l2_provider, "debug_executePayload", (B256::ZERO, Value::Null)).await?;
+    if !capabilities.l2_execute_payload {
+        warn!(
+            target: "preflight",
+            "L2 node does not support debug_executePayload; payload witness hints are ignored"
+        );
+    }
+    Ok(())
+}
+
+/// Returns the first [StateStrategy] that the L2 node supports, in order of preference.
+async fn detect_state_strategy(
+    l2_provider: &HostProvider,
+    header: &Header,
+) -> Result<StateStrategy> {
+    for strategy in [StateStrategy::DbGet, StateStrategy::ExecutionWitness] {
+        if probe_state_strategy(l2_provider, header, strategy).await.is_ok() {
+            return Ok(strategy);
+        }
+    }
+    warn!(
+        target: "preflight",
+        "L2 node supports neither debug_dbGet nor debug_executionWitness; state trie nodes that are \
+         not covered by proofs are reconstructed from eth_getProof"
+    );
+    Ok(StateStrategy::Proofs)
+}
+
+/// Checks that the L2 node supports the given [StateStrategy] for the block with the given header.
+async fn probe_state_strategy(
+    l2_provider: &HostProvider,
+    header: &Header,
+    strategy: StateStrategy,
+) -> Result<()> {
+    match strategy {
+        StateStrategy::DbGet => {
+            let _: Bytes =
+                l2_provider.client().request("debug_dbGet", [header.state_root]).await.map_err(
+                    |e| anyhow!("debug_dbGet of the state root of the agreed L2 block failed: {e}"),
+                )?;
+        }
+        StateStrategy::ExecutionWitness => {
+            let params = [BlockNumberOrTag::Number(header.number)];
+            if !supports_method(l2_provider, "debug_executionWitness", params).await? {
+                bail!("debug_executionWitness is not supported");
+            }
+        }
+        StateStrategy::Proofs => {}
+    }
+    Ok(())
+}
+
+/// Checks that the chain ID of the node matches the expected chain ID.
+async fn check_chain_id(provider: &HostProvider, expected: u64) -> Result<()> {
+    let chain_id =
+        provider.get_chain_id().await.map_err(|e| anyhow!("failed to fetch chain ID: {e}"))?;
+    if chain_id != expected {
+        bail!("chain ID is {chain_id}, but the rollup config expects {expected}");
+    }
+    Ok(())
+}
+
+/// Fetches the hash of the latest block of the node.
+async fn latest_hash(provider: &HostProvider) -> Result<B256> {
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+        .await
+        .map_err(|e| anyhow!("failed to fetch latest block: {e}"))?
+        .ok_or_else(|| anyhow!("latest block not found"))?;
+    Ok(block.header.hash)
+}
+
+/// Fetches and decodes the header of the block with the given hash with `debug_getRawHeader`.
+async fn fetch_raw_header(provider: &HostProvider, hash: B256) -> Result<Header> {
+    let raw_header: Bytes = provider
+        .client()
+        .request("debug_getRawHeader", [hash])
+        .await
+        .map_err(|e| anyhow!("debug_getRawHeader of block {hash} failed: {e}"))?;
+    Header::decode(&mut raw_header.as_ref())
+        .map_err(|e| anyhow!("debug_getRawHeader returned an invalid header: {e}"))
+}
+
+/// Returns whether the node supports the given method, by calling it with the given parameters.
+/// Errors other than a missing method, such as invalid parameters, mean that the method exists.
+async fn supports_method<P>(
+    provider: &HostProvider,
+    method: &'static str,
+    params: P,
+) -> Result<bool>
+where
+    P: serde::Serialize + Clone + std::fmt::Debug + Send + Sync + Unpin,
+{
+    let Err(e) = provider.client().request::<P, Value>(method, params).await else {
+        return Ok(true);
+    };
+    e.as_error_resp().map_or_else(
+        || Err(anyhow!("failed to call {method}: {e}")),
+        |payload| {
+            Ok(payload.code != METHOD_NOT_FOUND &&
+                !payload.message.contains("does not exist") &&
+                !payload.message.contains("not available"))
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{
+        mock_block, mock_header, mock_output_root, MockRpc, MESSAGE_PASSER_STORAGE_ROOT,
+    };
+    use alloy_rlp::Encodable;
+    use alloy_rpc_types::EIP1186AccountProofResponse;
+    use serde_json::json;
+
+    /// Serves a node with the given chain ID and supported methods, whose blocks are those of the
+    /// mock chain, and whose latest block is #100.
+    async fn mock_node(chain_id: u64, methods: &[&str]) -> MockRpc {
+        let methods = methods.iter().map(|method| method.to_string()).collect::<Vec<_>>();
+        MockRpc::serve(move |method, params| {
+            if !methods.iter().any(|m| m == method) {
+                return Err(format!("the method {method} does not exist/is not available"));
+            }
+            match method {
+                "eth_chainId" => Ok(json!(format!("{chain_id:#x}"))),
+                "eth_getBlockByNumber" => Ok(mock_block(100)),
+                "debug_getRawHeader" => {
+                    let hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+                    let (header, _) = (0..256).map(mock_header).find(|(_, h)| *h == hash).unwrap();
+                    let mut buf = Vec::new();
+                    header.encode(&mut buf);
+                    Ok(json!(Bytes::from(buf)))
+                }
+                "debug_getRawReceipts" | "eth_getBlockReceipts" => Ok(json!([])),
+                "debug_dbGet" => Ok(json!(Bytes::from_static(&[0xc0]))),
+                "debug_executePayload" => Err("invalid payload attributes".to_string()),
+                "debug_executionWitness" => Ok(json!({ "state": {}, "codes": {}, "keys": {} })),
+                "eth_getProof" => Ok(serde_json::to_value(EIP1186AccountProofResponse {
+                    storage_hash: MESSAGE_PASSER_STORAGE_ROOT,
+                    ..Default::default()
+                })
+                .unwrap()),
+                _ => unreachable!(),
+            }
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_l1() {
+        let rollup_config = RollupConfig { l1_chain_id: 1, ..Default::default() };
+        let cfg = HostCli::default();
+        let mut capabilities = RpcCapabilities::default();
+
+        let methods = &["eth_chainId", "eth_getBlockByNumber", "debug_getRawHeader"];
+        let node =
+            mock_node(1, &["eth_getBlockReceipts", methods[0], methods[1], methods[2]]).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        check_l1(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap();
+        assert!(!capabilities.l1_raw_receipts);
+
+        // Without any method to fetch receipts, the L1 node cannot be used.
+        let node = mock_node(1, methods).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        let err = check_l1(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap_err();
+        assert!(err.to_string().contains("eth_getBlockReceipts"));
+
+        // The chain ID must match the rollup config.
+        let node = mock_node(5, methods).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        let err = check_l1(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap_err();
+        assert!(err.to_string().contains("chain ID is 5"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_l2() {
+        let rollup_config = RollupConfig { l2_chain_id: 10, ..Default::default() };
+        let cfg = HostCli {
+            agreed_l2_head_hash: mock_header(100).1,
+            agreed_l2_output_root: mock_output_root(100),
+            ..Default::default()
+        };
+        let mut capabilities = RpcCapabilities::default();
+
+        let methods = &["eth_chainId", "debug_getRawHeader", "eth_getProof", "debug_dbGet"];
+        let node = mock_node(10, methods).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        check_l2(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap();
+        assert!(!capabilities.l2_execute_payload);
+        assert_eq!(capabilities.l2_state, StateStrategy::DbGet);
+
+        let node = mock_node(
+            10,
+            &[methods[0], methods[1], methods[2], methods[3], "debug_executePayload"],
+        )
+        .await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        check_l2(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap();
+        assert!(capabilities.l2_execute_payload);
+
+        // Without `debug_dbGet`, state is fetched through execution witnesses, or proofs.
+        let node =
+            mock_node(10, &[methods[0], methods[1], methods[2], "debug_executionWitness"]).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        check_l2(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap();
+        assert_eq!(capabilities.l2_state, StateStrategy::ExecutionWitness);
+        let node = mock_node(10, &methods[..3]).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        check_l2(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap();
+        assert_eq!(capabilities.l2_state, StateStrategy::Proofs);
+
+        // An explicitly selected state strategy must be supported.
+        let explicit = HostCli { l2_state_strategy: Some(StateStrategy::DbGet), ..cfg.clone() };
+        let err =
+            check_l2(&explicit, &provider, &rollup_config, &mut capabilities).await.unwrap_err();
+        assert!(err.to_string().contains("debug_dbGet"));
+
+        // The agreed output root must match the L2 node.
+        let cfg = HostCli { agreed_l2_output_root: B256::ZERO, ..cfg };
+        let node = mock_node(10, methods).await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        let err = check_l2(&cfg, &provider, &rollup_config, &mut capabilities).await.unwrap_err();
+        assert!(err.to_string().contains("agreed output root"));
+    }
+}
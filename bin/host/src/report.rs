@@ -0,0 +1,460 @@
+//! This module contains the run report of the host, a machine-readable summary of a native run of
+//! the client program that is written to `--report` once the client program exits.
+//!
+//! The statistics of the run are recorded into a shared [RunStats] by the [PreimageServer], which
+//! wraps its [PreimageFetcher] in a [RecordingPreimageFetcher], and by the [ReportObserver] of the
+//! client program.
+//!
+//! [PreimageServer]: crate::server::PreimageServer
+
+use crate::{cli::HostCli, client_exit_status, run_native_client};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use kona_client::{DerivedBlock, DriverObserver, FaultProofProgramError, ProgramObserver};
+use kona_preimage::{errors::PreimageOracleResult, PreimageFetcher, PreimageKey, PreimageKeyType};
+use kona_proof::BootInfo;
+use op_alloy_consensus::OpTxEnvelope;
+use op_alloy_protocol::L1BlockInfoTx;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// The [RunStats] of a run, shared between the preimage server and the client program.
+pub type SharedRunStats = Arc<Mutex<RunStats>>;
+
+/// The number of preimages of one [PreimageKeyType] that were served to the client program, and
+/// their total size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PreimageStats {
+    /// The number of preimages served.
+    pub count: u64,
+    /// The total size of the preimages served, in bytes.
+    pub bytes: u64,
+}
+
+/// The number of requests that the host sent to each of its endpoints.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RpcRequests {
+    /// The number of JSON-RPC requests sent to the L1 node.
+    pub l1: u64,
+    /// The number of JSON-RPC requests sent to the L2 node.
+    pub l2: u64,
+    /// The number of HTTP requests sent to the beacon node and the blob sources.
+    pub beacon: u64,
+}
+
+/// A phase of the client program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Loading the boot information and the agreed safe head, before the driver starts.
+    Prologue = 0,
+    /// Deriving the payload attributes of the next block.
+    Derivation = 1,
+    /// Executing a block and computing its output root.
+    Execution = 2,
+}
+
+/// The wall-clock time that the client program spent in each [Phase], in seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct PhaseTimes {
+    /// The time spent in the prologue.
+    pub prologue: f64,
+    /// The time spent deriving blocks.
+    pub derivation: f64,
+    /// The time spent executing blocks.
+    pub execution: f64,
+}
+
+/// The statistics of a run, recorded by the preimage server and the observer of the client program.
+#[derive(Debug, Default)]
+pub struct RunStats {
+    /// The boot information that the client program loaded.
+    boot_info: Option<BootInfo>,
+    /// The preimages served, by key type.
+    preimages: BTreeMap<&'static str, PreimageStats>,
+    /// The numbers of the L1 origins of the blocks executed.
+    l1_origins: BTreeSet<u64>,
+    /// The number of blocks derived and executed.
+    blocks: u64,
+    /// The output root of the last block executed, or of the L2 safe head that the client program
+    /// finished at.
+    output_root: Option<B256>,
+    /// The requests sent to the endpoints of the host.
+    rpc_requests: RpcRequests,
+    /// The current phase of the client program, and when it was entered.
+    phase: Option<(Phase, Instant)>,
+    /// The time spent in each phase, indexed by [Phase].
+    phase_times: [Duration; 3],
+}
+
+impl RunStats {
+    /// Records a preimage served to the client program.
+    pub fn record_preimage(&mut self, key: PreimageKey, len: usize) {
+        let stats = self.preimages.entry(key_type_name(key.key_type())).or_default();
+        stats.count += 1;
+        stats.bytes += len as u64;
+    }
+
+    /// Records the boot information that the client program loaded.
+    pub fn record_boot_info(&mut self, boot_info: &BootInfo) {
+        self.boot_info = Some(boot_info.clone());
+    }
+
+    /// Records a block derived and executed by the client program, with the number of its L1
+    /// origin if it is known.
+    pub fn record_block(&mut self, output_root: B256, l1_origin: Option<u64>) {
+        self.blocks += 1;
+        self.output_root = Some(output_root);
+        self.l1_origins.extend(l1_origin);
+    }
+
+    /// Records the output root of the L2 safe head that the client program finished at, which is
+    /// the agreed output root if no block was executed.
+    pub const fn record_output_root(&mut self, output_root: B256) {
+        self.output_root = Some(output_root);
+    }
+
+    /// Records the requests sent to the endpoints of the host.
+    pub const fn record_rpc_requests(&mut self, rpc_requests: RpcRequests) {
+        self.rpc_requests = rpc_requests;
+    }
+
+    /// Enters the given [Phase], or leaves the current phase if [None], adding the time spent in
+    /// the current phase to its total.
+    pub fn enter(&mut self, next: Option<Phase>) {
+        let now = Instant::now();
+        if let Some((phase, entered)) = self.phase.take() {
+            self.phase_times[phase as usize] += now - entered;
+        }
+        self.phase = next.map(|phase| (phase, now));
+    }
+
+    /// Returns the time spent in each [Phase].
+    pub fn phase_times(&self) -> PhaseTimes {
+        let [prologue, derivation, execution] = self.phase_times.map(|time| time.as_secs_f64());
+        PhaseTimes { prologue, derivation, execution }
+    }
+}
+
+/// Locks the [SharedRunStats], ignoring poisoning by a panicked recorder.
+pub(crate) fn lock(stats: &SharedRunStats) -> MutexGuard<'_, RunStats> {
+    stats.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the name of a [PreimageKeyType] in the run report.
+const fn key_type_name(key_type: PreimageKeyType) -> &'static str {
+    match key_type {
+        PreimageKeyType::Local => "local",
+        PreimageKeyType::Keccak256 => "keccak256",
+        PreimageKeyType::GlobalGeneric => "global-generic",
+        PreimageKeyType::Sha256 => "sha256",
+        PreimageKeyType::Blob => "blob",
+        PreimageKeyType::Precompile => "precompile",
+    }
+}
+
+/// A [PreimageFetcher] that records the preimages served by another into [SharedRunStats], if
+/// any.
+#[derive(Debug)]
+pub struct RecordingPreimageFetcher<F> {
+    inner: F,
+    stats: Option<SharedRunStats>,
+}
+
+impl<F> RecordingPreimageFetcher<F> {
+    /// Creates a new [RecordingPreimageFetcher] that wraps `inner`.
+    pub const fn new(inner: F, stats: Option<SharedRunStats>) -> Self {
+        Self { inner, stats }
+    }
+}
+
+#[async_trait]
+impl<F> PreimageFetcher for RecordingPreimageFetcher<F>
+where
+    F: PreimageFetcher + Send + Sync,
+{
+    async fn get_preimage(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let preimage = self.inner.get_preimage(key).await?;
+        if let Some(stats) = self.stats.as_ref() {
+            lock(stats).record_preimage(key, preimage.len());
+        }
+        Ok(preimage)
+    }
+}
+
+/// A [ProgramObserver] that records the boot information, the blocks and the phases of the client
+/// program into [SharedRunStats].
+#[derive(Debug, Clone)]
+pub struct ReportObserver {
+    stats: SharedRunStats,
+}
+
+impl ReportObserver {
+    /// Creates a new [ReportObserver].
+    pub const fn new(stats: SharedRunStats) -> Self {
+        Self { stats }
+    }
+}
+
+impl DriverObserver for ReportObserver {
+    fn on_start(&mut self) {
+        lock(&self.stats).enter(Some(Phase::Derivation));
+    }
+
+    fn on_attributes(&mut self, _: &OpPayloadAttributes) {
+        lock(&self.stats).enter(Some(Phase::Execution));
+    }
+
+    fn on_block(&mut self, block: DerivedBlock<'_>) {
+        let mut stats = lock(&self.stats);
+        stats.record_block(block.output_root, l1_origin_number(block.attributes));
+        stats.enter(Some(Phase::Derivation));
+    }
+
+    fn on_finish(&mut self, _: u64, output_root: B256) {
+        lock(&self.stats).record_output_root(output_root);
+    }
+}
+
+impl ProgramObserver for ReportObserver {
+    fn on_boot(&mut self, boot: &BootInfo) {
+        lock(&self.stats).record_boot_info(boot);
+    }
+}
+
+/// Returns the number of the L1 origin of a block from the L1 info transaction of its payload
+/// attributes, or [None] if it has none.
+fn l1_origin_number(attributes: &OpPayloadAttributes) -> Option<u64> {
+    let raw_tx = attributes.transactions.as_ref()?.first()?;
+    let OpTxEnvelope::Deposit(deposit) = OpTxEnvelope::decode_2718(&mut raw_tx.as_ref()).ok()?
+    else {
+        return None;
+    };
+    Some(L1BlockInfoTx::decode_calldata(deposit.input.as_ref()).ok()?.id().number)
+}
+
+/// The outcome of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    /// The client program validated the claim.
+    Valid,
+    /// The client program rejected the claim.
+    Invalid,
+    /// The client program or the host failed before the claim could be validated.
+    Error,
+}
+
+/// The result of a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunResult {
+    /// The outcome of the run.
+    pub status: RunStatus,
+    /// The variant of the [FaultProofProgramError] that the client program failed with, or `Host`
+    /// if the host failed.
+    pub error: Option<&'static str>,
+    /// The message of the error.
+    pub message: Option<String>,
+}
+
+impl RunResult {
+    /// Creates the [RunResult] of a run of the client program by the host.
+    pub fn new(result: &Result<Result<(), FaultProofProgramError>>) -> Self {
+        let (status, error) = match result {
+            Ok(Ok(())) => return Self { status: RunStatus::Valid, error: None, message: None },
+            Ok(Err(e @ FaultProofProgramError::InvalidClaim(..))) => {
+                (RunStatus::Invalid, ("InvalidClaim", e.to_string()))
+            }
+            Ok(Err(e @ FaultProofProgramError::OracleProviderError(_))) => {
+                (RunStatus::Error, ("OracleProviderError", e.to_string()))
+            }
+            Ok(Err(e @ FaultProofProgramError::Driver(_))) => {
+                (RunStatus::Error, ("Driver", e.to_string()))
+            }
+            Err(e) => (RunStatus::Error, ("Host", format!("{e:#}"))),
+        };
+        Self { status, error: Some(error.0), message: Some(error.1) }
+    }
+}
+
+/// The machine-readable report of a native run of the client program.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    /// The boot information that the client program loaded, if it got that far.
+    pub boot_info: Option<BootInfo>,
+    /// The result of the run.
+    pub result: RunResult,
+    /// The output root of the L2 safe head that the client program finished at, or of the last
+    /// block that it derived and executed if it failed before then.
+    pub output_root: Option<B256>,
+    /// The number of blocks that the client program derived and executed.
+    pub blocks: u64,
+    /// The number of distinct L1 origins of the blocks that the client program derived and
+    /// executed.
+    pub l1_blocks: u64,
+    /// The preimages served to the client program, by key type.
+    pub preimages: BTreeMap<&'static str, PreimageStats>,
+    /// The requests that the host sent to each of its endpoints.
+    pub rpc_requests: RpcRequests,
+    /// The wall-clock time that the client program spent in each phase, in seconds.
+    pub phases: PhaseTimes,
+    /// The wall-clock time of the whole run, including the preflight checks, in seconds.
+    pub elapsed: f64,
+}
+
+impl RunReport {
+    /// Creates the [RunReport] of a run from its result and statistics.
+    pub fn new(
+        result: &Result<Result<(), FaultProofProgramError>>,
+        stats: &RunStats,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            boot_info: stats.boot_info.clone(),
+            result: RunResult::new(result),
+            output_root: stats.output_root,
+            blocks: stats.blocks,
+            l1_blocks: stats.l1_origins.len() as u64,
+            preimages: stats.preimages.clone(),
+            rpc_requests: stats.rpc_requests,
+            phases: stats.phase_times(),
+            elapsed: elapsed.as_secs_f64(),
+        }
+    }
+}
+
+/// Starts the [PreimageServer] and the client program natively, as in
+/// [start_server_and_native_client], and writes a [RunReport] of the run to `cfg.report` once the
+/// client program exits. The report is written even if the host fails.
+///
+/// ## Returns
+/// - `Ok(exit_code)` if the client program exits and the report is written.
+/// - `Err(_)` if the host failed, or the report could not be written.
+///
+/// [PreimageServer]: crate::server::PreimageServer
+/// [start_server_and_native_client]: crate::start_server_and_native_client
+pub async fn start_server_and_reported_native_client(cfg: HostCli) -> Result<i32> {
+    let path = cfg.report.clone().ok_or_else(|| anyhow!("No report path"))?;
+    let started = Instant::now();
+
+    let stats = SharedRunStats::default();
+    let observer = ReportObserver::new(Arc::clone(&stats));
+    let kv_store = cfg.construct_kv_store();
+    let result = run_native_client(cfg, kv_store, observer, Some(Arc::clone(&stats))).await;
+
+    let report = RunReport::new(&result, &lock(&stats), started.elapsed());
+    std::fs::write(&path, serde_json::to_vec_pretty(&report)?)
+        .map_err(|e| anyhow!("Failed to write run report to {}: {e}", path.display()))?;
+    info!(target: "kona_host", "Wrote run report to {}", path.display());
+
+    client_exit_status(result?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{keccak256, Bytes, Sealable};
+    use op_alloy_consensus::TxDeposit;
+    use op_alloy_protocol::L1BlockInfoEcotone;
+
+    #[test]
+    fn test_run_stats() {
+        let mut stats = RunStats::default();
+        stats.record_preimage(PreimageKey::new(*keccak256([1]), PreimageKeyType::Keccak256), 3);
+        stats.record_preimage(PreimageKey::new(*keccak256([2]), PreimageKeyType::Keccak256), 5);
+        stats.record_preimage(PreimageKey::new_local(1), 32);
+
+        let hash = keccak256([3]);
+
+        // The host failed before the client program loaded its boot information.
+        let report = RunReport::new(&Err(anyhow!("no L1 node")), &stats, Duration::ZERO);
+        let report = serde_json::to_value(&report).unwrap();
+        assert_eq!(report["boot_info"], serde_json::Value::Null);
+        assert_eq!(report["output_root"], serde_json::Value::Null);
+
+        stats.enter(Some(Phase::Prologue));
+        stats.record_boot_info(&BootInfo {
+            l1_head: B256::ZERO,
+            agreed_l2_output_root: B256::ZERO,
+            claimed_l2_output_root: B256::ZERO,
+            claimed_l2_block_number: 1,
+            chain_id: 10,
+            rollup_config: Default::default(),
+        });
+        stats.enter(Some(Phase::Derivation));
+        stats.enter(Some(Phase::Execution));
+        stats.record_block(keccak256([4]), Some(7));
+        stats.record_block(keccak256([5]), Some(7));
+        stats.record_block(hash, None);
+        stats.record_output_root(hash);
+        stats.enter(None);
+
+        let result = Ok(Err(FaultProofProgramError::InvalidClaim(hash, B256::ZERO)));
+        let report = RunReport::new(&result, &stats, Duration::from_secs(1));
+        let report = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(report["result"]["status"], "invalid");
+        assert_eq!(report["result"]["error"], "InvalidClaim");
+        assert_eq!(report["output_root"], hash.to_string());
+        assert_eq!(report["blocks"], 3);
+        assert_eq!(report["l1_blocks"], 1);
+        assert_eq!(report["preimages"]["keccak256"]["count"], 2);
+        assert_eq!(report["preimages"]["keccak256"]["bytes"], 8);
+        assert_eq!(report["preimages"]["local"]["count"], 1);
+        assert_eq!(report["boot_info"]["chain_id"], 10);
+        assert!(report["phases"]["execution"].as_f64().unwrap() >= 0.0);
+        assert_eq!(report["elapsed"], 1.0);
+    }
+
+    #[test]
+    fn test_l1_origin_number() {
+        let l1_info =
+            L1BlockInfoTx::Ecotone(L1BlockInfoEcotone { number: 7, ..Default::default() });
+        let deposit = TxDeposit { input: l1_info.encode_calldata(), ..Default::default() };
+        let deposit = OpTxEnvelope::Deposit(deposit.seal_slow()).encoded_2718();
+        let attributes = |transactions| OpPayloadAttributes { transactions, ..Default::default() };
+
+        assert_eq!(l1_origin_number(&attributes(Some(vec![deposit.into()]))), Some(7));
+        assert_eq!(l1_origin_number(&attributes(Some(vec![Bytes::from_static(&[0x02])]))), None);
+        assert_eq!(l1_origin_number(&attributes(Some(vec![]))), None);
+        assert_eq!(l1_origin_number(&attributes(None)), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_report_on_failure() {
+        // The chain is not in the superchain registry, and the host has no rollup config to serve.
+        let path = std::env::temp_dir().join(format!("kona-report-{}.json", std::process::id()));
+        let cfg = HostCli {
+            l2_chain_id: Some(0xdead),
+            native: true,
+            report: Some(path.clone()),
+            ..Default::default()
+        };
+        assert!(start_server_and_reported_native_client(cfg).await.is_err());
+
+        let report: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report["result"]["status"], "error");
+        assert_eq!(report["boot_info"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_run_result() {
+        let result = RunResult::new(&Ok(Ok(())));
+        assert_eq!(result, RunResult { status: RunStatus::Valid, error: None, message: None });
+
+        let result = RunResult::new(&Err(anyhow!("no L1 node")));
+        assert_eq!(result.status, RunStatus::Error);
+        assert_eq!(result.error, Some("Host"));
+        assert_eq!(result.message.as_deref(), Some("no L1 node"));
+    }
+}
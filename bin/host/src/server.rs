@@ -6,6 +6,7 @@ use crate::{
     preimage::{
         OfflineHintRouter, OfflinePreimageFetcher, OnlineHintRouter, OnlinePreimageFetcher,
     },
+    report::{RecordingPreimageFetcher, SharedRunStats},
 };
 use anyhow::{anyhow, Result};
 use kona_preimage::{
@@ -34,6 +35,8 @@ where
     /// The fetcher for fetching preimages from a remote source. If [None], the server will only
     /// serve preimages that are already in the key-value store.
     fetcher: Option<Arc<RwLock<Fetcher<KV>>>>,
+    /// The statistics that the served preimages are recorded into, if any.
+    stats: Option<SharedRunStats>,
 }
 
 impl<P, H, KV> PreimageServer<P, H, KV>
@@ -51,7 +54,13 @@ where
         kv_store: Arc<RwLock<KV>>,
         fetcher: Option<Arc<RwLock<Fetcher<KV>>>>,
     ) -> Self {
-        Self { oracle_server, hint_reader, kv_store, fetcher }
+        Self { oracle_server, hint_reader, kv_store, fetcher, stats: None }
+    }
+
+    /// Sets the [SharedRunStats] that the served preimages are recorded into.
+    pub fn with_stats(mut self, stats: Option<SharedRunStats>) -> Self {
+        self.stats = stats;
+        self
     }
 
     /// Starts the [PreimageServer] and waits for incoming requests.
@@ -61,6 +70,7 @@ where
             self.kv_store.clone(),
             self.fetcher.clone(),
             self.oracle_server,
+            self.stats.clone(),
         ));
         let hint_router = spawn(Self::start_hint_router(self.hint_reader, self.fetcher));
 
@@ -77,6 +87,7 @@ where
         kv_store: Arc<RwLock<KV>>,
         fetcher: Option<Arc<RwLock<Fetcher<KV>>>>,
         oracle_server: P,
+        stats: Option<SharedRunStats>,
     ) -> Result<()> {
         #[inline(always)]
         async fn do_loop<F, P>(fetcher: &F, server: &P) -> Result<()>
@@ -98,9 +109,11 @@ where
 
         info!("Starting oracle server");
         if let Some(fetcher) = fetcher.as_ref() {
-            do_loop(&OnlinePreimageFetcher::new(Arc::clone(fetcher)), &oracle_server).await
+            let fetcher = OnlinePreimageFetcher::new(Arc::clone(fetcher));
+            do_loop(&RecordingPreimageFetcher::new(fetcher, stats), &oracle_server).await
         } else {
-            do_loop(&OfflinePreimageFetcher::new(Arc::clone(&kv_store)), &oracle_server).await
+            let fetcher = OfflinePreimageFetcher::new(Arc::clone(&kv_store));
+            do_loop(&RecordingPreimageFetcher::new(fetcher, stats), &oracle_server).await
         }
     }
 
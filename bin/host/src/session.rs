@@ -0,0 +1,436 @@
+//! This module contains the [SessionServer], which serves many concurrent client sessions over a
+//! Unix domain socket.
+//!
+//! A session is a pair of connections to the server's socket, one carrying preimage requests and
+//! one carrying hints. Each connection begins with a handshake frame, `len (u32 BE) ++ json`, where
+//! `json` is a [SessionHandshake] naming the channel that the connection carries. The preimage
+//! connection arrives first and carries the [SessionBoot] information of the session, and the
+//! server replies to it with the identifier it assigned to the session, as a `u64 BE`. The hint
+//! connection then names that identifier. Once both connections of a session have arrived, the
+//! session is served like a single client of the [PreimageServer] until its channels close.
+//!
+//! Identifiers are random, so that one client cannot claim the session of another. A session whose
+//! hint connection does not arrive within [PENDING_SESSION_TIMEOUT] is dropped, and at most
+//! [MAX_PENDING_SESSIONS] sessions wait for their hint connection at a time.
+//!
+//! Sessions keep their boot information in their own [LocalKeyValueStore], and share the remote
+//! key-value store, RPC providers and in-flight fetches of the server, so that preimages fetched
+//! for one session are served to all others from the store.
+//!
+//! [PreimageServer]: crate::server::PreimageServer
+
+use crate::{
+    blobs::OnlineBlobProvider,
+    cli::HostCli,
+    fetcher::{Fetcher, InFlightHints},
+    kv::{
+        ConcurrentKeyValueStore, KeyValueStore, LocalKeyValueStore, SharedKeyValueStore,
+        SplitKeyValueStore,
+    },
+    preflight::{preflight, RpcCapabilities},
+    start_native_preimage_server,
+    transport::HostProvider,
+};
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, Result};
+use kona_preimage::{Channel, UnixChannel, UnixChannelListener};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    spawn,
+    sync::{Mutex, RwLock, Semaphore},
+};
+use tracing::{error, info, warn};
+
+/// The maximum length of a [SessionHandshake] frame, in bytes.
+const MAX_HANDSHAKE_LEN: usize = 64 * 1024;
+
+/// The time a session may wait for its hint connection before it is dropped.
+pub const PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum number of sessions that may wait for their hint connection at a time.
+pub const MAX_PENDING_SESSIONS: usize = 256;
+
+/// The boot information of a client session, served to the client program from the session's
+/// [LocalKeyValueStore]. The rollup config of the session is the one the [SessionServer] was
+/// configured with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBoot {
+    /// Hash of the L1 head block.
+    pub l1_head: B256,
+    /// Hash of the agreed upon safe L2 block.
+    pub agreed_l2_head_hash: B256,
+    /// Agreed safe L2 output root to start derivation from.
+    pub agreed_l2_output_root: B256,
+    /// Claimed L2 output root to validate.
+    pub claimed_l2_output_root: B256,
+    /// Number of the L2 block that the claimed output root commits to.
+    pub claimed_l2_block_number: u64,
+}
+
+/// The handshake that begins each connection to a [SessionServer].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "channel", rename_all = "kebab-case")]
+pub enum SessionHandshake {
+    /// The connection carries the preimage requests of a new session.
+    Preimage {
+        /// The boot information of the session.
+        boot: SessionBoot,
+    },
+    /// The connection carries the hints of the session.
+    Hint {
+        /// The identifier that the server assigned to the session.
+        session: u64,
+    },
+}
+
+impl SessionHandshake {
+    /// Writes the handshake frame to the given [Channel].
+    pub async fn write<C: Channel + Send + Sync>(&self, channel: &C) -> Result<()> {
+        let json = serde_json::to_vec(self)?;
+        let frame = [(json.len() as u32).to_be_bytes().as_slice(), &json].concat();
+        channel.write(&frame).await.map_err(|e| anyhow!("Failed to write handshake: {e}"))?;
+        Ok(())
+    }
+
+    /// Reads a handshake frame from the given [Channel].
+    pub async fn read<C: Channel + Send + Sync>(channel: &C) -> Result<Self> {
+        let mut len = [0u8; 4];
+        channel.read_exact(&mut len).await.map_err(|e| anyhow!("Failed to read handshake: {e}"))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_HANDSHAKE_LEN {
+            bail!("Handshake of {len} bytes exceeds the maximum of {MAX_HANDSHAKE_LEN} bytes");
+        }
+
+        let mut json = vec![0u8; len];
+        channel
+            .read_exact(&mut json)
+            .await
+            .map_err(|e| anyhow!("Failed to read handshake: {e}"))?;
+        serde_json::from_slice(&json).map_err(|e| anyhow!("Invalid handshake: {e}"))
+    }
+}
+
+/// Connects a new client session to the [SessionServer] listening on the Unix domain socket at
+/// `path`.
+///
+/// ## Returns
+/// - `Ok((hint_chan, preimage_chan))`: The hint and preimage channels of the session.
+/// - `Err(_)`: The server could not be reached, or refused the session.
+pub async fn connect_session(
+    path: impl AsRef<Path>,
+    boot: SessionBoot,
+) -> Result<(UnixChannel, UnixChannel)> {
+    let preimage_chan = UnixChannel::connect(&path).await?;
+    SessionHandshake::Preimage { boot }.write(&preimage_chan).await?;
+    let mut session = [0u8; 8];
+    preimage_chan
+        .read_exact(&mut session)
+        .await
+        .map_err(|e| anyhow!("Server refused the session: {e}"))?;
+
+    let hint_chan = UnixChannel::connect(&path).await?;
+    SessionHandshake::Hint { session: u64::from_be_bytes(session) }.write(&hint_chan).await?;
+    Ok((hint_chan, preimage_chan))
+}
+
+/// A session whose hint connection has not arrived yet.
+#[derive(Debug)]
+struct PendingSession {
+    /// The preimage connection of the session.
+    preimage_chan: UnixChannel,
+    /// The boot information of the session.
+    boot: SessionBoot,
+    /// When the preimage connection arrived.
+    since: Instant,
+}
+
+/// The [SessionServer] accepts client sessions on a Unix domain socket, and serves each of them
+/// concurrently until its channels close.
+#[derive(Debug)]
+pub struct SessionServer {
+    /// The host configuration. The boot information of each session overrides the boot
+    /// information in this configuration.
+    cfg: HostCli,
+    /// The listener that sessions connect to.
+    listener: UnixChannelListener,
+    /// The key-value store for remote preimages, shared by all sessions.
+    remote_kv_store: ConcurrentKeyValueStore<dyn KeyValueStore + Send + Sync>,
+    /// The L1, blob and L2 providers, shared by all sessions. If [None], the server only serves
+    /// preimages that are already in the key-value store.
+    providers: Option<(HostProvider, OnlineBlobProvider, HostProvider)>,
+    /// The capabilities of the providers, as probed by the [preflight] checks.
+    capabilities: RpcCapabilities,
+    /// The hints that are being prefetched on behalf of any session.
+    in_flight: Arc<InFlightHints>,
+    /// The permits to fetch hints, shared by all sessions.
+    fetch_permits: Arc<Semaphore>,
+    /// The sessions that are waiting for their hint connection, by identifier.
+    pending: Mutex<HashMap<u64, PendingSession>>,
+}
+
+impl SessionServer {
+    /// Create a new [SessionServer] that accepts sessions from the given [UnixChannelListener].
+    pub async fn new(cfg: HostCli, listener: UnixChannelListener) -> Result<Self> {
+        let (providers, capabilities) = if !cfg.is_offline() {
+            let (l1_provider, blob_provider, l2_provider) = cfg.create_providers().await?;
+            let capabilities = preflight(&cfg, &l1_provider, &blob_provider, &l2_provider).await?;
+            (Some((l1_provider, blob_provider, l2_provider)), capabilities)
+        } else {
+            (None, RpcCapabilities::default())
+        };
+        Ok(Self {
+            remote_kv_store: cfg.construct_remote_kv_store(),
+            fetch_permits: cfg.fetch_permits(),
+            cfg,
+            listener,
+            providers,
+            capabilities,
+            in_flight: Default::default(),
+            pending: Default::default(),
+        })
+    }
+
+    /// Starts the [SessionServer] and accepts sessions until the listener fails.
+    pub async fn start(self) -> Result<()> {
+        info!(target: "session_server", "Accepting client sessions");
+        let server = Arc::new(self);
+        loop {
+            let channel = server.listener.accept().await?;
+            let server = Arc::clone(&server);
+            spawn(async move {
+                if let Err(e) = server.handle_connection(channel).await {
+                    warn!(target: "session_server", "Closed connection: {e}");
+                }
+            });
+        }
+    }
+
+    /// Reads the handshake of a new connection. A preimage connection opens a new session, and a
+    /// hint connection completes a pending session, which is then served.
+    async fn handle_connection(&self, channel: UnixChannel) -> Result<()> {
+        let handshake = SessionHandshake::read(&channel).await?;
+
+        let mut pending = self.pending.lock().await;
+        // Dropping the preimage connection of an expired session closes it for the client.
+        pending.retain(|_, p| p.since.elapsed() < PENDING_SESSION_TIMEOUT);
+
+        let (session, PendingSession { preimage_chan, boot, .. }) = match handshake {
+            SessionHandshake::Preimage { boot } => {
+                if pending.len() >= MAX_PENDING_SESSIONS {
+                    bail!("Too many sessions are waiting for their hint connection");
+                }
+                let session = loop {
+                    let session = rand::random();
+                    if !pending.contains_key(&session) {
+                        break session;
+                    }
+                };
+                channel
+                    .write(&u64::to_be_bytes(session))
+                    .await
+                    .map_err(|e| anyhow!("Failed to assign session: {e}"))?;
+                pending.insert(
+                    session,
+                    PendingSession { preimage_chan: channel, boot, since: Instant::now() },
+                );
+                return Ok(());
+            }
+            SessionHandshake::Hint { session } => (
+                session,
+                pending.remove(&session).ok_or_else(|| anyhow!("Unknown session {session}"))?,
+            ),
+        };
+        drop(pending);
+        let hint_chan = channel;
+
+        info!(target: "session_server", "Serving session {session}");
+        match self.serve_session(boot, hint_chan, preimage_chan).await {
+            Ok(()) => info!(target: "session_server", "Session {session} has exited"),
+            Err(e) => error!(target: "session_server", "Session {session} failed: {e}"),
+        }
+        Ok(())
+    }
+
+    /// Serves a session over its hint and preimage channels until they close.
+    async fn serve_session(
+        &self,
+        boot: SessionBoot,
+        hint_chan: UnixChannel,
+        preimage_chan: UnixChannel,
+    ) -> Result<()> {
+        let local_kv_store = LocalKeyValueStore::new(HostCli {
+            l1_head: boot.l1_head,
+            agreed_l2_head_hash: boot.agreed_l2_head_hash,
+            agreed_l2_output_root: boot.agreed_l2_output_root,
+            claimed_l2_output_root: boot.claimed_l2_output_root,
+            claimed_l2_block_number: boot.claimed_l2_block_number,
+            ..self.cfg.clone()
+        });
+        let kv_store: SharedKeyValueStore = Arc::new(RwLock::new(SplitKeyValueStore::new(
+            local_kv_store,
+            self.remote_kv_store.clone(),
+        )));
+
+        let fetcher = self.providers.as_ref().map(|(l1_provider, blob_provider, l2_provider)| {
+            Arc::new(RwLock::new(
+                Fetcher::new(
+                    kv_store.clone(),
+                    l1_provider.clone(),
+                    blob_provider.clone(),
+                    l2_provider.clone(),
+                    boot.agreed_l2_head_hash,
+                )
+                .with_generic_preimage_dir(self.cfg.generic_preimage_dir.clone())
+                .with_in_flight_hints(Arc::clone(&self.in_flight))
+                .with_capabilities(self.capabilities)
+                .with_fetch_permits(Arc::clone(&self.fetch_permits))
+                .with_retries(
+                    self.cfg.fetch_retries,
+                    Duration::from_millis(self.cfg.fetch_retry_delay_ms),
+                ),
+            ))
+        });
+
+        start_native_preimage_server(kv_store, fetcher, hint_chan, preimage_chan).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{b256, keccak256};
+    use kona_preimage::{
+        HintWriter, HintWriterClient, OracleReader, PreimageKey, PreimageKeyType,
+        PreimageOracleClient,
+    };
+    use kona_proof::boot::{L1_HEAD_KEY, L2_CLAIM_BLOCK_NUMBER_KEY};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_session_server() {
+        let dir = std::env::temp_dir().join(format!("kona-session-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("kona.sock");
+        let _ = std::fs::remove_file(&path);
+
+        let cfg =
+            HostCli { listen: Some(path.clone()), l2_chain_id: Some(10), ..Default::default() };
+        let listener = UnixChannelListener::bind(&path).unwrap();
+        let server = SessionServer::new(cfg, listener).await.unwrap();
+
+        // Seed the shared store with a preimage that every session reads.
+        let shared = b"shared preimage";
+        let shared_key = PreimageKey::new(*keccak256(shared), PreimageKeyType::Keccak256);
+        server.remote_kv_store.clone().set(shared_key.into(), shared.to_vec()).unwrap();
+        let server = spawn(server.start());
+
+        let sessions = (0..4u64)
+            .map(|i| {
+                let path = path.clone();
+                spawn(async move {
+                    let boot = SessionBoot {
+                        l1_head: B256::with_last_byte(i as u8),
+                        agreed_l2_head_hash: B256::ZERO,
+                        agreed_l2_output_root: B256::ZERO,
+                        claimed_l2_output_root: b256!(
+                            "0000000000000000000000000000000000000000000000000000000000000001"
+                        ),
+                        claimed_l2_block_number: 100 + i,
+                    };
+                    let (hint_chan, preimage_chan) = connect_session(&path, boot).await.unwrap();
+                    let hint_writer = HintWriter::new(hint_chan);
+                    let oracle = OracleReader::new(preimage_chan);
+
+                    hint_writer.write("l1-block-header 0x01").await.unwrap();
+                    let l1_head = oracle.get(PreimageKey::new_local(L1_HEAD_KEY.to())).await;
+                    let block_number =
+                        oracle.get(PreimageKey::new_local(L2_CLAIM_BLOCK_NUMBER_KEY.to())).await;
+                    assert_eq!(l1_head.unwrap(), boot.l1_head.as_slice());
+                    assert_eq!(block_number.unwrap(), boot.claimed_l2_block_number.to_be_bytes());
+                    assert_eq!(oracle.get(shared_key).await.unwrap(), shared);
+                })
+            })
+            .collect::<Vec<_>>();
+        for session in sessions {
+            session.await.unwrap();
+        }
+
+        server.abort();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_server_pending() {
+        let dir = std::env::temp_dir().join(format!("kona-session-pending-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("kona.sock");
+        let _ = std::fs::remove_file(&path);
+
+        let cfg =
+            HostCli { listen: Some(path.clone()), l2_chain_id: Some(10), ..Default::default() };
+        let listener = UnixChannelListener::bind(&path).unwrap();
+        let server = SessionServer::new(cfg, listener).await.unwrap();
+        let boot = SessionBoot {
+            l1_head: B256::ZERO,
+            agreed_l2_head_hash: B256::ZERO,
+            agreed_l2_output_root: B256::ZERO,
+            claimed_l2_output_root: B256::ZERO,
+            claimed_l2_block_number: 0,
+        };
+        let server = &server;
+        let open = |handshake: SessionHandshake| async move {
+            let (client, server_chan) = UnixChannel::pair().unwrap();
+            handshake.write(&client).await.unwrap();
+            (client, server.handle_connection(server_chan).await)
+        };
+
+        // A hint connection must name a session that the server assigned.
+        assert!(open(SessionHandshake::Hint { session: 7 }).await.1.is_err());
+
+        // New sessions are refused while too many are pending...
+        let mut clients = Vec::new();
+        for _ in 0..MAX_PENDING_SESSIONS {
+            let (client, result) = open(SessionHandshake::Preimage { boot }).await;
+            result.unwrap();
+            clients.push(client);
+        }
+        assert!(open(SessionHandshake::Preimage { boot }).await.1.is_err());
+
+        // ...until the pending sessions time out.
+        for pending in server.pending.lock().await.values_mut() {
+            pending.since -= PENDING_SESSION_TIMEOUT;
+        }
+        open(SessionHandshake::Preimage { boot }).await.1.unwrap();
+        assert_eq!(server.pending.lock().await.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_handshake_roundtrip() {
+        let (a, b) = UnixChannel::pair().unwrap();
+        let handshake = SessionHandshake::Hint { session: 7 };
+        handshake.write(&a).await.unwrap();
+        assert_eq!(SessionHandshake::read(&b).await.unwrap(), handshake);
+
+        let boot = SessionBoot {
+            l1_head: B256::ZERO,
+            agreed_l2_head_hash: B256::ZERO,
+            agreed_l2_output_root: B256::ZERO,
+            claimed_l2_output_root: B256::ZERO,
+            claimed_l2_block_number: 0,
+        };
+        let handshake = SessionHandshake::Preimage { boot };
+        handshake.write(&a).await.unwrap();
+        assert_eq!(SessionHandshake::read(&b).await.unwrap(), handshake);
+
+        a.write(&u32::MAX.to_be_bytes()).await.unwrap();
+        assert!(SessionHandshake::read(&b).await.is_err());
+    }
+}
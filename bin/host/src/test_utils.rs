@@ -0,0 +1,219 @@
+//! Test utilities for the host program.
+
+use alloy_consensus::Header;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{b256, keccak256, Bytes, Sealable, B256};
+use alloy_rlp::Encodable;
+use alloy_rpc_types::{Block, BlockTransactions, EIP1186AccountProofResponse, Transaction};
+use op_alloy_consensus::{OpTxEnvelope, TxDeposit};
+use op_alloy_protocol::{L1BlockInfoEcotone, L1BlockInfoTx};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// A handler for the requests served by a [MockRpc], which maps a method and its parameters to
+/// the result of the call, or to an error message.
+type Handler = dyn Fn(&str, &Value) -> Result<Value, String> + Send + Sync;
+
+/// A JSON-RPC server on localhost that serves canned responses over HTTP, standing in for the
+/// nodes that the host talks to.
+///
+/// `GET` requests, such as those of the beacon API, are served by calling the handler with the
+/// request path as the method and `null` parameters. The result is the response body, and errors
+/// are served with a `404` status.
+#[derive(Debug)]
+pub(crate) struct MockRpc {
+    /// The URL of the server.
+    pub(crate) url: String,
+    /// The task serving the requests.
+    task: JoinHandle<()>,
+}
+
+impl MockRpc {
+    /// Starts a [MockRpc] that serves requests with the given handler.
+    pub(crate) async fn serve(
+        handler: impl Fn(&str, &Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(Self::serve_connection(stream, Arc::clone(&handler)));
+            }
+        });
+        Self { url, task }
+    }
+
+    /// Serves the requests of a single HTTP connection until it is closed.
+    async fn serve_connection(stream: TcpStream, handler: Arc<Handler>) {
+        let mut stream = BufReader::new(stream);
+        loop {
+            // Read the request line and headers, keeping only the content length.
+            let mut request_line = String::new();
+            if stream.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+
+            let mut body = vec![0u8; content_length];
+            if stream.read_exact(&mut body).await.is_err() {
+                return;
+            }
+            let (status, response) = request_line.strip_prefix("GET ").map_or_else(
+                || ("200 OK", Self::respond_rpc(handler.as_ref(), &body)),
+                |path| Self::respond_get(handler.as_ref(), path),
+            );
+
+            let body = response.to_string();
+            let head = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n",
+                body.len()
+            );
+            if stream
+                .get_mut()
+                .write_all(&[head.as_bytes(), body.as_bytes()].concat())
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Responds to the body of a JSON-RPC request, which holds a single request or a batch.
+    fn respond_rpc(handler: &Handler, body: &[u8]) -> Value {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(requests)) => {
+                Value::Array(requests.iter().map(|r| Self::respond(handler, r)).collect())
+            }
+            Ok(request) => Self::respond(handler, &request),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": e.to_string() },
+            }),
+        }
+    }
+
+    /// Responds to a `GET` request for the path in the given request target, returning the status
+    /// and body of the response.
+    fn respond_get(handler: &Handler, target: &str) -> (&'static str, Value) {
+        let path = target.split_whitespace().next().unwrap_or_default();
+        match handler(path, &Value::Null) {
+            Ok(body) => ("200 OK", body),
+            Err(message) => ("404 Not Found", json!({ "message": message })),
+        }
+    }
+
+    /// Responds to a single JSON-RPC request.
+    fn respond(handler: &Handler, request: &Value) -> Value {
+        let method = request["method"].as_str().unwrap_or_default();
+        match handler(method, &request["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err(message) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32000, "message": message },
+            }),
+        }
+    }
+}
+
+impl MockRpc {
+    /// Starts a [MockRpc] that serves the L2 node of the mock chain. The L1 origin of each L2
+    /// block is the L1 block at half its number.
+    pub(crate) async fn l2_node() -> Self {
+        Self::serve(|method, params| match method {
+            "eth_getBlockByNumber" => Ok(mock_block(parse_quantity(&params[0]))),
+            "debug_getRawHeader" => {
+                let hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+                let (header, _) = (0..256).map(mock_header).find(|(_, h)| *h == hash).unwrap();
+                let mut buf = Vec::new();
+                header.encode(&mut buf);
+                Ok(json!(Bytes::from(buf)))
+            }
+            "eth_getProof" => Ok(serde_json::to_value(EIP1186AccountProofResponse {
+                storage_hash: MESSAGE_PASSER_STORAGE_ROOT,
+                ..Default::default()
+            })
+            .unwrap()),
+            "debug_getRawTransaction" => {
+                let tx_hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+                let l1_info = L1BlockInfoTx::Ecotone(L1BlockInfoEcotone {
+                    number: tx_hash[31] as u64 / 2,
+                    ..Default::default()
+                });
+                let deposit = TxDeposit { input: l1_info.encode_calldata(), ..Default::default() };
+                Ok(json!(Bytes::from(OpTxEnvelope::Deposit(deposit.seal_slow()).encoded_2718())))
+            }
+            _ => Err(format!("unsupported method {method}")),
+        })
+        .await
+    }
+}
+
+impl Drop for MockRpc {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The storage root of the message passer in every L2 block of the mock chain.
+pub(crate) const MESSAGE_PASSER_STORAGE_ROOT: B256 =
+    b256!("00000000000000000000000000000000000000000000000000000000000000aa");
+
+/// Returns the header of the block with the given number in the mock chain, and its hash.
+pub(crate) fn mock_header(number: u64) -> (Header, B256) {
+    let header =
+        Header { number, state_root: B256::with_last_byte(number as u8), ..Default::default() };
+    let hash = header.hash_slow();
+    (header, hash)
+}
+
+/// Returns the block with the given number in the mock chain, as served by
+/// `eth_getBlockByNumber`.
+pub(crate) fn mock_block(number: u64) -> Value {
+    let (header, _) = mock_header(number);
+    let block = Block::<Transaction> {
+        header: alloy_rpc_types::Header::from_consensus(header.seal_slow(), None, None),
+        transactions: BlockTransactions::Hashes(vec![B256::with_last_byte(number as u8)]),
+        ..Default::default()
+    };
+    serde_json::to_value(block).unwrap()
+}
+
+/// Returns the output root of the L2 block with the given number in the mock chain.
+pub(crate) fn mock_output_root(number: u64) -> B256 {
+    let (header, hash) = mock_header(number);
+    let mut raw_output = [0u8; 128];
+    raw_output[32..64].copy_from_slice(header.state_root.as_slice());
+    raw_output[64..96].copy_from_slice(MESSAGE_PASSER_STORAGE_ROOT.as_slice());
+    raw_output[96..128].copy_from_slice(hash.as_slice());
+    keccak256(raw_output)
+}
+
+/// Parses a hex-encoded JSON-RPC quantity.
+pub(crate) fn parse_quantity(value: &Value) -> u64 {
+    u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
@@ -0,0 +1,92 @@
+//! This module contains the [CountingTransport], the HTTP transport of the JSON-RPC providers of
+//! the host, which counts the requests that it sends for the run report.
+
+use alloy_json_rpc::{RequestPacket, ResponsePacket};
+use alloy_provider::RootProvider;
+use alloy_transport::{TransportError, TransportFut};
+use alloy_transport_http::Http;
+use reqwest::Client;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+use tower::Service;
+
+/// A JSON-RPC provider of the host, over a [CountingTransport].
+pub type HostProvider = RootProvider<CountingTransport>;
+
+/// An HTTP transport that counts the JSON-RPC requests that it sends. Each request of a batch is
+/// counted.
+#[derive(Debug, Clone)]
+pub struct CountingTransport {
+    /// The HTTP transport that sends the requests.
+    inner: Http<Client>,
+    /// The number of JSON-RPC requests sent, shared by the clones of the transport.
+    requests: Arc<AtomicU64>,
+}
+
+impl CountingTransport {
+    /// Creates a new [CountingTransport] that sends requests over `inner`.
+    pub fn new(inner: Http<Client>) -> Self {
+        Self { inner, requests: Arc::new(AtomicU64::new(0)) }
+    }
+
+    /// Returns the number of JSON-RPC requests that the transport has sent.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+}
+
+impl Service<RequestPacket> for CountingTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.requests.fetch_add(req.len() as u64, Ordering::Relaxed);
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_utils::MockRpc, HostCli};
+    use alloy_provider::Provider;
+    use alloy_rpc_client::BatchRequest;
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_counting_transport() {
+        let node = MockRpc::serve(|method, _| match method {
+            "eth_chainId" => Ok(json!("0xa")),
+            _ => Err(format!("the method {method} does not exist/is not available")),
+        })
+        .await;
+        let provider = HostCli::http_provider(&node.url).unwrap();
+        let transport = provider.client().transport();
+        assert_eq!(transport.requests(), 0);
+
+        // Allocating a request ID does not send a request.
+        provider.client().next_id();
+        assert_eq!(transport.requests(), 0);
+
+        provider.get_chain_id().await.unwrap();
+        assert!(provider.get_block_number().await.is_err());
+        assert_eq!(transport.requests(), 2);
+
+        // Each request of a batch is counted.
+        let mut batch = BatchRequest::new(provider.client());
+        let first = batch.add_call::<_, String>("eth_chainId", &()).unwrap();
+        let second = batch.add_call::<_, String>("eth_chainId", &()).unwrap();
+        batch.send().await.unwrap();
+        assert_eq!((first.await.unwrap(), second.await.unwrap()), ("0xa".into(), "0xa".into()));
+        assert_eq!(transport.requests(), 4);
+    }
+}
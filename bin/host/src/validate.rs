@@ -0,0 +1,116 @@
+//! This module contains the `validate-rollup-config` subcommand of the host, which checks a rollup
+//! config for internal consistency and diffs it against the superchain registry.
+
+use crate::cli::HostCli;
+use anyhow::Result;
+use kona_proof::{
+    errors::RollupConfigError,
+    rollup_config::{
+        check_rollup_config, diff_rollup_configs, rollup_config_hash, RollupConfigDiff,
+    },
+};
+use op_alloy_genesis::RollupConfig;
+use op_alloy_registry::ROLLUP_CONFIGS;
+use std::path::Path;
+
+/// The result of validating a [RollupConfig].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollupConfigValidation {
+    /// The internal inconsistencies of the config.
+    pub errors: Vec<RollupConfigError>,
+    /// The L2 chain ID that the config was looked up by in the superchain registry.
+    pub chain_id: u64,
+    /// The fields that differ from the config in the superchain registry, or [None] if the
+    /// registry has no config for the chain.
+    pub diffs: Option<Vec<RollupConfigDiff>>,
+}
+
+impl RollupConfigValidation {
+    /// Validates the [RollupConfig], diffing it against the superchain registry config of
+    /// `l2_chain_id`, or of its own `l2_chain_id` if [None].
+    pub fn new(config: &RollupConfig, l2_chain_id: Option<u64>) -> Self {
+        let chain_id = l2_chain_id.unwrap_or(config.l2_chain_id);
+        Self {
+            errors: check_rollup_config(config),
+            chain_id,
+            diffs: ROLLUP_CONFIGS
+                .get(&chain_id)
+                .map(|expected| diff_rollup_configs(expected, config)),
+        }
+    }
+
+    /// Returns `true` if the config is internally consistent, and does not differ from the
+    /// superchain registry.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty() && self.diffs.iter().all(Vec::is_empty)
+    }
+}
+
+/// Validates the rollup config at `path`, printing its hash, its problems and its differences from
+/// the superchain registry.
+///
+/// ## Returns
+/// - `Ok(0)` if the config is valid.
+/// - `Ok(1)` if the config has problems, or differs from the superchain registry.
+/// - `Err(_)` if the config could not be read.
+pub fn validate_rollup_config(path: &Path, l2_chain_id: Option<u64>) -> Result<i32> {
+    let cfg = HostCli { rollup_config_path: Some(path.to_path_buf()), ..Default::default() };
+    let config = cfg.read_rollup_config()?;
+    let validation = RollupConfigValidation::new(&config, l2_chain_id);
+
+    println!("Rollup config hash: {}", rollup_config_hash(&config));
+    for error in validation.errors.iter() {
+        println!("error: {error}");
+    }
+    match validation.diffs.as_ref() {
+        Some(diffs) => {
+            for diff in diffs {
+                println!(
+                    "differs from the superchain registry: {}: expected {}, found {}",
+                    diff.field, diff.expected, diff.actual
+                );
+            }
+        }
+        None => println!("No config for chain {} in the superchain registry", validation.chain_id),
+    }
+
+    if validation.is_valid() {
+        println!("Rollup config at {} is valid", path.display());
+        Ok(0)
+    } else {
+        println!("Rollup config at {} is invalid", path.display());
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rollup_config_validation() {
+        let mainnet = ROLLUP_CONFIGS.get(&10).unwrap();
+        let validation = RollupConfigValidation::new(mainnet, None);
+        assert_eq!(validation.diffs, Some(Vec::new()));
+        assert!(validation.is_valid());
+
+        let mut config = mainnet.clone();
+        config.block_time = 0;
+        let validation = RollupConfigValidation::new(&config, None);
+        assert_eq!(validation.errors, [RollupConfigError::ZeroBlockTime]);
+        assert_eq!(validation.diffs.as_ref().unwrap()[0].field, "block_time");
+        assert!(!validation.is_valid());
+
+        // A custom chain is only checked for internal consistency.
+        config.block_time = 2;
+        config.l2_chain_id = 0xbeefbabe;
+        let validation = RollupConfigValidation::new(&config, None);
+        assert_eq!(validation.diffs, None);
+        assert!(validation.is_valid());
+
+        // Unless it is diffed against a chain in the registry.
+        let validation = RollupConfigValidation::new(&config, Some(10));
+        assert_eq!(validation.diffs.as_ref().unwrap()[0].field, "l2_chain_id");
+        assert!(!validation.is_valid());
+    }
+}
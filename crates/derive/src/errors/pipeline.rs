@@ -98,6 +98,11 @@ pub enum PipelineError {
     /// Provider error variant.
     #[error("Blob provider error: {0}")]
     Provider(String),
+    /// The [PipelineSnapshot] is missing the state of a stage.
+    ///
+    /// [PipelineSnapshot]: crate::types::PipelineSnapshot
+    #[error("Pipeline snapshot is missing state for the {0} stage")]
+    MissingSnapshot(&'static str),
 }
 
 impl PipelineError {
@@ -5,7 +5,7 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
 extern crate alloc;
 
@@ -17,11 +17,16 @@ pub mod prelude {
     pub use crate::{
         attributes::*, errors::*, pipeline::*, sources::*, stages::*, traits::*, types::*,
     };
+
+    #[cfg(any(test, feature = "std"))]
+    pub use crate::prefetch::*;
 }
 
 pub mod attributes;
 pub mod errors;
 pub mod pipeline;
+#[cfg(any(test, feature = "std"))]
+pub mod prefetch;
 pub mod sources;
 pub mod stages;
 pub mod traits;
@@ -4,8 +4,9 @@ use crate::{
     errors::{PipelineError, PipelineErrorKind},
     traits::{
         L2ChainProvider, NextAttributes, OriginAdvancer, OriginProvider, Pipeline, SignalReceiver,
+        StageSnapshot,
     },
-    types::{ActivationSignal, PipelineResult, ResetSignal, Signal, StepResult},
+    types::{ActivationSignal, PipelineResult, PipelineSnapshot, ResetSignal, Signal, StepResult},
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use async_trait::async_trait;
@@ -48,6 +49,39 @@ where
     }
 }
 
+impl<S, P> DerivationPipeline<S, P>
+where
+    S: NextAttributes
+        + SignalReceiver
+        + OriginProvider
+        + OriginAdvancer
+        + StageSnapshot
+        + Debug
+        + Send,
+    P: L2ChainProvider + Send + Sync + Debug,
+{
+    /// Captures a [PipelineSnapshot] of the prepared attributes and the internal state of every
+    /// stage in the pipeline.
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        let mut snapshot =
+            PipelineSnapshot { prepared: self.prepared.clone(), ..Default::default() };
+        self.attributes.snapshot(&mut snapshot);
+        snapshot
+    }
+
+    /// Restores the prepared attributes and the internal state of every stage in the pipeline
+    /// from a [PipelineSnapshot].
+    ///
+    /// Stages are restored from the bottom [crate::stages::L1Traversal] on up, so that a
+    /// pipeline can resume derivation from the exact point the snapshot was captured without
+    /// replaying the L1 lookback window after a [`Signal::Reset`].
+    pub async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.attributes.restore(snapshot).await?;
+        self.prepared = snapshot.prepared.clone();
+        Ok(())
+    }
+}
+
 impl<S, P> OriginProvider for DerivationPipeline<S, P>
 where
     S: NextAttributes + SignalReceiver + OriginProvider + OriginAdvancer + Debug + Send,
@@ -326,4 +360,48 @@ mod tests {
         let result = pipeline.signal(ResetSignal::default().signal()).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_derivation_pipeline_snapshot_restore() {
+        let mut pipeline = new_test_pipeline();
+        let expected = default_test_payload_attributes();
+        pipeline.prepared.push_back(expected.clone());
+
+        let snapshot = pipeline.snapshot();
+        assert_eq!(snapshot.prepared.len(), 1);
+        assert!(snapshot.l1_traversal.is_some());
+        assert!(snapshot.attributes_queue.is_some());
+
+        let mut restored = new_test_pipeline();
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.next(), Some(expected));
+        assert_eq!(restored.origin(), pipeline.origin());
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn test_derivation_pipeline_restore_serialized_snapshot() {
+        let mut pipeline = new_test_pipeline();
+        // The attributes built by the pipeline always have a gas limit, which their serde
+        // representation requires.
+        let mut expected = default_test_payload_attributes();
+        expected.attributes.gas_limit = Some(30_000_000);
+        pipeline.prepared.push_back(expected.clone());
+
+        let json = serde_json::to_string(&pipeline.snapshot()).unwrap();
+        let snapshot = serde_json::from_str::<PipelineSnapshot>(&json).unwrap();
+        assert_eq!(snapshot, pipeline.snapshot());
+
+        let mut restored = new_test_pipeline();
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.next(), Some(expected));
+        assert_eq!(restored.origin(), pipeline.origin());
+    }
+
+    #[tokio::test]
+    async fn test_derivation_pipeline_restore_missing_snapshot() {
+        let mut pipeline = new_test_pipeline();
+        let result = pipeline.restore(&PipelineSnapshot::default()).await.unwrap_err();
+        assert_eq!(result, PipelineError::MissingSnapshot("l1 traversal").crit());
+    }
 }
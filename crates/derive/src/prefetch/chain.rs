@@ -0,0 +1,344 @@
+//! Contains the [PrefetchChainProvider], a [ChainProvider] that fetches L1 blocks ahead of the
+//! [L1Traversal] stage.
+//!
+//! [L1Traversal]: crate::stages::L1Traversal
+
+use crate::traits::ChainProvider;
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use op_alloy_protocol::BlockInfo;
+use std::sync::Mutex;
+use tokio::{sync::watch, task::AbortHandle};
+
+/// An L1 block fetched by the [PrefetchChainProvider].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefetchedBlock {
+    /// The block info.
+    pub info: BlockInfo,
+    /// The block header.
+    pub header: Header,
+    /// The receipts in the block.
+    pub receipts: Vec<Receipt>,
+    /// The transactions in the block.
+    pub transactions: Vec<TxEnvelope>,
+}
+
+/// The result of a prefetch task. [None] if any of the requests failed.
+type PrefetchResult = Option<Arc<PrefetchedBlock>>;
+
+/// An in-flight or completed prefetch of a single L1 block.
+#[derive(Debug)]
+struct PrefetchSlot {
+    /// Receives the result of the prefetch task.
+    rx: watch::Receiver<Option<PrefetchResult>>,
+    /// Aborts the prefetch task.
+    handle: AbortHandle,
+}
+
+impl PrefetchSlot {
+    /// Returns the prefetched block if the task has completed successfully.
+    fn ready(&self) -> Option<Arc<PrefetchedBlock>> {
+        self.rx.borrow().clone().flatten()
+    }
+}
+
+/// The state shared between clones of a [PrefetchChainProvider].
+#[derive(Debug, Default)]
+struct PrefetchState {
+    /// The prefetch window, keyed by block number.
+    blocks: BTreeMap<u64, PrefetchSlot>,
+    /// The last block returned by [ChainProvider::block_info_by_number].
+    last: Option<BlockInfo>,
+    /// Incremented every time the prefetch window is cancelled.
+    epoch: u64,
+}
+
+impl PrefetchState {
+    /// Aborts all in-flight prefetch tasks and clears the window.
+    fn cancel(&mut self) {
+        self.blocks.values().for_each(|slot| slot.handle.abort());
+        self.blocks.clear();
+        self.last = None;
+        self.epoch += 1;
+    }
+
+    /// Returns the prefetched block with the given hash, if it is in the window.
+    fn find(&self, hash: B256) -> Option<Arc<PrefetchedBlock>> {
+        self.blocks.values().filter_map(PrefetchSlot::ready).find(|b| b.info.hash == hash)
+    }
+}
+
+impl Drop for PrefetchState {
+    fn drop(&mut self) {
+        self.blocks.values().for_each(|slot| slot.handle.abort());
+    }
+}
+
+/// A [ChainProvider] that wraps another [ChainProvider] and prefetches the next `lookahead` L1
+/// blocks whenever a block is requested by number.
+///
+/// Blocks are fetched concurrently on the current `tokio` runtime. Requests by hash are served
+/// from the prefetch window when possible, and fall through to the inner provider otherwise.
+///
+/// The window is cancelled when a block outside of it is requested, which happens when the
+/// pipeline is reset, or when a prefetched block does not build on top of the previously returned
+/// block. It may also be cancelled manually with [PrefetchChainProvider::cancel].
+///
+/// Clones of the provider share the same prefetch window.
+#[derive(Debug, Clone)]
+pub struct PrefetchChainProvider<C: ChainProvider> {
+    /// The inner chain provider.
+    inner: C,
+    /// The number of blocks to fetch ahead of the requested block.
+    lookahead: u64,
+    /// The shared prefetch state.
+    state: Arc<Mutex<PrefetchState>>,
+}
+
+impl<C> PrefetchChainProvider<C>
+where
+    C: ChainProvider + Clone + Send + Sync + 'static,
+    C::Error: Send,
+{
+    /// Creates a new [PrefetchChainProvider] that fetches `lookahead` blocks ahead.
+    pub fn new(inner: C, lookahead: u64) -> Self {
+        Self { inner, lookahead, state: Arc::new(Mutex::new(PrefetchState::default())) }
+    }
+
+    /// Returns the number of blocks fetched ahead of the requested block.
+    pub const fn lookahead(&self) -> u64 {
+        self.lookahead
+    }
+
+    /// Aborts all in-flight prefetch tasks and clears the prefetch window.
+    pub fn cancel(&self) {
+        self.lock().cancel();
+    }
+
+    /// Returns the [BlockInfo]s of the prefetched blocks after the given block number that have
+    /// been fetched successfully, in ascending order.
+    pub fn prefetched_after(&self, number: u64) -> Vec<BlockInfo> {
+        let state = self.lock();
+        state
+            .blocks
+            .range(number.saturating_add(1)..)
+            .filter_map(|(_, slot)| slot.ready())
+            .map(|b| b.info)
+            .collect()
+    }
+
+    /// Returns the number of times the prefetch window has been cancelled.
+    pub(crate) fn epoch(&self) -> u64 {
+        self.lock().epoch
+    }
+
+    /// Locks the shared prefetch state.
+    fn lock(&self) -> std::sync::MutexGuard<'_, PrefetchState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the prefetched block with the given hash, if it is in the window.
+    fn find(&self, hash: B256) -> Option<Arc<PrefetchedBlock>> {
+        self.lock().find(hash)
+    }
+
+    /// Spawns a task that fetches the block with the given number.
+    fn spawn(&self, number: u64) -> PrefetchSlot {
+        let (tx, rx) = watch::channel(None);
+        let inner = self.inner.clone();
+        let handle = tokio::spawn(async move {
+            let block = Self::fetch(inner, number).await;
+            let _ = tx.send(Some(block.map(Arc::new)));
+        });
+        PrefetchSlot { rx, handle: handle.abort_handle() }
+    }
+
+    /// Fetches the block info, header, receipts, and transactions of the block with the given
+    /// number. The requests by hash are made concurrently.
+    async fn fetch(mut inner: C, number: u64) -> Option<PrefetchedBlock> {
+        let info = match inner.block_info_by_number(number).await {
+            Ok(info) => info,
+            Err(e) => {
+                debug!(target: "prefetch", "Failed to prefetch block {}: {}", number, e);
+                return None;
+            }
+        };
+        let (mut headers, mut receipts, mut transactions) = (inner.clone(), inner.clone(), inner);
+        let (header, receipts, transactions) = tokio::join!(
+            headers.header_by_hash(info.hash),
+            receipts.receipts_by_hash(info.hash),
+            transactions.block_info_and_transactions_by_hash(info.hash),
+        );
+        match (header, receipts, transactions) {
+            (Ok(header), Ok(receipts), Ok((_, transactions))) => {
+                Some(PrefetchedBlock { info, header, receipts, transactions })
+            }
+            _ => {
+                debug!(target: "prefetch", "Failed to prefetch data for block {}", number);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C> ChainProvider for PrefetchChainProvider<C>
+where
+    C: ChainProvider + Clone + Send + Sync + 'static,
+    C::Error: Send,
+{
+    type Error = C::Error;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        match self.find(hash) {
+            Some(block) => Ok(block.header.clone()),
+            None => self.inner.header_by_hash(hash).await,
+        }
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        let mut rx = {
+            let mut state = self.lock();
+            if !state.blocks.is_empty() && !state.blocks.contains_key(&number) {
+                debug!(target: "prefetch", "Block {} is outside of the prefetch window", number);
+                state.cancel();
+            }
+            state.blocks = state.blocks.split_off(&number);
+            for n in number..=number.saturating_add(self.lookahead) {
+                state.blocks.entry(n).or_insert_with(|| self.spawn(n));
+            }
+            state.blocks[&number].rx.clone()
+        };
+
+        let prefetched = rx.wait_for(Option::is_some).await.ok().and_then(|r| r.clone().flatten());
+
+        let parent = self.lock().last.filter(|last| last.number + 1 == number);
+        let info = match prefetched {
+            Some(block) if parent.iter().all(|p| p.hash == block.info.parent_hash) => block.info,
+            Some(_) => {
+                warn!(target: "prefetch", "Prefetched block {} does not extend the chain", number);
+                self.cancel();
+                self.inner.block_info_by_number(number).await?
+            }
+            None => {
+                self.lock().blocks.remove(&number);
+                self.inner.block_info_by_number(number).await?
+            }
+        };
+        self.lock().last = Some(info);
+        Ok(info)
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        match self.find(hash) {
+            Some(block) => Ok(block.receipts.clone()),
+            None => self.inner.receipts_by_hash(hash).await,
+        }
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        match self.find(hash) {
+            Some(block) => Ok((block.info, block.transactions.clone())),
+            None => self.inner.block_info_and_transactions_by_hash(hash).await,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_utils::TestChainProvider;
+
+    /// Builds a chain of `len` blocks in a [TestChainProvider].
+    pub(crate) fn test_chain(len: u64) -> (TestChainProvider, Vec<BlockInfo>) {
+        let mut provider = TestChainProvider::default();
+        let mut blocks = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..len {
+            let header = Header { number, parent_hash, ..Default::default() };
+            let hash = header.hash_slow();
+            let info = BlockInfo { hash, number, parent_hash, timestamp: number };
+            provider.insert_block_with_transactions(number, info, Vec::new());
+            provider.insert_receipts(hash, Vec::new());
+            provider.insert_header(hash, header);
+            blocks.push(info);
+            parent_hash = hash;
+        }
+        (provider, blocks)
+    }
+
+    /// Waits until the provider has prefetched `count` blocks after `number`.
+    pub(crate) async fn wait_prefetched<C>(
+        provider: &PrefetchChainProvider<C>,
+        number: u64,
+        count: usize,
+    ) where
+        C: ChainProvider + Clone + Send + Sync + 'static,
+        C::Error: Send,
+    {
+        while provider.prefetched_after(number).len() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_lookahead() {
+        let (inner, blocks) = test_chain(5);
+        let mut provider = PrefetchChainProvider::new(inner, 2);
+        assert_eq!(provider.block_info_by_number(0).await.unwrap(), blocks[0]);
+        wait_prefetched(&provider, 0, 2).await;
+        assert_eq!(provider.prefetched_after(0), blocks[1..3].to_vec());
+
+        let header = provider.header_by_hash(blocks[2].hash).await.unwrap();
+        assert_eq!(header.number, 2);
+        let (info, txs) =
+            provider.block_info_and_transactions_by_hash(blocks[2].hash).await.unwrap();
+        assert_eq!(info, blocks[2]);
+        assert!(txs.is_empty());
+
+        // Advancing slides the window forward, dropping the blocks behind it.
+        assert_eq!(provider.block_info_by_number(1).await.unwrap(), blocks[1]);
+        wait_prefetched(&provider, 1, 2).await;
+        assert_eq!(provider.prefetched_after(0), blocks[1..4].to_vec());
+        assert_eq!(provider.epoch(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_cancel_on_reset() {
+        let (inner, blocks) = test_chain(5);
+        let mut provider = PrefetchChainProvider::new(inner, 2);
+        provider.block_info_by_number(2).await.unwrap();
+        assert_eq!(provider.epoch(), 0);
+
+        // Requesting a block behind the window cancels it.
+        assert_eq!(provider.block_info_by_number(0).await.unwrap(), blocks[0]);
+        assert_eq!(provider.epoch(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_cancel_on_reorg() {
+        let (inner, blocks) = test_chain(3);
+        let mut provider = PrefetchChainProvider::new(inner, 1);
+        provider.block_info_by_number(0).await.unwrap();
+
+        // Pretend a different block 0 was returned previously.
+        provider.lock().last = Some(BlockInfo { hash: B256::with_last_byte(1), ..blocks[0] });
+        assert_eq!(provider.block_info_by_number(1).await.unwrap(), blocks[1]);
+        assert_eq!(provider.epoch(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_falls_back_on_failure() {
+        let (mut inner, blocks) = test_chain(2);
+        inner.clear_receipts();
+        let mut provider = PrefetchChainProvider::new(inner, 1);
+        assert_eq!(provider.block_info_by_number(0).await.unwrap(), blocks[0]);
+        assert!(provider.prefetched_after(0).is_empty());
+        assert!(provider.receipts_by_hash(blocks[0].hash).await.is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Contains the [PrefetchDataSource], a [DataAvailabilityProvider] that fetches L1 data ahead of
+//! the [L1Retrieval] stage.
+//!
+//! [L1Retrieval]: crate::stages::L1Retrieval
+
+use crate::{
+    errors::{PipelineError, PipelineErrorKind},
+    prefetch::PrefetchChainProvider,
+    traits::{ChainProvider, DataAvailabilityProvider},
+    types::PipelineResult,
+};
+use alloc::{boxed::Box, collections::VecDeque, string::ToString, vec::Vec};
+use alloy_primitives::{map::HashMap, Address, B256};
+use async_trait::async_trait;
+use op_alloy_protocol::BlockInfo;
+use tokio::task::JoinHandle;
+
+/// A task that drains a [DataAvailabilityProvider] for a single block.
+type DataTask<T> = JoinHandle<PipelineResult<Vec<T>>>;
+
+/// A [DataAvailabilityProvider] that wraps another [DataAvailabilityProvider] and drains it for
+/// the blocks that have been prefetched by a [PrefetchChainProvider].
+///
+/// Every block's data is fetched in its own task on the current `tokio` runtime, using a clone
+/// of the inner provider. For the prefetched headers, receipts, and transactions to be reused,
+/// the inner provider should be built on top of a clone of the same [PrefetchChainProvider].
+///
+/// Blocks ahead of the current one are fetched with the batcher address of the current block. If
+/// the batcher address has changed by the time a block is read, its data is fetched again.
+///
+/// In-flight tasks are cancelled when the [PrefetchChainProvider]'s window is cancelled, or
+/// manually with [PrefetchDataSource::cancel].
+#[derive(Debug)]
+pub struct PrefetchDataSource<D, C>
+where
+    D: DataAvailabilityProvider,
+    C: ChainProvider,
+{
+    /// The inner data availability provider.
+    inner: D,
+    /// The chain provider that determines the prefetch window.
+    chain: PrefetchChainProvider<C>,
+    /// The in-flight tasks, keyed by block hash, along with the block number and the batcher
+    /// address that the task was spawned with.
+    pending: HashMap<B256, (u64, Address, DataTask<D::Item>)>,
+    /// The data of the block that is currently being read.
+    current: Option<(B256, VecDeque<D::Item>)>,
+    /// The [PrefetchChainProvider]'s epoch that the in-flight tasks were spawned in.
+    epoch: u64,
+}
+
+impl<D, C> PrefetchDataSource<D, C>
+where
+    D: DataAvailabilityProvider + Clone + Send + Sync + 'static,
+    C: ChainProvider + Clone + Send + Sync + 'static,
+    C::Error: Send,
+{
+    /// Creates a new [PrefetchDataSource].
+    pub fn new(inner: D, chain: PrefetchChainProvider<C>) -> Self {
+        let epoch = chain.epoch();
+        Self { inner, chain, pending: HashMap::default(), current: None, epoch }
+    }
+
+    /// Aborts all in-flight prefetch tasks and drops the data of the current block.
+    pub fn cancel(&mut self) {
+        self.pending.drain().for_each(|(_, (_, _, handle))| handle.abort());
+        self.current = None;
+    }
+
+    /// Spawns a task that drains the inner provider for the given block and batcher address.
+    fn spawn(&self, block: BlockInfo, batcher_address: Address) -> DataTask<D::Item> {
+        let mut inner = self.inner.clone();
+        inner.clear();
+        tokio::spawn(async move {
+            let mut items = Vec::new();
+            loop {
+                match inner.next(&block, batcher_address).await {
+                    Ok(item) => items.push(item),
+                    Err(PipelineErrorKind::Temporary(PipelineError::Eof)) => return Ok(items),
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+
+    /// Opens the data of the given block, spawning tasks for the blocks prefetched after it.
+    async fn open(
+        &mut self,
+        block: &BlockInfo,
+        batcher_address: Address,
+    ) -> PipelineResult<VecDeque<D::Item>> {
+        let epoch = self.chain.epoch();
+        if epoch != self.epoch {
+            debug!(target: "prefetch", "Prefetch window cancelled, aborting data tasks");
+            self.cancel();
+            self.epoch = epoch;
+        }
+
+        let handle = match self.pending.remove(&block.hash) {
+            Some((_, batcher, handle)) if batcher == batcher_address => handle,
+            Some((_, _, handle)) => {
+                debug!(target: "prefetch", "Batcher address changed, refetching block {}", block.number);
+                handle.abort();
+                self.spawn(*block, batcher_address)
+            }
+            None => self.spawn(*block, batcher_address),
+        };
+        self.pending.retain(|_, (number, _, handle)| {
+            let keep = *number > block.number;
+            if !keep {
+                handle.abort();
+            }
+            keep
+        });
+        for next in self.chain.prefetched_after(block.number) {
+            if !self.pending.contains_key(&next.hash) {
+                let handle = self.spawn(next, batcher_address);
+                self.pending.insert(next.hash, (next.number, batcher_address, handle));
+            }
+        }
+
+        match handle.await {
+            Ok(items) => items.map(VecDeque::from),
+            Err(e) => Err(PipelineError::Provider(e.to_string()).temp()),
+        }
+    }
+}
+
+impl<D, C> Drop for PrefetchDataSource<D, C>
+where
+    D: DataAvailabilityProvider,
+    C: ChainProvider,
+{
+    fn drop(&mut self) {
+        self.pending.values().for_each(|(_, _, handle)| handle.abort());
+    }
+}
+
+#[async_trait]
+impl<D, C> DataAvailabilityProvider for PrefetchDataSource<D, C>
+where
+    D: DataAvailabilityProvider + Clone + Send + Sync + 'static,
+    C: ChainProvider + Clone + Send + Sync + 'static,
+    C::Error: Send,
+{
+    type Item = D::Item;
+
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> PipelineResult<Self::Item> {
+        if self.current.as_ref().map(|(hash, _)| *hash) != Some(block_ref.hash) {
+            let items = self.open(block_ref, batcher_address).await?;
+            self.current = Some((block_ref.hash, items));
+        }
+        self.current
+            .as_mut()
+            .and_then(|(_, items)| items.pop_front())
+            .ok_or(PipelineError::Eof.temp())
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefetch::chain::tests::{test_chain, wait_prefetched};
+    use alloy_primitives::Bytes;
+
+    /// A [DataAvailabilityProvider] that returns the block number of the requested block once,
+    /// followed by the batcher address.
+    #[derive(Debug, Clone, Default)]
+    struct NumberDAP {
+        open: bool,
+    }
+
+    #[async_trait]
+    impl DataAvailabilityProvider for NumberDAP {
+        type Item = Bytes;
+
+        async fn next(
+            &mut self,
+            block_ref: &BlockInfo,
+            batcher_address: Address,
+        ) -> PipelineResult<Self::Item> {
+            if self.open {
+                return Err(PipelineError::Eof.temp());
+            }
+            self.open = true;
+            Ok([&block_ref.number.to_be_bytes()[..], batcher_address.as_slice()].concat().into())
+        }
+
+        fn clear(&mut self) {
+            self.open = false;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_data() {
+        let (inner, blocks) = test_chain(4);
+        let mut chain = PrefetchChainProvider::new(inner, 2);
+        let mut source = PrefetchDataSource::new(NumberDAP::default(), chain.clone());
+
+        for block in &blocks[..3] {
+            chain.block_info_by_number(block.number).await.unwrap();
+            wait_prefetched(&chain, block.number, (3 - block.number as usize).min(2)).await;
+
+            let data = source.next(block, Address::ZERO).await.unwrap();
+            assert_eq!(data[..8], block.number.to_be_bytes());
+            let err = source.next(block, Address::ZERO).await.unwrap_err();
+            assert_eq!(err, PipelineError::Eof.temp());
+            source.clear();
+
+            // The data for the blocks in the prefetch window is being fetched.
+            let mut pending = source.pending.values().map(|(n, _, _)| *n).collect::<Vec<_>>();
+            pending.sort_unstable();
+            assert_eq!(pending, (block.number + 1..=(block.number + 2).min(3)).collect::<Vec<_>>());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_data_cancelled() {
+        let (inner, blocks) = test_chain(4);
+        let mut chain = PrefetchChainProvider::new(inner, 2);
+        let mut source = PrefetchDataSource::new(NumberDAP::default(), chain.clone());
+
+        chain.block_info_by_number(2).await.unwrap();
+        wait_prefetched(&chain, 2, 1).await;
+        source.next(&blocks[2], Address::ZERO).await.unwrap();
+        assert_eq!(source.pending.len(), 1);
+
+        // Resetting the chain provider aborts the data tasks.
+        chain.block_info_by_number(0).await.unwrap();
+        let data = source.next(&blocks[0], Address::ZERO).await.unwrap();
+        assert_eq!(data[..8], 0u64.to_be_bytes());
+        assert!(!source.pending.contains_key(&blocks[3].hash));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_data_batcher_changed() {
+        let (inner, blocks) = test_chain(4);
+        let mut chain = PrefetchChainProvider::new(inner, 2);
+        let mut source = PrefetchDataSource::new(NumberDAP::default(), chain.clone());
+
+        chain.block_info_by_number(0).await.unwrap();
+        wait_prefetched(&chain, 0, 2).await;
+        source.next(&blocks[0], Address::ZERO).await.unwrap();
+        source.clear();
+        assert_eq!(source.pending[&blocks[1].hash].1, Address::ZERO);
+
+        // The block is refetched with the new batcher address.
+        let batcher = Address::repeat_byte(0xbe);
+        chain.block_info_by_number(1).await.unwrap();
+        wait_prefetched(&chain, 1, 2).await;
+        let data = source.next(&blocks[1], batcher).await.unwrap();
+        assert_eq!(data[8..], batcher);
+
+        // Blocks that were already in flight keep their batcher address until they are read.
+        assert_eq!(source.pending[&blocks[2].hash].1, Address::ZERO);
+        assert_eq!(source.pending[&blocks[3].hash].1, batcher);
+        source.clear();
+        let data = source.next(&blocks[2], batcher).await.unwrap();
+        assert_eq!(data[8..], batcher);
+    }
+}
@@ -0,0 +1,27 @@
+//! Prefetching wrappers for the L1 data providers of the derivation pipeline.
+//!
+//! The [L1Traversal] and [L1Retrieval] stages only request the next L1 block's data once the
+//! previous block has been exhausted, which makes syncing a range of L1 blocks from a remote
+//! provider latency-bound. The types in this module wrap a [ChainProvider] and a
+//! [DataAvailabilityProvider] and fetch the next `lookahead` L1 blocks concurrently on a `tokio`
+//! runtime, while the pipeline is still processing the current block.
+//!
+//! - [PrefetchChainProvider] fetches the [BlockInfo], header, receipts, and transactions of the
+//!   upcoming blocks by number.
+//! - [PrefetchDataSource] drains the [DataAvailabilityProvider] for every block that the
+//!   [PrefetchChainProvider] has already resolved, which includes fetching blob sidecars.
+//!
+//! When the pipeline is reset to a block outside of the prefetch window, or an L1 reorg is
+//! detected within it, all in-flight requests are cancelled.
+//!
+//! [L1Traversal]: crate::stages::L1Traversal
+//! [L1Retrieval]: crate::stages::L1Retrieval
+//! [ChainProvider]: crate::traits::ChainProvider
+//! [DataAvailabilityProvider]: crate::traits::DataAvailabilityProvider
+//! [BlockInfo]: op_alloy_protocol::BlockInfo
+
+mod chain;
+pub use chain::{PrefetchChainProvider, PrefetchedBlock};
+
+mod data;
+pub use data::PrefetchDataSource;
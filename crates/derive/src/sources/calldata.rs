@@ -5,7 +5,7 @@ use crate::{
     traits::{ChainProvider, DataAvailabilityProvider},
     types::PipelineResult,
 };
-use alloc::{boxed::Box, collections::VecDeque};
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
 use alloy_consensus::{Transaction, TxEnvelope};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
@@ -21,8 +21,9 @@ where
     pub chain_provider: CP,
     /// The batch inbox address.
     pub batch_inbox_address: Address,
-    /// The L1 Signer.
-    pub signer: Address,
+    /// Signers that are authorized to post batches in addition to the batcher address of the
+    /// active system config.
+    pub authorized_batchers: Vec<Address>,
     /// Current calldata.
     pub calldata: VecDeque<Bytes>,
     /// Whether the calldata source is open.
@@ -31,12 +32,29 @@ where
 
 impl<CP: ChainProvider + Send> CalldataSource<CP> {
     /// Creates a new calldata source.
-    pub const fn new(chain_provider: CP, batch_inbox_address: Address, signer: Address) -> Self {
-        Self { chain_provider, batch_inbox_address, signer, calldata: VecDeque::new(), open: false }
+    pub const fn new(chain_provider: CP, batch_inbox_address: Address) -> Self {
+        Self {
+            chain_provider,
+            batch_inbox_address,
+            authorized_batchers: Vec::new(),
+            calldata: VecDeque::new(),
+            open: false,
+        }
+    }
+
+    /// Sets the signers that are authorized to post batches in addition to the batcher address of
+    /// the active system config.
+    pub fn with_authorized_batchers(mut self, authorized_batchers: Vec<Address>) -> Self {
+        self.authorized_batchers = authorized_batchers;
+        self
     }
 
     /// Loads the calldata into the source if it is not open.
-    async fn load_calldata(&mut self, block_ref: &BlockInfo) -> Result<(), CP::Error> {
+    async fn load_calldata(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> Result<(), CP::Error> {
         if self.open {
             return Ok(());
         }
@@ -58,7 +76,8 @@ impl<CP: ChainProvider + Send> CalldataSource<CP> {
                 if to != self.batch_inbox_address {
                     return None;
                 }
-                if tx.recover_signer().ok()? != self.signer {
+                let signer = tx.recover_signer().ok()?;
+                if signer != batcher_address && !self.authorized_batchers.contains(&signer) {
                     return None;
                 }
                 Some(data.to_vec().into())
@@ -75,8 +94,12 @@ impl<CP: ChainProvider + Send> CalldataSource<CP> {
 impl<CP: ChainProvider + Send> DataAvailabilityProvider for CalldataSource<CP> {
     type Item = Bytes;
 
-    async fn next(&mut self, block_ref: &BlockInfo) -> PipelineResult<Self::Item> {
-        self.load_calldata(block_ref).await.map_err(Into::into)?;
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> PipelineResult<Self::Item> {
+        self.load_calldata(block_ref, batcher_address).await.map_err(Into::into)?;
         self.calldata.pop_front().ok_or(PipelineError::Eof.temp())
     }
 
@@ -122,7 +145,7 @@ mod tests {
     }
 
     pub(crate) fn default_test_calldata_source() -> CalldataSource<TestChainProvider> {
-        CalldataSource::new(TestChainProvider::default(), Default::default(), Default::default())
+        CalldataSource::new(TestChainProvider::default(), Default::default())
     }
 
     #[tokio::test]
@@ -139,13 +162,13 @@ mod tests {
     async fn test_load_calldata_open() {
         let mut source = default_test_calldata_source();
         source.open = true;
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_ok());
     }
 
     #[tokio::test]
     async fn test_load_calldata_provider_err() {
         let mut source = default_test_calldata_source();
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_err());
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_err());
     }
 
     #[tokio::test]
@@ -154,7 +177,7 @@ mod tests {
         let block_info = BlockInfo::default();
         source.chain_provider.insert_block_with_transactions(0, block_info, Vec::new());
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_ok());
         assert!(source.calldata.is_empty());
         assert!(source.open);
     }
@@ -167,7 +190,7 @@ mod tests {
         let tx = test_legacy_tx(batch_inbox_address);
         source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_ok());
         assert!(source.calldata.is_empty());
         assert!(source.open);
     }
@@ -181,7 +204,7 @@ mod tests {
         let tx = test_legacy_tx(batch_inbox_address);
         source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_ok());
         assert!(source.calldata.is_empty());
         assert!(source.open);
     }
@@ -192,11 +215,11 @@ mod tests {
         let mut source = default_test_calldata_source();
         source.batch_inbox_address = batch_inbox_address;
         let tx = test_legacy_tx(batch_inbox_address);
-        source.signer = tx.recover_signer().unwrap();
+        let signer = tx.recover_signer().unwrap();
         let block_info = BlockInfo::default();
         source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), signer).await.is_ok());
         assert!(!source.calldata.is_empty()); // Calldata is NOT empty.
         assert!(source.open);
     }
@@ -207,11 +230,11 @@ mod tests {
         let mut source = default_test_calldata_source();
         source.batch_inbox_address = batch_inbox_address;
         let tx = test_eip2930_tx(batch_inbox_address);
-        source.signer = tx.recover_signer().unwrap();
+        let signer = tx.recover_signer().unwrap();
         let block_info = BlockInfo::default();
         source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), signer).await.is_ok());
         assert!(!source.calldata.is_empty()); // Calldata is NOT empty.
         assert!(source.open);
     }
@@ -222,11 +245,11 @@ mod tests {
         let mut source = default_test_calldata_source();
         source.batch_inbox_address = batch_inbox_address;
         let tx = test_blob_tx(batch_inbox_address);
-        source.signer = tx.recover_signer().unwrap();
+        let signer = tx.recover_signer().unwrap();
         let block_info = BlockInfo::default();
         source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
         assert!(!source.open); // Source is not open by default.
-        assert!(source.load_calldata(&BlockInfo::default()).await.is_ok());
+        assert!(source.load_calldata(&BlockInfo::default(), signer).await.is_ok());
         assert!(source.calldata.is_empty());
         assert!(source.open);
     }
@@ -235,8 +258,22 @@ mod tests {
     async fn test_next_err_loading_calldata() {
         let mut source = default_test_calldata_source();
         assert!(matches!(
-            source.next(&BlockInfo::default()).await,
+            source.next(&BlockInfo::default(), Address::ZERO).await,
             Err(PipelineErrorKind::Temporary(_))
         ));
     }
+
+    #[tokio::test]
+    async fn test_load_calldata_authorized_batcher() {
+        let batch_inbox_address = address!("0123456789012345678901234567890123456789");
+        let tx = test_legacy_tx(batch_inbox_address);
+        let signer = tx.recover_signer().unwrap();
+        let mut source =
+            default_test_calldata_source().with_authorized_batchers(vec![Address::ZERO, signer]);
+        source.batch_inbox_address = batch_inbox_address;
+        let block_info = BlockInfo::default();
+        source.chain_provider.insert_block_with_transactions(0, block_info, vec![tx]);
+        assert!(source.load_calldata(&BlockInfo::default(), Address::ZERO).await.is_ok());
+        assert_eq!(source.calldata.len(), 1);
+    }
 }
@@ -6,8 +6,8 @@ use crate::{
     traits::{BlobProvider, ChainProvider, DataAvailabilityProvider},
     types::PipelineResult,
 };
-use alloc::{boxed::Box, fmt::Debug};
-use alloy_primitives::Bytes;
+use alloc::{boxed::Box, fmt::Debug, vec::Vec};
+use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
 use op_alloy_genesis::RollupConfig;
 use op_alloy_protocol::BlockInfo;
@@ -43,14 +43,20 @@ where
 
     /// Instantiates a new [EthereumDataSource] from parts.
     pub fn new_from_parts(provider: C, blobs: B, cfg: &RollupConfig) -> Self {
-        let signer =
-            cfg.genesis.system_config.as_ref().map(|sc| sc.batcher_address).unwrap_or_default();
         Self {
             ecotone_timestamp: cfg.ecotone_time,
-            blob_source: BlobSource::new(provider.clone(), blobs, cfg.batch_inbox_address, signer),
-            calldata_source: CalldataSource::new(provider, cfg.batch_inbox_address, signer),
+            blob_source: BlobSource::new(provider.clone(), blobs, cfg.batch_inbox_address),
+            calldata_source: CalldataSource::new(provider, cfg.batch_inbox_address),
         }
     }
+
+    /// Sets the signers that are authorized to post batches in addition to the batcher address of
+    /// the active system config.
+    pub fn with_authorized_batchers(mut self, authorized_batchers: Vec<Address>) -> Self {
+        self.blob_source.authorized_batchers = authorized_batchers.clone();
+        self.calldata_source.authorized_batchers = authorized_batchers;
+        self
+    }
 }
 
 #[async_trait]
@@ -61,13 +67,17 @@ where
 {
     type Item = Bytes;
 
-    async fn next(&mut self, block_ref: &BlockInfo) -> PipelineResult<Self::Item> {
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> PipelineResult<Self::Item> {
         let ecotone_enabled =
             self.ecotone_timestamp.map(|e| block_ref.timestamp >= e).unwrap_or(false);
         if ecotone_enabled {
-            self.blob_source.next(block_ref).await
+            self.blob_source.next(block_ref, batcher_address).await
         } else {
-            self.calldata_source.next(block_ref).await
+            self.calldata_source.next(block_ref, batcher_address).await
         }
     }
 
@@ -87,15 +97,14 @@ mod tests {
     use alloy_consensus::TxEnvelope;
     use alloy_eips::eip2718::Decodable2718;
     use alloy_primitives::{address, Address};
-    use op_alloy_genesis::{RollupConfig, SystemConfig};
+    use op_alloy_genesis::RollupConfig;
     use op_alloy_protocol::BlockInfo;
 
     fn default_test_blob_source() -> BlobSource<TestChainProvider, TestBlobProvider> {
         let chain_provider = TestChainProvider::default();
         let blob_fetcher = TestBlobProvider::default();
-        let batcher_address = Address::default();
-        let signer = Address::default();
-        BlobSource::new(chain_provider, blob_fetcher, batcher_address, signer)
+        let batch_inbox_address = Address::default();
+        BlobSource::new(chain_provider, blob_fetcher, batch_inbox_address)
     }
 
     #[tokio::test]
@@ -103,10 +112,10 @@ mod tests {
         let chain = TestChainProvider::default();
         let blob = TestBlobProvider::default();
         let cfg = RollupConfig::default();
-        let mut calldata = CalldataSource::new(chain.clone(), Address::ZERO, Address::ZERO);
+        let mut calldata = CalldataSource::new(chain.clone(), Address::ZERO);
         calldata.calldata.insert(0, Default::default());
         calldata.open = true;
-        let mut blob = BlobSource::new(chain, blob, Address::ZERO, Address::ZERO);
+        let mut blob = BlobSource::new(chain, blob, Address::ZERO);
         blob.data = vec![Default::default()];
         blob.open = true;
         let mut data_source = EthereumDataSource::new(blob, calldata, &cfg);
@@ -124,12 +133,12 @@ mod tests {
         let mut blob = default_test_blob_source();
         blob.open = true;
         blob.data.push(BlobData { data: None, calldata: Some(Bytes::default()) });
-        let calldata = CalldataSource::new(chain.clone(), Address::ZERO, Address::ZERO);
+        let calldata = CalldataSource::new(chain.clone(), Address::ZERO);
         let cfg = RollupConfig { ecotone_time: Some(0), ..Default::default() };
 
         // Should successfully retrieve a blob batch from the block
         let mut data_source = EthereumDataSource::new(blob, calldata, &cfg);
-        let data = data_source.next(&BlockInfo::default()).await.unwrap();
+        let data = data_source.next(&BlockInfo::default(), Address::ZERO).await.unwrap();
         assert_eq!(data, Bytes::default());
     }
 
@@ -141,9 +150,7 @@ mod tests {
         let batch_inbox = address!("FF00000000000000000000000000000000000010");
         let block_ref = BlockInfo { number: 10, ..Default::default() };
 
-        let mut cfg = RollupConfig::default();
-        cfg.genesis.system_config = Some(SystemConfig { batcher_address, ..Default::default() });
-        cfg.batch_inbox_address = batch_inbox;
+        let cfg = RollupConfig { batch_inbox_address: batch_inbox, ..Default::default() };
 
         // load a test batcher transaction
         let raw_batcher_tx = include_bytes!("../../testdata/raw_batcher_tx.hex");
@@ -151,8 +158,19 @@ mod tests {
         chain.insert_block_with_transactions(10, block_ref, alloc::vec![tx]);
 
         // Should successfully retrieve a calldata batch from the block
-        let mut data_source = EthereumDataSource::new_from_parts(chain, blob, &cfg);
-        let calldata_batch = data_source.next(&block_ref).await.unwrap();
+        let mut data_source = EthereumDataSource::new_from_parts(chain.clone(), blob.clone(), &cfg);
+        let calldata_batch = data_source.next(&block_ref, batcher_address).await.unwrap();
+        assert_eq!(calldata_batch.len(), 119823);
+
+        // Data posted by a signer other than the active batcher is ignored.
+        data_source.clear();
+        let err = data_source.next(&block_ref, Address::ZERO).await.unwrap_err();
+        assert_eq!(err, crate::errors::PipelineError::Eof.temp());
+
+        // Unless the signer is explicitly authorized.
+        let mut data_source = EthereumDataSource::new_from_parts(chain, blob, &cfg)
+            .with_authorized_batchers(alloc::vec![batcher_address]);
+        let calldata_batch = data_source.next(&block_ref, Address::ZERO).await.unwrap();
         assert_eq!(calldata_batch.len(), 119823);
     }
 }
@@ -9,6 +9,8 @@
 
 mod blob_data;
 pub use blob_data::BlobData;
+#[cfg(test)]
+pub(crate) use blob_data::{BLOB_ENCODING_ROUNDS, BLOB_MAX_DATA_SIZE};
 
 mod ethereum;
 pub use ethereum::EthereumDataSource;
@@ -4,9 +4,9 @@ use crate::{
     errors::{PipelineError, ResetError},
     traits::{
         AttributesBuilder, AttributesProvider, NextAttributes, OriginAdvancer, OriginProvider,
-        SignalReceiver,
+        SignalReceiver, StageSnapshot,
     },
-    types::{PipelineResult, Signal},
+    types::{AttributesQueueSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use async_trait::async_trait;
@@ -192,6 +192,38 @@ where
     }
 }
 
+#[async_trait]
+impl<P, AB> StageSnapshot for AttributesQueue<P, AB>
+where
+    P: AttributesProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+    AB: AttributesBuilder + Send + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.attributes_queue = Some(AttributesQueueSnapshot {
+            is_last_in_span: self.is_last_in_span,
+            batch: self.batch.clone(),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let state = snapshot
+            .attributes_queue
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("attributes queue").crit())?;
+        self.is_last_in_span = state.is_last_in_span;
+        self.batch = state.batch.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
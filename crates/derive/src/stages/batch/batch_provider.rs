@@ -4,8 +4,11 @@ use super::NextBatchProvider;
 use crate::{
     errors::PipelineError,
     stages::{BatchQueue, BatchValidator},
-    traits::{AttributesProvider, L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{
+        AttributesProvider, L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver,
+        StageSnapshot,
+    },
+    types::{BatchProviderSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use async_trait::async_trait;
@@ -89,6 +92,18 @@ where
         }
         Ok(())
     }
+
+    /// Takes ownership of the previous stage from whichever stage is active in the mux,
+    /// leaving the mux uninitialized.
+    fn take_prev(&mut self) -> P {
+        if let Some(batch_validator) = self.batch_validator.take() {
+            batch_validator.prev
+        } else if let Some(batch_queue) = self.batch_queue.take() {
+            batch_queue.prev
+        } else {
+            self.prev.take().expect("Must have previous stage")
+        }
+    }
 }
 
 #[async_trait]
@@ -173,13 +188,59 @@ where
     }
 }
 
+#[async_trait]
+impl<P, F> StageSnapshot for BatchProvider<P, F>
+where
+    P: NextBatchProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+    F: L2ChainProvider + Clone + Send + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        if let Some(batch_validator) = self.batch_validator.as_ref() {
+            batch_validator.snapshot(snapshot);
+        } else if let Some(batch_queue) = self.batch_queue.as_ref() {
+            batch_queue.snapshot(snapshot);
+        } else if let Some(prev) = self.prev.as_ref() {
+            prev.snapshot(snapshot);
+            snapshot.batch_provider = None;
+        }
+    }
+
+    /// Restores the [BatchProvider] from the [PipelineSnapshot], activating the stage that was
+    /// active in the mux when the snapshot was captured.
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        let prev = self.take_prev();
+        match snapshot.batch_provider {
+            Some(BatchProviderSnapshot::BatchQueue { .. }) => {
+                let batch_queue = self.batch_queue.insert(BatchQueue::new(
+                    self.cfg.clone(),
+                    prev,
+                    self.provider.clone(),
+                ));
+                batch_queue.restore(snapshot).await
+            }
+            Some(BatchProviderSnapshot::BatchValidator { .. }) => {
+                let batch_validator =
+                    self.batch_validator.insert(BatchValidator::new(self.cfg.clone(), prev));
+                batch_validator.restore(snapshot).await
+            }
+            None => self.prev.insert(prev).restore(snapshot).await,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::BatchProvider;
     use crate::{
         test_utils::{TestL2ChainProvider, TestNextBatchProvider},
-        traits::{OriginProvider, SignalReceiver},
-        types::ResetSignal,
+        traits::{OriginProvider, SignalReceiver, StageSnapshot},
+        types::{BatchProviderSnapshot, PipelineSnapshot, ResetSignal},
     };
     use alloc::{sync::Arc, vec};
     use op_alloy_genesis::RollupConfig;
@@ -296,4 +357,37 @@ mod test {
         };
         assert!(bv.l1_blocks.len() == 1);
     }
+
+    #[tokio::test]
+    async fn test_batch_provider_snapshot_restore() {
+        let provider = TestNextBatchProvider::new(vec![]);
+        let l2_provider = TestL2ChainProvider::default();
+        let cfg = Arc::new(RollupConfig::default());
+        let mut batch_provider = BatchProvider::new(cfg.clone(), provider, l2_provider.clone());
+        batch_provider.signal(ResetSignal::default().signal()).await.unwrap();
+
+        let mut snapshot = PipelineSnapshot::default();
+        batch_provider.snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot.batch_provider,
+            Some(BatchProviderSnapshot::BatchQueue {
+                origin: Some(BlockInfo::default()),
+                l1_blocks: vec![BlockInfo::default()],
+                batches: vec![],
+                next_spans: vec![],
+            })
+        );
+
+        // Restore into a fresh batch provider that has not yet initialized the mux.
+        let provider = TestNextBatchProvider::new(vec![]);
+        let mut restored = BatchProvider::new(cfg, provider, l2_provider);
+        restored.restore(&snapshot).await.unwrap();
+        assert!(restored.prev.is_none());
+        assert!(restored.batch_validator.is_none());
+        let Some(batch_queue) = restored.batch_queue.as_ref() else {
+            panic!("Expected BatchQueue");
+        };
+        assert_eq!(batch_queue.origin, Some(BlockInfo::default()));
+        assert_eq!(batch_queue.l1_blocks, vec![BlockInfo::default()]);
+    }
 }
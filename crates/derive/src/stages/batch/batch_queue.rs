@@ -3,8 +3,11 @@
 use super::NextBatchProvider;
 use crate::{
     errors::{PipelineEncodingError, PipelineError, PipelineErrorKind, ResetError},
-    traits::{AttributesProvider, L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, ResetSignal, Signal},
+    traits::{
+        AttributesProvider, L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver,
+        StageSnapshot,
+    },
+    types::{BatchProviderSnapshot, PipelineResult, PipelineSnapshot, ResetSignal, Signal},
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use async_trait::async_trait;
@@ -457,6 +460,43 @@ where
     }
 }
 
+#[async_trait]
+impl<P, BF> StageSnapshot for BatchQueue<P, BF>
+where
+    P: NextBatchProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+    BF: L2ChainProvider + Send + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.batch_provider = Some(BatchProviderSnapshot::BatchQueue {
+            origin: self.origin,
+            l1_blocks: self.l1_blocks.clone(),
+            batches: self.batches.clone(),
+            next_spans: self.next_spans.clone(),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let Some(BatchProviderSnapshot::BatchQueue { origin, l1_blocks, batches, next_spans }) =
+            snapshot.batch_provider.as_ref()
+        else {
+            return Err(PipelineError::MissingSnapshot("batch queue").crit());
+        };
+        self.origin = *origin;
+        self.l1_blocks = l1_blocks.clone();
+        self.batches = batches.clone();
+        self.next_spans = next_spans.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,8 +3,8 @@
 use crate::{
     errors::{PipelineEncodingError, PipelineError},
     stages::NextBatchProvider,
-    traits::{L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{BatchStreamSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use async_trait::async_trait;
@@ -217,6 +217,36 @@ where
     }
 }
 
+#[async_trait]
+impl<P, BF> StageSnapshot for BatchStream<P, BF>
+where
+    P: BatchStreamProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Debug
+        + Send,
+    BF: L2ChainProvider + Send + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.batch_stream =
+            Some(BatchStreamSnapshot { span: self.span.clone(), buffer: self.buffer.clone() });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let state = snapshot
+            .batch_stream
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("batch stream").crit())?;
+        self.span = state.span.clone();
+        self.buffer = state.buffer.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -256,6 +286,28 @@ mod test {
         assert!(stream.span.is_none());
     }
 
+    #[tokio::test]
+    async fn test_batch_stream_snapshot_restore() {
+        let config = Arc::new(RollupConfig { holocene_time: Some(0), ..RollupConfig::default() });
+        let prev = TestBatchStreamProvider::new(vec![]);
+        let mut stream = BatchStream::new(prev, config.clone(), TestL2ChainProvider::default());
+        stream.buffer.push_back(SingleBatch { timestamp: 2, ..Default::default() });
+        stream.span = Some(SpanBatch { chain_id: 10, ..Default::default() });
+
+        let mut snapshot = PipelineSnapshot::default();
+        stream.snapshot(&mut snapshot);
+
+        let prev = TestBatchStreamProvider::new(vec![]);
+        let mut restored = BatchStream::new(prev, config, TestL2ChainProvider::default());
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.buffer, stream.buffer);
+        assert_eq!(restored.span, stream.span);
+
+        // Restoring from an empty snapshot fails.
+        let err = restored.restore(&PipelineSnapshot::default()).await.unwrap_err();
+        assert_eq!(err, PipelineError::MissingSnapshot("batch stream").crit());
+    }
+
     #[tokio::test]
     async fn test_batch_stream_flush_channel() {
         let config = Arc::new(RollupConfig { holocene_time: Some(0), ..RollupConfig::default() });
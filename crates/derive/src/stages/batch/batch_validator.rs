@@ -5,8 +5,8 @@ use super::NextBatchProvider;
 use crate::{
     errors::ResetError,
     prelude::{OriginProvider, PipelineError, PipelineErrorKind},
-    traits::{AttributesProvider, OriginAdvancer, SignalReceiver},
-    types::{PipelineResult, ResetSignal, Signal},
+    traits::{AttributesProvider, OriginAdvancer, SignalReceiver, StageSnapshot},
+    types::{BatchProviderSnapshot, PipelineResult, PipelineSnapshot, ResetSignal, Signal},
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use async_trait::async_trait;
@@ -308,6 +308,38 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for BatchValidator<P>
+where
+    P: NextBatchProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.batch_provider = Some(BatchProviderSnapshot::BatchValidator {
+            origin: self.origin,
+            l1_blocks: self.l1_blocks.clone(),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let Some(BatchProviderSnapshot::BatchValidator { origin, l1_blocks }) =
+            snapshot.batch_provider.as_ref()
+        else {
+            return Err(PipelineError::MissingSnapshot("batch validator").crit());
+        };
+        self.origin = *origin;
+        self.l1_blocks = l1_blocks.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -3,8 +3,8 @@
 use super::{ChannelReaderProvider, NextFrameProvider};
 use crate::{
     prelude::{OriginProvider, PipelineError},
-    traits::{OriginAdvancer, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, SignalReceiver, StageSnapshot},
+    types::{BufferedChannel, ChannelProviderSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::{hex, Bytes};
@@ -13,7 +13,7 @@ use core::fmt::Debug;
 use op_alloy_genesis::{
     RollupConfig, MAX_RLP_BYTES_PER_CHANNEL_BEDROCK, MAX_RLP_BYTES_PER_CHANNEL_FJORD,
 };
-use op_alloy_protocol::{BlockInfo, Channel};
+use op_alloy_protocol::BlockInfo;
 
 /// The [ChannelAssembler] stage is responsible for assembling the [Frame]s from the [FrameQueue]
 /// stage into a raw compressed [Channel].
@@ -30,8 +30,8 @@ where
     pub(crate) cfg: Arc<RollupConfig>,
     /// The previous stage of the derivation pipeline.
     pub(crate) prev: P,
-    /// The current [Channel] being assembled.
-    pub(crate) channel: Option<Channel>,
+    /// The current [BufferedChannel] being assembled.
+    pub(crate) channel: Option<BufferedChannel>,
 }
 
 impl<P> ChannelAssembler<P>
@@ -91,7 +91,7 @@ where
                 hex::encode(next_frame.id),
                 origin.number
             );
-            self.channel = Some(Channel::new(next_frame.id, origin));
+            self.channel = Some(BufferedChannel::new(next_frame.id, origin));
         }
 
         if let Some(channel) = self.channel.as_mut() {
@@ -182,6 +182,36 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for ChannelAssembler<P>
+where
+    P: NextFrameProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.channel_provider = Some(ChannelProviderSnapshot::ChannelAssembler {
+            channel: self.channel.clone().map(Box::new),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let Some(ChannelProviderSnapshot::ChannelAssembler { channel }) =
+            snapshot.channel_provider.as_ref()
+        else {
+            return Err(PipelineError::MissingSnapshot("channel assembler").crit());
+        };
+        self.channel = channel.as_deref().cloned();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::ChannelAssembler;
@@ -4,15 +4,15 @@ use super::NextFrameProvider;
 use crate::{
     errors::{PipelineError, PipelineErrorKind},
     stages::ChannelReaderProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{BufferedChannel, ChannelProviderSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use alloy_primitives::{hex, map::HashMap, Bytes};
 use async_trait::async_trait;
 use core::fmt::Debug;
 use op_alloy_genesis::RollupConfig;
-use op_alloy_protocol::{BlockInfo, Channel, ChannelId, Frame};
+use op_alloy_protocol::{BlockInfo, ChannelId, Frame};
 
 /// The maximum size of a channel bank.
 pub(crate) const MAX_CHANNEL_BANK_SIZE: usize = 100_000_000;
@@ -39,7 +39,7 @@ where
     /// The rollup configuration.
     pub(crate) cfg: Arc<RollupConfig>,
     /// Map of channels by ID.
-    pub(crate) channels: HashMap<ChannelId, Channel>,
+    pub(crate) channels: HashMap<ChannelId, BufferedChannel>,
     /// Channels in FIFO order.
     pub(crate) channel_queue: VecDeque<ChannelId>,
     /// The previous stage of the derivation pipeline.
@@ -87,7 +87,7 @@ where
         let current_channel = match self.channels.get_mut(&frame.id) {
             Some(c) => c,
             None => {
-                let channel = Channel::new(frame.id, origin);
+                let channel = BufferedChannel::new(frame.id, origin);
                 self.channel_queue.push_back(frame.id);
                 self.channels.insert(frame.id, channel);
                 self.channels.get_mut(&frame.id).expect("Channel must be in queue")
@@ -240,6 +240,38 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for ChannelBank<P>
+where
+    P: NextFrameProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.channel_provider = Some(ChannelProviderSnapshot::ChannelBank {
+            channels: self.channels.clone(),
+            channel_queue: self.channel_queue.clone(),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let Some(ChannelProviderSnapshot::ChannelBank { channels, channel_queue }) =
+            snapshot.channel_provider.as_ref()
+        else {
+            return Err(PipelineError::MissingSnapshot("channel bank").crit());
+        };
+        self.channels = channels.clone();
+        self.channel_queue = channel_queue.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,7 +300,9 @@ mod tests {
         let cfg = Arc::new(RollupConfig::default());
         let mut channel_bank = ChannelBank::new(cfg, mock);
         channel_bank.channel_queue.push_back([0xFF; 16]);
-        channel_bank.channels.insert([0xFF; 16], Channel::new([0xFF; 16], BlockInfo::default()));
+        channel_bank
+            .channels
+            .insert([0xFF; 16], BufferedChannel::new([0xFF; 16], BlockInfo::default()));
         let err = channel_bank.try_read_channel_at_index(0).unwrap_err();
         assert_eq!(err, PipelineError::MissingOrigin.crit());
     }
@@ -280,7 +314,9 @@ mod tests {
         let cfg = Arc::new(RollupConfig::default());
         let mut channel_bank = ChannelBank::new(cfg, mock);
         channel_bank.channel_queue.push_back([0xFF; 16]);
-        channel_bank.channels.insert([0xFF; 16], Channel::new([0xFF; 16], BlockInfo::default()));
+        channel_bank
+            .channels
+            .insert([0xFF; 16], BufferedChannel::new([0xFF; 16], BlockInfo::default()));
         let err = channel_bank.try_read_channel_at_index(0).unwrap_err();
         assert_eq!(err, PipelineError::Eof.temp());
     }
@@ -292,7 +328,7 @@ mod tests {
         let mut channel_bank = ChannelBank::new(cfg, mock);
         let id: ChannelId = [0xFF; 16];
         channel_bank.channel_queue.push_back(id);
-        let mut channel = Channel::new(id, BlockInfo::default());
+        let mut channel = BufferedChannel::new(id, BlockInfo::default());
         channel
             .add_frame(
                 Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
@@ -327,7 +363,7 @@ mod tests {
         let mut channel_bank = ChannelBank::new(cfg, mock);
         let id: ChannelId = [0xFF; 16];
         channel_bank.channel_queue.push_back(id);
-        let mut channel = Channel::new(id, BlockInfo::default());
+        let mut channel = BufferedChannel::new(id, BlockInfo::default());
         channel
             .add_frame(
                 Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
@@ -362,7 +398,7 @@ mod tests {
         let mut channel_bank = ChannelBank::new(cfg, mock);
         let id: ChannelId = [0xFF; 16];
         channel_bank.channel_queue.push_back(id);
-        let mut channel = Channel::new(id, BlockInfo::default());
+        let mut channel = BufferedChannel::new(id, BlockInfo::default());
         channel
             .add_frame(
                 Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
@@ -406,7 +442,7 @@ mod tests {
         let mock = TestNextFrameProvider::new(vec![]);
         let cfg = Arc::new(RollupConfig::default());
         let mut channel_bank = ChannelBank::new(cfg, mock);
-        channel_bank.channels.insert([0xFF; 16], Channel::default());
+        channel_bank.channels.insert([0xFF; 16], BufferedChannel::default());
         channel_bank.channel_queue.push_back([0xFF; 16]);
         assert!(!channel_bank.prev.reset);
         channel_bank.signal(ResetSignal::default().signal()).await.unwrap();
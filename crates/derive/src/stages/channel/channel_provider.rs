@@ -3,8 +3,8 @@
 use super::{ChannelAssembler, ChannelBank, ChannelReaderProvider, NextFrameProvider};
 use crate::{
     errors::PipelineError,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{ChannelProviderSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::Bytes;
@@ -79,6 +79,18 @@ where
         }
         Ok(())
     }
+
+    /// Takes ownership of the previous stage from whichever stage is active in the mux,
+    /// leaving the mux uninitialized.
+    fn take_prev(&mut self) -> P {
+        if let Some(channel_assembler) = self.channel_assembler.take() {
+            channel_assembler.prev
+        } else if let Some(channel_bank) = self.channel_bank.take() {
+            channel_bank.prev
+        } else {
+            self.prev.take().expect("Must have previous stage")
+        }
+    }
 }
 
 #[async_trait]
@@ -152,6 +164,48 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for ChannelProvider<P>
+where
+    P: NextFrameProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        if let Some(channel_assembler) = self.channel_assembler.as_ref() {
+            channel_assembler.snapshot(snapshot);
+        } else if let Some(channel_bank) = self.channel_bank.as_ref() {
+            channel_bank.snapshot(snapshot);
+        } else if let Some(prev) = self.prev.as_ref() {
+            prev.snapshot(snapshot);
+            snapshot.channel_provider = None;
+        }
+    }
+
+    /// Restores the [ChannelProvider] from the [PipelineSnapshot], activating the stage that was
+    /// active in the mux when the snapshot was captured.
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        let prev = self.take_prev();
+        match snapshot.channel_provider {
+            Some(ChannelProviderSnapshot::ChannelBank { .. }) => {
+                let channel_bank =
+                    self.channel_bank.insert(ChannelBank::new(self.cfg.clone(), prev));
+                channel_bank.restore(snapshot).await
+            }
+            Some(ChannelProviderSnapshot::ChannelAssembler { .. }) => {
+                let channel_assembler =
+                    self.channel_assembler.insert(ChannelAssembler::new(self.cfg.clone(), prev));
+                channel_assembler.restore(snapshot).await
+            }
+            None => self.prev.insert(prev).restore(snapshot).await,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::ChannelProvider;
@@ -159,8 +213,8 @@ mod test {
         prelude::{OriginProvider, PipelineError},
         stages::ChannelReaderProvider,
         test_utils::TestNextFrameProvider,
-        traits::SignalReceiver,
-        types::ResetSignal,
+        traits::{SignalReceiver, StageSnapshot},
+        types::{ChannelProviderSnapshot, PipelineSnapshot, ResetSignal},
     };
     use alloc::{sync::Arc, vec};
     use op_alloy_genesis::RollupConfig;
@@ -349,4 +403,49 @@ mod test {
         };
         assert!(channel_assembler.channel.is_none());
     }
+
+    #[tokio::test]
+    async fn test_channel_provider_snapshot_restore_assembler() {
+        let frames = [crate::frame!(0xFF, 0, vec![0xDD; 50], false)];
+        let provider = TestNextFrameProvider::new(frames.into_iter().rev().map(Ok).collect());
+        let cfg = Arc::new(RollupConfig { holocene_time: Some(0), ..Default::default() });
+        let mut channel_provider = ChannelProvider::new(cfg.clone(), provider);
+
+        // Load in the first frame.
+        assert_eq!(
+            channel_provider.next_data().await.unwrap_err(),
+            PipelineError::NotEnoughData.temp()
+        );
+        let mut snapshot = PipelineSnapshot::default();
+        channel_provider.snapshot(&mut snapshot);
+        assert!(matches!(
+            snapshot.channel_provider,
+            Some(ChannelProviderSnapshot::ChannelAssembler { channel: Some(_) })
+        ));
+
+        // Restore into a fresh channel provider that has not yet initialized the mux.
+        let provider = TestNextFrameProvider::new(vec![]);
+        let mut restored = ChannelProvider::new(cfg, provider);
+        restored.restore(&snapshot).await.unwrap();
+        assert!(restored.prev.is_none());
+        assert!(restored.channel_bank.is_none());
+        let Some(channel_assembler) = restored.channel_assembler.as_ref() else {
+            panic!("Expected ChannelAssembler");
+        };
+        assert_eq!(channel_assembler.channel.as_ref().map(|c| c.len()), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_channel_provider_restore_uninitialized() {
+        let provider = TestNextFrameProvider::new(vec![]);
+        let cfg = Arc::new(RollupConfig::default());
+        let mut channel_provider = ChannelProvider::new(cfg, provider);
+        assert!(channel_provider.attempt_update().is_ok());
+        assert!(channel_provider.channel_bank.is_some());
+
+        channel_provider.restore(&PipelineSnapshot::default()).await.unwrap();
+        assert!(channel_provider.prev.is_some());
+        assert!(channel_provider.channel_bank.is_none());
+        assert!(channel_provider.channel_assembler.is_none());
+    }
 }
@@ -3,8 +3,8 @@
 use crate::{
     errors::PipelineError,
     stages::BatchStreamProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{ChannelReaderSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::Bytes;
@@ -44,6 +44,11 @@ where
     prev: P,
     /// The batch reader.
     next_batch: Option<BatchReader>,
+    /// The raw data and maximum decompressed size of the channel being read, retained so the
+    /// [BatchReader] can be reconstructed when the stage is restored from a [PipelineSnapshot].
+    channel: Option<(Bytes, usize)>,
+    /// The number of batches read from the current channel.
+    batches_read: usize,
     /// The rollup coonfiguration.
     cfg: Arc<RollupConfig>,
 }
@@ -54,7 +59,7 @@ where
 {
     /// Create a new [ChannelReader] stage.
     pub const fn new(prev: P, cfg: Arc<RollupConfig>) -> Self {
-        Self { prev, next_batch: None, cfg }
+        Self { prev, next_batch: None, channel: None, batches_read: 0, cfg }
     }
 
     /// Creates the batch reader from available channel data.
@@ -72,6 +77,8 @@ where
 
             self.next_batch =
                 Some(BatchReader::new(&channel[..], max_rlp_bytes_per_channel as usize));
+            self.channel = Some((channel, max_rlp_bytes_per_channel as usize));
+            self.batches_read = 0;
         }
         Ok(())
     }
@@ -80,6 +87,8 @@ where
     /// decoding / decompression state to a fresh start.
     pub fn next_channel(&mut self) {
         self.next_batch = None;
+        self.channel = None;
+        self.batches_read = 0;
     }
 }
 
@@ -122,7 +131,10 @@ where
             .next_batch(self.cfg.as_ref())
             .ok_or(PipelineError::NotEnoughData.temp())
         {
-            Ok(batch) => Ok(batch),
+            Ok(batch) => {
+                self.batches_read += 1;
+                Ok(batch)
+            }
             Err(e) => {
                 self.next_channel();
                 Err(e)
@@ -150,7 +162,7 @@ where
             Signal::FlushChannel => {
                 // Drop the current in-progress channel.
                 warn!(target: "channel-reader", "Flushed channel");
-                self.next_batch = None;
+                self.next_channel();
             }
             s => {
                 self.prev.signal(s).await?;
@@ -161,6 +173,55 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for ChannelReader<P>
+where
+    P: ChannelReaderProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Debug
+        + Send,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        let (channel, max_rlp_bytes_per_channel) = self.channel.clone().unzip();
+        snapshot.channel_reader = Some(ChannelReaderSnapshot {
+            channel,
+            max_rlp_bytes_per_channel: max_rlp_bytes_per_channel.unwrap_or_default(),
+            batches_read: self.batches_read,
+        });
+    }
+
+    /// Restores the [ChannelReader] from the [PipelineSnapshot].
+    ///
+    /// The [BatchReader] is rebuilt from the channel's raw data, and the batches that were
+    /// already read are decoded and skipped.
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let state = snapshot
+            .channel_reader
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("channel reader").crit())?;
+
+        self.next_channel();
+        if let Some(channel) = state.channel.clone() {
+            let mut reader = BatchReader::new(&channel[..], state.max_rlp_bytes_per_channel);
+            for _ in 0..state.batches_read {
+                if reader.next_batch(self.cfg.as_ref()).is_none() {
+                    // The channel is exhausted, continue with the next channel.
+                    return Ok(());
+                }
+            }
+            self.next_batch = Some(reader);
+            self.channel = Some((channel, state.max_rlp_bytes_per_channel));
+            self.batches_read = state.batches_read;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -254,4 +315,38 @@ mod test {
         reader.flush();
         assert!(reader.next_batch.is_none());
     }
+
+    #[tokio::test]
+    async fn test_channel_reader_snapshot_restore() {
+        let raw = new_compressed_batch_data();
+        let mock = TestChannelReaderProvider::new(vec![Ok(Some(raw.clone()))]);
+        let mut reader = ChannelReader::new(mock, Arc::new(RollupConfig::default()));
+        reader.set_batch_reader().await.unwrap();
+
+        let mut snapshot = PipelineSnapshot::default();
+        reader.snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot.channel_reader,
+            Some(ChannelReaderSnapshot {
+                channel: Some(raw),
+                max_rlp_bytes_per_channel: MAX_RLP_BYTES_PER_CHANNEL_BEDROCK as usize,
+                batches_read: 0,
+            })
+        );
+
+        // The restored reader decodes the same batch without pulling from the previous stage.
+        let mock = TestChannelReaderProvider::new(vec![]);
+        let mut restored = ChannelReader::new(mock, Arc::new(RollupConfig::default()));
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.next_batch().await.unwrap(), reader.next_batch().await.unwrap());
+        assert_eq!(restored.batches_read, 1);
+
+        // Restoring after a batch has been read skips the batches that were already read.
+        restored.snapshot(&mut snapshot);
+        let mock = TestChannelReaderProvider::new(vec![]);
+        let mut skipped = ChannelReader::new(mock, Arc::new(RollupConfig::default()));
+        skipped.restore(&snapshot).await.unwrap();
+        assert_eq!(skipped.batches_read, 1);
+        assert_eq!(skipped.next_batch().await, restored.next_batch().await);
+    }
 }
@@ -3,8 +3,8 @@
 use crate::{
     errors::PipelineError,
     stages::NextFrameProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{FrameQueueSnapshot, PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use alloy_primitives::Bytes;
@@ -190,6 +190,33 @@ where
     }
 }
 
+#[async_trait]
+impl<P> StageSnapshot for FrameQueue<P>
+where
+    P: FrameQueueProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send
+        + Debug,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.frame_queue = Some(FrameQueueSnapshot { queue: self.queue.clone() });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let state = snapshot
+            .frame_queue
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("frame queue").crit())?;
+        self.queue = state.queue.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -206,6 +233,21 @@ pub(crate) mod tests {
         assert!(frame_queue.prev.reset);
     }
 
+    #[tokio::test]
+    async fn test_frame_queue_snapshot_restore() {
+        let mock = TestFrameQueueProvider::new(vec![]);
+        let mut frame_queue = FrameQueue::new(mock, Default::default());
+        frame_queue.queue.push_back(crate::frame!(0xFF, 0, vec![0xDD; 50], true));
+
+        let mut snapshot = PipelineSnapshot::default();
+        frame_queue.snapshot(&mut snapshot);
+
+        let mock = TestFrameQueueProvider::new(vec![]);
+        let mut restored = FrameQueue::new(mock, Default::default());
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.queue, frame_queue.queue);
+    }
+
     #[tokio::test]
     async fn test_frame_queue_empty_bytes() {
         let data = vec![Ok(Bytes::from(vec![0x00]))];
@@ -3,8 +3,13 @@
 use crate::{
     errors::{PipelineError, PipelineErrorKind},
     stages::FrameQueueProvider,
-    traits::{DataAvailabilityProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{ActivationSignal, PipelineResult, ResetSignal, Signal},
+    traits::{
+        DataAvailabilityProvider, OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot,
+    },
+    types::{
+        ActivationSignal, L1RetrievalSnapshot, PipelineResult, PipelineSnapshot, ResetSignal,
+        Signal,
+    },
 };
 use alloc::boxed::Box;
 use alloy_primitives::Address;
@@ -45,6 +50,8 @@ where
     pub provider: DAP,
     /// The current block ref.
     pub next: Option<BlockInfo>,
+    /// The number of data items consumed from the current block ref.
+    pub consumed: usize,
 }
 
 impl<DAP, P> L1Retrieval<DAP, P>
@@ -57,7 +64,7 @@ where
     ///
     /// [L1Traversal]: crate::stages::L1Traversal
     pub const fn new(prev: P, provider: DAP) -> Self {
-        Self { prev, provider, next: None }
+        Self { prev, provider, next: None, consumed: 0 }
     }
 }
 
@@ -92,11 +99,15 @@ where
         // SAFETY: The above check ensures that `next` is not None.
         let next = self.next.as_ref().expect("infallible");
 
-        match self.provider.next(next).await {
-            Ok(data) => Ok(data),
+        match self.provider.next(next, self.prev.batcher_addr()).await {
+            Ok(data) => {
+                self.consumed += 1;
+                Ok(data)
+            }
             Err(e) => {
                 if let PipelineErrorKind::Temporary(PipelineError::Eof) = e {
                     self.next = None;
+                    self.consumed = 0;
                     self.provider.clear();
                 }
                 Err(e)
@@ -127,6 +138,7 @@ where
             Signal::Reset(ResetSignal { l1_origin, .. }) |
             Signal::Activation(ActivationSignal { l1_origin, .. }) => {
                 self.next = Some(l1_origin);
+                self.consumed = 0;
             }
             _ => {}
         }
@@ -134,6 +146,48 @@ where
     }
 }
 
+#[async_trait]
+impl<DAP, P> StageSnapshot for L1Retrieval<DAP, P>
+where
+    DAP: DataAvailabilityProvider + Send,
+    P: L1RetrievalProvider
+        + OriginAdvancer
+        + OriginProvider
+        + SignalReceiver
+        + StageSnapshot
+        + Send,
+{
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        self.prev.snapshot(snapshot);
+        snapshot.l1_retrieval =
+            Some(L1RetrievalSnapshot { next: self.next, consumed: self.consumed });
+    }
+
+    /// Restores the [L1Retrieval] stage from the [PipelineSnapshot].
+    ///
+    /// The [DataAvailabilityProvider] is cleared and, if a block ref was in progress, its data
+    /// is re-opened and the items that were already consumed are skipped.
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        self.prev.restore(snapshot).await?;
+        let state = snapshot
+            .l1_retrieval
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("l1 retrieval").crit())?;
+
+        self.provider.clear();
+        self.next = state.next;
+        self.consumed = 0;
+        if let Some(next) = self.next.as_ref() {
+            let batcher_address = self.prev.batcher_addr();
+            while self.consumed < state.consumed {
+                self.provider.next(next, batcher_address).await?;
+                self.consumed += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,10 +298,51 @@ mod tests {
     async fn test_l1_retrieval_existing_data_errors() {
         let traversal = new_populated_test_traversal();
         let dap = TestDAP { results: vec![Err(PipelineError::Eof.temp())] };
-        let mut retrieval =
-            L1Retrieval { prev: traversal, provider: dap, next: Some(BlockInfo::default()) };
+        let mut retrieval = L1Retrieval {
+            prev: traversal,
+            provider: dap,
+            next: Some(BlockInfo::default()),
+            consumed: 0,
+        };
         let data = retrieval.next_data().await.unwrap_err();
         assert_eq!(data, PipelineError::Eof.temp());
         assert!(retrieval.next.is_none());
     }
+
+    #[tokio::test]
+    async fn test_l1_retrieval_snapshot_restore() {
+        let traversal = new_populated_test_traversal();
+        let results = vec![Ok(Bytes::default()), Ok(Bytes::default())];
+        let mut retrieval = L1Retrieval::new(traversal, TestDAP { results });
+        retrieval.next_data().await.unwrap();
+
+        let mut snapshot = PipelineSnapshot::default();
+        retrieval.snapshot(&mut snapshot);
+        assert_eq!(
+            snapshot.l1_retrieval,
+            Some(L1RetrievalSnapshot { next: Some(BlockInfo::default()), consumed: 1 })
+        );
+        assert!(snapshot.l1_traversal.as_ref().unwrap().done);
+
+        // The consumed data item cannot be skipped if the provider has no data.
+        let traversal = new_populated_test_traversal();
+        let mut restored = L1Retrieval::new(traversal, TestDAP::default());
+        assert_eq!(restored.restore(&snapshot).await.unwrap_err(), PipelineError::Eof.temp());
+
+        snapshot.l1_retrieval.as_mut().unwrap().consumed = 0;
+        restored.restore(&snapshot).await.unwrap();
+        assert_eq!(restored.next, Some(BlockInfo::default()));
+        assert_eq!(restored.consumed, 0);
+        assert!(restored.prev.done);
+    }
+
+    #[tokio::test]
+    async fn test_l1_retrieval_restore_missing_snapshot() {
+        let traversal = new_populated_test_traversal();
+        let mut retrieval = L1Retrieval::new(traversal, TestDAP::default());
+        let mut snapshot = PipelineSnapshot::default();
+        retrieval.prev.snapshot(&mut snapshot);
+        let err = retrieval.restore(&snapshot).await.unwrap_err();
+        assert_eq!(err, PipelineError::MissingSnapshot("l1 retrieval").crit());
+    }
 }
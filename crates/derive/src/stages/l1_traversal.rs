@@ -3,10 +3,13 @@
 use crate::{
     errors::{PipelineError, ResetError},
     stages::L1RetrievalProvider,
-    traits::{ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{ActivationSignal, PipelineResult, ResetSignal, Signal},
+    traits::{ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{
+        ActivationSignal, BatcherUpdate, L1TraversalSnapshot, PipelineResult, PipelineSnapshot,
+        ResetSignal, Signal,
+    },
 };
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 use alloy_primitives::Address;
 use async_trait::async_trait;
 use op_alloy_genesis::{RollupConfig, SystemConfig};
@@ -19,6 +22,9 @@ use op_alloy_protocol::BlockInfo;
 /// which are used to traverse the L1 chain. When the [L1Traversal] stage is advanced,
 /// it fetches the next L1 [BlockInfo] from the data source and updates the [SystemConfig]
 /// with the receipts from the block.
+///
+/// Every change of the batcher address is recorded in the stage's batcher history, which can be
+/// queried with [L1Traversal::batcher_at].
 #[derive(Debug, Clone)]
 pub struct L1Traversal<Provider: ChainProvider> {
     /// The current block in the traversal stage.
@@ -29,6 +35,8 @@ pub struct L1Traversal<Provider: ChainProvider> {
     pub done: bool,
     /// The system config.
     pub system_config: SystemConfig,
+    /// The history of batcher addresses, in ascending order of L1 block number.
+    pub batcher_history: Vec<BatcherUpdate>,
     /// A reference to the rollup config.
     pub rollup_config: Arc<RollupConfig>,
 }
@@ -51,15 +59,42 @@ impl<F: ChainProvider + Send> L1RetrievalProvider for L1Traversal<F> {
 
 impl<F: ChainProvider> L1Traversal<F> {
     /// Creates a new [L1Traversal] instance.
+    ///
+    /// The [SystemConfig] is initialized from the genesis of the [RollupConfig], until the stage
+    /// is reset.
     pub fn new(data_source: F, cfg: Arc<RollupConfig>) -> Self {
+        let system_config = cfg.genesis.system_config.unwrap_or_default();
         Self {
             block: Some(BlockInfo::default()),
             data_source,
             done: false,
-            system_config: SystemConfig::default(),
+            system_config,
+            batcher_history: vec![BatcherUpdate::new(
+                cfg.genesis.l1,
+                system_config.batcher_address,
+            )],
             rollup_config: cfg,
         }
     }
+
+    /// Returns the batcher address that was active at the given L1 block number, according to
+    /// the batcher history.
+    pub fn batcher_at(&self, number: u64) -> Option<Address> {
+        self.batcher_history
+            .iter()
+            .rev()
+            .find(|update| update.l1_block.number <= number)
+            .map(|update| update.batcher_address)
+    }
+
+    /// Records the batcher address of the current [SystemConfig] in the batcher history, if it
+    /// differs from the last recorded address.
+    fn record_batcher(&mut self, block: &BlockInfo) {
+        let batcher_address = self.system_config.batcher_address;
+        if self.batcher_history.last().map(|u| u.batcher_address) != Some(batcher_address) {
+            self.batcher_history.push(BatcherUpdate::new(block.id(), batcher_address));
+        }
+    }
 }
 
 #[async_trait]
@@ -96,6 +131,7 @@ impl<F: ChainProvider + Send> OriginAdvancer for L1Traversal<F> {
         ) {
             return Err(PipelineError::SystemConfigUpdate(e).crit());
         }
+        self.record_batcher(&next_l1_origin);
 
         let prev_block_holocene = self.rollup_config.is_holocene_active(block.timestamp);
         let next_block_holocene = self.rollup_config.is_holocene_active(next_l1_origin.timestamp);
@@ -129,6 +165,14 @@ impl<F: ChainProvider + Send> SignalReceiver for L1Traversal<F> {
                 self.block = Some(l1_origin);
                 self.done = false;
                 self.system_config = system_config.expect("System config must be provided.");
+
+                // Drop the history past the new origin, which may have been reorged out.
+                self.batcher_history.retain(|u| {
+                    u.l1_block.number < l1_origin.number ||
+                        (u.l1_block.number == l1_origin.number &&
+                            u.l1_block.hash == l1_origin.hash)
+                });
+                self.record_batcher(&l1_origin);
             }
             _ => {}
         }
@@ -137,6 +181,30 @@ impl<F: ChainProvider + Send> SignalReceiver for L1Traversal<F> {
     }
 }
 
+#[async_trait]
+impl<F: ChainProvider + Send> StageSnapshot for L1Traversal<F> {
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot) {
+        snapshot.l1_traversal = Some(L1TraversalSnapshot {
+            block: self.block,
+            done: self.done,
+            system_config: self.system_config,
+            batcher_history: self.batcher_history.clone(),
+        });
+    }
+
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()> {
+        let state = snapshot
+            .l1_traversal
+            .as_ref()
+            .ok_or(PipelineError::MissingSnapshot("l1 traversal").crit())?;
+        self.block = state.block;
+        self.done = state.done;
+        self.system_config = state.system_config;
+        self.batcher_history = state.batcher_history.clone();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -325,4 +393,24 @@ pub(crate) mod tests {
         let expected = address!("000000000000000000000000000000000000bEEF");
         assert_eq!(traversal.system_config.batcher_address, expected);
     }
+
+    #[tokio::test]
+    async fn test_l1_traversal_batcher_history() {
+        let blocks = vec![BlockInfo::default(), BlockInfo { number: 1, ..Default::default() }];
+        let receipts = new_receipts();
+        let mut traversal = new_test_traversal(blocks, receipts);
+        assert!(traversal.advance_origin().await.is_ok());
+        let expected = address!("000000000000000000000000000000000000bEEF");
+        assert_eq!(traversal.batcher_history.len(), 2);
+        assert_eq!(traversal.batcher_at(0), Some(Address::ZERO));
+        assert_eq!(traversal.batcher_at(1), Some(expected));
+        assert_eq!(traversal.batcher_at(10), Some(expected));
+
+        // Resetting to before the update drops it from the history.
+        let signal =
+            ResetSignal { system_config: Some(SystemConfig::default()), ..Default::default() };
+        assert!(traversal.signal(signal.signal()).await.is_ok());
+        assert_eq!(traversal.batcher_history.len(), 1);
+        assert_eq!(traversal.batcher_at(1), Some(Address::ZERO));
+    }
 }
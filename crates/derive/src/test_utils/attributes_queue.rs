@@ -4,8 +4,9 @@ use crate::{
     errors::{BuilderError, PipelineError, PipelineErrorKind},
     traits::{
         AttributesBuilder, AttributesProvider, OriginAdvancer, OriginProvider, SignalReceiver,
+        StageSnapshot,
     },
-    types::{PipelineResult, Signal},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, string::ToString, vec::Vec};
 use alloy_eips::BlockNumHash;
@@ -99,3 +100,12 @@ pub const fn new_test_attributes_provider(
 ) -> TestAttributesProvider {
     TestAttributesProvider { origin, batches, reset: false, flushed: false }
 }
+
+#[async_trait]
+impl StageSnapshot for TestAttributesProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
@@ -3,8 +3,8 @@
 use crate::{
     errors::PipelineError,
     stages::NextBatchProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, vec::Vec};
 use async_trait::async_trait;
@@ -73,3 +73,12 @@ impl SignalReceiver for TestNextBatchProvider {
         Ok(())
     }
 }
+
+#[async_trait]
+impl StageSnapshot for TestNextBatchProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
@@ -5,8 +5,8 @@
 use crate::{
     errors::PipelineError,
     stages::BatchStreamProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, vec::Vec};
 use async_trait::async_trait;
@@ -67,3 +67,12 @@ impl SignalReceiver for TestBatchStreamProvider {
         Ok(())
     }
 }
+
+#[async_trait]
+impl StageSnapshot for TestBatchStreamProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
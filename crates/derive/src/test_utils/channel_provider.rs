@@ -5,8 +5,8 @@
 use crate::{
     errors::PipelineError,
     stages::NextFrameProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, vec::Vec};
 use async_trait::async_trait;
@@ -63,3 +63,12 @@ impl SignalReceiver for TestNextFrameProvider {
         Ok(())
     }
 }
+
+#[async_trait]
+impl StageSnapshot for TestNextFrameProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
@@ -5,8 +5,8 @@
 use crate::{
     errors::PipelineError,
     stages::ChannelReaderProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, vec::Vec};
 use alloy_primitives::Bytes;
@@ -60,3 +60,12 @@ impl SignalReceiver for TestChannelReaderProvider {
         Ok(())
     }
 }
+
+#[async_trait]
+impl StageSnapshot for TestChannelReaderProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
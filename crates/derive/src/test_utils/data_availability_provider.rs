@@ -2,7 +2,7 @@
 
 use crate::{errors::PipelineError, traits::DataAvailabilityProvider, types::PipelineResult};
 use alloc::{boxed::Box, vec::Vec};
-use alloy_primitives::Bytes;
+use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
 use core::fmt::Debug;
 use op_alloy_protocol::BlockInfo;
@@ -18,7 +18,7 @@ pub struct TestDAP {
 impl DataAvailabilityProvider for TestDAP {
     type Item = Bytes;
 
-    async fn next(&mut self, _: &BlockInfo) -> PipelineResult<Self::Item> {
+    async fn next(&mut self, _: &BlockInfo, _: Address) -> PipelineResult<Self::Item> {
         self.results.pop().unwrap_or(Err(PipelineError::Eof.temp()))
     }
 
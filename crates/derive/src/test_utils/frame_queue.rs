@@ -3,8 +3,8 @@
 use crate::{
     errors::PipelineError,
     stages::FrameQueueProvider,
-    traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    traits::{OriginAdvancer, OriginProvider, SignalReceiver, StageSnapshot},
+    types::{PipelineResult, PipelineSnapshot, Signal},
 };
 use alloc::{boxed::Box, vec::Vec};
 use alloy_primitives::Bytes;
@@ -65,3 +65,12 @@ impl SignalReceiver for TestFrameQueueProvider {
         Ok(())
     }
 }
+
+#[async_trait]
+impl StageSnapshot for TestFrameQueueProvider {
+    fn snapshot(&self, _: &mut PipelineSnapshot) {}
+
+    async fn restore(&mut self, _: &PipelineSnapshot) -> PipelineResult<()> {
+        Ok(())
+    }
+}
@@ -47,3 +47,5 @@ mod frames;
 pub use frames::{FrameQueueAsserter, FrameQueueBuilder};
 
 mod macros;
+
+pub mod regression;
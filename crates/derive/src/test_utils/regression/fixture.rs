@@ -0,0 +1,149 @@
+//! The JSON fixture format for the regression-snapshot harness.
+
+use alloc::{string::String, vec::Vec};
+use alloy_consensus::{BlockBody, Header, Receipt, TxEnvelope};
+use alloy_eips::eip4844::Blob;
+use alloy_primitives::B256;
+use op_alloy_consensus::{OpBlock, OpTxEnvelope};
+use op_alloy_genesis::{RollupConfig, SystemConfig};
+use op_alloy_protocol::{BlockInfo, L2BlockInfo};
+use op_alloy_rpc_types_engine::{OpAttributesWithParent, OpPayloadAttributes};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A recorded derivation run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivationFixture {
+    /// A short description of the fixture.
+    pub name: String,
+    /// The rollup config of the chain.
+    pub rollup_config: RollupConfig,
+    /// The number of the L1 block that the pipeline starts deriving from.
+    pub l1_origin: u64,
+    /// The L2 safe head that the pipeline starts deriving from.
+    pub l2_safe_head: L2BlockInfo,
+    /// The recorded L1 chain, in ascending order.
+    pub l1_blocks: Vec<FixtureL1Block>,
+    /// The recorded L2 chain, starting at the safe head, in ascending order.
+    pub l2_blocks: Vec<FixtureL2Block>,
+    /// The attributes that the pipeline is expected to produce, in order.
+    #[serde(with = "expected_attributes")]
+    pub expected: Vec<OpAttributesWithParent>,
+}
+
+impl DerivationFixture {
+    /// Parses a [DerivationFixture] from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the [DerivationFixture] to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// The fixture encoding of [OpAttributesWithParent], with camelCase keys like the rest of the
+/// fixture.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FixtureAttributes {
+    attributes: OpPayloadAttributes,
+    parent: L2BlockInfo,
+    is_last_in_span: bool,
+}
+
+/// (De)serializes the expected attributes of a [DerivationFixture] as [FixtureAttributes].
+mod expected_attributes {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        expected: &[OpAttributesWithParent],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(expected.iter().map(|a| FixtureAttributes {
+            attributes: a.attributes.clone(),
+            parent: a.parent,
+            is_last_in_span: a.is_last_in_span,
+        }))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<OpAttributesWithParent>, D::Error> {
+        let expected = Vec::<FixtureAttributes>::deserialize(deserializer)?;
+        Ok(expected
+            .into_iter()
+            .map(|a| OpAttributesWithParent::new(a.attributes, a.parent, a.is_last_in_span))
+            .collect())
+    }
+}
+
+/// A recorded L1 block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureL1Block {
+    /// The block header.
+    pub header: Header,
+    /// The receipts in the block.
+    #[serde(default)]
+    pub receipts: Vec<Receipt>,
+    /// The transactions in the block.
+    #[serde(default)]
+    pub transactions: Vec<TxEnvelope>,
+    /// The blobs referenced by the transactions in the block.
+    #[serde(default)]
+    pub blobs: Vec<FixtureBlob>,
+}
+
+impl FixtureL1Block {
+    /// Returns the [BlockInfo] of the block.
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo::new(
+            self.header.hash_slow(),
+            self.header.number,
+            self.header.parent_hash,
+            self.header.timestamp,
+        )
+    }
+}
+
+/// A recorded blob, keyed by its versioned hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureBlob {
+    /// The versioned hash of the blob.
+    pub hash: B256,
+    /// The blob data.
+    pub blob: Blob,
+}
+
+/// A recorded L2 block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureL2Block {
+    /// The block info.
+    pub info: L2BlockInfo,
+    /// The system config after the block.
+    pub system_config: SystemConfig,
+    /// The block header. Only required if span batches overlapping the block are derived.
+    #[serde(default)]
+    pub header: Option<Header>,
+    /// The transactions in the block. Only required if span batches overlapping the block are
+    /// derived.
+    #[serde(default)]
+    pub transactions: Vec<OpTxEnvelope>,
+}
+
+impl FixtureL2Block {
+    /// Returns the full [OpBlock], if the header was recorded.
+    pub fn block(&self) -> Option<OpBlock> {
+        let header = self.header.clone()?;
+        let body = BlockBody {
+            transactions: self.transactions.clone(),
+            ommers: Vec::new(),
+            withdrawals: None,
+        };
+        Some(OpBlock { header, body })
+    }
+}
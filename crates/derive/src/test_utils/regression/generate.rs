@@ -0,0 +1,643 @@
+//! Generates the `batcher_data.json` snapshot.
+//!
+//! The snapshot is built the way the op-batcher posts a chain to L1, rather than being recorded
+//! from a devnet: L2 blocks with signed user transactions are batched into singular and span
+//! batches, the batches are RLP-encoded into zlib and brotli compressed channels, the channels are
+//! split into frames, and the frames are posted by signed batcher transactions, both as calldata
+//! and as blobs with KZG commitments. The L1 and L2 chains are linked by hash, and the expected
+//! attributes are recorded from the pipeline and cross-checked against the batched blocks.
+//!
+//! The L1 chain exercises:
+//! - Block 1: the last frame of channel A as calldata, and a transaction to the batch inbox from a
+//!   signer that is not the batcher.
+//! - Block 2: the first frame of channel A as calldata, completing the channel out of order. The
+//!   channel holds zlib compressed singular batches for L2 blocks 1 to 5.
+//! - Block 3: channel B in a blob, with a zlib compressed span batch for L2 blocks 6 to 12, and a
+//!   transaction that is not sent to the batch inbox. Fjord activates at L2 block 12.
+//! - Block 4: channel C split across the two blobs of one transaction, with a brotli compressed
+//!   span batch for L2 blocks 13 to 18.
+//! - Block 5: Granite activates. The first frame of channel D, with stale singular batches for L2
+//!   blocks 19 to 23 that carry different transactions than the batched blocks.
+//! - Block 8: the last frame of channel D, which has timed out under the Granite channel timeout
+//!   but not under the channel timeout before Granite, and channel E with a span batch for L2
+//!   blocks 19 to 29.
+//! - Block 9: channel F with a brotli compressed span batch for L2 blocks 30 to 53.
+//! - Block 10: Holocene activates at L2 block 60. Channel G, with stale singular batches for L2
+//!   blocks 54 to 59, in two frames that are posted in reverse order in one transaction, and
+//!   channel H with a stale singular batch for L2 block 55 followed by the batch for L2 block 54.
+//!   The frame queue prunes the out of order frames of channel G, and the future batch of channel H
+//!   is dropped along with the rest of the channel.
+//! - Block 11: channel I with a span batch for L2 blocks 54 to 65.
+//! - Blocks 12 and 13: no batcher transactions.
+//!
+//! Run `cargo test -p kona-derive --features test-utils generate_batcher_data_fixture -- --ignored`
+//! to regenerate the snapshot.
+
+use crate::{
+    sources::{BlobData, BLOB_ENCODING_ROUNDS, BLOB_MAX_DATA_SIZE},
+    test_utils::regression::{
+        run_fixture, DerivationFixture, FixtureBlob, FixtureL1Block, FixtureL2Block,
+    },
+};
+use alloc::{string::ToString, vec, vec::Vec};
+use alloy_consensus::{
+    constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH},
+    proofs::{calculate_receipt_root, calculate_transaction_root},
+    Eip658Value, Header, Receipt, ReceiptEnvelope, SignableTransaction, Signed, TxEip1559,
+    TxEip4844, TxEnvelope,
+};
+use alloy_eips::{
+    eip2718::Encodable2718,
+    eip4844::{
+        builder::{PartialSidecar, SidecarBuilder, SidecarCoder},
+        utils::WholeFe,
+        Blob, DATA_GAS_PER_BLOB, FIELD_ELEMENTS_PER_BLOB,
+    },
+    BlockNumHash,
+};
+use alloy_primitives::{keccak256, Address, Bytes, TxKind, B256, B64, U256};
+use core::ops::RangeInclusive;
+use k256::ecdsa::SigningKey;
+use op_alloy_consensus::OpTxType;
+use op_alloy_genesis::{ChainGenesis, RollupConfig, SystemConfig};
+use op_alloy_protocol::{
+    compress_brotli, Batch, BlockInfo, BrotliLevel, ChannelId, Frame, L2BlockInfo, SingleBatch,
+    SpanBatch, DERIVATION_VERSION_0,
+};
+use op_alloy_rpc_types_engine::OpAttributesWithParent;
+
+/// The path of the generated snapshot.
+const FIXTURE_PATH: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/regression/batcher_data.json");
+
+/// The L1 chain ID.
+const L1_CHAIN_ID: u64 = 900;
+
+/// The L2 chain ID.
+const L2_CHAIN_ID: u64 = 901;
+
+/// The L1 block time.
+const L1_BLOCK_TIME: u64 = 12;
+
+/// The L2 block time.
+const L2_BLOCK_TIME: u64 = 2;
+
+/// The number of L2 blocks that are batched.
+const L2_BLOCKS: u64 = 65;
+
+/// The number of L1 blocks after genesis.
+const L1_BLOCKS: u64 = 13;
+
+/// The L2 block gas limit.
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// The L1 base fee.
+const BASE_FEE: u64 = 1_000_000_000;
+
+/// The batch inbox address.
+const BATCH_INBOX: Address = Address::new([
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x09, 0x01,
+]);
+
+/// Returns the signing key derived from `seed`.
+fn signing_key(seed: &str) -> SigningKey {
+    SigningKey::from_slice(keccak256(seed).as_slice()).unwrap()
+}
+
+/// Signs the transaction with the key.
+fn sign<T: SignableTransaction<alloy_primitives::PrimitiveSignature>>(
+    tx: T,
+    key: &SigningKey,
+) -> Signed<T> {
+    let (signature, recovery_id) =
+        key.sign_prehash_recoverable(tx.signature_hash().as_slice()).unwrap();
+    tx.into_signed((signature, recovery_id).into())
+}
+
+/// A [SidecarCoder] that encodes each piece of ingested data into its own blob, with the encoding
+/// of the blob data source.
+#[derive(Debug, Default)]
+struct OpBlobCoder;
+
+impl SidecarCoder for OpBlobCoder {
+    fn required_fe(&self, _: &[u8]) -> usize {
+        FIELD_ELEMENTS_PER_BLOB as usize
+    }
+
+    fn code(&mut self, builder: &mut PartialSidecar, data: &[u8]) {
+        for fe in encode_blob(data).chunks_exact(32) {
+            builder.ingest_valid_fe(WholeFe::new(fe).unwrap());
+        }
+    }
+
+    fn finish(self, _: &mut PartialSidecar) {}
+
+    fn decode_all(&mut self, blobs: &[Blob]) -> Option<Vec<Vec<u8>>> {
+        blobs
+            .iter()
+            .map(|blob| {
+                let data = BlobData { data: Some(Bytes::from(*blob)), calldata: None };
+                data.decode().ok().map(Into::into)
+            })
+            .collect()
+    }
+}
+
+/// Encodes `data` into a blob, the inverse of [BlobData::decode].
+///
+/// Each round encodes 127 bytes of data into 4 field elements: 4 * 31 bytes are stored in the low
+/// 31 bytes of the field elements, and the remaining 3 bytes are split into 6 bit chunks that are
+/// stored in their high order bytes. The first round starts with the encoding version and the
+/// 3 byte length of the data.
+fn encode_blob(data: &[u8]) -> Blob {
+    assert!(data.len() <= BLOB_MAX_DATA_SIZE);
+    let mut padded = data.to_vec();
+    padded.resize(BLOB_MAX_DATA_SIZE, 0);
+
+    let mut blob = Blob::ZERO;
+    let mut offset = 0;
+    for round in 0..BLOB_ENCODING_ROUNDS {
+        if round > 0 && offset >= data.len() {
+            break;
+        }
+
+        let mut first = [0u8; 31];
+        if round == 0 {
+            first[0] = 0;
+            first[1..4].copy_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+            first[4..].copy_from_slice(&padded[..27]);
+            offset = 27;
+        } else {
+            first.copy_from_slice(&padded[offset..offset + 31]);
+            offset += 31;
+        }
+
+        let (x, y, z) = (padded[offset], padded[offset + 32], padded[offset + 64]);
+        let high = [
+            x & 0b0011_1111,
+            (y & 0b0000_1111) | ((x & 0b1100_0000) >> 2),
+            z & 0b0011_1111,
+            ((z & 0b1100_0000) >> 2) | ((y & 0b1111_0000) >> 4),
+        ];
+        let low = [
+            &first[..],
+            &padded[offset + 1..offset + 32],
+            &padded[offset + 33..offset + 64],
+            &padded[offset + 65..offset + 96],
+        ];
+        offset += 96;
+
+        let round = &mut blob[round * 128..(round + 1) * 128];
+        for (i, fe) in round.chunks_exact_mut(32).enumerate() {
+            fe[0] = high[i];
+            fe[1..].copy_from_slice(low[i]);
+        }
+    }
+    blob
+}
+
+/// Encodes the batches into channel data: the RLP encoding of each batch, compressed with brotli
+/// or zlib.
+fn channel_data(batches: &[Batch], brotli: bool) -> Vec<u8> {
+    let mut rlp = Vec::new();
+    for batch in batches {
+        let mut encoded = Vec::new();
+        batch.encode(&mut encoded).unwrap();
+        alloy_rlp::Encodable::encode(&Bytes::from(encoded), &mut rlp);
+    }
+
+    if brotli {
+        let mut data = vec![0x01];
+        data.extend(compress_brotli(&rlp, BrotliLevel::Brotli10).unwrap());
+        data
+    } else {
+        miniz_oxide::deflate::compress_to_vec_zlib(&rlp, 9)
+    }
+}
+
+/// Splits channel data into `count` frames of the channel.
+fn frames(id: ChannelId, data: &[u8], count: usize) -> Vec<Frame> {
+    let chunks = data.chunks(data.len().div_ceil(count)).collect::<Vec<_>>();
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| Frame::new(id, i as u16, chunk.to_vec(), i == chunks.len() - 1))
+        .collect()
+}
+
+/// Encodes the frames into batcher transaction data.
+fn batcher_data(frames: &[Frame]) -> Vec<u8> {
+    let mut data = vec![DERIVATION_VERSION_0];
+    for frame in frames {
+        data.extend(frame.encode());
+    }
+    data
+}
+
+/// Returns the intrinsic gas of a transaction with the given input.
+fn intrinsic_gas(input: &[u8]) -> u64 {
+    21_000 + input.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum::<u64>()
+}
+
+/// A signer of transactions.
+struct Signer {
+    key: SigningKey,
+    nonce: u64,
+}
+
+impl Signer {
+    fn new(seed: &str) -> Self {
+        Self { key: signing_key(seed), nonce: 0 }
+    }
+
+    fn address(&self) -> Address {
+        Address::from_private_key(&self.key)
+    }
+
+    /// Signs an EIP-1559 transaction to `to` with the given input.
+    fn eip1559(&mut self, chain_id: u64, to: Address, input: Vec<u8>) -> TxEnvelope {
+        let tx = TxEip1559 {
+            chain_id,
+            nonce: self.nonce,
+            gas_limit: intrinsic_gas(&input),
+            max_fee_per_gas: 2 * BASE_FEE as u128,
+            max_priority_fee_per_gas: BASE_FEE as u128,
+            to: TxKind::Call(to),
+            value: U256::from(self.nonce),
+            input: input.into(),
+            ..Default::default()
+        };
+        self.nonce += 1;
+        sign(tx, &self.key).into()
+    }
+
+    /// Signs an EIP-4844 transaction to the batch inbox that carries the given blob data, returning
+    /// the transaction and its blobs.
+    fn eip4844(&mut self, data: &[Vec<u8>]) -> (TxEnvelope, Vec<FixtureBlob>) {
+        let mut builder = SidecarBuilder::from_coder_and_capacity(OpBlobCoder, data.len());
+        for data in data {
+            builder.ingest(data);
+        }
+        let sidecar = builder.build().unwrap();
+        let blob_versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        let blobs = blob_versioned_hashes
+            .iter()
+            .zip(sidecar.blobs.iter())
+            .map(|(hash, blob)| FixtureBlob { hash: *hash, blob: *blob })
+            .collect();
+
+        let tx = TxEip4844 {
+            chain_id: L1_CHAIN_ID,
+            nonce: self.nonce,
+            gas_limit: intrinsic_gas(&[]),
+            max_fee_per_gas: 2 * BASE_FEE as u128,
+            max_priority_fee_per_gas: BASE_FEE as u128,
+            to: BATCH_INBOX,
+            blob_versioned_hashes,
+            max_fee_per_blob_gas: 1,
+            ..Default::default()
+        };
+        self.nonce += 1;
+        (sign(tx, &self.key).into(), blobs)
+    }
+}
+
+/// Builds the L1 block after `parent` that contains the transactions.
+fn l1_block(
+    parent: &Header,
+    transactions: Vec<TxEnvelope>,
+    blobs: Vec<FixtureBlob>,
+) -> FixtureL1Block {
+    let mut cumulative_gas_used = 0;
+    let receipts = transactions
+        .iter()
+        .map(|tx| {
+            cumulative_gas_used += alloy_consensus::Transaction::gas_limit(tx);
+            Receipt {
+                status: Eip658Value::Eip658(true),
+                cumulative_gas_used: cumulative_gas_used as u128,
+                logs: Vec::new(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let envelopes = transactions
+        .iter()
+        .zip(receipts.iter())
+        .map(|(tx, receipt)| match tx {
+            TxEnvelope::Eip4844(_) => ReceiptEnvelope::Eip4844(receipt.clone().with_bloom()),
+            _ => ReceiptEnvelope::Eip1559(receipt.clone().with_bloom()),
+        })
+        .collect::<Vec<_>>();
+
+    let number = parent.number + 1;
+    let header = Header {
+        parent_hash: parent.hash_slow(),
+        transactions_root: calculate_transaction_root(&transactions),
+        receipts_root: calculate_receipt_root(&envelopes),
+        gas_used: cumulative_gas_used,
+        blob_gas_used: Some(blobs.len() as u64 * DATA_GAS_PER_BLOB),
+        ..l1_header(number)
+    };
+    FixtureL1Block { header, receipts, transactions, blobs }
+}
+
+/// Returns the header of an empty L1 block at `number`.
+fn l1_header(number: u64) -> Header {
+    Header {
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        state_root: EMPTY_ROOT_HASH,
+        transactions_root: EMPTY_ROOT_HASH,
+        receipts_root: EMPTY_ROOT_HASH,
+        number,
+        gas_limit: GAS_LIMIT,
+        timestamp: number * L1_BLOCK_TIME,
+        mix_hash: B256::with_last_byte(number as u8 + 1),
+        base_fee_per_gas: Some(BASE_FEE),
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        parent_beacon_block_root: Some(B256::with_last_byte(number as u8 + 0x80)),
+        ..Default::default()
+    }
+}
+
+/// Returns the number of user transactions in the L2 block at `number`.
+const fn user_transactions(number: u64) -> usize {
+    match number {
+        2 | 5 | 8 | 14 | 17 | 20 | 27 | 31 | 44 | 54 | 58 | 61 => 1,
+        11 | 23 | 55 | 64 => 2,
+        _ => 0,
+    }
+}
+
+/// Builds the fixture, recording the expected attributes from the pipeline.
+async fn batcher_data_fixture() -> DerivationFixture {
+    let mut batcher = Signer::new("batcher");
+    let mut intruder = Signer::new("intruder");
+    let mut user = Signer::new("user");
+    let mut stale = Signer::new("stale");
+
+    // The L2 chain, with the user transactions of each block.
+    let l1_genesis = l1_header(0);
+    let system_config = SystemConfig {
+        batcher_address: batcher.address(),
+        overhead: U256::from(0xbc),
+        scalar: U256::from(0xa6fe0),
+        gas_limit: GAS_LIMIT,
+        eip1559_denominator: Some(250),
+        eip1559_elasticity: Some(6),
+        ..Default::default()
+    };
+    let l2_genesis = Header { number: 0, timestamp: 0, gas_limit: GAS_LIMIT, ..Default::default() };
+    let mut l2_headers = vec![l2_genesis];
+    let mut l2_transactions = vec![Vec::new()];
+    for number in 1..=L2_BLOCKS {
+        let transactions = (0..user_transactions(number))
+            .map(|i| user.eip1559(L2_CHAIN_ID, Address::with_last_byte(i as u8 + 1), vec![i as u8]))
+            .collect::<Vec<_>>();
+        let header = Header {
+            parent_hash: l2_headers.last().unwrap().hash_slow(),
+            transactions_root: calculate_transaction_root(&transactions),
+            number,
+            timestamp: number * L2_BLOCK_TIME,
+            gas_limit: GAS_LIMIT,
+            ..Default::default()
+        };
+        l2_headers.push(header);
+        l2_transactions.push(transactions);
+    }
+
+    let cfg = RollupConfig {
+        genesis: ChainGenesis {
+            l1: BlockNumHash { number: 0, hash: l1_genesis.hash_slow() },
+            l2: BlockNumHash { number: 0, hash: l2_headers[0].hash_slow() },
+            l2_time: 0,
+            system_config: Some(system_config),
+        },
+        block_time: L2_BLOCK_TIME,
+        max_sequencer_drift: 600,
+        seq_window_size: 8,
+        channel_timeout: 4,
+        granite_channel_timeout: 2,
+        l1_chain_id: L1_CHAIN_ID,
+        l2_chain_id: L2_CHAIN_ID,
+        regolith_time: Some(0),
+        canyon_time: Some(0),
+        delta_time: Some(0),
+        ecotone_time: Some(0),
+        fjord_time: Some(24),
+        granite_time: Some(60),
+        holocene_time: Some(120),
+        batch_inbox_address: BATCH_INBOX,
+        deposit_contract_address: Address::with_last_byte(1),
+        l1_system_config_address: Address::with_last_byte(2),
+        protocol_versions_address: Address::with_last_byte(3),
+        ..Default::default()
+    };
+
+    // The batches reference the L1 blocks that they are built on, so the L1 chain is built block
+    // by block, and each batch is posted after the L1 origins of its blocks.
+    let epoch = |number: u64| number * L2_BLOCK_TIME / L1_BLOCK_TIME;
+    let l2_info = |l1: &[FixtureL1Block], number: u64| {
+        let header = &l2_headers[number as usize];
+        let epoch = epoch(number);
+        L2BlockInfo::new(
+            BlockInfo::new(header.hash_slow(), number, header.parent_hash, header.timestamp),
+            BlockNumHash { number: epoch, hash: l1[epoch as usize].header.hash_slow() },
+            number - epoch * L1_BLOCK_TIME / L2_BLOCK_TIME,
+        )
+    };
+    let single_batch = |l1: &[FixtureL1Block], number: u64| {
+        let info = l2_info(l1, number);
+        SingleBatch {
+            parent_hash: info.block_info.parent_hash,
+            epoch_num: info.l1_origin.number,
+            epoch_hash: info.l1_origin.hash,
+            timestamp: info.block_info.timestamp,
+            transactions: l2_transactions[number as usize]
+                .iter()
+                .map(|tx| tx.encoded_2718().into())
+                .collect(),
+        }
+    };
+    // A batch for the L2 block with a transaction of the stale signer instead of its own.
+    let mut stale_batch = |l1: &[FixtureL1Block], number: u64| {
+        let transaction = stale.eip1559(L2_CHAIN_ID, Address::with_last_byte(0xee), Vec::new());
+        Batch::Single(SingleBatch {
+            transactions: vec![transaction.encoded_2718().into()],
+            ..single_batch(l1, number)
+        })
+    };
+    let span_batch = |l1: &[FixtureL1Block], numbers: RangeInclusive<u64>| {
+        let mut span = SpanBatch { chain_id: L2_CHAIN_ID, ..Default::default() };
+        for number in numbers {
+            let seq_num = l2_info(l1, number).seq_num;
+            span.append_singular_batch(single_batch(l1, number), seq_num).unwrap();
+        }
+        Batch::Span(span)
+    };
+
+    let mut l1 = vec![FixtureL1Block { header: l1_genesis, ..Default::default() }];
+
+    // Channel A: singular batches for the L2 blocks of epoch 0, in two frames that are posted in
+    // reverse order. A transaction from a signer that is not the batcher is posted alongside.
+    let batches = (1..=5).map(|n| Batch::Single(single_batch(&l1, n))).collect::<Vec<_>>();
+    let channel_a = frames(channel_id("channel a"), &channel_data(&batches, false), 2);
+    let transactions = vec![
+        intruder.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_a[..1])),
+        batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_a[1..])),
+    ];
+    l1.push(l1_block(&l1[0].header, transactions, Vec::new()));
+    let transactions =
+        vec![batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_a[..1]))];
+    l1.push(l1_block(&l1[1].header, transactions, Vec::new()));
+
+    // Channel B: a span batch for the L2 blocks of epoch 1 and the first block of epoch 2, in a
+    // blob. A transaction that is not sent to the batch inbox is posted alongside.
+    let batches = [span_batch(&l1, 6..=12)];
+    let channel_b = frames(channel_id("channel b"), &channel_data(&batches, false), 1);
+    let (blob_tx, blobs) = batcher.eip4844(&[batcher_data(&channel_b)]);
+    let transactions =
+        vec![intruder.eip1559(L1_CHAIN_ID, Address::with_last_byte(0xaa), Vec::new()), blob_tx];
+    l1.push(l1_block(&l1[2].header, transactions, blobs));
+
+    // Channel C: a brotli compressed span batch for the rest of epoch 2 and the first block of
+    // epoch 3, in two frames that are posted in the two blobs of one transaction.
+    let batches = [span_batch(&l1, 13..=18)];
+    let channel_c = frames(channel_id("channel c"), &channel_data(&batches, true), 2);
+    let (blob_tx, blobs) =
+        batcher.eip4844(&[batcher_data(&channel_c[..1]), batcher_data(&channel_c[1..])]);
+    l1.push(l1_block(&l1[3].header, vec![blob_tx], blobs));
+
+    // Channel D: stale singular batches for the rest of epoch 3, in two frames that are posted
+    // three L1 blocks apart, after Granite activates.
+    let empty = |l1: &mut Vec<FixtureL1Block>| {
+        let parent = &l1[l1.len() - 1].header;
+        l1.push(l1_block(parent, Vec::new(), Vec::new()));
+    };
+    let batches = (19..=23).map(|n| stale_batch(&l1, n)).collect::<Vec<_>>();
+    let channel_d = frames(channel_id("channel d"), &channel_data(&batches, false), 2);
+    let transactions =
+        vec![batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_d[..1]))];
+    l1.push(l1_block(&l1[4].header, transactions, Vec::new()));
+    empty(&mut l1);
+    empty(&mut l1);
+
+    // Channel E: a span batch for the rest of epoch 3 and epoch 4, posted after the last frame of
+    // the timed out channel D.
+    let batches = [span_batch(&l1, 19..=29)];
+    let channel_e = frames(channel_id("channel e"), &channel_data(&batches, false), 1);
+    let transactions = vec![
+        batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_d[1..])),
+        batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_e)),
+    ];
+    l1.push(l1_block(&l1[7].header, transactions, Vec::new()));
+
+    // Channel F: a brotli compressed span batch for epochs 5 to 8.
+    let batches = [span_batch(&l1, 30..=53)];
+    let channel_f = frames(channel_id("channel f"), &channel_data(&batches, true), 1);
+    let transactions = vec![batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_f))];
+    l1.push(l1_block(&l1[8].header, transactions, Vec::new()));
+
+    // Channels G and H: stale batches for epoch 9, after Holocene activates. The frames of channel
+    // G are out of order, and the first batch of channel H is a future batch.
+    let batches = (54..=59).map(|n| stale_batch(&l1, n)).collect::<Vec<_>>();
+    let mut channel_g = frames(channel_id("channel g"), &channel_data(&batches, false), 2);
+    channel_g.reverse();
+    let batches = [stale_batch(&l1, 55), Batch::Single(single_batch(&l1, 54))];
+    let channel_h = frames(channel_id("channel h"), &channel_data(&batches, false), 1);
+    let transactions = vec![
+        batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_g)),
+        batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_h)),
+    ];
+    l1.push(l1_block(&l1[9].header, transactions, Vec::new()));
+
+    // Channel I: a span batch for epochs 9 and 10, across the Holocene activation.
+    let batches = [span_batch(&l1, 54..=65)];
+    let channel_i = frames(channel_id("channel i"), &channel_data(&batches, false), 1);
+    let transactions = vec![batcher.eip1559(L1_CHAIN_ID, BATCH_INBOX, batcher_data(&channel_i))];
+    l1.push(l1_block(&l1[10].header, transactions, Vec::new()));
+
+    while (l1.len() as u64) <= L1_BLOCKS {
+        empty(&mut l1);
+    }
+
+    let l2_blocks = (0..=L2_BLOCKS)
+        .map(|number| FixtureL2Block {
+            info: l2_info(&l1, number),
+            system_config,
+            header: None,
+            transactions: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    let placeholder = OpAttributesWithParent::new(Default::default(), Default::default(), false);
+    let mut fixture = DerivationFixture {
+        name: "Calldata and blob batcher data with singular and span batches across the Fjord, \
+               Granite, and Holocene activations"
+            .to_string(),
+        rollup_config: cfg,
+        l1_origin: 0,
+        l2_safe_head: l2_blocks[0].info,
+        l1_blocks: l1,
+        l2_blocks,
+        expected: vec![placeholder; L2_BLOCKS as usize],
+    };
+    fixture.expected = run_fixture(&fixture).await.unwrap();
+
+    // Every batched block is derived in order, on top of the block before it, with the user
+    // transactions of the block after its deposits.
+    assert_eq!(fixture.expected.len(), L2_BLOCKS as usize);
+    for (i, attributes) in fixture.expected.iter().enumerate() {
+        let number = i as u64 + 1;
+        assert_eq!(attributes.parent, fixture.l2_blocks[i].info);
+        assert_eq!(attributes.attributes.payload_attributes.timestamp, number * L2_BLOCK_TIME);
+        let transactions = attributes.attributes.transactions.as_ref().unwrap();
+        let user = l2_transactions[number as usize]
+            .iter()
+            .map(|tx| Bytes::from(tx.encoded_2718()))
+            .collect::<Vec<_>>();
+        let (deposits, batched) = transactions.split_at(transactions.len() - user.len());
+        assert_eq!(batched, user);
+        assert!(!deposits.is_empty());
+        assert!(deposits.iter().all(|tx| tx[0] == OpTxType::Deposit as u8));
+
+        // The EIP-1559 parameters are zero in the first Holocene block, and taken from the system
+        // config after it.
+        let eip_1559_params = attributes.attributes.eip_1559_params;
+        match number {
+            ..60 => assert_eq!(eip_1559_params, None),
+            60 => assert_eq!(eip_1559_params, Some(B64::ZERO)),
+            _ => assert_eq!(eip_1559_params, Some(B64::new([0, 0, 0, 250, 0, 0, 0, 6]))),
+        }
+    }
+    fixture
+}
+
+/// Returns the [ChannelId] derived from `seed`.
+fn channel_id(seed: &str) -> ChannelId {
+    keccak256(seed)[..16].try_into().unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn generate_batcher_data_fixture() {
+    let fixture = batcher_data_fixture().await;
+    std::fs::write(FIXTURE_PATH, fixture.to_json().unwrap() + "\n").unwrap();
+}
+
+#[tokio::test]
+async fn test_batcher_data_fixture_is_reproducible() {
+    let fixture = batcher_data_fixture().await;
+    let expected = std::fs::read_to_string(FIXTURE_PATH).unwrap();
+    assert_eq!(fixture.to_json().unwrap() + "\n", expected);
+}
+
+#[test]
+fn test_encode_blob() {
+    for len in [0, 1, 27, 28, 123, 124, 1000, BLOB_MAX_DATA_SIZE] {
+        let data = (0..len).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+        let blob = BlobData { data: Some(Bytes::from(encode_blob(&data))), calldata: None };
+        assert_eq!(blob.decode().unwrap(), data);
+    }
+}
@@ -0,0 +1,30 @@
+//! A deterministic derivation regression-snapshot harness.
+//!
+//! A [DerivationFixture] records an L1 chain (headers, receipts, transactions, and blobs), the
+//! L2 chain that was derived from it, and the exact sequence of [OpAttributesWithParent] that the
+//! derivation pipeline is expected to produce. The [FixtureProvider] serves the recorded chains
+//! to the pipeline, and [run_fixture] drives a full [DerivationPipeline] over them the same way
+//! the client's driver does.
+//!
+//! Snapshots are stored as JSON in `testdata/regression`. The expected attributes of a snapshot
+//! were recorded from this pipeline, so a snapshot catches changes to its behavior, but it is not
+//! a cross-client conformance vector. The `batcher_data.json` snapshot is built by the `generate`
+//! test module, which posts batcher data to the recorded L1 chain in the formats of the
+//! op-batcher.
+//!
+//! [OpAttributesWithParent]: op_alloy_rpc_types_engine::OpAttributesWithParent
+//! [DerivationPipeline]: crate::pipeline::DerivationPipeline
+
+mod fixture;
+pub use fixture::{DerivationFixture, FixtureBlob, FixtureL1Block, FixtureL2Block};
+
+mod provider;
+pub use provider::{FixtureProvider, FixtureProviderError};
+
+mod runner;
+pub use runner::{
+    check_fixture, new_fixture_pipeline, run_fixture, FixturePipeline, RegressionError,
+};
+
+#[cfg(test)]
+mod generate;
@@ -0,0 +1,176 @@
+//! Contains the [FixtureProvider], which serves a [DerivationFixture] to the pipeline.
+
+use crate::{
+    errors::{PipelineError, PipelineErrorKind},
+    test_utils::regression::{DerivationFixture, FixtureL1Block, FixtureL2Block},
+    traits::{BlobProvider, ChainProvider, L2ChainProvider},
+};
+use alloc::{boxed::Box, string::ToString, sync::Arc, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_eips::eip4844::{Blob, IndexedBlobHash};
+use alloy_primitives::{map::HashMap, B256};
+use async_trait::async_trait;
+use op_alloy_consensus::OpBlock;
+use op_alloy_genesis::{RollupConfig, SystemConfig};
+use op_alloy_protocol::{BatchValidationProvider, BlockInfo, L2BlockInfo};
+use thiserror::Error;
+
+/// An error returned by the [FixtureProvider].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FixtureProviderError {
+    /// The requested L1 block number is past the end of the recorded chain.
+    #[error("L1 block number {0} is past the recorded chain head")]
+    L1BlockNumberPastHead(u64),
+    /// The requested L1 block is not in the fixture.
+    #[error("L1 block {0} not found in fixture")]
+    L1BlockNotFound(B256),
+    /// The requested L2 block is not in the fixture.
+    #[error("L2 block {0} not found in fixture")]
+    L2BlockNotFound(u64),
+    /// The full L2 block is not in the fixture.
+    #[error("L2 block {0} has no recorded body")]
+    L2BlockBodyMissing(u64),
+    /// The requested blob is not in the fixture.
+    #[error("Blob {0} not found in fixture")]
+    BlobNotFound(B256),
+}
+
+impl From<FixtureProviderError> for PipelineErrorKind {
+    fn from(val: FixtureProviderError) -> Self {
+        match val {
+            FixtureProviderError::L1BlockNumberPastHead(_) => PipelineError::EndOfSource.crit(),
+            _ => PipelineError::Provider(val.to_string()).crit(),
+        }
+    }
+}
+
+/// The indexed contents of a [DerivationFixture].
+#[derive(Debug, Default)]
+struct FixtureIndex {
+    /// The L1 blocks, keyed by number.
+    l1_by_number: HashMap<u64, (BlockInfo, FixtureL1Block)>,
+    /// The L1 block numbers, keyed by hash.
+    l1_by_hash: HashMap<B256, u64>,
+    /// The L2 blocks, keyed by number.
+    l2_by_number: HashMap<u64, FixtureL2Block>,
+    /// The blobs, keyed by versioned hash.
+    blobs: HashMap<B256, Blob>,
+}
+
+/// A [ChainProvider], [BlobProvider], and [L2ChainProvider] that serves the chains recorded in a
+/// [DerivationFixture].
+///
+/// Missing data is a critical error, with the exception of L1 blocks past the end of the recorded
+/// chain, which end the data source.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureProvider {
+    index: Arc<FixtureIndex>,
+}
+
+impl FixtureProvider {
+    /// Creates a new [FixtureProvider] from the given [DerivationFixture].
+    pub fn new(fixture: &DerivationFixture) -> Self {
+        let mut index = FixtureIndex::default();
+        for block in &fixture.l1_blocks {
+            let info = block.block_info();
+            index.l1_by_hash.insert(info.hash, info.number);
+            index.blobs.extend(block.blobs.iter().map(|b| (b.hash, b.blob)));
+            index.l1_by_number.insert(info.number, (info, block.clone()));
+        }
+        for block in &fixture.l2_blocks {
+            index.l2_by_number.insert(block.info.block_info.number, block.clone());
+        }
+        Self { index: Arc::new(index) }
+    }
+
+    /// Returns the L1 block with the given hash.
+    fn l1_block(&self, hash: B256) -> Result<&(BlockInfo, FixtureL1Block), FixtureProviderError> {
+        self.index
+            .l1_by_hash
+            .get(&hash)
+            .and_then(|n| self.index.l1_by_number.get(n))
+            .ok_or(FixtureProviderError::L1BlockNotFound(hash))
+    }
+
+    /// Returns the L2 block with the given number.
+    fn l2_block(&self, number: u64) -> Result<&FixtureL2Block, FixtureProviderError> {
+        self.index.l2_by_number.get(&number).ok_or(FixtureProviderError::L2BlockNotFound(number))
+    }
+}
+
+#[async_trait]
+impl ChainProvider for FixtureProvider {
+    type Error = FixtureProviderError;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        Ok(self.l1_block(hash)?.1.header.clone())
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        self.index
+            .l1_by_number
+            .get(&number)
+            .map(|(info, _)| *info)
+            .ok_or(FixtureProviderError::L1BlockNumberPastHead(number))
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        Ok(self.l1_block(hash)?.1.receipts.clone())
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        let (info, block) = self.l1_block(hash)?;
+        Ok((*info, block.transactions.clone()))
+    }
+}
+
+#[async_trait]
+impl BlobProvider for FixtureProvider {
+    type Error = FixtureProviderError;
+
+    async fn get_blobs(
+        &mut self,
+        _: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Box<Blob>>, Self::Error> {
+        blob_hashes
+            .iter()
+            .map(|h| {
+                self.index
+                    .blobs
+                    .get(&h.hash)
+                    .map(|b| Box::new(*b))
+                    .ok_or(FixtureProviderError::BlobNotFound(h.hash))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BatchValidationProvider for FixtureProvider {
+    type Error = FixtureProviderError;
+
+    async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo, Self::Error> {
+        Ok(self.l2_block(number)?.info)
+    }
+
+    async fn block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error> {
+        self.l2_block(number)?.block().ok_or(FixtureProviderError::L2BlockBodyMissing(number))
+    }
+}
+
+#[async_trait]
+impl L2ChainProvider for FixtureProvider {
+    type Error = FixtureProviderError;
+
+    async fn system_config_by_number(
+        &mut self,
+        number: u64,
+        _: Arc<RollupConfig>,
+    ) -> Result<SystemConfig, <Self as L2ChainProvider>::Error> {
+        Ok(self.l2_block(number)?.system_config)
+    }
+}
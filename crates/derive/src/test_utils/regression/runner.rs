@@ -0,0 +1,258 @@
+//! The regression-snapshot runner.
+
+use crate::{
+    attributes::StatefulAttributesBuilder,
+    errors::{PipelineError, PipelineErrorKind, ResetError},
+    pipeline::{DerivationPipeline, PipelineBuilder},
+    sources::EthereumDataSource,
+    stages::{
+        AttributesQueue, BatchProvider, BatchStream, ChannelProvider, ChannelReader, FrameQueue,
+        L1Retrieval, L1Traversal,
+    },
+    test_utils::regression::{DerivationFixture, FixtureProvider, FixtureProviderError},
+    traits::{ChainProvider, L2ChainProvider, OriginProvider, Pipeline, SignalReceiver},
+    types::{ActivationSignal, ResetSignal, StepResult},
+};
+use alloc::{sync::Arc, vec::Vec};
+use op_alloy_protocol::BatchValidationProvider;
+use op_alloy_rpc_types_engine::OpAttributesWithParent;
+use thiserror::Error;
+
+/// A [DerivationPipeline] backed by a [FixtureProvider].
+pub type FixturePipeline = DerivationPipeline<
+    AttributesQueue<
+        BatchProvider<
+            BatchStream<
+                ChannelReader<
+                    ChannelProvider<
+                        FrameQueue<
+                            L1Retrieval<
+                                EthereumDataSource<FixtureProvider, FixtureProvider>,
+                                L1Traversal<FixtureProvider>,
+                            >,
+                        >,
+                    >,
+                >,
+                FixtureProvider,
+            >,
+            FixtureProvider,
+        >,
+        StatefulAttributesBuilder<FixtureProvider, FixtureProvider>,
+    >,
+    FixtureProvider,
+>;
+
+/// An error returned by the regression-snapshot runner.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegressionError {
+    /// The pipeline returned a critical error.
+    #[error("Pipeline error: {0}")]
+    Pipeline(#[from] PipelineErrorKind),
+    /// The fixture is missing data.
+    #[error("Fixture error: {0}")]
+    Fixture(#[from] FixtureProviderError),
+    /// The pipeline ran out of data before producing all of the expected attributes.
+    #[error("Expected {expected} attributes, but the pipeline produced {produced}")]
+    MissingAttributes {
+        /// The number of expected attributes.
+        expected: usize,
+        /// The number of produced attributes.
+        produced: usize,
+    },
+    /// The produced attributes do not match the expected attributes.
+    #[error("Attributes at index {0} do not match the fixture")]
+    Mismatch(usize),
+}
+
+/// Builds a [FixturePipeline] over the chains recorded in the [DerivationFixture].
+pub async fn new_fixture_pipeline(
+    fixture: &DerivationFixture,
+) -> Result<(FixturePipeline, FixtureProvider), RegressionError> {
+    let cfg = Arc::new(fixture.rollup_config.clone());
+    let mut provider = FixtureProvider::new(fixture);
+    let origin = provider.block_info_by_number(fixture.l1_origin).await?;
+
+    let attributes =
+        StatefulAttributesBuilder::new(cfg.clone(), provider.clone(), provider.clone());
+    let dap = EthereumDataSource::new_from_parts(provider.clone(), provider.clone(), &cfg);
+    let pipeline = PipelineBuilder::new()
+        .rollup_config(cfg)
+        .dap_source(dap)
+        .l2_chain_provider(provider.clone())
+        .chain_provider(provider.clone())
+        .builder(attributes)
+        .origin(origin)
+        .build();
+    Ok((pipeline, provider))
+}
+
+/// Runs the derivation pipeline over the chains recorded in the [DerivationFixture], returning
+/// the attributes it produced.
+///
+/// The pipeline is stepped until it has produced as many attributes as the fixture expects, or
+/// until the recorded L1 chain is exhausted. After each produced attributes, the safe head is
+/// advanced to the next block in the recorded L2 chain. Reset errors are handled in the same way
+/// as the client's driver.
+pub async fn run_fixture(
+    fixture: &DerivationFixture,
+) -> Result<Vec<OpAttributesWithParent>, RegressionError> {
+    let (mut pipeline, mut provider) = new_fixture_pipeline(fixture).await?;
+    let cfg = Arc::new(fixture.rollup_config.clone());
+    let mut safe_head = fixture.l2_safe_head;
+    let mut produced = Vec::with_capacity(fixture.expected.len());
+
+    while produced.len() < fixture.expected.len() {
+        match pipeline.step(safe_head).await {
+            StepResult::PreparedAttributes | StepResult::AdvancedOrigin => {}
+            StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => match e {
+                PipelineErrorKind::Temporary(_) => {}
+                PipelineErrorKind::Reset(e) => {
+                    let system_config = provider
+                        .system_config_by_number(safe_head.block_info.number, cfg.clone())
+                        .await?;
+                    let l1_origin = pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?;
+                    let signal = if matches!(e, ResetError::HoloceneActivation) {
+                        ActivationSignal {
+                            l2_safe_head: safe_head,
+                            l1_origin,
+                            system_config: Some(system_config),
+                        }
+                        .signal()
+                    } else {
+                        ResetSignal {
+                            l2_safe_head: safe_head,
+                            l1_origin,
+                            system_config: Some(system_config),
+                        }
+                        .signal()
+                    };
+                    pipeline.signal(signal).await?;
+                }
+                PipelineErrorKind::Critical(PipelineError::EndOfSource) => break,
+                PipelineErrorKind::Critical(_) => return Err(e.into()),
+            },
+        }
+
+        if let Some(attributes) = pipeline.next() {
+            produced.push(attributes);
+            if produced.len() < fixture.expected.len() {
+                safe_head =
+                    provider.l2_block_info_by_number(safe_head.block_info.number + 1).await?;
+            }
+        }
+    }
+
+    Ok(produced)
+}
+
+/// Runs the [DerivationFixture] with [run_fixture], and checks that the produced attributes
+/// exactly match the expected attributes.
+pub async fn check_fixture(fixture: &DerivationFixture) -> Result<(), RegressionError> {
+    let produced = run_fixture(fixture).await?;
+    if let Some(index) = produced.iter().zip(&fixture.expected).position(|(p, e)| p != e) {
+        return Err(RegressionError::Mismatch(index));
+    }
+    if produced.len() < fixture.expected.len() {
+        return Err(RegressionError::MissingAttributes {
+            expected: fixture.expected.len(),
+            produced: produced.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hardfork_transitions() -> DerivationFixture {
+        let json = include_str!("../../../testdata/regression/hardfork_transitions.json");
+        DerivationFixture::from_json(json).unwrap()
+    }
+
+    fn batcher_data() -> DerivationFixture {
+        let json = include_str!("../../../testdata/regression/batcher_data.json");
+        DerivationFixture::from_json(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_regression_hardfork_transitions() {
+        let fixture = hardfork_transitions();
+        assert!(!fixture.expected.is_empty());
+        check_fixture(&fixture).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_regression_mismatch() {
+        let mut fixture = hardfork_transitions();
+        fixture.expected[5].attributes.payload_attributes.timestamp += 1;
+        assert_eq!(check_fixture(&fixture).await.unwrap_err(), RegressionError::Mismatch(5));
+    }
+
+    #[tokio::test]
+    async fn test_regression_missing_attributes() {
+        let mut fixture = hardfork_transitions();
+        let expected = fixture.expected.len();
+        fixture.expected.push(fixture.expected[expected - 1].clone());
+        assert_eq!(
+            check_fixture(&fixture).await.unwrap_err(),
+            RegressionError::MissingAttributes { expected: expected + 1, produced: expected }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_regression_missing_l2_block() {
+        let mut fixture = hardfork_transitions();
+        fixture.l2_blocks.truncate(3);
+        assert_eq!(
+            check_fixture(&fixture).await.unwrap_err(),
+            RegressionError::Fixture(FixtureProviderError::L2BlockNotFound(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_regression_batcher_data() {
+        let fixture = batcher_data();
+        assert!(fixture.l1_blocks.iter().any(|block| !block.blobs.is_empty()));
+        check_fixture(&fixture).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_regression_batcher_data_granite_channel_timeout() {
+        // Channel D only times out under the Granite channel timeout. Without it, the stale batch
+        // for L2 block 19 is derived.
+        let mut fixture = batcher_data();
+        fixture.rollup_config.granite_time = None;
+        assert_eq!(check_fixture(&fixture).await.unwrap_err(), RegressionError::Mismatch(18));
+    }
+
+    #[tokio::test]
+    async fn test_regression_batcher_data_holocene_ordering() {
+        // The out of order frames of channel G are only pruned after Holocene. Without it, the
+        // stale batch for L2 block 54 is derived.
+        let mut fixture = batcher_data();
+        fixture.rollup_config.holocene_time = None;
+        assert_eq!(check_fixture(&fixture).await.unwrap_err(), RegressionError::Mismatch(53));
+    }
+
+    #[tokio::test]
+    async fn test_regression_unauthorized_batcher() {
+        let mut fixture = batcher_data();
+        let batcher = alloy_primitives::Address::repeat_byte(0xbb);
+        if let Some(system_config) = fixture.rollup_config.genesis.system_config.as_mut() {
+            system_config.batcher_address = batcher;
+        }
+        for block in fixture.l2_blocks.iter_mut() {
+            block.system_config.batcher_address = batcher;
+        }
+        // The batcher transactions are ignored, so every block is derived from an empty batch.
+        let produced = run_fixture(&fixture).await.unwrap();
+        assert!(!produced.is_empty());
+        for attributes in produced {
+            let transactions = attributes.attributes.transactions.unwrap_or_default();
+            assert!(transactions
+                .iter()
+                .all(|tx| tx[0] == op_alloy_consensus::OpTxType::Deposit as u8));
+        }
+    }
+}
@@ -4,7 +4,7 @@ use alloc::boxed::Box;
 use async_trait::async_trait;
 use op_alloy_protocol::BlockInfo;
 
-use crate::types::{PipelineResult, Signal};
+use crate::types::{PipelineResult, PipelineSnapshot, Signal};
 
 /// Providers a way for the pipeline to accept a signal from the driver.
 #[async_trait]
@@ -26,3 +26,16 @@ pub trait OriginAdvancer {
     /// This method is the equivalent of the reference implementation `advance_l1_block`.
     async fn advance_origin(&mut self) -> PipelineResult<()>;
 }
+
+/// Provides a way to capture and restore the internal state of a stage.
+///
+/// Like [SignalReceiver], stages recurse into the previous stage before handling their own state,
+/// so a call on the top-level stage captures or restores the entire stack.
+#[async_trait]
+pub trait StageSnapshot {
+    /// Writes the internal state of the stage into the [PipelineSnapshot].
+    fn snapshot(&self, snapshot: &mut PipelineSnapshot);
+
+    /// Restores the internal state of the stage from the [PipelineSnapshot].
+    async fn restore(&mut self, snapshot: &PipelineSnapshot) -> PipelineResult<()>;
+}
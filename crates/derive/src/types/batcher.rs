@@ -0,0 +1,24 @@
+//! Contains the [BatcherUpdate] type, which records a change of the batcher address.
+
+use alloy_eips::BlockNumHash;
+use alloy_primitives::Address;
+
+/// A change of the batcher address in the [SystemConfig], as observed by the [L1Traversal] stage.
+///
+/// [SystemConfig]: op_alloy_genesis::SystemConfig
+/// [L1Traversal]: crate::stages::L1Traversal
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatcherUpdate {
+    /// The L1 block from which the batcher address is active.
+    pub l1_block: BlockNumHash,
+    /// The batcher address.
+    pub batcher_address: Address,
+}
+
+impl BatcherUpdate {
+    /// Creates a new [BatcherUpdate].
+    pub const fn new(l1_block: BlockNumHash, batcher_address: Address) -> Self {
+        Self { l1_block, batcher_address }
+    }
+}
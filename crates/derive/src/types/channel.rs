@@ -0,0 +1,107 @@
+//! Contains the [BufferedChannel] type, a [Channel] that remembers the frames added to it.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+use op_alloy_protocol::{BlockInfo, Channel, ChannelError, ChannelId, Frame};
+
+/// A [Channel] buffered by the channel stages, together with the [Frame]s that were added to it
+/// and the L1 blocks that they were included in, in the order that they were added.
+///
+/// A [Channel] does not expose its frames, so the frames are kept alongside it in order to
+/// serialize the channel into a [PipelineSnapshot]. The channel is rebuilt by replaying the frames,
+/// which reproduces its state exactly.
+///
+/// [PipelineSnapshot]: crate::types::PipelineSnapshot
+#[derive(Debug, Clone, Default)]
+pub struct BufferedChannel {
+    /// The channel.
+    channel: Channel,
+    /// The block that the channel was opened at.
+    open_block: BlockInfo,
+    /// The frames added to the channel, with the L1 blocks that they were included in.
+    frames: Vec<(BlockInfo, Frame)>,
+}
+
+impl BufferedChannel {
+    /// Creates a new [BufferedChannel] with the given [ChannelId], opened at `open_block`.
+    pub fn new(id: ChannelId, open_block: BlockInfo) -> Self {
+        Self { channel: Channel::new(id, open_block), open_block, frames: Vec::new() }
+    }
+
+    /// Rebuilds a [BufferedChannel] by adding the `frames` to a new channel, in order.
+    pub fn from_frames(
+        id: ChannelId,
+        open_block: BlockInfo,
+        frames: impl IntoIterator<Item = (BlockInfo, Frame)>,
+    ) -> Result<Self, ChannelError> {
+        let mut channel = Self::new(id, open_block);
+        for (l1_inclusion_block, frame) in frames {
+            channel.add_frame(frame, l1_inclusion_block)?;
+        }
+        Ok(channel)
+    }
+
+    /// Adds a frame to the channel. See [Channel::add_frame].
+    pub fn add_frame(
+        &mut self,
+        frame: Frame,
+        l1_inclusion_block: BlockInfo,
+    ) -> Result<(), ChannelError> {
+        self.channel.add_frame(frame.clone(), l1_inclusion_block)?;
+        self.frames.push((l1_inclusion_block, frame));
+        Ok(())
+    }
+
+    /// Returns the block that the channel was opened at.
+    pub const fn open_block(&self) -> BlockInfo {
+        self.open_block
+    }
+
+    /// Returns the frames added to the channel, with the L1 blocks that they were included in.
+    pub fn frames(&self) -> &[(BlockInfo, Frame)] {
+        &self.frames
+    }
+}
+
+impl Deref for BufferedChannel {
+    type Target = Channel;
+
+    fn deref(&self) -> &Self::Target {
+        &self.channel
+    }
+}
+
+impl PartialEq for BufferedChannel {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id() &&
+            self.open_block == other.open_block &&
+            self.frames == other.frames
+    }
+}
+
+impl Eq for BufferedChannel {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_buffered_channel_from_frames() {
+        let open_block = BlockInfo { number: 1, ..Default::default() };
+        let mut channel = BufferedChannel::new([0xFF; 16], open_block);
+        channel.add_frame(crate::frame!(0xFF, 1, vec![0xDD; 50], true), open_block).unwrap();
+        assert!(channel
+            .add_frame(crate::frame!(0xEE, 0, vec![0xDD; 50], false), open_block)
+            .is_err());
+        channel.add_frame(crate::frame!(0xFF, 0, vec![0xDD; 50], false), open_block).unwrap();
+        assert!(channel.is_ready());
+
+        let rebuilt =
+            BufferedChannel::from_frames([0xFF; 16], open_block, channel.frames().to_vec())
+                .unwrap();
+        assert_eq!(rebuilt, channel);
+        assert_eq!(rebuilt.size(), channel.size());
+        assert_eq!(rebuilt.frame_data(), channel.frame_data());
+    }
+}
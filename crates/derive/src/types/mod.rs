@@ -1,7 +1,23 @@
 //! Primitive types for `kona-derive`.
 
+mod batcher;
+pub use batcher::BatcherUpdate;
+
 mod results;
 pub use results::{PipelineResult, StepResult};
 
 mod signals;
 pub use signals::{ActivationSignal, ResetSignal, Signal};
+
+mod channel;
+pub use channel::BufferedChannel;
+
+#[cfg(feature = "serde")]
+pub(crate) mod serde_repr;
+
+mod snapshot;
+pub use snapshot::{
+    AttributesQueueSnapshot, BatchProviderSnapshot, BatchStreamSnapshot, ChannelProviderSnapshot,
+    ChannelReaderSnapshot, FrameQueueSnapshot, L1RetrievalSnapshot, L1TraversalSnapshot,
+    PipelineSnapshot,
+};
@@ -0,0 +1,289 @@
+//! Serde representations of the `op-alloy-protocol` types held in a [PipelineSnapshot], which do
+//! not implement serde themselves.
+//!
+//! [PipelineSnapshot]: crate::types::PipelineSnapshot
+
+use crate::types::BufferedChannel;
+use alloc::vec::Vec;
+use alloy_primitives::{map::HashMap, BlockHash, Bytes, FixedBytes, B128};
+use op_alloy_protocol::{
+    Batch, BatchWithInclusionBlock, BlockInfo, ChannelId, Frame, SingleBatch, SpanBatch,
+    SpanBatchElement,
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A type that is serialized through a representation that implements serde.
+pub(crate) trait SerdeRepr: Sized {
+    /// The serializable representation of the type.
+    type Repr: Serialize + DeserializeOwned;
+
+    /// Returns the representation of the value.
+    fn to_repr(&self) -> Self::Repr;
+
+    /// Returns the value of the representation.
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+/// Serializes an [Option] of a [SerdeRepr] type, for `#[serde(with = "...")]`.
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn serialize<T: SerdeRepr, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.as_ref().map(T::to_repr).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T: SerdeRepr, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<T::Repr>::deserialize(deserializer).map(|repr| repr.map(T::from_repr))
+    }
+}
+
+/// Serializes a sequence of a [SerdeRepr] type, such as a [Vec] or a
+/// [VecDeque](alloc::collections::VecDeque), for `#[serde(with = "...")]`.
+pub(crate) mod seq {
+    use super::*;
+
+    pub(crate) fn serialize<C, T, S>(values: &C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        for<'a> &'a C: IntoIterator<Item = &'a T>,
+        T: SerdeRepr,
+        S: Serializer,
+    {
+        serializer.collect_seq(values.into_iter().map(T::to_repr))
+    }
+
+    pub(crate) fn deserialize<'de, C, T, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        C: FromIterator<T>,
+        T: SerdeRepr,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<T::Repr>::deserialize(deserializer)?.into_iter().map(T::from_repr).collect())
+    }
+}
+
+/// Serializes the channels of the channel bank, keyed by their [ChannelId], as a sequence of
+/// channels, for `#[serde(with = "...")]`.
+pub(crate) mod channels {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        channels: &HashMap<ChannelId, BufferedChannel>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(channels.values())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<ChannelId, BufferedChannel>, D::Error> {
+        Ok(Vec::<BufferedChannel>::deserialize(deserializer)?
+            .into_iter()
+            .map(|channel| (channel.id(), channel))
+            .collect())
+    }
+}
+
+/// The representation of a [Frame].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct FrameRepr {
+    id: B128,
+    number: u16,
+    data: Bytes,
+    is_last: bool,
+}
+
+impl SerdeRepr for Frame {
+    type Repr = FrameRepr;
+
+    fn to_repr(&self) -> Self::Repr {
+        FrameRepr {
+            id: self.id.into(),
+            number: self.number,
+            data: self.data.clone().into(),
+            is_last: self.is_last,
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self { id: repr.id.0, number: repr.number, data: repr.data.into(), is_last: repr.is_last }
+    }
+}
+
+/// The representation of a [SingleBatch].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SingleBatchRepr {
+    parent_hash: BlockHash,
+    epoch_num: u64,
+    epoch_hash: BlockHash,
+    timestamp: u64,
+    transactions: Vec<Bytes>,
+}
+
+impl SerdeRepr for SingleBatch {
+    type Repr = SingleBatchRepr;
+
+    fn to_repr(&self) -> Self::Repr {
+        SingleBatchRepr {
+            parent_hash: self.parent_hash,
+            epoch_num: self.epoch_num,
+            epoch_hash: self.epoch_hash,
+            timestamp: self.timestamp,
+            transactions: self.transactions.clone(),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            parent_hash: repr.parent_hash,
+            epoch_num: repr.epoch_num,
+            epoch_hash: repr.epoch_hash,
+            timestamp: repr.timestamp,
+            transactions: repr.transactions,
+        }
+    }
+}
+
+/// The representation of a [SpanBatchElement].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SpanBatchElementRepr {
+    epoch_num: u64,
+    timestamp: u64,
+    transactions: Vec<Bytes>,
+}
+
+/// The representation of a [SpanBatch].
+///
+/// The fields of a [SpanBatch] that cache its encoding are not represented, as the span batches
+/// decoded by the pipeline never populate them.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SpanBatchRepr {
+    parent_check: FixedBytes<20>,
+    l1_origin_check: FixedBytes<20>,
+    genesis_timestamp: u64,
+    chain_id: u64,
+    batches: Vec<SpanBatchElementRepr>,
+}
+
+impl SerdeRepr for SpanBatch {
+    type Repr = SpanBatchRepr;
+
+    fn to_repr(&self) -> Self::Repr {
+        SpanBatchRepr {
+            parent_check: self.parent_check,
+            l1_origin_check: self.l1_origin_check,
+            genesis_timestamp: self.genesis_timestamp,
+            chain_id: self.chain_id,
+            batches: self
+                .batches
+                .iter()
+                .map(|element| SpanBatchElementRepr {
+                    epoch_num: element.epoch_num,
+                    timestamp: element.timestamp,
+                    transactions: element.transactions.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            parent_check: repr.parent_check,
+            l1_origin_check: repr.l1_origin_check,
+            genesis_timestamp: repr.genesis_timestamp,
+            chain_id: repr.chain_id,
+            batches: repr
+                .batches
+                .into_iter()
+                .map(|element| SpanBatchElement {
+                    epoch_num: element.epoch_num,
+                    timestamp: element.timestamp,
+                    transactions: element.transactions,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The representation of a [Batch].
+#[derive(Serialize, Deserialize)]
+pub(crate) enum BatchRepr {
+    Single(SingleBatchRepr),
+    Span(SpanBatchRepr),
+}
+
+impl SerdeRepr for Batch {
+    type Repr = BatchRepr;
+
+    fn to_repr(&self) -> Self::Repr {
+        match self {
+            Self::Single(batch) => BatchRepr::Single(batch.to_repr()),
+            Self::Span(batch) => BatchRepr::Span(batch.to_repr()),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        match repr {
+            BatchRepr::Single(batch) => Self::Single(SingleBatch::from_repr(batch)),
+            BatchRepr::Span(batch) => Self::Span(SpanBatch::from_repr(batch)),
+        }
+    }
+}
+
+/// The representation of a [BatchWithInclusionBlock].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct BatchWithInclusionBlockRepr {
+    inclusion_block: BlockInfo,
+    batch: BatchRepr,
+}
+
+impl SerdeRepr for BatchWithInclusionBlock {
+    type Repr = BatchWithInclusionBlockRepr;
+
+    fn to_repr(&self) -> Self::Repr {
+        BatchWithInclusionBlockRepr {
+            inclusion_block: self.inclusion_block,
+            batch: self.batch.to_repr(),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self::new(repr.inclusion_block, Batch::from_repr(repr.batch))
+    }
+}
+
+/// The representation of a [BufferedChannel].
+#[derive(Serialize, Deserialize)]
+struct BufferedChannelRepr {
+    id: B128,
+    open_block: BlockInfo,
+    frames: Vec<(BlockInfo, FrameRepr)>,
+}
+
+impl Serialize for BufferedChannel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BufferedChannelRepr {
+            id: self.id().into(),
+            open_block: self.open_block(),
+            frames: self.frames().iter().map(|(block, frame)| (*block, frame.to_repr())).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BufferedChannel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = BufferedChannelRepr::deserialize(deserializer)?;
+        Self::from_frames(
+            repr.id.0,
+            repr.open_block,
+            repr.frames.into_iter().map(|(block, frame)| (block, Frame::from_repr(frame))),
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,302 @@
+//! Snapshot types for the `kona-derive` pipeline.
+//!
+//! A [PipelineSnapshot] captures the internal buffers of every stage in the pipeline, allowing
+//! a consumer to restore a pipeline to an exact mid-derivation state without re-deriving from
+//! `channel_timeout` L1 blocks back after a [Signal::Reset].
+//!
+//! With the `serde` feature, a [PipelineSnapshot] can be serialized, to restore a pipeline in
+//! another process.
+//!
+//! [Signal::Reset]: crate::types::Signal::Reset
+
+use crate::types::{BatcherUpdate, BufferedChannel};
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use alloy_primitives::{map::HashMap, Bytes};
+use op_alloy_genesis::SystemConfig;
+use op_alloy_protocol::{
+    BatchWithInclusionBlock, BlockInfo, ChannelId, Frame, SingleBatch, SpanBatch,
+};
+use op_alloy_rpc_types_engine::OpAttributesWithParent;
+
+/// A snapshot of the internal state of the derivation pipeline.
+///
+/// Each stage fills in its own section of the snapshot when captured, and reads its section back
+/// when restored. A section is [None] if the stage has not been captured.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineSnapshot {
+    /// The prepared [OpAttributesWithParent] buffered in the pipeline.
+    pub prepared: VecDeque<OpAttributesWithParent>,
+    /// The state of the [L1Traversal] stage.
+    ///
+    /// [L1Traversal]: crate::stages::L1Traversal
+    pub l1_traversal: Option<L1TraversalSnapshot>,
+    /// The state of the [L1Retrieval] stage.
+    ///
+    /// [L1Retrieval]: crate::stages::L1Retrieval
+    pub l1_retrieval: Option<L1RetrievalSnapshot>,
+    /// The state of the [FrameQueue] stage.
+    ///
+    /// [FrameQueue]: crate::stages::FrameQueue
+    pub frame_queue: Option<FrameQueueSnapshot>,
+    /// The state of the [ChannelProvider] stage.
+    ///
+    /// [ChannelProvider]: crate::stages::ChannelProvider
+    pub channel_provider: Option<ChannelProviderSnapshot>,
+    /// The state of the [ChannelReader] stage.
+    ///
+    /// [ChannelReader]: crate::stages::ChannelReader
+    pub channel_reader: Option<ChannelReaderSnapshot>,
+    /// The state of the [BatchStream] stage.
+    ///
+    /// [BatchStream]: crate::stages::BatchStream
+    pub batch_stream: Option<BatchStreamSnapshot>,
+    /// The state of the [BatchProvider] stage.
+    ///
+    /// [BatchProvider]: crate::stages::BatchProvider
+    pub batch_provider: Option<BatchProviderSnapshot>,
+    /// The state of the [AttributesQueue] stage.
+    ///
+    /// [AttributesQueue]: crate::stages::AttributesQueue
+    pub attributes_queue: Option<AttributesQueueSnapshot>,
+}
+
+/// A snapshot of the [L1Traversal] stage.
+///
+/// [L1Traversal]: crate::stages::L1Traversal
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1TraversalSnapshot {
+    /// The current block in the traversal stage.
+    pub block: Option<BlockInfo>,
+    /// Whether or not the current block has been consumed.
+    pub done: bool,
+    /// The system config.
+    pub system_config: SystemConfig,
+    /// The history of batcher addresses.
+    pub batcher_history: Vec<BatcherUpdate>,
+}
+
+/// A snapshot of the [L1Retrieval] stage.
+///
+/// The data availability provider's iterator is not captured. Instead, the number of items
+/// consumed from the current block is recorded, and the data is re-opened and skipped forward on
+/// restore.
+///
+/// [L1Retrieval]: crate::stages::L1Retrieval
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1RetrievalSnapshot {
+    /// The L1 block that data is currently being retrieved for.
+    pub next: Option<BlockInfo>,
+    /// The number of data items consumed from the `next` block.
+    pub consumed: usize,
+}
+
+/// A snapshot of the [FrameQueue] stage.
+///
+/// [FrameQueue]: crate::stages::FrameQueue
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameQueueSnapshot {
+    /// The queued frames.
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::seq"))]
+    pub queue: VecDeque<Frame>,
+}
+
+/// A snapshot of the [ChannelProvider] stage, holding the state of whichever stage is active
+/// in the multiplexer.
+///
+/// [ChannelProvider]: crate::stages::ChannelProvider
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelProviderSnapshot {
+    /// The [ChannelBank] stage is active.
+    ///
+    /// [ChannelBank]: crate::stages::ChannelBank
+    ChannelBank {
+        /// Map of channels by ID.
+        #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::channels"))]
+        channels: HashMap<ChannelId, BufferedChannel>,
+        /// Channels in FIFO order.
+        channel_queue: VecDeque<ChannelId>,
+    },
+    /// The [ChannelAssembler] stage is active.
+    ///
+    /// [ChannelAssembler]: crate::stages::ChannelAssembler
+    ChannelAssembler {
+        /// The channel being assembled.
+        channel: Option<Box<BufferedChannel>>,
+    },
+}
+
+/// A snapshot of the [ChannelReader] stage.
+///
+/// [ChannelReader]: crate::stages::ChannelReader
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelReaderSnapshot {
+    /// The raw data of the channel currently being read, if any.
+    pub channel: Option<Bytes>,
+    /// The maximum number of RLP bytes that may be decompressed from the channel.
+    pub max_rlp_bytes_per_channel: usize,
+    /// The number of batches already read from the channel.
+    pub batches_read: usize,
+}
+
+/// A snapshot of the [BatchStream] stage.
+///
+/// [BatchStream]: crate::stages::BatchStream
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchStreamSnapshot {
+    /// The staged span batch.
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::option"))]
+    pub span: Option<SpanBatch>,
+    /// The buffer of single batches derived from the span batch.
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::seq"))]
+    pub buffer: VecDeque<SingleBatch>,
+}
+
+/// A snapshot of the [BatchProvider] stage, holding the state of whichever stage is active in
+/// the multiplexer.
+///
+/// [BatchProvider]: crate::stages::BatchProvider
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatchProviderSnapshot {
+    /// The [BatchQueue] stage is active.
+    ///
+    /// [BatchQueue]: crate::stages::BatchQueue
+    BatchQueue {
+        /// The L1 origin of the stage.
+        origin: Option<BlockInfo>,
+        /// The window of L1 blocks.
+        l1_blocks: Vec<BlockInfo>,
+        /// The buffered batches.
+        #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::seq"))]
+        batches: Vec<BatchWithInclusionBlock>,
+        /// The cached single batches derived from span batches.
+        #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::seq"))]
+        next_spans: Vec<SingleBatch>,
+    },
+    /// The [BatchValidator] stage is active.
+    ///
+    /// [BatchValidator]: crate::stages::BatchValidator
+    BatchValidator {
+        /// The L1 origin of the stage.
+        origin: Option<BlockInfo>,
+        /// The window of L1 blocks.
+        l1_blocks: Vec<BlockInfo>,
+    },
+}
+
+/// A snapshot of the [AttributesQueue] stage.
+///
+/// [AttributesQueue]: crate::stages::AttributesQueue
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributesQueueSnapshot {
+    /// Whether the current batch is the last in its span.
+    pub is_last_in_span: bool,
+    /// The current batch being processed.
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::serde_repr::option"))]
+    pub batch: Option<SingleBatch>,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::{b256, Address, FixedBytes};
+    use op_alloy_protocol::{Batch, SpanBatchElement};
+
+    #[test]
+    fn test_pipeline_snapshot_serde_roundtrip() {
+        let block = BlockInfo { number: 10, timestamp: 20, ..Default::default() };
+        let single = SingleBatch {
+            parent_hash: b256!("0000000000000000000000000000000000000000000000000000000000000001"),
+            epoch_num: 10,
+            timestamp: 22,
+            transactions: vec![Bytes::from_static(&[0x7e, 0x01])],
+            ..Default::default()
+        };
+        let span = SpanBatch {
+            parent_check: FixedBytes::repeat_byte(0x02),
+            l1_origin_check: FixedBytes::repeat_byte(0x03),
+            batches: vec![SpanBatchElement::from(single.clone())],
+            ..Default::default()
+        };
+        let mut channel = BufferedChannel::new([0xFF; 16], block);
+        channel.add_frame(crate::frame!(0xFF, 1, vec![0xDD; 50], true), block).unwrap();
+        channel.add_frame(crate::frame!(0xFF, 0, vec![0xEE; 50], false), block).unwrap();
+
+        let snapshot = PipelineSnapshot {
+            l1_traversal: Some(L1TraversalSnapshot {
+                block: Some(block),
+                done: true,
+                system_config: SystemConfig {
+                    batcher_address: Address::repeat_byte(1),
+                    ..Default::default()
+                },
+                batcher_history: vec![BatcherUpdate::new(block.id(), Address::repeat_byte(1))],
+            }),
+            l1_retrieval: Some(L1RetrievalSnapshot { next: Some(block), consumed: 2 }),
+            frame_queue: Some(FrameQueueSnapshot {
+                queue: [crate::frame!(0xAA, 0, vec![0xBB; 10], true)].into(),
+            }),
+            channel_provider: Some(ChannelProviderSnapshot::ChannelBank {
+                channels: [(channel.id(), channel.clone())].into_iter().collect(),
+                channel_queue: [channel.id()].into(),
+            }),
+            channel_reader: Some(ChannelReaderSnapshot {
+                channel: Some(Bytes::from_static(&[0x01, 0x02])),
+                max_rlp_bytes_per_channel: 100,
+                batches_read: 1,
+            }),
+            batch_stream: Some(BatchStreamSnapshot {
+                span: Some(span.clone()),
+                buffer: [single.clone()].into(),
+            }),
+            batch_provider: Some(BatchProviderSnapshot::BatchQueue {
+                origin: Some(block),
+                l1_blocks: vec![block],
+                batches: vec![
+                    BatchWithInclusionBlock::new(block, Batch::Single(single.clone())),
+                    BatchWithInclusionBlock::new(block, Batch::Span(span)),
+                ],
+                next_spans: vec![single.clone()],
+            }),
+            attributes_queue: Some(AttributesQueueSnapshot {
+                is_last_in_span: true,
+                batch: Some(single),
+            }),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored = serde_json::from_str::<PipelineSnapshot>(&json).unwrap();
+        assert_eq!(restored, snapshot);
+
+        let Some(ChannelProviderSnapshot::ChannelBank { channels, .. }) =
+            restored.channel_provider.as_ref()
+        else {
+            panic!("Expected ChannelBank");
+        };
+        let restored_channel = &channels[&channel.id()];
+        assert!(restored_channel.is_ready());
+        assert_eq!(restored_channel.frame_data(), channel.frame_data());
+    }
+
+    #[test]
+    fn test_buffered_channel_deserialize_invalid_frames() {
+        let mut channel = BufferedChannel::new([0xFF; 16], BlockInfo::default());
+        channel
+            .add_frame(crate::frame!(0xFF, 0, vec![0xDD; 50], true), BlockInfo::default())
+            .unwrap();
+        let mut json = serde_json::to_value(&channel).unwrap();
+        let frames = json["frames"].as_array_mut().unwrap();
+        frames.push(frames[0].clone());
+        assert!(serde_json::from_value::<BufferedChannel>(json).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! Contains the blocks produced by the derivation driver.
+
+use alloy_consensus::{Header, Sealed};
+use alloy_primitives::B256;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+/// An L2 block that was derived and executed by the [Driver](crate::Driver), passed to the
+/// [DriverObserver](crate::DriverObserver) of
+/// [Driver::advance_to_target_with](crate::Driver::advance_to_target_with).
+#[derive(Debug, Clone, Copy)]
+pub struct DerivedBlock<'a> {
+    /// The payload attributes that the block was built from.
+    pub attributes: &'a OpPayloadAttributes,
+    /// The header of the executed block.
+    pub header: &'a Sealed<Header>,
+    /// The output root of the block.
+    pub output_root: B256,
+}
@@ -15,7 +15,10 @@ use op_alloy_genesis::RollupConfig;
 use op_alloy_protocol::L2BlockInfo;
 use op_alloy_rpc_types_engine::OpAttributesWithParent;
 
-use crate::{DriverError, DriverPipeline, DriverResult, Executor, PipelineCursor, TipCursor};
+use crate::{
+    DerivedBlock, DriverError, DriverObserver, DriverPipeline, DriverResult, Executor,
+    PipelineCursor, TipCursor,
+};
 
 /// The Rollup Driver entrypoint.
 #[derive(Debug)]
@@ -70,19 +73,42 @@ where
     ///   output root.
     /// - `Err(e)` - An error if the block could not be produced.
     pub async fn advance_to_target(
+        &mut self,
+        cfg: &RollupConfig,
+        target: Option<u64>,
+    ) -> DriverResult<(u64, B256), E::Error> {
+        self.advance_to_target_with(cfg, target, |_: DerivedBlock<'_>| {}).await
+    }
+
+    /// Advances the derivation pipeline to the target block number, notifying `observer` as each
+    /// block is derived and executed along the way.
+    ///
+    /// ## Takes
+    /// - `cfg`: The rollup configuration.
+    /// - `target`: The target block number.
+    /// - `observer`: The [DriverObserver] of the derived blocks, notified in order as each block's
+    ///   attributes are derived and after it is executed, and once the target is reached.
+    ///
+    /// ## Returns
+    /// - `Ok((number, output_root))` - A tuple containing the number of the produced block and the
+    ///   output root.
+    /// - `Err(e)` - An error if the block could not be produced.
+    pub async fn advance_to_target_with(
         &mut self,
         cfg: &RollupConfig,
         mut target: Option<u64>,
+        mut observer: impl DriverObserver + Send,
     ) -> DriverResult<(u64, B256), E::Error> {
+        observer.on_start();
         loop {
             // Check if we have reached the target block number.
             if let Some(tb) = target {
                 if self.cursor.l2_safe_head().block_info.number >= tb {
                     info!(target: "client", "Derivation complete, reached L2 safe head.");
-                    return Ok((
-                        self.cursor.l2_safe_head().block_info.number,
-                        *self.cursor.l2_safe_head_output_root(),
-                    ));
+                    let number = self.cursor.l2_safe_head().block_info.number;
+                    let output_root = *self.cursor.l2_safe_head_output_root();
+                    observer.on_finish(number, output_root);
+                    return Ok((number, output_root));
                 }
             }
 
@@ -107,6 +133,7 @@ where
                     return Err(DriverError::Pipeline(e));
                 }
             };
+            observer.on_attributes(&attributes);
 
             self.executor.update_safe_head(self.cursor.l2_safe_head_header().clone());
             let header = match self.executor.execute_payload(attributes.clone()).await {
@@ -150,9 +177,18 @@ where
                 }
             };
 
+            // Compute the output root of the block, and pass it to the observer.
+            let header = header.seal_slow();
+            let output_root = self.executor.compute_output_root().map_err(DriverError::Executor)?;
+            observer.on_block(DerivedBlock {
+                attributes: &attributes,
+                header: &header,
+                output_root,
+            });
+
             // Construct the block.
             let block = OpBlock {
-                header: header.clone(),
+                header: header.inner().clone(),
                 body: BlockBody {
                     transactions: attributes
                         .transactions
@@ -171,11 +207,7 @@ where
                 &block,
                 &self.pipeline.rollup_config().genesis,
             )?;
-            let cursor = TipCursor::new(
-                l2_info,
-                header.clone().seal_slow(),
-                self.executor.compute_output_root().map_err(DriverError::Executor)?,
-            );
+            let cursor = TipCursor::new(l2_info, header, output_root);
             self.cursor.advance(origin, cursor);
         }
     }
@@ -0,0 +1,138 @@
+//! Contains the [EngineClient], a client for the authenticated Engine API.
+
+use crate::engine::EngineError;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{Address, B256};
+use alloy_provider::{ext::EngineApi, Provider, RootProvider};
+use alloy_rpc_client::RpcClient;
+use alloy_rpc_types::EIP1186AccountProofResponse;
+use alloy_rpc_types_engine::{
+    ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, JwtSecret, PayloadId, PayloadStatus,
+};
+use alloy_transport::{RpcError, TransportError, TransportErrorKind};
+use alloy_transport_http::{
+    hyper::body::Bytes,
+    hyper_util::{
+        client::legacy::{connect::HttpConnector, Client},
+        rt::TokioExecutor,
+    },
+    AuthLayer, AuthService, Http, HyperClient,
+};
+use http_body_util::Full;
+use op_alloy_rpc_types_engine::{OpExecutionPayloadEnvelopeV3, OpPayloadAttributes};
+use tower::ServiceBuilder;
+use url::Url;
+
+/// The Engine API methods used by the [EngineClient].
+const ENGINE_CAPABILITIES: [&str; 3] =
+    ["engine_forkchoiceUpdatedV3", "engine_getPayloadV3", "engine_newPayloadV3"];
+
+/// An HTTP transport that authenticates every request with a JWT.
+pub type AuthTransport =
+    Http<HyperClient<Full<Bytes>, AuthService<Client<HttpConnector, Full<Bytes>>>>>;
+
+/// A client for the authenticated Engine API of an execution client.
+///
+/// Requests are sent by a [RootProvider] over an [AuthTransport], which signs a JWT with the
+/// [JwtSecret] that is shared with the execution client. The `eth` namespace is served on the same
+/// endpoint. The methods that take or return OP Stack payload types are sent as raw requests, as
+/// the [EngineApi] only covers the L1 payload types.
+#[derive(Debug, Clone)]
+pub struct EngineClient {
+    /// The provider for the authenticated RPC endpoint.
+    provider: RootProvider<AuthTransport>,
+}
+
+impl EngineClient {
+    /// Creates a new [EngineClient] for the authenticated RPC endpoint at `url`.
+    pub fn new(url: Url, secret: JwtSecret) -> Self {
+        let hyper = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
+        let service = ServiceBuilder::new().layer(AuthLayer::new(secret)).service(hyper);
+        let transport = Http::with_client(HyperClient::with_service(service), url);
+        Self { provider: RootProvider::new(RpcClient::new(transport, false)) }
+    }
+
+    /// Returns the [RootProvider] of the authenticated RPC endpoint.
+    pub const fn provider(&self) -> &RootProvider<AuthTransport> {
+        &self.provider
+    }
+
+    /// Exchanges the list of supported Engine API methods with the execution client.
+    pub async fn exchange_capabilities(&self) -> Result<Vec<String>, EngineError> {
+        let capabilities = ENGINE_CAPABILITIES.iter().map(ToString::to_string).collect();
+        self.provider
+            .exchange_capabilities(capabilities)
+            .await
+            .map_err(|e| rpc_error("engine_exchangeCapabilities", e))
+    }
+
+    /// Updates the forkchoice state of the execution client, optionally starting to build a
+    /// payload with the given attributes on top of the head.
+    pub async fn fork_choice_updated_v3(
+        &self,
+        state: ForkchoiceState,
+        attributes: Option<OpPayloadAttributes>,
+    ) -> Result<ForkchoiceUpdated, EngineError> {
+        const METHOD: &str = "engine_forkchoiceUpdatedV3";
+        self.provider
+            .client()
+            .request(METHOD, (state, attributes))
+            .await
+            .map_err(|e| rpc_error(METHOD, e))
+    }
+
+    /// Fetches the payload that is being built with the given ID.
+    pub async fn get_payload_v3(
+        &self,
+        payload_id: PayloadId,
+    ) -> Result<OpExecutionPayloadEnvelopeV3, EngineError> {
+        const METHOD: &str = "engine_getPayloadV3";
+        self.provider
+            .client()
+            .request(METHOD, (payload_id,))
+            .await
+            .map_err(|e| rpc_error(METHOD, e))
+    }
+
+    /// Sends a payload to the execution client for validation and execution.
+    pub async fn new_payload_v3(
+        &self,
+        payload: ExecutionPayloadV3,
+        parent_beacon_block_root: B256,
+    ) -> Result<PayloadStatus, EngineError> {
+        self.provider
+            .new_payload_v3(payload, Vec::new(), parent_beacon_block_root)
+            .await
+            .map_err(|e| rpc_error("engine_newPayloadV3", e))
+    }
+
+    /// Fetches the account proof of the given address at the given block number.
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> Result<EIP1186AccountProofResponse, EngineError> {
+        self.provider
+            .get_proof(address, Vec::new())
+            .number(block_number)
+            .await
+            .map_err(|e| rpc_error("eth_getProof", e))
+    }
+}
+
+/// Converts the [TransportError] of a request to `method` into an [EngineError].
+fn rpc_error(method: &str, err: TransportError) -> EngineError {
+    match err {
+        RpcError::ErrorResp(payload) => {
+            EngineError::Rpc { code: payload.code, message: payload.message.to_string() }
+        }
+        RpcError::NullResp => EngineError::EmptyResponse(method.to_string()),
+        RpcError::SerError(e) => EngineError::Serialization(e.to_string()),
+        RpcError::DeserError { err, .. } => EngineError::Serialization(err.to_string()),
+        RpcError::Transport(TransportErrorKind::HttpError(e)) => EngineError::HttpStatus(e.status),
+        e => EngineError::Transport(e.to_string()),
+    }
+}
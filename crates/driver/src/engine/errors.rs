@@ -0,0 +1,59 @@
+//! Contains the error type for the Engine API [Executor].
+//!
+//! [Executor]: crate::Executor
+
+use alloc::string::String;
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::PayloadStatusEnum;
+use thiserror::Error;
+
+/// An error returned by the [EngineClient] or the [EngineExecutor].
+///
+/// [EngineClient]: crate::engine::EngineClient
+/// [EngineExecutor]: crate::engine::EngineExecutor
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    /// The request to the execution client failed.
+    #[error("Transport error: {0}")]
+    Transport(String),
+    /// The execution client responded with a non-success HTTP status.
+    #[error("HTTP status {0}")]
+    HttpStatus(u16),
+    /// The execution client responded with a JSON-RPC error.
+    #[error("RPC error {code}: {message}")]
+    Rpc {
+        /// The JSON-RPC error code.
+        code: i64,
+        /// The JSON-RPC error message.
+        message: String,
+    },
+    /// The response could not be (de)serialized.
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+    /// The response contained neither a result nor an error.
+    #[error("Empty response to {0}")]
+    EmptyResponse(String),
+    /// The execution client did not accept a payload or forkchoice update.
+    #[error("Payload status: {0}")]
+    PayloadStatus(PayloadStatusEnum),
+    /// The execution client did not start building a payload.
+    #[error("Missing payload ID")]
+    MissingPayloadId,
+    /// A payload was executed before the safe head was set.
+    #[error("Missing safe head")]
+    MissingSafeHead,
+    /// A payload contains a transaction that could not be decoded.
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    /// The block hash of a payload does not match its contents.
+    #[error("Block hash mismatch: expected {expected}, computed {computed}")]
+    BlockHashMismatch {
+        /// The block hash reported by the execution client.
+        expected: B256,
+        /// The block hash computed from the payload.
+        computed: B256,
+    },
+    /// The output root was requested before a payload was executed.
+    #[error("No executed block to compute the output root for")]
+    MissingExecutedBlock,
+}
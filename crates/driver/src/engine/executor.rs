@@ -0,0 +1,392 @@
+//! Contains the [EngineExecutor], an [Executor] backed by an external execution client.
+
+use crate::{
+    engine::{EngineClient, EngineError},
+    Executor,
+};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+use alloy_consensus::{
+    proofs::{calculate_transaction_root, calculate_withdrawals_root},
+    Header, Sealable, Sealed, EMPTY_OMMER_ROOT_HASH,
+};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{address, keccak256, Address, B256};
+use alloy_rpc_types_engine::{ExecutionPayloadV3, ForkchoiceState};
+use async_trait::async_trait;
+use core::time::Duration;
+use op_alloy_consensus::OpTxEnvelope;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+/// The address of the `L2ToL1MessagePasser` predeploy, whose storage root is committed to in the
+/// output root.
+const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+/// The version of the output root.
+const OUTPUT_ROOT_VERSION: u8 = 0;
+
+/// The default interval at which [EngineExecutor::wait_until_ready] polls the execution client.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An [Executor] that builds blocks on an external execution client over the Engine API.
+///
+/// Every payload is built on top of the safe head with `engine_forkchoiceUpdatedV3`, fetched with
+/// `engine_getPayloadV3`, and imported with `engine_newPayloadV3`, after which the new block is
+/// made the head and safe block of the execution client. The storage root of the
+/// `L2ToL1MessagePasser` is then fetched with `eth_getProof`, so that the output root of the
+/// executed block can be computed without further requests.
+#[derive(Debug)]
+pub struct EngineExecutor {
+    /// The Engine API client.
+    client: EngineClient,
+    /// The header of the safe head, that the next payload is built on top of.
+    safe_head: Option<Sealed<Header>>,
+    /// The header of the last executed block, along with the storage root of the
+    /// `L2ToL1MessagePasser` at that block.
+    executed: Option<(Sealed<Header>, B256)>,
+    /// The finalized block hash sent in forkchoice updates.
+    finalized: B256,
+    /// The interval at which [EngineExecutor::wait_until_ready] polls the execution client.
+    poll_interval: Duration,
+}
+
+impl EngineExecutor {
+    /// Creates a new [EngineExecutor].
+    pub const fn new(client: EngineClient) -> Self {
+        Self {
+            client,
+            safe_head: None,
+            executed: None,
+            finalized: B256::ZERO,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the finalized block hash that is sent in forkchoice updates. Defaults to the zero
+    /// hash, which signals that no block has been finalized.
+    pub const fn with_finalized(mut self, finalized: B256) -> Self {
+        self.finalized = finalized;
+        self
+    }
+
+    /// Sets the interval at which [EngineExecutor::wait_until_ready] polls the execution client.
+    pub const fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the [EngineClient].
+    pub const fn client(&self) -> &EngineClient {
+        &self.client
+    }
+
+    /// Returns a forkchoice state with the given block as the head and safe block.
+    const fn forkchoice(&self, hash: B256) -> ForkchoiceState {
+        ForkchoiceState {
+            head_block_hash: hash,
+            safe_block_hash: hash,
+            finalized_block_hash: self.finalized,
+        }
+    }
+
+    /// Builds, imports, and canonicalizes a payload with the given attributes on top of the safe
+    /// head, returning the header of the new block.
+    async fn build_payload(
+        &mut self,
+        attributes: OpPayloadAttributes,
+    ) -> Result<Sealed<Header>, EngineError> {
+        let parent = self.safe_head.as_ref().ok_or(EngineError::MissingSafeHead)?.seal();
+
+        let updated =
+            self.client.fork_choice_updated_v3(self.forkchoice(parent), Some(attributes)).await?;
+        if !updated.is_valid() {
+            return Err(EngineError::PayloadStatus(updated.payload_status.status));
+        }
+        let payload_id = updated.payload_id.ok_or(EngineError::MissingPayloadId)?;
+
+        let envelope = self.client.get_payload_v3(payload_id).await?;
+        let header =
+            payload_to_header(&envelope.execution_payload, envelope.parent_beacon_block_root)?;
+
+        let status = self
+            .client
+            .new_payload_v3(envelope.execution_payload, envelope.parent_beacon_block_root)
+            .await?;
+        if !status.is_valid() {
+            return Err(EngineError::PayloadStatus(status.status));
+        }
+
+        let updated =
+            self.client.fork_choice_updated_v3(self.forkchoice(header.seal()), None).await?;
+        if !updated.is_valid() {
+            return Err(EngineError::PayloadStatus(updated.payload_status.status));
+        }
+
+        Ok(header)
+    }
+}
+
+#[async_trait]
+impl Executor for EngineExecutor {
+    type Error = EngineError;
+
+    /// Waits until the execution client accepts authenticated Engine API requests.
+    async fn wait_until_ready(&mut self) {
+        loop {
+            match self.client.exchange_capabilities().await {
+                Ok(_) => return,
+                Err(e) => {
+                    warn!(target: "engine", "Execution client not ready: {e}");
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    /// Updates the safe header, that the next payload is built on top of.
+    fn update_safe_head(&mut self, header: Sealed<Header>) {
+        self.safe_head = Some(header);
+    }
+
+    /// Executes the given [OpPayloadAttributes] on top of the safe head.
+    async fn execute_payload(
+        &mut self,
+        attributes: OpPayloadAttributes,
+    ) -> Result<Header, Self::Error> {
+        let header = self.build_payload(attributes).await?;
+        let proof = self.client.get_proof(L2_TO_L1_MESSAGE_PASSER, header.number).await?;
+
+        info!(
+            target: "engine",
+            "Executed block # {number} | Hash: {hash}",
+            number = header.number,
+            hash = header.seal(),
+        );
+        self.executed = Some((header.clone(), proof.storage_hash));
+        self.safe_head = Some(header.clone());
+        Ok(header.unseal())
+    }
+
+    /// Computes the output root of the last executed block.
+    fn compute_output_root(&mut self) -> Result<B256, Self::Error> {
+        let (header, storage_root) =
+            self.executed.as_ref().ok_or(EngineError::MissingExecutedBlock)?;
+
+        let mut raw_output = [0u8; 128];
+        raw_output[31] = OUTPUT_ROOT_VERSION;
+        raw_output[32..64].copy_from_slice(header.state_root.as_ref());
+        raw_output[64..96].copy_from_slice(storage_root.as_ref());
+        raw_output[96..128].copy_from_slice(header.seal().as_ref());
+        Ok(keccak256(raw_output))
+    }
+}
+
+/// Reconstructs the header of the block in an [ExecutionPayloadV3], and checks it against the
+/// block hash reported by the execution client.
+pub(crate) fn payload_to_header(
+    payload: &ExecutionPayloadV3,
+    parent_beacon_block_root: B256,
+) -> Result<Sealed<Header>, EngineError> {
+    let inner = &payload.payload_inner.payload_inner;
+    let transactions = inner
+        .transactions
+        .iter()
+        .map(|tx| OpTxEnvelope::decode_2718(&mut tx.as_ref()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| EngineError::InvalidTransaction(e.to_string()))?;
+
+    let header = Header {
+        parent_hash: inner.parent_hash,
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: inner.fee_recipient,
+        state_root: inner.state_root,
+        transactions_root: calculate_transaction_root(&transactions),
+        receipts_root: inner.receipts_root,
+        logs_bloom: inner.logs_bloom,
+        number: inner.block_number,
+        gas_limit: inner.gas_limit,
+        gas_used: inner.gas_used,
+        timestamp: inner.timestamp,
+        extra_data: inner.extra_data.clone(),
+        mix_hash: inner.prev_randao,
+        base_fee_per_gas: Some(inner.base_fee_per_gas.saturating_to()),
+        withdrawals_root: Some(calculate_withdrawals_root(&payload.payload_inner.withdrawals)),
+        blob_gas_used: Some(payload.blob_gas_used),
+        excess_blob_gas: Some(payload.excess_blob_gas),
+        parent_beacon_block_root: Some(parent_beacon_block_root),
+        ..Default::default()
+    }
+    .seal_slow();
+
+    if header.seal() != inner.block_hash {
+        return Err(EngineError::BlockHashMismatch {
+            expected: inner.block_hash,
+            computed: header.seal(),
+        });
+    }
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::test_utils::MockEngine;
+    use alloc::vec;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::Bytes;
+    use alloy_rpc_types_engine::{JwtSecret, PayloadAttributes, PayloadId, PayloadStatusEnum};
+    use op_alloy_consensus::TxDeposit;
+
+    fn secret(byte: u8) -> JwtSecret {
+        JwtSecret::from_hex(alloy_primitives::hex::encode([byte; 32])).unwrap()
+    }
+
+    fn genesis() -> Sealed<Header> {
+        Header { number: 10, timestamp: 100, ..Default::default() }.seal_slow()
+    }
+
+    fn attributes(timestamp: u64) -> OpPayloadAttributes {
+        let deposit = OpTxEnvelope::Deposit(TxDeposit::default().seal_slow());
+        OpPayloadAttributes {
+            payload_attributes: PayloadAttributes {
+                timestamp,
+                prev_randao: B256::repeat_byte(0x01),
+                suggested_fee_recipient: Address::repeat_byte(0x02),
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::repeat_byte(0x03)),
+                target_blobs_per_block: None,
+                max_blobs_per_block: None,
+            },
+            transactions: Some(vec![Bytes::from(deposit.encoded_2718())]),
+            no_tx_pool: Some(true),
+            gas_limit: Some(30_000_000),
+            eip_1559_params: None,
+        }
+    }
+
+    async fn new_executor() -> (EngineExecutor, MockEngine) {
+        let engine = MockEngine::spawn(secret(0xaa), genesis()).await;
+        let executor =
+            EngineExecutor::new(EngineClient::new(engine.url.parse().unwrap(), secret(0xaa)))
+                .with_poll_interval(Duration::from_millis(10));
+        (executor, engine)
+    }
+
+    #[tokio::test]
+    async fn test_engine_executor_execute_payloads() {
+        let (mut executor, engine) = new_executor().await;
+        let storage_root = B256::repeat_byte(0x04);
+        engine.state.lock().unwrap().storage_root = storage_root;
+
+        executor.wait_until_ready().await;
+        executor.update_safe_head(genesis());
+        let first = executor.execute_payload(attributes(102)).await.unwrap();
+        assert_eq!(first.number, 11);
+        assert_eq!(first.parent_hash, genesis().seal());
+        assert_eq!(first.timestamp, 102);
+
+        // The output root commits to the executed block and the message passer storage root.
+        let mut raw_output = [0u8; 128];
+        raw_output[32..64].copy_from_slice(first.state_root.as_ref());
+        raw_output[64..96].copy_from_slice(storage_root.as_ref());
+        raw_output[96..128].copy_from_slice(first.hash_slow().as_ref());
+        assert_eq!(executor.compute_output_root().unwrap(), keccak256(raw_output));
+
+        executor.update_safe_head(first.clone().seal_slow());
+        let second = executor.execute_payload(attributes(104)).await.unwrap();
+        assert_eq!(second.number, 12);
+        assert_eq!(second.parent_hash, first.hash_slow());
+
+        let state = engine.state.lock().unwrap();
+        assert_eq!(state.forkchoice.head_block_hash, second.hash_slow());
+        assert_eq!(state.forkchoice.safe_block_hash, second.hash_slow());
+        assert_eq!(
+            state.calls[..6],
+            [
+                "engine_exchangeCapabilities",
+                "engine_forkchoiceUpdatedV3",
+                "engine_getPayloadV3",
+                "engine_newPayloadV3",
+                "engine_forkchoiceUpdatedV3",
+                "eth_getProof",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_executor_rejected_payload() {
+        let (mut executor, engine) = new_executor().await;
+        engine.state.lock().unwrap().reject_payloads = true;
+
+        executor.update_safe_head(genesis());
+        let err = executor.execute_payload(attributes(102)).await.unwrap_err();
+        assert_eq!(
+            err,
+            EngineError::PayloadStatus(PayloadStatusEnum::Invalid {
+                validation_error: "rejected".into()
+            })
+        );
+        assert_eq!(executor.compute_output_root(), Err(EngineError::MissingExecutedBlock));
+    }
+
+    #[tokio::test]
+    async fn test_engine_executor_unknown_safe_head() {
+        let (mut executor, _engine) = new_executor().await;
+        executor.update_safe_head(Header::default().seal_slow());
+        let err = executor.execute_payload(attributes(102)).await.unwrap_err();
+        assert_eq!(err, EngineError::PayloadStatus(PayloadStatusEnum::Syncing));
+    }
+
+    #[tokio::test]
+    async fn test_engine_executor_missing_safe_head() {
+        let (mut executor, _engine) = new_executor().await;
+        let err = executor.execute_payload(attributes(102)).await.unwrap_err();
+        assert_eq!(err, EngineError::MissingSafeHead);
+    }
+
+    #[tokio::test]
+    async fn test_engine_executor_unauthorized() {
+        let engine = MockEngine::spawn(secret(0xaa), genesis()).await;
+        let client = EngineClient::new(engine.url.parse().unwrap(), secret(0xbb));
+        assert_eq!(client.exchange_capabilities().await, Err(EngineError::HttpStatus(401)));
+        assert!(engine.state.lock().unwrap().calls.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engine_client_rpc_error() {
+        let (executor, _engine) = new_executor().await;
+        let err = executor.client().get_payload_v3(PayloadId::new([9; 8])).await.unwrap_err();
+        assert_eq!(err, EngineError::Rpc { code: -38001, message: "Unknown payload".into() });
+    }
+
+    #[test]
+    fn test_payload_to_header_hash_mismatch() {
+        let payload = ExecutionPayloadV3 {
+            payload_inner: alloy_rpc_types_engine::ExecutionPayloadV2 {
+                payload_inner: alloy_rpc_types_engine::ExecutionPayloadV1 {
+                    parent_hash: B256::ZERO,
+                    fee_recipient: Address::ZERO,
+                    state_root: B256::ZERO,
+                    receipts_root: B256::ZERO,
+                    logs_bloom: Default::default(),
+                    prev_randao: B256::ZERO,
+                    block_number: 1,
+                    gas_limit: 0,
+                    gas_used: 0,
+                    timestamp: 0,
+                    extra_data: Bytes::new(),
+                    base_fee_per_gas: Default::default(),
+                    block_hash: B256::repeat_byte(0x05),
+                    transactions: vec![],
+                },
+                withdrawals: vec![],
+            },
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
+        };
+        let err = payload_to_header(&payload, B256::ZERO).unwrap_err();
+        assert!(matches!(
+            err,
+            EngineError::BlockHashMismatch { expected, .. } if expected == B256::repeat_byte(0x05)
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+//! An [Executor] that drives an external execution client over the Engine API.
+//!
+//! The [EngineExecutor] builds every derived block on an execution client through the
+//! authenticated Engine API (`engine_forkchoiceUpdatedV3`, `engine_getPayloadV3`, and
+//! `engine_newPayloadV3`), and computes output roots from `eth_getProof` responses. Requests are
+//! authenticated with a [JwtSecret], as specified by the Engine API.
+//!
+//! [Executor]: crate::Executor
+//! [JwtSecret]: alloy_rpc_types_engine::JwtSecret
+
+mod errors;
+pub use errors::EngineError;
+
+mod client;
+pub use client::{AuthTransport, EngineClient};
+
+mod executor;
+pub use executor::EngineExecutor;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,281 @@
+//! A mock execution client that serves the Engine API over HTTP, for testing.
+
+use alloy_consensus::{
+    proofs::calculate_transaction_root, Header, Sealable, Sealed, EMPTY_OMMER_ROOT_HASH,
+    EMPTY_ROOT_HASH,
+};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{keccak256, map::HashMap, Address, B256, U256};
+use alloy_rpc_types::EIP1186AccountProofResponse;
+use alloy_rpc_types_engine::{
+    BlobsBundleV1, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, ForkchoiceState,
+    ForkchoiceUpdated, JwtSecret, PayloadId, PayloadStatus, PayloadStatusEnum,
+};
+use op_alloy_consensus::OpTxEnvelope;
+use op_alloy_rpc_types_engine::{OpExecutionPayloadEnvelopeV3, OpPayloadAttributes};
+use serde_json::{json, Value};
+use std::{
+    string::{String, ToString},
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// The state of the [MockEngine].
+#[derive(Debug, Default)]
+pub(crate) struct MockEngineState {
+    /// The blocks known to the execution client, keyed by hash.
+    pub(crate) blocks: HashMap<B256, Sealed<Header>>,
+    /// The payloads that have been built, keyed by payload ID.
+    pub(crate) payloads: HashMap<PayloadId, OpExecutionPayloadEnvelopeV3>,
+    /// The headers of the payloads that have been built, keyed by hash.
+    pub(crate) built: HashMap<B256, Sealed<Header>>,
+    /// The forkchoice state of the execution client.
+    pub(crate) forkchoice: ForkchoiceState,
+    /// The storage root returned for every account proof.
+    pub(crate) storage_root: B256,
+    /// Whether `engine_newPayloadV3` rejects every payload.
+    pub(crate) reject_payloads: bool,
+    /// The methods that have been called, in order.
+    pub(crate) calls: Vec<String>,
+}
+
+/// A mock execution client that builds empty-state blocks from payload attributes, and serves
+/// them over the authenticated Engine API.
+#[derive(Debug)]
+pub(crate) struct MockEngine {
+    /// The URL of the server.
+    pub(crate) url: String,
+    /// The shared state of the execution client.
+    pub(crate) state: Arc<Mutex<MockEngineState>>,
+    /// The server task.
+    handle: JoinHandle<()>,
+}
+
+impl MockEngine {
+    /// Starts a new [MockEngine] that knows about the given genesis block.
+    pub(crate) async fn spawn(secret: JwtSecret, genesis: Sealed<Header>) -> Self {
+        let mut state = MockEngineState::default();
+        state.forkchoice.head_block_hash = genesis.seal();
+        state.blocks.insert(genesis.seal(), genesis);
+        let state = Arc::new(Mutex::new(state));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, secret, server_state.clone()));
+            }
+        });
+        Self { url, state, handle }
+    }
+}
+
+impl Drop for MockEngine {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Serves HTTP requests on a single connection.
+async fn serve(mut stream: TcpStream, secret: JwtSecret, state: Arc<Mutex<MockEngineState>>) {
+    let mut buf = Vec::new();
+    loop {
+        // Read the request head.
+        let head_end = loop {
+            if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break i + 4;
+            }
+            let mut chunk = [0u8; 4096];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+        };
+        let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+        let header = |name: &str| {
+            head.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+            })
+        };
+        let length: usize = header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let authorized = header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer ").map(|token| secret.validate(token).is_ok()))
+            .unwrap_or_default();
+
+        // Read the request body.
+        while buf.len() < head_end + length {
+            let mut chunk = [0u8; 4096];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+        let body: Vec<u8> = buf.drain(..head_end + length).skip(head_end).collect();
+
+        let response = if authorized {
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = handle(&request, &mut state.lock().unwrap());
+            let body = serde_json::to_string(&response).unwrap();
+            format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            )
+        } else {
+            "HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\n\r\n".to_string()
+        };
+        if stream.write_all(response.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Handles a single JSON-RPC request.
+fn handle(request: &Value, state: &mut MockEngineState) -> Value {
+    let method = request["method"].as_str().unwrap_or_default();
+    let params = &request["params"];
+    state.calls.push(method.to_string());
+
+    let result = match method {
+        "engine_exchangeCapabilities" => Ok(params[0].clone()),
+        "engine_forkchoiceUpdatedV3" => {
+            let forkchoice: ForkchoiceState = serde_json::from_value(params[0].clone()).unwrap();
+            let Some(parent) = state.blocks.get(&forkchoice.head_block_hash).cloned() else {
+                let updated = ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing);
+                return response(request, Ok(serde_json::to_value(updated).unwrap()));
+            };
+            state.forkchoice = forkchoice;
+
+            let mut updated = ForkchoiceUpdated::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(parent.seal());
+            if !params[1].is_null() {
+                let attributes: OpPayloadAttributes =
+                    serde_json::from_value(params[1].clone()).unwrap();
+                let payload_id = PayloadId::new((state.payloads.len() as u64 + 1).to_be_bytes());
+                let (envelope, header) = build_payload(&parent, attributes);
+                state.payloads.insert(payload_id, envelope);
+                state.built.insert(header.seal(), header);
+                updated = updated.with_payload_id(payload_id);
+            }
+            Ok(serde_json::to_value(updated).unwrap())
+        }
+        "engine_getPayloadV3" => {
+            let payload_id: PayloadId = serde_json::from_value(params[0].clone()).unwrap();
+            state
+                .payloads
+                .get(&payload_id)
+                .map(|envelope| serde_json::to_value(envelope).unwrap())
+                .ok_or((-38001, "Unknown payload"))
+        }
+        "engine_newPayloadV3" => {
+            let payload: ExecutionPayloadV3 = serde_json::from_value(params[0].clone()).unwrap();
+            let inner = &payload.payload_inner.payload_inner;
+            let status = if state.reject_payloads {
+                PayloadStatus::new(
+                    PayloadStatusEnum::Invalid { validation_error: "rejected".to_string() },
+                    Some(inner.parent_hash),
+                )
+            } else {
+                let header = state.built[&inner.block_hash].clone();
+                state.blocks.insert(header.seal(), header);
+                PayloadStatus::new(PayloadStatusEnum::Valid, Some(inner.block_hash))
+            };
+            Ok(serde_json::to_value(status).unwrap())
+        }
+        "eth_getProof" => {
+            let address: Address = serde_json::from_value(params[0].clone()).unwrap();
+            let proof = EIP1186AccountProofResponse {
+                address,
+                storage_hash: state.storage_root,
+                ..Default::default()
+            };
+            Ok(serde_json::to_value(proof).unwrap())
+        }
+        _ => Err((-32601, "Method not found")),
+    };
+    response(request, result)
+}
+
+/// Wraps a result in a JSON-RPC response.
+fn response(request: &Value, result: Result<Value, (i64, &str)>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
+
+/// Builds a payload with the given attributes on top of the parent block.
+fn build_payload(
+    parent: &Sealed<Header>,
+    attributes: OpPayloadAttributes,
+) -> (OpExecutionPayloadEnvelopeV3, Sealed<Header>) {
+    let number = parent.number + 1;
+    let transactions = attributes.transactions.unwrap_or_default();
+    let decoded = transactions
+        .iter()
+        .map(|tx| OpTxEnvelope::decode_2718(&mut tx.as_ref()).unwrap())
+        .collect::<Vec<_>>();
+    let parent_beacon_block_root =
+        attributes.payload_attributes.parent_beacon_block_root.unwrap_or_default();
+
+    let header = Header {
+        parent_hash: parent.seal(),
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: attributes.payload_attributes.suggested_fee_recipient,
+        state_root: keccak256(number.to_be_bytes()),
+        transactions_root: calculate_transaction_root(&decoded),
+        receipts_root: EMPTY_ROOT_HASH,
+        number,
+        gas_limit: attributes.gas_limit.unwrap_or(30_000_000),
+        timestamp: attributes.payload_attributes.timestamp,
+        mix_hash: attributes.payload_attributes.prev_randao,
+        base_fee_per_gas: Some(1),
+        withdrawals_root: Some(EMPTY_ROOT_HASH),
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        parent_beacon_block_root: Some(parent_beacon_block_root),
+        ..Default::default()
+    };
+
+    let execution_payload = ExecutionPayloadV3 {
+        payload_inner: ExecutionPayloadV2 {
+            payload_inner: ExecutionPayloadV1 {
+                parent_hash: header.parent_hash,
+                fee_recipient: header.beneficiary,
+                state_root: header.state_root,
+                receipts_root: header.receipts_root,
+                logs_bloom: header.logs_bloom,
+                prev_randao: header.mix_hash,
+                block_number: header.number,
+                gas_limit: header.gas_limit,
+                gas_used: header.gas_used,
+                timestamp: header.timestamp,
+                extra_data: header.extra_data.clone(),
+                base_fee_per_gas: U256::from(1),
+                block_hash: header.hash_slow(),
+                transactions,
+            },
+            withdrawals: Vec::new(),
+        },
+        blob_gas_used: 0,
+        excess_blob_gas: 0,
+    };
+    let envelope = OpExecutionPayloadEnvelopeV3 {
+        execution_payload,
+        block_value: U256::ZERO,
+        blobs_bundle: BlobsBundleV1::new([]),
+        should_override_builder: false,
+        parent_beacon_block_root,
+    };
+    (envelope, header.seal_slow())
+}
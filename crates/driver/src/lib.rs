@@ -5,7 +5,7 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "engine")), no_std)]
 
 extern crate alloc;
 
@@ -29,3 +29,12 @@ pub use cursor::PipelineCursor;
 
 mod tip;
 pub use tip::TipCursor;
+
+mod block;
+pub use block::DerivedBlock;
+
+mod observer;
+pub use observer::DriverObserver;
+
+#[cfg(any(test, feature = "engine"))]
+pub mod engine;
@@ -0,0 +1,35 @@
+//! Contains the observer of the derivation driver.
+
+use crate::DerivedBlock;
+use alloy_primitives::B256;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+/// An observer of the [Driver](crate::Driver), notified as it derives and executes L2 blocks in
+/// [Driver::advance_to_target_with](crate::Driver::advance_to_target_with).
+///
+/// Any `FnMut(DerivedBlock<'_>)` closure is a [DriverObserver] that only observes the executed
+/// blocks.
+pub trait DriverObserver {
+    /// Called once, before the driver begins deriving blocks.
+    fn on_start(&mut self) {}
+
+    /// Called when the pipeline has derived the payload attributes of a block, before the block is
+    /// executed.
+    fn on_attributes(&mut self, _attributes: &OpPayloadAttributes) {}
+
+    /// Called after a block is executed, with the block and its output root.
+    fn on_block(&mut self, _block: DerivedBlock<'_>) {}
+
+    /// Called once the driver has reached the target block, with the number and output root of
+    /// the L2 safe head that it stopped at.
+    fn on_finish(&mut self, _number: u64, _output_root: B256) {}
+}
+
+impl<F> DriverObserver for F
+where
+    F: FnMut(DerivedBlock<'_>),
+{
+    fn on_block(&mut self, block: DerivedBlock<'_>) {
+        self(block)
+    }
+}
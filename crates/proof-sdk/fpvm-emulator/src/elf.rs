@@ -0,0 +1,210 @@
+//! A minimal loader for statically linked client program ELFs.
+
+use crate::errors::ElfError;
+
+/// The ELF magic bytes.
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+/// The `EI_CLASS` value for 32-bit objects.
+const ELFCLASS32: u8 = 1;
+/// The `EI_CLASS` value for 64-bit objects.
+const ELFCLASS64: u8 = 2;
+/// The `EI_DATA` value for little-endian objects.
+const ELFDATA2LSB: u8 = 1;
+/// The `EI_DATA` value for big-endian objects.
+const ELFDATA2MSB: u8 = 2;
+/// The `e_type` value for executables.
+const ET_EXEC: u16 = 2;
+/// The `e_machine` value for MIPS.
+const EM_MIPS: u16 = 8;
+/// The `e_machine` value for RISC-V.
+const EM_RISCV: u16 = 243;
+/// The `p_type` value for loadable segments.
+const PT_LOAD: u32 = 1;
+
+/// The instruction set architecture of a client program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    /// 32-bit big-endian MIPS, as run by Cannon.
+    Mips32,
+    /// 64-bit little-endian RISC-V, as run by Asterisc.
+    RiscV64,
+}
+
+impl Arch {
+    /// Returns whether the architecture stores multi-byte values in big-endian order.
+    pub const fn is_big_endian(&self) -> bool {
+        matches!(self, Self::Mips32)
+    }
+}
+
+/// A loadable segment of a [Program].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The virtual address the segment is loaded at.
+    pub vaddr: u64,
+    /// The initialized contents of the segment. The remainder, up to `mem_size`, is zeroed.
+    pub data: Vec<u8>,
+    /// The size of the segment in memory.
+    pub mem_size: u64,
+}
+
+/// A client program, parsed from a statically linked ELF executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// The architecture of the program.
+    pub arch: Arch,
+    /// The entry point of the program.
+    pub entry: u64,
+    /// The loadable segments of the program.
+    pub segments: Vec<Segment>,
+}
+
+impl Program {
+    /// Parses a [Program] from the raw bytes of an ELF executable. Only 32-bit big-endian MIPS
+    /// and 64-bit little-endian RISC-V executables are accepted.
+    pub fn parse(elf: &[u8]) -> Result<Self, ElfError> {
+        if elf.get(..4) != Some(ELF_MAGIC.as_slice()) {
+            return Err(ElfError::InvalidMagic);
+        }
+        let class = *elf.get(4).ok_or(ElfError::UnexpectedEof)?;
+        let data = *elf.get(5).ok_or(ElfError::UnexpectedEof)?;
+        let reader = Reader { elf, big_endian: data == ELFDATA2MSB };
+
+        let machine = reader.u16(18)?;
+        let arch = match (class, data, machine) {
+            (ELFCLASS32, ELFDATA2MSB, EM_MIPS) => Arch::Mips32,
+            (ELFCLASS64, ELFDATA2LSB, EM_RISCV) => Arch::RiscV64,
+            _ => return Err(ElfError::UnsupportedTarget { class, data, machine }),
+        };
+        let kind = reader.u16(16)?;
+        if kind != ET_EXEC {
+            return Err(ElfError::UnsupportedType(kind));
+        }
+
+        // Read the fields of the ELF header that differ in layout between the two classes.
+        let wide = class == ELFCLASS64;
+        let (entry, phoff, phentsize, phnum) = if wide {
+            (reader.u64(24)?, reader.u64(32)?, reader.u16(54)?, reader.u16(56)?)
+        } else {
+            (reader.u32(24)? as u64, reader.u32(28)? as u64, reader.u16(42)?, reader.u16(44)?)
+        };
+
+        let mut segments = Vec::new();
+        for i in 0..phnum as u64 {
+            let header = (phoff + i * phentsize as u64) as usize;
+            if reader.u32(header)? != PT_LOAD {
+                continue;
+            }
+            let (offset, vaddr, file_size, mem_size) = if wide {
+                (
+                    reader.u64(header + 8)?,
+                    reader.u64(header + 16)?,
+                    reader.u64(header + 32)?,
+                    reader.u64(header + 40)?,
+                )
+            } else {
+                (
+                    reader.u32(header + 4)? as u64,
+                    reader.u32(header + 8)? as u64,
+                    reader.u32(header + 16)? as u64,
+                    reader.u32(header + 20)? as u64,
+                )
+            };
+            if file_size > mem_size {
+                return Err(ElfError::InvalidSegment(vaddr));
+            }
+            let data = reader.bytes(offset as usize, file_size as usize)?.to_vec();
+            segments.push(Segment { vaddr, data, mem_size });
+        }
+
+        Ok(Self { arch, entry, segments })
+    }
+}
+
+/// A bounds-checked reader over the raw bytes of an ELF file.
+#[derive(Debug)]
+struct Reader<'a> {
+    elf: &'a [u8],
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&[u8], ElfError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.elf.get(offset..end))
+            .ok_or(ElfError::UnexpectedEof)
+    }
+
+    fn array<const N: usize>(&self, offset: usize) -> Result<[u8; N], ElfError> {
+        let mut bytes: [u8; N] = self.bytes(offset, N)?.try_into().expect("length checked");
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, ElfError> {
+        self.array(offset).map(u16::from_be_bytes)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, ElfError> {
+        self.array(offset).map(u32::from_be_bytes)
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, ElfError> {
+        self.array(offset).map(u64::from_be_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::build_elf;
+
+    #[test]
+    fn test_parse_mips() {
+        let elf = build_elf(Arch::Mips32, 0x1000, &[(0x1000, &[1, 2, 3, 4], 0x2000)]);
+        let program = Program::parse(&elf).unwrap();
+        assert_eq!(program.arch, Arch::Mips32);
+        assert_eq!(program.entry, 0x1000);
+        assert_eq!(
+            program.segments,
+            vec![Segment { vaddr: 0x1000, data: vec![1, 2, 3, 4], mem_size: 0x2000 }]
+        );
+    }
+
+    #[test]
+    fn test_parse_riscv() {
+        let elf = build_elf(
+            Arch::RiscV64,
+            0x10_0000,
+            &[(0x10_0000, &[0x13, 0, 0, 0], 4), (0x20_0000, &[0xff], 0x10)],
+        );
+        let program = Program::parse(&elf).unwrap();
+        assert_eq!(program.arch, Arch::RiscV64);
+        assert_eq!(program.entry, 0x10_0000);
+        assert_eq!(program.segments.len(), 2);
+        assert_eq!(program.segments[1].vaddr, 0x20_0000);
+        assert_eq!(program.segments[1].data, vec![0xff]);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(Program::parse(b"\x7fELG"), Err(ElfError::InvalidMagic));
+
+        let mut elf = build_elf(Arch::Mips32, 0x1000, &[(0x1000, &[1, 2, 3, 4], 4)]);
+        assert_eq!(Program::parse(&elf[..40]), Err(ElfError::UnexpectedEof));
+
+        // Little-endian MIPS is not supported.
+        elf[5] = ELFDATA2LSB;
+        assert_eq!(
+            Program::parse(&elf),
+            Err(ElfError::UnsupportedTarget {
+                class: ELFCLASS32,
+                data: ELFDATA2LSB,
+                machine: 8 << 8
+            })
+        );
+    }
+}
@@ -0,0 +1,376 @@
+//! The [Emulator], which runs a client program to completion.
+
+use crate::{
+    elf::{Arch, Program},
+    errors::{EmulatorError, EmulatorResult},
+    kernel::{Kernel, Syscall, SyscallOutcome},
+    memory::Memory,
+    mips::Mips32,
+    pipe::PipeChannel,
+    riscv::Riscv64,
+    PAGE_SIZE,
+};
+use std::fmt::Debug;
+use tracing::{debug, trace};
+
+/// The initial stack pointer of MIPS32 programs.
+const MIPS_STACK_TOP: u64 = 0x7fff_d000;
+/// The region that anonymous mappings are placed in for MIPS32 programs.
+const MIPS_MMAP_REGION: (u64, u64) = (0x2000_0000, 0x6000_0000);
+/// The initial stack pointer of RV64 programs.
+const RISCV_STACK_TOP: u64 = 0x7f_ffff_d000;
+/// The region that anonymous mappings are placed in for RV64 programs.
+const RISCV_MMAP_REGION: (u64, u64) = (0x20_0000_0000, 0x60_0000_0000);
+
+/// The outcome of executing a single instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// The instruction completed, and execution continues.
+    Continue,
+    /// The instruction requested a syscall. The program counter has been advanced past it.
+    Syscall(Syscall),
+}
+
+/// A processor that executes the instructions of a client program.
+pub(crate) trait Cpu: Debug + Send {
+    /// Returns the address of the next instruction to execute.
+    fn pc(&self) -> u64;
+
+    /// Executes a single instruction.
+    fn step(&mut self, memory: &mut Memory) -> EmulatorResult<Step>;
+
+    /// Writes the result of the last requested syscall to the registers, following the Linux
+    /// calling convention of the architecture.
+    fn set_syscall_result(&mut self, result: Result<u64, u64>);
+}
+
+/// The report of a client program that ran to completion in the [Emulator].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmulatorReport {
+    /// The exit code of the program.
+    pub exit_code: u8,
+    /// The number of instructions executed.
+    pub instructions: u64,
+    /// The number of syscalls issued.
+    pub syscalls: u64,
+    /// The highest number of bytes of memory in use at once, including the program image.
+    pub peak_memory: u64,
+    /// The last [MAX_OUTPUT] bytes the program wrote to stdout.
+    ///
+    /// [MAX_OUTPUT]: crate::MAX_OUTPUT
+    pub stdout: Vec<u8>,
+    /// The last [MAX_OUTPUT] bytes the program wrote to stderr.
+    ///
+    /// [MAX_OUTPUT]: crate::MAX_OUTPUT
+    pub stderr: Vec<u8>,
+}
+
+/// An in-process emulator for FPVM client programs.
+///
+/// The hint and preimage file descriptors of the program are wired to the given client ends of
+/// [pipe](crate::pipe)s, whose host ends can be served by a `PreimageServer`.
+#[derive(Debug)]
+pub struct Emulator {
+    /// The architecture of the program.
+    arch: Arch,
+    /// The processor running the program.
+    cpu: Box<dyn Cpu>,
+    /// The address space of the program.
+    memory: Memory,
+    /// The kernel serving the syscalls of the program.
+    kernel: Kernel,
+    /// The maximum number of instructions to execute.
+    step_limit: Option<u64>,
+}
+
+impl Emulator {
+    /// Loads the client program ELF, wiring file descriptors 3 and 4 to `hint` and 5 and 6 to
+    /// `preimage`.
+    pub fn new(elf: &[u8], hint: PipeChannel, preimage: PipeChannel) -> EmulatorResult<Self> {
+        let program = Program::parse(elf)?;
+
+        let mut memory = Memory::new(program.arch.is_big_endian());
+        let mut image_end = 0;
+        for segment in &program.segments {
+            memory.write(segment.vaddr, &segment.data)?;
+            image_end = image_end.max(segment.vaddr.saturating_add(segment.mem_size));
+        }
+        let brk_start = image_end.div_ceil(PAGE_SIZE) * PAGE_SIZE;
+
+        let (cpu, (mmap_start, mmap_end)): (Box<dyn Cpu>, _) = match program.arch {
+            Arch::Mips32 => (
+                Box::new(Mips32::new(program.entry as u32, MIPS_STACK_TOP as u32)),
+                MIPS_MMAP_REGION,
+            ),
+            Arch::RiscV64 => {
+                (Box::new(Riscv64::new(program.entry, RISCV_STACK_TOP)), RISCV_MMAP_REGION)
+            }
+        };
+        let kernel = Kernel::new(hint, preimage, brk_start, mmap_start, mmap_end);
+
+        Ok(Self { arch: program.arch, cpu, memory, kernel, step_limit: None })
+    }
+
+    /// Caps the memory the program may use, in bytes, including the program image.
+    pub fn with_memory_limit(mut self, limit: u64) -> Self {
+        self.memory = self.memory.with_limit(limit);
+        self
+    }
+
+    /// Caps the number of instructions the program may execute.
+    pub const fn with_step_limit(mut self, limit: u64) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    /// Returns the architecture of the loaded program.
+    pub const fn arch(&self) -> Arch {
+        self.arch
+    }
+
+    /// Runs the program until it exits, returning its [EmulatorReport].
+    ///
+    /// Reads from the hint and preimage file descriptors wait for the host to respond, so the host
+    /// ends of the pipes must be served concurrently.
+    pub async fn run(mut self) -> EmulatorResult<EmulatorReport> {
+        let mut instructions = 0u64;
+        let mut syscalls = 0u64;
+        loop {
+            if let Some(limit) = self.step_limit {
+                if instructions >= limit {
+                    return Err(EmulatorError::StepLimitExceeded(limit));
+                }
+            }
+
+            let pc = self.cpu.pc();
+            let step = self.cpu.step(&mut self.memory)?;
+            instructions += 1;
+
+            let Step::Syscall(syscall) = step else {
+                continue;
+            };
+            syscalls += 1;
+            trace!(target: "fpvm_emulator", "Syscall at {pc:#x}: {syscall:?}");
+            match self.kernel.handle(syscall, pc, &mut self.memory).await? {
+                SyscallOutcome::Return(result) => self.cpu.set_syscall_result(result),
+                SyscallOutcome::Exit(exit_code) => {
+                    debug!(
+                        target: "fpvm_emulator",
+                        "Program exited with code {exit_code} after {instructions} instructions"
+                    );
+                    return Ok(EmulatorReport {
+                        exit_code,
+                        instructions,
+                        syscalls,
+                        peak_memory: self.memory.peak(),
+                        stdout: std::mem::take(&mut self.kernel.stdout),
+                        stderr: std::mem::take(&mut self.kernel.stderr),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pipe::pipe,
+        test_utils::{build_elf, mips, riscv},
+    };
+    use async_trait::async_trait;
+    use kona_preimage::{
+        errors::PreimageOracleResult, HintReader, HintReaderServer, HintRouter, OracleServer,
+        PreimageFetcher, PreimageKey, PreimageKeyType, PreimageOracleServer,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Builds an [Emulator] for the program, returning it with the host ends of its pipes.
+    fn emulator(arch: Arch, text: &[u32], data: &[u8]) -> (Emulator, PipeChannel, PipeChannel) {
+        let text = text
+            .iter()
+            .flat_map(|insn| match arch {
+                Arch::Mips32 => insn.to_be_bytes(),
+                Arch::RiscV64 => insn.to_le_bytes(),
+            })
+            .collect::<Vec<_>>();
+        let elf = build_elf(
+            arch,
+            0x1000,
+            &[(0x1000, &text, text.len() as u64), (0x2000, data, data.len() as u64)],
+        );
+        let (hint_host, hint_client) = pipe();
+        let (preimage_host, preimage_client) = pipe();
+        let emulator = Emulator::new(&elf, hint_client, preimage_client).unwrap();
+        (emulator, hint_host, preimage_host)
+    }
+
+    #[tokio::test]
+    async fn test_emulator_mips_hello() {
+        use mips::*;
+        let (emulator, _, _) = emulator(
+            Arch::Mips32,
+            &[
+                addiu(4, 0, 1),
+                lui(5, 0),
+                ori(5, 5, 0x2000),
+                addiu(6, 0, 5),
+                addiu(2, 0, 4004),
+                syscall(),
+                addiu(4, 0, 7),
+                addiu(2, 0, 4246),
+                syscall(),
+            ],
+            b"hello",
+        );
+        assert_eq!(emulator.arch(), Arch::Mips32);
+
+        let report = emulator.run().await.unwrap();
+        assert_eq!(report.exit_code, 7);
+        assert_eq!(report.stdout, b"hello");
+        assert_eq!(report.instructions, 9);
+        assert_eq!(report.syscalls, 2);
+        assert_eq!(report.peak_memory, 2 * PAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_emulator_riscv_hello() {
+        use riscv::*;
+        let (emulator, _, _) = emulator(
+            Arch::RiscV64,
+            &[
+                addi(10, 0, 2),
+                lui(11, 0x2),
+                addi(12, 0, 5),
+                addi(17, 0, 64),
+                ecall(),
+                // A write to an unknown file descriptor fails with `EBADF`, which becomes the
+                // exit code.
+                addi(10, 0, 9),
+                addi(17, 0, 64),
+                ecall(),
+                sub(10, 0, 10),
+                addi(17, 0, 93),
+                ecall(),
+            ],
+            b"hello",
+        );
+        assert_eq!(emulator.arch(), Arch::RiscV64);
+
+        let report = emulator.run().await.unwrap();
+        assert_eq!(report.exit_code, 9);
+        assert_eq!(report.stderr, b"hello");
+        assert_eq!(report.instructions, 11);
+        assert_eq!(report.syscalls, 3);
+    }
+
+    /// A [PreimageFetcher] that serves a single preimage.
+    struct TestFetcher(PreimageKey, Vec<u8>);
+
+    #[async_trait]
+    impl PreimageFetcher for TestFetcher {
+        async fn get_preimage(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            assert_eq!(key, self.0);
+            Ok(self.1.clone())
+        }
+    }
+
+    /// A [HintRouter] that records the hints it receives.
+    #[derive(Default, Clone)]
+    struct TestRouter(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl HintRouter for TestRouter {
+        async fn route_hint(&self, hint: String) -> PreimageOracleResult<()> {
+            self.0.lock().unwrap().push(hint);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_emulator_preimage_oracle() {
+        use mips::*;
+
+        let key = PreimageKey::new([7; 32], PreimageKeyType::Local);
+        let mut data = <[u8; 32]>::from(key).to_vec();
+        data.extend_from_slice(&4u32.to_be_bytes());
+        data.extend_from_slice(b"ping");
+
+        // Write the hint, wait for the acknowledgement, request the preimage for the key, and
+        // echo the preimage to stdout.
+        let text = [
+            [addiu(4, 0, 4), addiu(5, 0, 0x2020), addiu(6, 0, 8), addiu(2, 0, 4004), syscall()],
+            [addiu(4, 0, 3), addiu(5, 0, 0x3000), addiu(6, 0, 1), addiu(2, 0, 4003), syscall()],
+            [addiu(4, 0, 6), addiu(5, 0, 0x2000), addiu(6, 0, 32), addiu(2, 0, 4004), syscall()],
+            [addiu(4, 0, 5), addiu(5, 0, 0x3000), addiu(6, 0, 8), addiu(2, 0, 4003), syscall()],
+            [addiu(4, 0, 5), addiu(5, 0, 0x3008), addiu(6, 0, 64), addiu(2, 0, 4003), syscall()],
+            [addiu(4, 0, 1), addiu(5, 0, 0x3008), or(6, 2, 0), addiu(2, 0, 4004), syscall()],
+            [addiu(4, 0, 0), addiu(2, 0, 4246), syscall(), 0, 0],
+        ]
+        .concat();
+        let (emulator, hint_host, preimage_host) = emulator(Arch::Mips32, &text, &data);
+
+        let router = TestRouter::default();
+        let hints = router.0.clone();
+        let server = tokio::spawn(async move {
+            HintReader::new(hint_host).next_hint(&router).await.unwrap();
+            let fetcher = TestFetcher(key, b"pong from the host".to_vec());
+            OracleServer::new(preimage_host).next_preimage_request(&fetcher).await.unwrap();
+        });
+
+        let report = emulator.run().await.unwrap();
+        server.await.unwrap();
+        assert_eq!(report.exit_code, 0);
+        assert_eq!(report.stdout, b"pong from the host");
+        assert_eq!(hints.lock().unwrap().as_slice(), ["ping".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_emulator_host_closed() {
+        use riscv::*;
+        let (emulator, hint_host, preimage_host) = emulator(
+            Arch::RiscV64,
+            &[addi(10, 0, 5), lui(11, 0x3), addi(12, 0, 8), addi(17, 0, 63), ecall()],
+            &[],
+        );
+        drop((hint_host, preimage_host));
+        assert!(matches!(emulator.run().await, Err(EmulatorError::Channel(_))));
+    }
+
+    #[tokio::test]
+    async fn test_emulator_unsupported_syscall() {
+        use riscv::*;
+        let (emulator, _, _) = emulator(Arch::RiscV64, &[addi(17, 0, 56), ecall()], &[]);
+        assert!(matches!(
+            emulator.run().await,
+            Err(EmulatorError::UnsupportedSyscall { pc: 0x1004, number: 56 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_emulator_memory_limit() {
+        use mips::*;
+        // Touch a new page on every iteration.
+        let (emulator, _, _) = emulator(
+            Arch::Mips32,
+            &[lui(8, 0x1000), addiu(9, 0, 0x1000), sw(0, 8, 0), addu(8, 8, 9), beq(0, 0, -3), 0],
+            &[],
+        );
+        let limit = 16 * PAGE_SIZE;
+        assert!(matches!(
+            emulator.with_memory_limit(limit).run().await,
+            Err(EmulatorError::MemoryLimitExceeded(l)) if l == limit
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_emulator_step_limit() {
+        use mips::*;
+        let (emulator, _, _) = emulator(Arch::Mips32, &[beq(0, 0, -1), 0], &[]);
+        assert!(matches!(
+            emulator.with_step_limit(100).run().await,
+            Err(EmulatorError::StepLimitExceeded(100))
+        ));
+    }
+}
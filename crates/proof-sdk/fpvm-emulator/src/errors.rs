@@ -0,0 +1,78 @@
+//! Errors for the `kona-fpvm-emulator` crate.
+
+use kona_preimage::errors::ChannelError;
+use thiserror::Error;
+
+/// An error that occurred while loading a client program ELF.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    /// The file does not start with the ELF magic.
+    #[error("Invalid ELF magic")]
+    InvalidMagic,
+    /// The file ended before a header or segment could be read.
+    #[error("Unexpected end of ELF file")]
+    UnexpectedEof,
+    /// The ELF class, data encoding and machine combination is not supported.
+    #[error("Unsupported ELF target: class {class}, data {data}, machine {machine}")]
+    UnsupportedTarget {
+        /// The `EI_CLASS` identification byte.
+        class: u8,
+        /// The `EI_DATA` identification byte.
+        data: u8,
+        /// The `e_machine` field.
+        machine: u16,
+    },
+    /// The ELF is not an executable.
+    #[error("Unsupported ELF type: {0}")]
+    UnsupportedType(u16),
+    /// A loadable segment is larger on disk than in memory.
+    #[error("Invalid segment at {0:#x}: file size exceeds memory size")]
+    InvalidSegment(u64),
+}
+
+/// An error that halted the emulator.
+#[derive(Error, Debug)]
+pub enum EmulatorError {
+    /// The client program could not be loaded.
+    #[error("Failed to load program: {0}")]
+    Elf(#[from] ElfError),
+    /// The instruction at the given program counter is not supported.
+    #[error("Unsupported instruction {instruction:#010x} at pc {pc:#x}")]
+    UnsupportedInstruction {
+        /// The program counter of the instruction.
+        pc: u64,
+        /// The raw instruction.
+        instruction: u32,
+    },
+    /// The program issued a syscall that is not supported.
+    #[error("Unsupported syscall {number} at pc {pc:#x}")]
+    UnsupportedSyscall {
+        /// The program counter of the syscall instruction.
+        pc: u64,
+        /// The syscall number.
+        number: u64,
+    },
+    /// The program accessed memory at an address that is not naturally aligned.
+    #[error("Unaligned {size}-byte memory access at {address:#x}")]
+    UnalignedAccess {
+        /// The address of the access.
+        address: u64,
+        /// The size of the access, in bytes.
+        size: u64,
+    },
+    /// The program trapped, e.g. by executing a `break` or a conditional trap instruction.
+    #[error("Program trapped at pc {0:#x}")]
+    Trap(u64),
+    /// The program touched more memory than the configured limit.
+    #[error("Memory limit of {0} bytes exceeded")]
+    MemoryLimitExceeded(u64),
+    /// The program executed more instructions than the configured limit.
+    #[error("Step limit of {0} instructions exceeded")]
+    StepLimitExceeded(u64),
+    /// A hint or preimage channel was closed by the host.
+    #[error(transparent)]
+    Channel(#[from] ChannelError),
+}
+
+/// A [Result] type for the [EmulatorError] enum.
+pub type EmulatorResult<T> = Result<T, EmulatorError>;
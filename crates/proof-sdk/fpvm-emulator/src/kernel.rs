@@ -0,0 +1,286 @@
+//! The emulated Linux kernel, implementing the syscalls that client programs issue.
+
+use crate::{
+    errors::{EmulatorError, EmulatorResult},
+    memory::Memory,
+    pipe::PipeChannel,
+    PAGE_SIZE,
+};
+use kona_preimage::Channel;
+
+/// The `EBADF` error number, returned for reads and writes on unknown file descriptors.
+pub(crate) const EBADF: u64 = 9;
+/// The `ENOMEM` error number, returned when the mapping region is exhausted.
+pub(crate) const ENOMEM: u64 = 12;
+/// The `EINVAL` error number, returned for unsupported `fcntl` commands.
+pub(crate) const EINVAL: u64 = 22;
+
+/// The `F_GETFD` command of `fcntl`.
+const F_GETFD: u64 = 1;
+/// The `F_GETFL` command of `fcntl`.
+const F_GETFL: u64 = 3;
+/// The `O_WRONLY` file status flag.
+const O_WRONLY: u64 = 1;
+
+/// The maximum number of bytes transferred by a single `read` or `write` syscall.
+const MAX_IO: u64 = 64 * 1024;
+
+/// The maximum number of bytes of stdout and of stderr that are kept. Once a stream exceeds it,
+/// its oldest bytes are discarded.
+pub const MAX_OUTPUT: usize = 1024 * 1024;
+
+/// A syscall issued by the program, decoded from the registers of its architecture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Syscall {
+    /// `read(fd, address, len)`
+    Read { fd: u64, address: u64, len: u64 },
+    /// `write(fd, address, len)`
+    Write { fd: u64, address: u64, len: u64 },
+    /// `exit(code)` or `exit_group(code)`
+    Exit(u64),
+    /// `brk(address)`
+    Brk(u64),
+    /// An anonymous `mmap(address, len, ..)`
+    Mmap { len: u64 },
+    /// `munmap(address, len)`
+    Munmap { address: u64, len: u64 },
+    /// `fcntl(fd, cmd, ..)`
+    Fcntl { fd: u64, cmd: u64 },
+    /// A syscall that the FPVM acknowledges without effect, such as signal handling, scheduling
+    /// and memory advice.
+    Ignored(u64),
+    /// A syscall that the emulator does not implement.
+    Unsupported(u64),
+}
+
+/// The outcome of a [Syscall].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SyscallOutcome {
+    /// The syscall returned a value, or failed with an error number.
+    Return(Result<u64, u64>),
+    /// The program exited with the given status.
+    Exit(u8),
+}
+
+/// The file descriptors and memory regions of the emulated process.
+#[derive(Debug)]
+pub(crate) struct Kernel {
+    /// The client end of the hint pipe, read from fd 3 and written to fd 4.
+    hint: PipeChannel,
+    /// The client end of the preimage pipe, read from fd 5 and written to fd 6.
+    preimage: PipeChannel,
+    /// The last [MAX_OUTPUT] bytes written to stdout.
+    pub(crate) stdout: Vec<u8>,
+    /// The last [MAX_OUTPUT] bytes written to stderr.
+    pub(crate) stderr: Vec<u8>,
+    /// The lowest address the program break may be set to.
+    brk_start: u64,
+    /// The current program break.
+    brk: u64,
+    /// The highest address the program break may be set to.
+    brk_end: u64,
+    /// The address of the next anonymous mapping.
+    mmap_next: u64,
+    /// The end of the region that anonymous mappings are placed in.
+    mmap_end: u64,
+}
+
+impl Kernel {
+    /// Creates a new [Kernel] with the given client pipe ends, program break and mapping region.
+    pub(crate) const fn new(
+        hint: PipeChannel,
+        preimage: PipeChannel,
+        brk_start: u64,
+        mmap_start: u64,
+        mmap_end: u64,
+    ) -> Self {
+        Self {
+            hint,
+            preimage,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            brk_start,
+            brk: brk_start,
+            brk_end: mmap_start,
+            mmap_next: mmap_start,
+            mmap_end,
+        }
+    }
+
+    /// Handles a syscall issued at `pc`, returning its outcome.
+    pub(crate) async fn handle(
+        &mut self,
+        syscall: Syscall,
+        pc: u64,
+        memory: &mut Memory,
+    ) -> EmulatorResult<SyscallOutcome> {
+        let result = match syscall {
+            Syscall::Read { fd, address, len } => {
+                let channel = match fd {
+                    0 => return Ok(SyscallOutcome::Return(Ok(0))),
+                    3 => &self.hint,
+                    5 => &self.preimage,
+                    _ => return Ok(SyscallOutcome::Return(Err(EBADF))),
+                };
+                let mut buf = vec![0u8; len.min(MAX_IO) as usize];
+                let read = channel.read(&mut buf).await?;
+                memory.write(address, &buf[..read])?;
+                Ok(read as u64)
+            }
+            Syscall::Write { fd, address, len } => {
+                let mut buf = vec![0u8; len.min(MAX_IO) as usize];
+                memory.read(address, &mut buf);
+                match fd {
+                    1 => append_output(&mut self.stdout, &buf),
+                    2 => append_output(&mut self.stderr, &buf),
+                    4 => {
+                        self.hint.write(&buf).await?;
+                    }
+                    6 => {
+                        self.preimage.write(&buf).await?;
+                    }
+                    _ => return Ok(SyscallOutcome::Return(Err(EBADF))),
+                }
+                Ok(buf.len() as u64)
+            }
+            Syscall::Exit(code) => return Ok(SyscallOutcome::Exit(code as u8)),
+            Syscall::Brk(address) => {
+                if address >= self.brk_start && address <= self.brk_end {
+                    self.brk = address;
+                }
+                Ok(self.brk)
+            }
+            Syscall::Mmap { len } => {
+                let len = len.div_ceil(PAGE_SIZE) * PAGE_SIZE;
+                match self.mmap_next.checked_add(len).filter(|end| *end <= self.mmap_end) {
+                    Some(end) => {
+                        let address = self.mmap_next;
+                        self.mmap_next = end;
+                        Ok(address)
+                    }
+                    None => Err(ENOMEM),
+                }
+            }
+            Syscall::Munmap { address, len } => {
+                memory.release(address, len);
+                Ok(0)
+            }
+            Syscall::Fcntl { fd, cmd } => match (cmd, fd) {
+                (F_GETFD, 0..=6) => Ok(0),
+                // The standard streams and pipe ends are opened read-only or write-only.
+                (F_GETFL, 0 | 3 | 5) => Ok(0),
+                (F_GETFL, 1 | 2 | 4 | 6) => Ok(O_WRONLY),
+                (F_GETFD | F_GETFL, _) => Err(EBADF),
+                _ => Err(EINVAL),
+            },
+            Syscall::Ignored(_) => Ok(0),
+            Syscall::Unsupported(number) => {
+                return Err(EmulatorError::UnsupportedSyscall { pc, number })
+            }
+        };
+        Ok(SyscallOutcome::Return(result))
+    }
+}
+
+/// Appends `data` to the output of a standard stream, discarding its oldest bytes beyond
+/// [MAX_OUTPUT].
+fn append_output(output: &mut Vec<u8>, data: &[u8]) {
+    output.extend_from_slice(data);
+    if output.len() > MAX_OUTPUT {
+        output.drain(..output.len() - MAX_OUTPUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipe::pipe;
+
+    fn kernel() -> Kernel {
+        let ((_, hint), (_, preimage)) = (pipe(), pipe());
+        Kernel::new(hint, preimage, 0x1_0000, 0x2_0000, 0x2_0000 + 4 * PAGE_SIZE)
+    }
+
+    #[tokio::test]
+    async fn test_kernel_brk() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        // The break may not shrink below the program image, or grow into the mapping region.
+        for (address, expected) in [(0, 0x1_0000), (0x1_8000, 0x1_8000), (0x2_0001, 0x1_8000)] {
+            let outcome = kernel.handle(Syscall::Brk(address), 0, &mut memory).await.unwrap();
+            assert_eq!(outcome, SyscallOutcome::Return(Ok(expected)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kernel_output_limit() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        let writes = MAX_OUTPUT as u64 / MAX_IO + 2;
+        for i in 0..writes {
+            memory.write(0, &[i as u8; MAX_IO as usize]).unwrap();
+            let write = Syscall::Write { fd: 2, address: 0, len: MAX_IO };
+            let outcome = kernel.handle(write, 0, &mut memory).await.unwrap();
+            assert_eq!(outcome, SyscallOutcome::Return(Ok(MAX_IO)));
+        }
+
+        // Only the last writes are kept.
+        assert!(kernel.stdout.is_empty());
+        assert_eq!(kernel.stderr.len(), MAX_OUTPUT);
+        assert_eq!(kernel.stderr[0], 2);
+        assert_eq!(kernel.stderr[MAX_OUTPUT - 1], writes as u8 - 1);
+    }
+
+    #[tokio::test]
+    async fn test_kernel_mmap() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        for (len, expected) in [
+            (1, Ok(0x2_0000)),
+            (2 * PAGE_SIZE, Ok(0x2_0000 + PAGE_SIZE)),
+            (2 * PAGE_SIZE, Err(ENOMEM)),
+            (PAGE_SIZE, Ok(0x2_0000 + 3 * PAGE_SIZE)),
+        ] {
+            let outcome = kernel.handle(Syscall::Mmap { len }, 0, &mut memory).await.unwrap();
+            assert_eq!(outcome, SyscallOutcome::Return(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kernel_fcntl() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        for (fd, cmd, expected) in [
+            (0, F_GETFL, Ok(0)),
+            (2, F_GETFL, Ok(O_WRONLY)),
+            (5, F_GETFL, Ok(0)),
+            (6, F_GETFL, Ok(O_WRONLY)),
+            (4, F_GETFD, Ok(0)),
+            (7, F_GETFL, Err(EBADF)),
+            (7, F_GETFD, Err(EBADF)),
+            (3, 4, Err(EINVAL)),
+        ] {
+            let outcome = kernel.handle(Syscall::Fcntl { fd, cmd }, 0, &mut memory).await.unwrap();
+            assert_eq!(outcome, SyscallOutcome::Return(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kernel_ignored() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        let outcome = kernel.handle(Syscall::Ignored(124), 0, &mut memory).await.unwrap();
+        assert_eq!(outcome, SyscallOutcome::Return(Ok(0)));
+    }
+
+    #[tokio::test]
+    async fn test_kernel_bad_fd() {
+        let (mut kernel, mut memory) = (kernel(), Memory::new(false));
+        for syscall in [
+            Syscall::Read { fd: 7, address: 0, len: 1 },
+            Syscall::Write { fd: 3, address: 0, len: 1 },
+        ] {
+            let outcome = kernel.handle(syscall, 0, &mut memory).await.unwrap();
+            assert_eq!(outcome, SyscallOutcome::Return(Err(EBADF)));
+        }
+        assert!(matches!(
+            kernel.handle(Syscall::Unsupported(56), 0x40, &mut memory).await,
+            Err(EmulatorError::UnsupportedSyscall { pc: 0x40, number: 56 })
+        ));
+    }
+}
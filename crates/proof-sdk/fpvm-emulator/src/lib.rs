@@ -0,0 +1,31 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/anton-rs/kona/main/assets/square.png",
+    html_favicon_url = "https://raw.githubusercontent.com/anton-rs/kona/main/assets/favicon.ico"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+pub mod errors;
+pub use errors::{ElfError, EmulatorError, EmulatorResult};
+
+mod elf;
+pub use elf::{Arch, Program, Segment};
+
+mod memory;
+pub use memory::{Memory, PAGE_SIZE};
+
+mod pipe;
+pub use pipe::{pipe, PipeChannel};
+
+mod kernel;
+pub use kernel::MAX_OUTPUT;
+
+mod mips;
+mod riscv;
+
+mod emulator;
+pub use emulator::{Emulator, EmulatorReport};
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,247 @@
+//! Sparse, paged memory for the emulated program.
+
+use crate::errors::{EmulatorError, EmulatorResult};
+use std::collections::HashMap;
+
+/// The size of a memory page, in bytes.
+pub const PAGE_SIZE: u64 = 4096;
+
+/// A single page of memory.
+type Page = Box<[u8; PAGE_SIZE as usize]>;
+
+/// The sparse address space of the emulated program.
+///
+/// Pages are allocated the first time they are written to, and reads from unallocated pages
+/// return zeroes, mirroring the zero-initialized memory of the FPVMs. The number of allocated pages
+/// is tracked to report the peak memory usage of the program, and may be capped with a limit.
+#[derive(Debug)]
+pub struct Memory {
+    /// The allocated pages, keyed by page number.
+    pages: HashMap<u64, Page>,
+    /// Whether multi-byte values are stored in big-endian order.
+    big_endian: bool,
+    /// The maximum number of pages that may be allocated.
+    page_limit: Option<u64>,
+    /// The highest number of pages that have been allocated at once.
+    peak_pages: u64,
+}
+
+impl Memory {
+    /// Creates a new, empty [Memory].
+    pub fn new(big_endian: bool) -> Self {
+        Self { pages: HashMap::new(), big_endian, page_limit: None, peak_pages: 0 }
+    }
+
+    /// Caps the memory that may be allocated, in bytes. The limit is rounded up to a whole page.
+    pub const fn with_limit(mut self, limit: u64) -> Self {
+        self.page_limit = Some(limit.div_ceil(PAGE_SIZE));
+        self
+    }
+
+    /// Returns the number of bytes currently allocated.
+    pub fn allocated(&self) -> u64 {
+        self.pages.len() as u64 * PAGE_SIZE
+    }
+
+    /// Returns the highest number of bytes that have been allocated at once.
+    pub const fn peak(&self) -> u64 {
+        self.peak_pages * PAGE_SIZE
+    }
+
+    /// Releases the whole pages within the given range.
+    pub fn release(&mut self, address: u64, len: u64) {
+        let first = address.div_ceil(PAGE_SIZE);
+        let end = address.saturating_add(len) / PAGE_SIZE;
+        if end.saturating_sub(first) > self.pages.len() as u64 {
+            self.pages.retain(|page, _| !(first..end).contains(page));
+        } else {
+            (first..end).for_each(|page| {
+                self.pages.remove(&page);
+            });
+        }
+    }
+
+    /// Returns the page with the given number, allocating it if needed.
+    fn page_mut(&mut self, number: u64) -> EmulatorResult<&mut Page> {
+        if !self.pages.contains_key(&number) {
+            let count = self.pages.len() as u64 + 1;
+            if let Some(limit) = self.page_limit {
+                if count > limit {
+                    return Err(EmulatorError::MemoryLimitExceeded(limit * PAGE_SIZE));
+                }
+            }
+            self.peak_pages = self.peak_pages.max(count);
+        }
+        Ok(self.pages.entry(number).or_insert_with(|| Box::new([0; PAGE_SIZE as usize])))
+    }
+
+    /// Reads `buf.len()` bytes starting at `address`.
+    pub fn read(&self, address: u64, buf: &mut [u8]) {
+        let mut done = 0;
+        while done < buf.len() {
+            let current = address.wrapping_add(done as u64);
+            let offset = (current % PAGE_SIZE) as usize;
+            let len = (PAGE_SIZE as usize - offset).min(buf.len() - done);
+            match self.pages.get(&(current / PAGE_SIZE)) {
+                Some(page) => buf[done..done + len].copy_from_slice(&page[offset..offset + len]),
+                None => buf[done..done + len].fill(0),
+            }
+            done += len;
+        }
+    }
+
+    /// Writes `data` starting at `address`.
+    pub fn write(&mut self, address: u64, data: &[u8]) -> EmulatorResult<()> {
+        let mut done = 0;
+        while done < data.len() {
+            let current = address.wrapping_add(done as u64);
+            let offset = (current % PAGE_SIZE) as usize;
+            let len = (PAGE_SIZE as usize - offset).min(data.len() - done);
+            let page = self.page_mut(current / PAGE_SIZE)?;
+            page[offset..offset + len].copy_from_slice(&data[done..done + len]);
+            done += len;
+        }
+        Ok(())
+    }
+
+    /// Reads a naturally aligned, `N`-byte value at `address` in the byte order of the program,
+    /// returning it zero-extended.
+    fn load<const N: usize>(&self, address: u64) -> EmulatorResult<u64> {
+        if address & (N as u64 - 1) != 0 {
+            return Err(EmulatorError::UnalignedAccess { address, size: N as u64 });
+        }
+        let mut bytes = [0u8; N];
+        if let Some(page) = self.pages.get(&(address / PAGE_SIZE)) {
+            let offset = (address % PAGE_SIZE) as usize;
+            bytes.copy_from_slice(&page[offset..offset + N]);
+        }
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes.iter().fold(0, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    /// Writes the low `N` bytes of `value` at the naturally aligned `address` in the byte order of
+    /// the program.
+    fn store<const N: usize>(&mut self, address: u64, value: u64) -> EmulatorResult<()> {
+        if address & (N as u64 - 1) != 0 {
+            return Err(EmulatorError::UnalignedAccess { address, size: N as u64 });
+        }
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (value >> (8 * (N - 1 - i))) as u8;
+        }
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        let offset = (address % PAGE_SIZE) as usize;
+        self.page_mut(address / PAGE_SIZE)?[offset..offset + N].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Reads a byte at `address`.
+    pub fn load_u8(&self, address: u64) -> u8 {
+        self.load::<1>(address).unwrap_or_default() as u8
+    }
+
+    /// Reads an aligned halfword at `address`.
+    pub fn load_u16(&self, address: u64) -> EmulatorResult<u16> {
+        self.load::<2>(address).map(|v| v as u16)
+    }
+
+    /// Reads an aligned word at `address`.
+    pub fn load_u32(&self, address: u64) -> EmulatorResult<u32> {
+        self.load::<4>(address).map(|v| v as u32)
+    }
+
+    /// Reads an aligned doubleword at `address`.
+    pub fn load_u64(&self, address: u64) -> EmulatorResult<u64> {
+        self.load::<8>(address)
+    }
+
+    /// Writes a byte at `address`.
+    pub fn store_u8(&mut self, address: u64, value: u8) -> EmulatorResult<()> {
+        self.store::<1>(address, value as u64)
+    }
+
+    /// Writes an aligned halfword at `address`.
+    pub fn store_u16(&mut self, address: u64, value: u16) -> EmulatorResult<()> {
+        self.store::<2>(address, value as u64)
+    }
+
+    /// Writes an aligned word at `address`.
+    pub fn store_u32(&mut self, address: u64, value: u32) -> EmulatorResult<()> {
+        self.store::<4>(address, value as u64)
+    }
+
+    /// Writes an aligned doubleword at `address`.
+    pub fn store_u64(&mut self, address: u64, value: u64) -> EmulatorResult<()> {
+        self.store::<8>(address, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_byte_order() {
+        let mut memory = Memory::new(true);
+        memory.store_u32(0x100, 0x0102_0304).unwrap();
+        assert_eq!(memory.load_u8(0x100), 0x01);
+        assert_eq!(memory.load_u16(0x102).unwrap(), 0x0304);
+
+        let mut memory = Memory::new(false);
+        memory.store_u64(0x100, 0x0102_0304_0506_0708).unwrap();
+        assert_eq!(memory.load_u8(0x100), 0x08);
+        assert_eq!(memory.load_u32(0x104).unwrap(), 0x0102_0304);
+    }
+
+    #[test]
+    fn test_memory_unaligned() {
+        let mut memory = Memory::new(true);
+        assert!(matches!(
+            memory.load_u32(0x101),
+            Err(EmulatorError::UnalignedAccess { address: 0x101, size: 4 })
+        ));
+        assert!(matches!(
+            memory.store_u16(0x101, 0),
+            Err(EmulatorError::UnalignedAccess { address: 0x101, size: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_memory_cross_page() {
+        let mut memory = Memory::new(false);
+        let data = (0..=255).collect::<Vec<u8>>();
+        memory.write(PAGE_SIZE - 100, &data).unwrap();
+        let mut buf = [0u8; 256];
+        memory.read(PAGE_SIZE - 100, &mut buf);
+        assert_eq!(buf.as_slice(), data.as_slice());
+        assert_eq!(memory.allocated(), 2 * PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_memory_peak_and_limit() {
+        let mut memory = Memory::new(false).with_limit(2 * PAGE_SIZE);
+
+        // Reads do not allocate.
+        assert_eq!(memory.load_u64(0x8000).unwrap(), 0);
+        assert_eq!(memory.peak(), 0);
+
+        memory.store_u8(0, 1).unwrap();
+        memory.store_u8(PAGE_SIZE, 1).unwrap();
+        assert_eq!(memory.peak(), 2 * PAGE_SIZE);
+        assert!(matches!(
+            memory.store_u8(2 * PAGE_SIZE, 1),
+            Err(EmulatorError::MemoryLimitExceeded(limit)) if limit == 2 * PAGE_SIZE
+        ));
+
+        // Released pages no longer count towards the limit, but the peak is retained.
+        memory.release(0, PAGE_SIZE);
+        assert_eq!(memory.allocated(), PAGE_SIZE);
+        memory.store_u8(2 * PAGE_SIZE, 1).unwrap();
+        assert_eq!(memory.peak(), 2 * PAGE_SIZE);
+        assert_eq!(memory.load_u8(0), 0);
+    }
+}
@@ -0,0 +1,459 @@
+//! An interpreter for the MIPS32 instruction subset supported by Cannon.
+
+use crate::{
+    emulator::{Cpu, Step},
+    errors::{EmulatorError, EmulatorResult},
+    kernel::Syscall,
+    memory::Memory,
+};
+
+/// The `exit` syscall number.
+const SYS_EXIT: u32 = 4001;
+/// The `read` syscall number.
+const SYS_READ: u32 = 4003;
+/// The `write` syscall number.
+const SYS_WRITE: u32 = 4004;
+/// The `brk` syscall number.
+const SYS_BRK: u32 = 4045;
+/// The `mmap` syscall number.
+const SYS_MMAP: u32 = 4090;
+/// The `munmap` syscall number.
+const SYS_MUNMAP: u32 = 4091;
+/// The `fcntl` syscall number.
+const SYS_FCNTL: u32 = 4055;
+/// The `exit_group` syscall number.
+const SYS_EXIT_GROUP: u32 = 4246;
+
+/// The syscalls that Cannon acknowledges without effect: `close`, `getpid`, `sched_yield`,
+/// `nanosleep`, `rt_sigaction`, `rt_sigprocmask`, `sigaltstack`, `madvise`, `gettid`,
+/// `sched_getaffinity`, `clock_gettime`, `prlimit64` and `getrandom`.
+const IGNORED_SYSCALLS: [u32; 13] =
+    [4006, 4020, 4162, 4166, 4194, 4195, 4206, 4218, 4222, 4240, 4263, 4338, 4353];
+
+/// The state of a MIPS32 processor.
+///
+/// Branches and jumps have a single delay slot, which is modelled by tracking the address of the
+/// instruction after the current one in `next_pc`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Mips32 {
+    /// The general purpose registers.
+    pub(crate) regs: [u32; 32],
+    /// The `hi` multiply/divide register.
+    pub(crate) hi: u32,
+    /// The `lo` multiply/divide register.
+    pub(crate) lo: u32,
+    /// The address of the current instruction.
+    pub(crate) pc: u32,
+    /// The address of the next instruction.
+    pub(crate) next_pc: u32,
+}
+
+impl Mips32 {
+    /// Creates a new [Mips32] processor that starts executing at `entry` with the given stack
+    /// pointer.
+    pub(crate) fn new(entry: u32, sp: u32) -> Self {
+        let mut cpu = Self { pc: entry, next_pc: entry.wrapping_add(4), ..Default::default() };
+        cpu.regs[29] = sp;
+        cpu
+    }
+
+    /// Advances to the next instruction, or to `target` after the delay slot if it is set.
+    fn advance(&mut self, target: Option<u32>) {
+        self.pc = self.next_pc;
+        self.next_pc = target.unwrap_or_else(|| self.next_pc.wrapping_add(4));
+    }
+
+    /// Writes `value` to the register `index`, discarding writes to `$zero`.
+    const fn set(&mut self, index: u32, value: u32) {
+        if index != 0 {
+            self.regs[index as usize] = value;
+        }
+    }
+
+    /// Decodes the syscall requested by the registers.
+    fn syscall(&self) -> Syscall {
+        let [a0, a1, a2] = [self.regs[4], self.regs[5], self.regs[6]].map(u64::from);
+        match self.regs[2] {
+            SYS_READ => Syscall::Read { fd: a0, address: a1, len: a2 },
+            SYS_WRITE => Syscall::Write { fd: a0, address: a1, len: a2 },
+            SYS_EXIT | SYS_EXIT_GROUP => Syscall::Exit(a0),
+            SYS_BRK => Syscall::Brk(a0),
+            SYS_MMAP => Syscall::Mmap { len: a1 },
+            SYS_MUNMAP => Syscall::Munmap { address: a0, len: a1 },
+            SYS_FCNTL => Syscall::Fcntl { fd: a0, cmd: a1 },
+            number if IGNORED_SYSCALLS.contains(&number) => Syscall::Ignored(number as u64),
+            number => Syscall::Unsupported(number as u64),
+        }
+    }
+
+    /// Executes an instruction with the `SPECIAL` opcode.
+    fn special(&mut self, insn: u32) -> EmulatorResult<Step> {
+        let (rs, rt, rd) = ((insn >> 21) & 31, (insn >> 16) & 31, (insn >> 11) & 31);
+        let (a, b) = (self.regs[rs as usize], self.regs[rt as usize]);
+        let shamt = (insn >> 6) & 31;
+        let value = match insn & 63 {
+            0x00 => b << shamt,
+            0x02 => b >> shamt,
+            0x03 => ((b as i32) >> shamt) as u32,
+            0x04 => b << (a & 31),
+            0x06 => b >> (a & 31),
+            0x07 => ((b as i32) >> (a & 31)) as u32,
+            0x08 => {
+                self.advance(Some(a));
+                return Ok(Step::Continue);
+            }
+            0x09 => {
+                self.set(rd, self.pc.wrapping_add(8));
+                self.advance(Some(a));
+                return Ok(Step::Continue);
+            }
+            0x0a => {
+                if b == 0 {
+                    self.set(rd, a);
+                }
+                return self.next();
+            }
+            0x0b => {
+                if b != 0 {
+                    self.set(rd, a);
+                }
+                return self.next();
+            }
+            0x0c => {
+                let syscall = self.syscall();
+                self.advance(None);
+                return Ok(Step::Syscall(syscall));
+            }
+            0x0d => return Err(EmulatorError::Trap(self.pc as u64)),
+            0x0f => return self.next(),
+            0x10 => self.hi,
+            0x11 => {
+                self.hi = a;
+                return self.next();
+            }
+            0x12 => self.lo,
+            0x13 => {
+                self.lo = a;
+                return self.next();
+            }
+            0x18..=0x1b => {
+                (self.hi, self.lo) = match insn & 63 {
+                    0x18 => split((a as i32 as i64).wrapping_mul(b as i32 as i64) as u64),
+                    0x19 => split((a as u64) * (b as u64)),
+                    // The result of a division by zero is unpredictable; leave `hi` and `lo` as is.
+                    _ if b == 0 => (self.hi, self.lo),
+                    0x1a => (
+                        (a as i32).wrapping_rem(b as i32) as u32,
+                        (a as i32).wrapping_div(b as i32) as u32,
+                    ),
+                    _ => (a % b, a / b),
+                };
+                return self.next();
+            }
+            0x20 | 0x21 => a.wrapping_add(b),
+            0x22 | 0x23 => a.wrapping_sub(b),
+            0x24 => a & b,
+            0x25 => a | b,
+            0x26 => a ^ b,
+            0x27 => !(a | b),
+            0x2a => ((a as i32) < (b as i32)) as u32,
+            0x2b => (a < b) as u32,
+            funct @ 0x30..=0x36 => {
+                let trap = match funct {
+                    0x30 => (a as i32) >= (b as i32),
+                    0x31 => a >= b,
+                    0x32 => (a as i32) < (b as i32),
+                    0x33 => a < b,
+                    0x34 => a == b,
+                    0x36 => a != b,
+                    _ => return self.unsupported(insn),
+                };
+                if trap {
+                    return Err(EmulatorError::Trap(self.pc as u64));
+                }
+                return self.next();
+            }
+            _ => return self.unsupported(insn),
+        };
+        self.set(rd, value);
+        self.next()
+    }
+
+    /// Executes an instruction with the `SPECIAL2` opcode.
+    fn special2(&mut self, insn: u32) -> EmulatorResult<Step> {
+        let (rs, rt, rd) = ((insn >> 21) & 31, (insn >> 16) & 31, (insn >> 11) & 31);
+        let (a, b) = (self.regs[rs as usize], self.regs[rt as usize]);
+        let acc = ((self.hi as u64) << 32) | self.lo as u64;
+        match insn & 63 {
+            0x00 => {
+                let product = (a as i32 as i64).wrapping_mul(b as i32 as i64) as u64;
+                (self.hi, self.lo) = split(acc.wrapping_add(product));
+            }
+            0x01 => (self.hi, self.lo) = split(acc.wrapping_add(a as u64 * b as u64)),
+            0x02 => self.set(rd, a.wrapping_mul(b)),
+            0x04 => {
+                let product = (a as i32 as i64).wrapping_mul(b as i32 as i64) as u64;
+                (self.hi, self.lo) = split(acc.wrapping_sub(product));
+            }
+            0x05 => (self.hi, self.lo) = split(acc.wrapping_sub(a as u64 * b as u64)),
+            0x20 => self.set(rd, a.leading_zeros()),
+            0x21 => self.set(rd, a.leading_ones()),
+            _ => return self.unsupported(insn),
+        }
+        self.next()
+    }
+
+    /// Executes a load or store instruction.
+    fn memory(&mut self, insn: u32, memory: &mut Memory) -> EmulatorResult<Step> {
+        let (rs, rt) = ((insn >> 21) & 31, (insn >> 16) & 31);
+        let address = self.regs[rs as usize].wrapping_add(insn as i16 as u32) as u64;
+        let value = self.regs[rt as usize];
+        let aligned = address & !3;
+        let shift = (address as u32 & 3) * 8;
+        match insn >> 26 {
+            0x20 => self.set(rt, memory.load_u8(address) as i8 as u32),
+            0x21 => self.set(rt, memory.load_u16(address)? as i16 as u32),
+            0x22 => {
+                let mask = u32::MAX << shift;
+                self.set(rt, (value & !mask) | (memory.load_u32(aligned)? << shift));
+            }
+            0x23 | 0x30 => self.set(rt, memory.load_u32(address)?),
+            0x24 => self.set(rt, memory.load_u8(address) as u32),
+            0x25 => self.set(rt, memory.load_u16(address)? as u32),
+            0x26 => {
+                let mask = u32::MAX >> (24 - shift);
+                self.set(rt, (value & !mask) | (memory.load_u32(aligned)? >> (24 - shift)));
+            }
+            0x28 => memory.store_u8(address, value as u8)?,
+            0x29 => memory.store_u16(address, value as u16)?,
+            0x2a => {
+                let mask = u32::MAX >> shift;
+                let word = memory.load_u32(aligned)?;
+                memory.store_u32(aligned, (word & !mask) | (value >> shift))?;
+            }
+            0x2b => memory.store_u32(address, value)?,
+            0x2e => {
+                let mask = u32::MAX << (24 - shift);
+                let word = memory.load_u32(aligned)?;
+                memory.store_u32(aligned, (word & !mask) | (value << (24 - shift)))?;
+            }
+            // The emulated program is single-threaded, so store-conditionals always succeed.
+            0x38 => {
+                memory.store_u32(address, value)?;
+                self.set(rt, 1);
+            }
+            _ => return self.unsupported(insn),
+        }
+        self.next()
+    }
+
+    /// Advances past the current instruction.
+    fn next(&mut self) -> EmulatorResult<Step> {
+        self.advance(None);
+        Ok(Step::Continue)
+    }
+
+    /// Returns an [EmulatorError::UnsupportedInstruction] error for `insn`.
+    const fn unsupported(&self, insn: u32) -> EmulatorResult<Step> {
+        Err(EmulatorError::UnsupportedInstruction { pc: self.pc as u64, instruction: insn })
+    }
+}
+
+impl Cpu for Mips32 {
+    fn pc(&self) -> u64 {
+        self.pc as u64
+    }
+
+    fn step(&mut self, memory: &mut Memory) -> EmulatorResult<Step> {
+        let insn = memory.load_u32(self.pc as u64)?;
+        let (rs, rt) = ((insn >> 21) & 31, (insn >> 16) & 31);
+        let (a, b) = (self.regs[rs as usize], self.regs[rt as usize]);
+        let imm = insn as i16 as u32;
+        let branch = self.next_pc.wrapping_add(imm << 2);
+
+        let value = match insn >> 26 {
+            0x00 => return self.special(insn),
+            0x01 => {
+                let taken = match rt {
+                    0x00 | 0x10 => (a as i32) < 0,
+                    0x01 | 0x11 => (a as i32) >= 0,
+                    _ => return self.unsupported(insn),
+                };
+                if rt & 0x10 != 0 {
+                    self.set(31, self.pc.wrapping_add(8));
+                }
+                self.advance(taken.then_some(branch));
+                return Ok(Step::Continue);
+            }
+            op @ (0x02 | 0x03) => {
+                if op == 0x03 {
+                    self.set(31, self.pc.wrapping_add(8));
+                }
+                let target = (self.next_pc & 0xf000_0000) | ((insn & 0x03ff_ffff) << 2);
+                self.advance(Some(target));
+                return Ok(Step::Continue);
+            }
+            op @ 0x04..=0x07 => {
+                let taken = match op {
+                    0x04 => a == b,
+                    0x05 => a != b,
+                    0x06 => (a as i32) <= 0,
+                    _ => (a as i32) > 0,
+                };
+                self.advance(taken.then_some(branch));
+                return Ok(Step::Continue);
+            }
+            0x08 | 0x09 => a.wrapping_add(imm),
+            0x0a => ((a as i32) < (imm as i32)) as u32,
+            0x0b => (a < imm) as u32,
+            0x0c => a & (insn & 0xffff),
+            0x0d => a | (insn & 0xffff),
+            0x0e => a ^ (insn & 0xffff),
+            0x0f => insn << 16,
+            0x1c => return self.special2(insn),
+            // Prefetches are hints, and have no architectural effect.
+            0x33 => return self.next(),
+            0x20..=0x38 => return self.memory(insn, memory),
+            _ => return self.unsupported(insn),
+        };
+        self.set(rt, value);
+        self.next()
+    }
+
+    fn set_syscall_result(&mut self, result: Result<u64, u64>) {
+        let (value, error) = match result {
+            Ok(value) => (value as u32, 0),
+            Err(errno) => (errno as u32, 1),
+        };
+        self.regs[2] = value;
+        self.regs[7] = error;
+    }
+}
+
+/// Splits a 64-bit value into its high and low words.
+const fn split(value: u64) -> (u32, u32) {
+    ((value >> 32) as u32, value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mips::*;
+
+    /// Runs the given instructions until the program counter leaves them.
+    fn run(program: &[u32], setup: impl FnOnce(&mut Mips32, &mut Memory)) -> (Mips32, Memory) {
+        let mut memory = Memory::new(true);
+        for (i, insn) in program.iter().enumerate() {
+            memory.store_u32(0x1000 + 4 * i as u64, *insn).unwrap();
+        }
+        let mut cpu = Mips32::new(0x1000, 0x8000);
+        setup(&mut cpu, &mut memory);
+        while (0x1000..0x1000 + 4 * program.len() as u32).contains(&cpu.pc) {
+            assert_eq!(cpu.step(&mut memory).unwrap(), Step::Continue);
+        }
+        (cpu, memory)
+    }
+
+    #[test]
+    fn test_mips_arithmetic() {
+        let (cpu, _) = run(
+            &[
+                addiu(8, 0, -5),
+                addiu(9, 0, 3),
+                addu(10, 8, 9),
+                slt(11, 8, 9),
+                sltu(12, 8, 9),
+                sra(13, 8, 1),
+                srl(14, 8, 28),
+                lui(15, 0x1234),
+                ori(15, 15, 0xabcd),
+                mult(8, 9),
+                mflo(16),
+                mfhi(17),
+                divu(8, 9),
+                mflo(18),
+                mul(19, 9, 9),
+                clz(20, 9),
+            ],
+            |_, _| {},
+        );
+        assert_eq!(cpu.regs[10], -2i32 as u32);
+        assert_eq!(cpu.regs[11], 1);
+        assert_eq!(cpu.regs[12], 0);
+        assert_eq!(cpu.regs[13], -3i32 as u32);
+        assert_eq!(cpu.regs[14], 0xf);
+        assert_eq!(cpu.regs[15], 0x1234_abcd);
+        assert_eq!(cpu.regs[16], -15i32 as u32);
+        assert_eq!(cpu.regs[17], u32::MAX);
+        assert_eq!(cpu.regs[18], (-5i32 as u32) / 3);
+        assert_eq!(cpu.regs[19], 9);
+        assert_eq!(cpu.regs[20], 30);
+    }
+
+    #[test]
+    fn test_mips_delay_slot() {
+        let (cpu, _) = run(
+            &[
+                // The delay slot of a taken branch is executed.
+                beq(0, 0, 2),
+                addiu(8, 0, 1),
+                addiu(9, 0, 1),
+                // The delay slot of a jump-and-link is executed, and `$ra` skips it.
+                jal(0x1018 >> 2),
+                addiu(10, 0, 1),
+                addiu(11, 0, 1),
+                addiu(12, 0, 1),
+            ],
+            |_, _| {},
+        );
+        assert_eq!(cpu.regs[8], 1);
+        assert_eq!(cpu.regs[9], 0);
+        assert_eq!(cpu.regs[10], 1);
+        assert_eq!(cpu.regs[11], 0);
+        assert_eq!(cpu.regs[12], 1);
+        assert_eq!(cpu.regs[31], 0x1014);
+    }
+
+    #[test]
+    fn test_mips_unaligned_word() {
+        let (cpu, memory) = run(
+            &[
+                // Load the unaligned word at 0x2001 into `$t0`.
+                lwl(8, 5, 0),
+                lwr(8, 5, 3),
+                // Store it back at 0x2011.
+                swl(8, 5, 16),
+                swr(8, 5, 19),
+            ],
+            |cpu, memory| {
+                cpu.regs[5] = 0x2001;
+                memory.store_u32(0x2000, 0x0011_2233).unwrap();
+                memory.store_u32(0x2004, 0x4455_6677).unwrap();
+            },
+        );
+        assert_eq!(cpu.regs[8], 0x1122_3344);
+        assert_eq!(memory.load_u32(0x2010).unwrap(), 0x0011_2233);
+        assert_eq!(memory.load_u32(0x2014).unwrap(), 0x4400_0000);
+    }
+
+    #[test]
+    fn test_mips_unsupported() {
+        let mut memory = Memory::new(true);
+        // `ldc1` is not available with soft-float.
+        memory.store_u32(0x1000, 0xd400_0000).unwrap();
+        let mut cpu = Mips32::new(0x1000, 0x8000);
+        assert!(matches!(
+            cpu.step(&mut memory),
+            Err(EmulatorError::UnsupportedInstruction { pc: 0x1000, instruction: 0xd400_0000 })
+        ));
+    }
+
+    #[test]
+    fn test_mips_syscall_result() {
+        let mut cpu = Mips32::new(0x1000, 0x8000);
+        cpu.set_syscall_result(Ok(4));
+        assert_eq!((cpu.regs[2], cpu.regs[7]), (4, 0));
+        cpu.set_syscall_result(Err(9));
+        assert_eq!((cpu.regs[2], cpu.regs[7]), (9, 1));
+    }
+}
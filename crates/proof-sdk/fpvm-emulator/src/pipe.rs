@@ -0,0 +1,129 @@
+//! A byte-stream [Channel], backed by [async_channel]'s unbounded channel primitives.
+//!
+//! Reads from a [PipeChannel] may consume part of a write, or span several writes, matching the
+//! semantics of the file descriptors that the client program reads from on an FPVM.
+
+use async_channel::{unbounded, Receiver, Sender};
+use async_trait::async_trait;
+use kona_preimage::{
+    errors::{ChannelError, ChannelResult},
+    Channel,
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Creates a connected pair of [PipeChannel]s. Bytes written to one end are read from the other.
+pub fn pipe() -> (PipeChannel, PipeChannel) {
+    let (a_write, b_read) = unbounded();
+    let (b_write, a_read) = unbounded();
+    (
+        PipeChannel { read: a_read, write: a_write, buffer: Default::default() },
+        PipeChannel { read: b_read, write: b_write, buffer: Default::default() },
+    )
+}
+
+/// One end of a bidirectional byte-stream pipe.
+#[derive(Debug, Clone)]
+pub struct PipeChannel {
+    /// The receiver of the pipe.
+    read: Receiver<Vec<u8>>,
+    /// The sender of the pipe.
+    write: Sender<Vec<u8>>,
+    /// The bytes that have been received, but not yet read.
+    buffer: Arc<Mutex<VecDeque<u8>>>,
+}
+
+impl PipeChannel {
+    /// Moves up to `buf.len()` buffered bytes into `buf`, returning the number of bytes moved.
+    fn drain_into(&self, buf: &mut [u8]) -> usize {
+        let mut buffer = self.buffer.lock().expect("pipe buffer poisoned");
+        let len = buffer.len().min(buf.len());
+        buffer.drain(..len).zip(buf.iter_mut()).for_each(|(byte, slot)| *slot = byte);
+        len
+    }
+
+    /// Waits for the next write from the counterparty and appends it to the buffer.
+    async fn fill(&self) -> ChannelResult<()> {
+        let data = self.read.recv().await.map_err(|_| ChannelError::Closed)?;
+        self.buffer.lock().expect("pipe buffer poisoned").extend(data);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for PipeChannel {
+    async fn read(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        loop {
+            let len = self.drain_into(buf);
+            if len > 0 || buf.is_empty() {
+                return Ok(len);
+            }
+            self.fill().await?;
+        }
+    }
+
+    async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            read += self.drain_into(&mut buf[read..]);
+            if read < buf.len() {
+                self.fill().await?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    async fn write(&self, buf: &[u8]) -> ChannelResult<usize> {
+        self.write.send(buf.to_vec()).await.map_err(|_| ChannelError::Closed)?;
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pipe_partial_reads() {
+        let (a, b) = pipe();
+        a.write(&[1, 2, 3, 4]).await.unwrap();
+        a.write(&[5, 6]).await.unwrap();
+
+        let mut buf = [0u8; 3];
+        assert_eq!(b.read(&mut buf).await.unwrap(), 3);
+        assert_eq!(buf, [1, 2, 3]);
+
+        // A read returns the remainder of the buffered bytes before waiting for the next write.
+        assert_eq!(b.read(&mut buf).await.unwrap(), 1);
+        assert_eq!(buf[0], 4);
+        assert_eq!(b.read(&mut buf).await.unwrap(), 2);
+        assert_eq!(buf[..2], [5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_pipe_read_exact_spans_writes() {
+        let (a, b) = pipe();
+        b.write(&[1, 2]).await.unwrap();
+        b.write(&[3]).await.unwrap();
+        b.write(&[4, 5]).await.unwrap();
+
+        let mut buf = [0u8; 4];
+        assert_eq!(a.read_exact(&mut buf).await.unwrap(), 4);
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(a.read(&mut buf).await.unwrap(), 1);
+        assert_eq!(buf[0], 5);
+    }
+
+    #[tokio::test]
+    async fn test_pipe_closed() {
+        let (a, b) = pipe();
+        a.write(&[1]).await.unwrap();
+        drop(a);
+
+        let mut buf = [0u8; 2];
+        assert!(matches!(b.read_exact(&mut buf).await, Err(ChannelError::Closed)));
+        assert!(matches!(b.write(&[1]).await, Err(ChannelError::Closed)));
+    }
+}
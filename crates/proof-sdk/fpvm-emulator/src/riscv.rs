@@ -0,0 +1,687 @@
+//! An interpreter for the RV64IMAC instruction set supported by Asterisc.
+
+use crate::{
+    emulator::{Cpu, Step},
+    errors::{EmulatorError, EmulatorResult},
+    kernel::Syscall,
+    memory::Memory,
+};
+
+/// The `fcntl` syscall number.
+const SYS_FCNTL: u64 = 25;
+/// The `read` syscall number.
+const SYS_READ: u64 = 63;
+/// The `write` syscall number.
+const SYS_WRITE: u64 = 64;
+/// The `exit` syscall number.
+const SYS_EXIT: u64 = 93;
+/// The `exit_group` syscall number.
+const SYS_EXIT_GROUP: u64 = 94;
+/// The `brk` syscall number.
+const SYS_BRK: u64 = 214;
+/// The `munmap` syscall number.
+const SYS_MUNMAP: u64 = 215;
+/// The `mmap` syscall number.
+const SYS_MMAP: u64 = 222;
+
+/// The syscalls that Asterisc acknowledges without effect: `close`, `nanosleep`,
+/// `clock_gettime`, `sched_getaffinity`, `sched_yield`, `sigaltstack`, `rt_sigaction`,
+/// `rt_sigprocmask`, `getpid`, `gettid`, `madvise`, `prlimit64` and `getrandom`.
+const IGNORED_SYSCALLS: [u64; 13] =
+    [57, 101, 113, 123, 124, 132, 134, 135, 172, 178, 233, 261, 278];
+
+/// The `ecall` instruction.
+const ECALL: u32 = 0x0000_0073;
+/// The `ebreak` instruction.
+const EBREAK: u32 = 0x0010_0073;
+
+/// The state of an RV64 processor.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Riscv64 {
+    /// The general purpose registers.
+    pub(crate) regs: [u64; 32],
+    /// The address of the current instruction.
+    pub(crate) pc: u64,
+}
+
+impl Riscv64 {
+    /// Creates a new [Riscv64] processor that starts executing at `entry` with the given stack
+    /// pointer.
+    pub(crate) fn new(entry: u64, sp: u64) -> Self {
+        let mut cpu = Self { pc: entry, ..Default::default() };
+        cpu.regs[2] = sp;
+        cpu
+    }
+
+    /// Writes `value` to the register `index`, discarding writes to `x0`.
+    const fn set(&mut self, index: u32, value: u64) {
+        if index != 0 {
+            self.regs[index as usize] = value;
+        }
+    }
+
+    /// Decodes the syscall requested by the registers.
+    fn syscall(&self) -> Syscall {
+        let [a0, a1, a2] = [self.regs[10], self.regs[11], self.regs[12]];
+        match self.regs[17] {
+            SYS_READ => Syscall::Read { fd: a0, address: a1, len: a2 },
+            SYS_WRITE => Syscall::Write { fd: a0, address: a1, len: a2 },
+            SYS_EXIT | SYS_EXIT_GROUP => Syscall::Exit(a0),
+            SYS_BRK => Syscall::Brk(a0),
+            SYS_MMAP => Syscall::Mmap { len: a1 },
+            SYS_MUNMAP => Syscall::Munmap { address: a0, len: a1 },
+            SYS_FCNTL => Syscall::Fcntl { fd: a0, cmd: a1 },
+            number if IGNORED_SYSCALLS.contains(&number) => Syscall::Ignored(number),
+            number => Syscall::Unsupported(number),
+        }
+    }
+
+    /// Executes the 32-bit instruction `insn`, which is `len` bytes long in memory.
+    fn execute(&mut self, insn: u32, len: u64, memory: &mut Memory) -> EmulatorResult<Step> {
+        let (rd, rs1, rs2) = ((insn >> 7) & 31, (insn >> 15) & 31, (insn >> 20) & 31);
+        let (a, b) = (self.regs[rs1 as usize], self.regs[rs2 as usize]);
+        let funct3 = (insn >> 12) & 7;
+        let funct7 = insn >> 25;
+        let next = self.pc.wrapping_add(len);
+        let pc = self.pc;
+        let unsupported = || Err(EmulatorError::UnsupportedInstruction { pc, instruction: insn });
+
+        let value = match insn & 0x7f {
+            0x37 => imm_u(insn),
+            0x17 => self.pc.wrapping_add(imm_u(insn)),
+            0x6f => {
+                self.set(rd, next);
+                self.pc = self.pc.wrapping_add(imm_j(insn));
+                return Ok(Step::Continue);
+            }
+            0x67 if funct3 == 0 => {
+                let target = a.wrapping_add(imm_i(insn)) & !1;
+                self.set(rd, next);
+                self.pc = target;
+                return Ok(Step::Continue);
+            }
+            0x63 => {
+                let taken = match funct3 {
+                    0 => a == b,
+                    1 => a != b,
+                    4 => (a as i64) < (b as i64),
+                    5 => (a as i64) >= (b as i64),
+                    6 => a < b,
+                    7 => a >= b,
+                    _ => return unsupported(),
+                };
+                self.pc = if taken { self.pc.wrapping_add(imm_b(insn)) } else { next };
+                return Ok(Step::Continue);
+            }
+            0x03 => {
+                let address = a.wrapping_add(imm_i(insn));
+                match funct3 {
+                    0 => memory.load_u8(address) as i8 as u64,
+                    1 => memory.load_u16(address)? as i16 as u64,
+                    2 => memory.load_u32(address)? as i32 as u64,
+                    3 => memory.load_u64(address)?,
+                    4 => memory.load_u8(address) as u64,
+                    5 => memory.load_u16(address)? as u64,
+                    6 => memory.load_u32(address)? as u64,
+                    _ => return unsupported(),
+                }
+            }
+            0x23 => {
+                let address = a.wrapping_add(imm_s(insn));
+                match funct3 {
+                    0 => memory.store_u8(address, b as u8)?,
+                    1 => memory.store_u16(address, b as u16)?,
+                    2 => memory.store_u32(address, b as u32)?,
+                    3 => memory.store_u64(address, b)?,
+                    _ => return unsupported(),
+                }
+                self.pc = next;
+                return Ok(Step::Continue);
+            }
+            0x13 => {
+                let imm = imm_i(insn);
+                let shamt = (insn >> 20) & 63;
+                match (funct3, insn >> 26) {
+                    (0, _) => a.wrapping_add(imm),
+                    (1, 0) => a << shamt,
+                    (2, _) => ((a as i64) < (imm as i64)) as u64,
+                    (3, _) => (a < imm) as u64,
+                    (4, _) => a ^ imm,
+                    (5, 0x00) => a >> shamt,
+                    (5, 0x10) => ((a as i64) >> shamt) as u64,
+                    (6, _) => a | imm,
+                    (7, _) => a & imm,
+                    _ => return unsupported(),
+                }
+            }
+            0x1b => {
+                let shamt = (insn >> 20) & 31;
+                let value = match (funct3, funct7) {
+                    (0, _) => (a as u32).wrapping_add(imm_i(insn) as u32),
+                    (1, 0x00) => (a as u32) << shamt,
+                    (5, 0x00) => (a as u32) >> shamt,
+                    (5, 0x20) => ((a as i32) >> shamt) as u32,
+                    _ => return unsupported(),
+                };
+                value as i32 as u64
+            }
+            0x33 => match (funct7, funct3) {
+                (0x00, 0) => a.wrapping_add(b),
+                (0x20, 0) => a.wrapping_sub(b),
+                (0x00, 1) => a << (b & 63),
+                (0x00, 2) => ((a as i64) < (b as i64)) as u64,
+                (0x00, 3) => (a < b) as u64,
+                (0x00, 4) => a ^ b,
+                (0x00, 5) => a >> (b & 63),
+                (0x20, 5) => ((a as i64) >> (b & 63)) as u64,
+                (0x00, 6) => a | b,
+                (0x00, 7) => a & b,
+                (0x01, 0) => a.wrapping_mul(b),
+                (0x01, 1) => ((a as i64 as i128 * b as i64 as i128) >> 64) as u64,
+                (0x01, 2) => ((a as i64 as i128).wrapping_mul(b as i128) >> 64) as u64,
+                (0x01, 3) => ((a as u128 * b as u128) >> 64) as u64,
+                (0x01, 4) if b == 0 => u64::MAX,
+                (0x01, 4) => (a as i64).wrapping_div(b as i64) as u64,
+                (0x01, 5) => a.checked_div(b).unwrap_or(u64::MAX),
+                (0x01, 6) if b == 0 => a,
+                (0x01, 6) => (a as i64).wrapping_rem(b as i64) as u64,
+                (0x01, 7) => a.checked_rem(b).unwrap_or(a),
+                _ => return unsupported(),
+            },
+            0x3b => {
+                let (a, b) = (a as u32, b as u32);
+                let value = match (funct7, funct3) {
+                    (0x00, 0) => a.wrapping_add(b),
+                    (0x20, 0) => a.wrapping_sub(b),
+                    (0x00, 1) => a << (b & 31),
+                    (0x00, 5) => a >> (b & 31),
+                    (0x20, 5) => ((a as i32) >> (b & 31)) as u32,
+                    (0x01, 0) => a.wrapping_mul(b),
+                    (0x01, 4) if b == 0 => u32::MAX,
+                    (0x01, 4) => (a as i32).wrapping_div(b as i32) as u32,
+                    (0x01, 5) => a.checked_div(b).unwrap_or(u32::MAX),
+                    (0x01, 6) if b == 0 => a,
+                    (0x01, 6) => (a as i32).wrapping_rem(b as i32) as u32,
+                    (0x01, 7) => a.checked_rem(b).unwrap_or(a),
+                    _ => return unsupported(),
+                };
+                value as i32 as u64
+            }
+            0x2f => return self.atomic(insn, memory),
+            // The emulated program is single-threaded, so fences have no effect.
+            0x0f => {
+                self.pc = next;
+                return Ok(Step::Continue);
+            }
+            0x73 if insn == ECALL => {
+                let syscall = self.syscall();
+                self.pc = next;
+                return Ok(Step::Syscall(syscall));
+            }
+            0x73 if insn == EBREAK => return Err(EmulatorError::Trap(self.pc)),
+            _ => return unsupported(),
+        };
+        self.set(rd, value);
+        self.pc = next;
+        Ok(Step::Continue)
+    }
+
+    /// Executes an instruction from the `A` extension.
+    fn atomic(&mut self, insn: u32, memory: &mut Memory) -> EmulatorResult<Step> {
+        let (rd, rs1, rs2) = ((insn >> 7) & 31, (insn >> 15) & 31, (insn >> 20) & 31);
+        let (address, b) = (self.regs[rs1 as usize], self.regs[rs2 as usize]);
+        let word = match (insn >> 12) & 7 {
+            2 => true,
+            3 => false,
+            _ => {
+                return Err(EmulatorError::UnsupportedInstruction { pc: self.pc, instruction: insn })
+            }
+        };
+        let load = |memory: &Memory| -> EmulatorResult<u64> {
+            if word {
+                memory.load_u32(address).map(|v| v as i32 as u64)
+            } else {
+                memory.load_u64(address)
+            }
+        };
+        let store = |memory: &mut Memory, value: u64| {
+            if word {
+                memory.store_u32(address, value as u32)
+            } else {
+                memory.store_u64(address, value)
+            }
+        };
+        // Sign-extends 32-bit operands so that signed comparisons behave for both widths.
+        let b = if word { b as i32 as u64 } else { b };
+
+        let value = match insn >> 27 {
+            // The emulated program is single-threaded, so reservations always hold.
+            0x02 => load(memory)?,
+            0x03 => {
+                store(memory, b)?;
+                0
+            }
+            funct5 => {
+                let old = load(memory)?;
+                let new = match funct5 {
+                    0x00 => old.wrapping_add(b),
+                    0x01 => b,
+                    0x04 => old ^ b,
+                    0x08 => old | b,
+                    0x0c => old & b,
+                    0x10 => (old as i64).min(b as i64) as u64,
+                    0x14 => (old as i64).max(b as i64) as u64,
+                    0x18 if word => (old as u32).min(b as u32) as u64,
+                    0x1c if word => (old as u32).max(b as u32) as u64,
+                    0x18 => old.min(b),
+                    0x1c => old.max(b),
+                    _ => {
+                        return Err(EmulatorError::UnsupportedInstruction {
+                            pc: self.pc,
+                            instruction: insn,
+                        })
+                    }
+                };
+                store(memory, new)?;
+                old
+            }
+        };
+        self.set(rd, value);
+        self.pc = self.pc.wrapping_add(4);
+        Ok(Step::Continue)
+    }
+}
+
+impl Cpu for Riscv64 {
+    fn pc(&self) -> u64 {
+        self.pc
+    }
+
+    fn step(&mut self, memory: &mut Memory) -> EmulatorResult<Step> {
+        let low = memory.load_u16(self.pc)?;
+        if low & 3 != 3 {
+            let insn = expand(low).ok_or(EmulatorError::UnsupportedInstruction {
+                pc: self.pc,
+                instruction: low as u32,
+            })?;
+            return self.execute(insn, 2, memory);
+        }
+        let high = memory.load_u16(self.pc.wrapping_add(2))?;
+        self.execute(((high as u32) << 16) | low as u32, 4, memory)
+    }
+
+    fn set_syscall_result(&mut self, result: Result<u64, u64>) {
+        self.regs[10] = match result {
+            Ok(value) => value,
+            Err(errno) => errno.wrapping_neg(),
+        };
+    }
+}
+
+/// Decodes the sign-extended I-type immediate of `insn`.
+const fn imm_i(insn: u32) -> u64 {
+    (insn as i32 >> 20) as u64
+}
+
+/// Decodes the sign-extended S-type immediate of `insn`.
+const fn imm_s(insn: u32) -> u64 {
+    (((insn as i32 >> 25) << 5) | ((insn >> 7) & 0x1f) as i32) as u64
+}
+
+/// Decodes the sign-extended B-type immediate of `insn`.
+const fn imm_b(insn: u32) -> u64 {
+    let imm = ((insn >> 31) << 12) |
+        (((insn >> 7) & 1) << 11) |
+        (((insn >> 25) & 0x3f) << 5) |
+        (((insn >> 8) & 0xf) << 1);
+    sext(imm, 13) as i32 as u64
+}
+
+/// Decodes the sign-extended U-type immediate of `insn`.
+const fn imm_u(insn: u32) -> u64 {
+    (insn & 0xffff_f000) as i32 as u64
+}
+
+/// Decodes the sign-extended J-type immediate of `insn`.
+const fn imm_j(insn: u32) -> u64 {
+    let imm = ((insn >> 31) << 20) |
+        (((insn >> 12) & 0xff) << 12) |
+        (((insn >> 20) & 1) << 11) |
+        (((insn >> 21) & 0x3ff) << 1);
+    sext(imm, 21) as i32 as u64
+}
+
+/// Sign-extends the low `bits` bits of `value`.
+const fn sext(value: u32, bits: u32) -> u32 {
+    ((value << (32 - bits)) as i32 >> (32 - bits)) as u32
+}
+
+/// Encodes an R-type instruction.
+pub(crate) const fn r_type(op: u32, rd: u32, funct3: u32, rs1: u32, rs2: u32, funct7: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | op
+}
+
+/// Encodes an I-type instruction.
+pub(crate) const fn i_type(op: u32, rd: u32, funct3: u32, rs1: u32, imm: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | op
+}
+
+/// Encodes an S-type instruction.
+pub(crate) const fn s_type(op: u32, funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (((imm >> 5) & 0x7f) << 25) |
+        (rs2 << 20) |
+        (rs1 << 15) |
+        (funct3 << 12) |
+        ((imm & 0x1f) << 7) |
+        op
+}
+
+/// Encodes a B-type instruction.
+pub(crate) const fn b_type(op: u32, funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (((imm >> 12) & 1) << 31) |
+        (((imm >> 5) & 0x3f) << 25) |
+        (rs2 << 20) |
+        (rs1 << 15) |
+        (funct3 << 12) |
+        (((imm >> 1) & 0xf) << 8) |
+        (((imm >> 11) & 1) << 7) |
+        op
+}
+
+/// Encodes a U-type instruction.
+pub(crate) const fn u_type(op: u32, rd: u32, imm: u32) -> u32 {
+    (imm & 0xffff_f000) | (rd << 7) | op
+}
+
+/// Encodes a J-type instruction.
+pub(crate) const fn j_type(op: u32, rd: u32, imm: u32) -> u32 {
+    (((imm >> 20) & 1) << 31) |
+        (((imm >> 1) & 0x3ff) << 21) |
+        (((imm >> 11) & 1) << 20) |
+        (((imm >> 12) & 0xff) << 12) |
+        (rd << 7) |
+        op
+}
+
+/// Expands a 16-bit instruction from the `C` extension into its 32-bit equivalent. Returns
+/// [None] for reserved encodings and for the floating point loads and stores.
+pub(crate) fn expand(insn: u16) -> Option<u32> {
+    let c = insn as u32;
+    let bits = |hi: u32, lo: u32| (c >> lo) & ((1 << (hi - lo + 1)) - 1);
+    let rd = bits(11, 7);
+    let rs2 = bits(6, 2);
+    let rd_prime = 8 + bits(4, 2);
+    let rs1_prime = 8 + bits(9, 7);
+    let imm6 = sext((bits(12, 12) << 5) | bits(6, 2), 6);
+    let shamt = (bits(12, 12) << 5) | bits(6, 2);
+
+    let expanded = match (c & 3, bits(15, 13)) {
+        // c.addi4spn
+        (0, 0) => {
+            let imm =
+                (bits(12, 11) << 4) | (bits(10, 7) << 6) | (bits(6, 6) << 2) | (bits(5, 5) << 3);
+            if imm == 0 {
+                return None;
+            }
+            i_type(0x13, rd_prime, 0, 2, imm)
+        }
+        // c.lw
+        (0, 2) => {
+            let imm = (bits(12, 10) << 3) | (bits(6, 6) << 2) | (bits(5, 5) << 6);
+            i_type(0x03, rd_prime, 2, rs1_prime, imm)
+        }
+        // c.ld
+        (0, 3) => i_type(0x03, rd_prime, 3, rs1_prime, (bits(12, 10) << 3) | (bits(6, 5) << 6)),
+        // c.sw
+        (0, 6) => {
+            let imm = (bits(12, 10) << 3) | (bits(6, 6) << 2) | (bits(5, 5) << 6);
+            s_type(0x23, 2, rs1_prime, rd_prime, imm)
+        }
+        // c.sd
+        (0, 7) => s_type(0x23, 3, rs1_prime, rd_prime, (bits(12, 10) << 3) | (bits(6, 5) << 6)),
+        // c.addi
+        (1, 0) => i_type(0x13, rd, 0, rd, imm6),
+        // c.addiw
+        (1, 1) if rd != 0 => i_type(0x1b, rd, 0, rd, imm6),
+        // c.li
+        (1, 2) => i_type(0x13, rd, 0, 0, imm6),
+        // c.addi16sp
+        (1, 3) if rd == 2 => {
+            let imm = (bits(12, 12) << 9) |
+                (bits(6, 6) << 4) |
+                (bits(5, 5) << 6) |
+                (bits(4, 3) << 7) |
+                (bits(2, 2) << 5);
+            if imm == 0 {
+                return None;
+            }
+            i_type(0x13, 2, 0, 2, sext(imm, 10))
+        }
+        // c.lui
+        (1, 3) if imm6 != 0 => u_type(0x37, rd, imm6 << 12),
+        (1, 4) => match bits(11, 10) {
+            // c.srli
+            0 => i_type(0x13, rs1_prime, 5, rs1_prime, shamt),
+            // c.srai
+            1 => i_type(0x13, rs1_prime, 5, rs1_prime, 0x400 | shamt),
+            // c.andi
+            2 => i_type(0x13, rs1_prime, 7, rs1_prime, imm6),
+            // c.sub, c.xor, c.or, c.and, c.subw, c.addw
+            _ => {
+                let (op, funct3, funct7) = match (bits(12, 12), bits(6, 5)) {
+                    (0, 0) => (0x33, 0, 0x20),
+                    (0, 1) => (0x33, 4, 0),
+                    (0, 2) => (0x33, 6, 0),
+                    (0, 3) => (0x33, 7, 0),
+                    (1, 0) => (0x3b, 0, 0x20),
+                    (1, 1) => (0x3b, 0, 0),
+                    _ => return None,
+                };
+                r_type(op, rs1_prime, funct3, rs1_prime, rd_prime, funct7)
+            }
+        },
+        // c.j
+        (1, 5) => {
+            let imm = (bits(12, 12) << 11) |
+                (bits(11, 11) << 4) |
+                (bits(10, 9) << 8) |
+                (bits(8, 8) << 10) |
+                (bits(7, 7) << 6) |
+                (bits(6, 6) << 7) |
+                (bits(5, 3) << 1) |
+                (bits(2, 2) << 5);
+            j_type(0x6f, 0, sext(imm, 12))
+        }
+        // c.beqz, c.bnez
+        (1, funct3 @ (6 | 7)) => {
+            let imm = (bits(12, 12) << 8) |
+                (bits(11, 10) << 3) |
+                (bits(6, 5) << 6) |
+                (bits(4, 3) << 1) |
+                (bits(2, 2) << 5);
+            b_type(0x63, funct3 - 6, rs1_prime, 0, sext(imm, 9))
+        }
+        // c.slli
+        (2, 0) => i_type(0x13, rd, 1, rd, shamt),
+        // c.lwsp
+        (2, 2) if rd != 0 => {
+            let imm = (bits(12, 12) << 5) | (bits(6, 4) << 2) | (bits(3, 2) << 6);
+            i_type(0x03, rd, 2, 2, imm)
+        }
+        // c.ldsp
+        (2, 3) if rd != 0 => {
+            let imm = (bits(12, 12) << 5) | (bits(6, 5) << 3) | (bits(4, 2) << 6);
+            i_type(0x03, rd, 3, 2, imm)
+        }
+        (2, 4) => match (bits(12, 12), rd, rs2) {
+            (0, 0, _) => return None,
+            // c.jr
+            (0, _, 0) => i_type(0x67, 0, 0, rd, 0),
+            // c.mv
+            (0, _, _) => r_type(0x33, rd, 0, 0, rs2, 0),
+            // c.ebreak
+            (_, 0, 0) => EBREAK,
+            // c.jalr
+            (_, _, 0) => i_type(0x67, 1, 0, rd, 0),
+            // c.add
+            _ => r_type(0x33, rd, 0, rd, rs2, 0),
+        },
+        // c.swsp
+        (2, 6) => s_type(0x23, 2, 2, rs2, (bits(12, 9) << 2) | (bits(8, 7) << 6)),
+        // c.sdsp
+        (2, 7) => s_type(0x23, 3, 2, rs2, (bits(12, 10) << 3) | (bits(9, 7) << 6)),
+        _ => return None,
+    };
+    Some(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::riscv::*;
+
+    /// Runs the given instructions until the program counter leaves them.
+    fn run(program: &[u32], setup: impl FnOnce(&mut Riscv64, &mut Memory)) -> (Riscv64, Memory) {
+        let mut memory = Memory::new(false);
+        for (i, insn) in program.iter().enumerate() {
+            memory.store_u32(0x1000 + 4 * i as u64, *insn).unwrap();
+        }
+        let mut cpu = Riscv64::new(0x1000, 0x8000);
+        setup(&mut cpu, &mut memory);
+        while (0x1000..0x1000 + 4 * program.len() as u64).contains(&cpu.pc) {
+            assert_eq!(cpu.step(&mut memory).unwrap(), Step::Continue);
+        }
+        (cpu, memory)
+    }
+
+    #[test]
+    fn test_riscv_arithmetic() {
+        let (cpu, _) = run(
+            &[
+                addi(5, 0, -5),
+                addi(6, 0, 3),
+                add(7, 5, 6),
+                slt(28, 5, 6),
+                sltu(29, 5, 6),
+                srai(30, 5, 1),
+                lui(31, 0x12345),
+                addiw(31, 31, 0x678),
+                mul(8, 5, 6),
+                div(9, 5, 6),
+                rem(18, 5, 6),
+                divu(19, 6, 0),
+                rem(20, 5, 0),
+                addw(21, 5, 0),
+            ],
+            |_, _| {},
+        );
+        assert_eq!(cpu.regs[7], -2i64 as u64);
+        assert_eq!(cpu.regs[28], 1);
+        assert_eq!(cpu.regs[29], 0);
+        assert_eq!(cpu.regs[30], -3i64 as u64);
+        assert_eq!(cpu.regs[31], 0x1234_5678);
+        assert_eq!(cpu.regs[8], -15i64 as u64);
+        assert_eq!(cpu.regs[9], -1i64 as u64);
+        assert_eq!(cpu.regs[18], -2i64 as u64);
+        // Division by zero returns all ones, and the remainder is the dividend.
+        assert_eq!(cpu.regs[19], u64::MAX);
+        assert_eq!(cpu.regs[20], -5i64 as u64);
+        assert_eq!(cpu.regs[21], -5i64 as u64);
+    }
+
+    #[test]
+    fn test_riscv_memory_and_atomics() {
+        let (cpu, memory) = run(
+            &[
+                addi(5, 0, 0x100),
+                addi(6, 0, -1),
+                sd(5, 6, 0),
+                lw(7, 5, 0),
+                lwu(28, 5, 4),
+                amoadd_w(29, 6, 5),
+                lr_d(30, 5),
+                sc_d(31, 0, 5),
+            ],
+            |_, _| {},
+        );
+        assert_eq!(cpu.regs[7], u64::MAX);
+        assert_eq!(cpu.regs[28], u32::MAX as u64);
+        // The word at 0x100 was all ones, and had all ones added to it.
+        assert_eq!(cpu.regs[29], u64::MAX);
+        assert_eq!(cpu.regs[30], 0xffff_ffff_ffff_fffe);
+        assert_eq!(cpu.regs[31], 0);
+        assert_eq!(memory.load_u64(0x100).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_riscv_jumps_and_branches() {
+        let (cpu, _) = run(
+            &[addi(5, 0, 1), bne(5, 0, 8), addi(6, 0, 1), jal(1, 8), addi(7, 0, 1), addi(28, 0, 1)],
+            |_, _| {},
+        );
+        assert_eq!(cpu.regs[6], 0);
+        assert_eq!(cpu.regs[7], 0);
+        assert_eq!(cpu.regs[28], 1);
+        assert_eq!(cpu.regs[1], 0x1010);
+    }
+
+    #[test]
+    fn test_riscv_compressed() {
+        let mut memory = Memory::new(false);
+        // c.li a0, 5; c.addi a0, -2; c.mv a1, a0; c.slli a1, 2; c.add a1, a0
+        for (i, insn) in [0x4515u16, 0x1579, 0x85aa, 0x058a, 0x95aa].iter().enumerate() {
+            memory.store_u16(0x1000 + 2 * i as u64, *insn).unwrap();
+        }
+        let mut cpu = Riscv64::new(0x1000, 0x8000);
+        for _ in 0..5 {
+            assert_eq!(cpu.step(&mut memory).unwrap(), Step::Continue);
+        }
+        assert_eq!(cpu.pc, 0x100a);
+        assert_eq!(cpu.regs[10], 3);
+        assert_eq!(cpu.regs[11], 15);
+    }
+
+    #[test]
+    fn test_riscv_expand() {
+        // c.addi4spn a0, sp, 16
+        assert_eq!(expand(0x0808), Some(addi(10, 2, 16)));
+        // c.ldsp ra, 8(sp)
+        assert_eq!(expand(0x60a2), Some(ld(1, 2, 8)));
+        // c.sdsp ra, 8(sp)
+        assert_eq!(expand(0xe406), Some(sd(2, 1, 8)));
+        // c.addi sp, -32
+        assert_eq!(expand(0x1101), Some(addi(2, 2, -32)));
+        // c.addi16sp sp, -32
+        assert_eq!(expand(0x713d), Some(addi(2, 2, -32)));
+        // c.jr ra
+        assert_eq!(expand(0x8082), Some(jalr(0, 1, 0)));
+        // c.beqz a0, 8
+        assert_eq!(expand(0xc501), Some(beq(10, 0, 8)));
+        // c.j -4
+        assert_eq!(expand(0xbff5), Some(jal(0, -4)));
+        // The all-zero instruction is illegal, and c.fld is not supported.
+        assert_eq!(expand(0x0000), None);
+        assert_eq!(expand(0x2000 | 0x0408), None);
+    }
+
+    #[test]
+    fn test_riscv_unsupported() {
+        let mut memory = Memory::new(false);
+        // fadd.d fa0, fa0, fa1
+        memory.store_u32(0x1000, 0x02b5_7553).unwrap();
+        let mut cpu = Riscv64::new(0x1000, 0x8000);
+        assert!(matches!(
+            cpu.step(&mut memory),
+            Err(EmulatorError::UnsupportedInstruction { pc: 0x1000, instruction: 0x02b5_7553 })
+        ));
+    }
+
+    #[test]
+    fn test_riscv_syscall_result() {
+        let mut cpu = Riscv64::new(0x1000, 0x8000);
+        cpu.set_syscall_result(Ok(4));
+        assert_eq!(cpu.regs[10], 4);
+        cpu.set_syscall_result(Err(9));
+        assert_eq!(cpu.regs[10], -9i64 as u64);
+    }
+}
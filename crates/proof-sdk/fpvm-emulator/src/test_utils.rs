@@ -0,0 +1,269 @@
+//! Test utilities for assembling client programs.
+
+use crate::elf::Arch;
+
+/// Builds a statically linked ELF executable for `arch`, with one loadable segment per
+/// `(vaddr, data, mem_size)` tuple.
+pub(crate) fn build_elf(arch: Arch, entry: u64, segments: &[(u64, &[u8], u64)]) -> Vec<u8> {
+    let wide = arch == Arch::RiscV64;
+    let mut elf = Vec::new();
+    let put = |elf: &mut Vec<u8>, value: u64, size: usize| {
+        let bytes = value.to_be_bytes();
+        let mut field = bytes[8 - size..].to_vec();
+        if wide {
+            field.reverse();
+        }
+        elf.extend_from_slice(&field);
+    };
+    let word = if wide { 8 } else { 4 };
+    let (header_size, entry_size) = if wide { (64, 56) } else { (52, 32) };
+
+    // Identification.
+    elf.extend_from_slice(&[0x7f, b'E', b'L', b'F', if wide { 2 } else { 1 }]);
+    elf.extend_from_slice(&[if wide { 1 } else { 2 }, 1]);
+    elf.resize(16, 0);
+
+    // ELF header.
+    put(&mut elf, 2, 2);
+    put(&mut elf, if wide { 243 } else { 8 }, 2);
+    put(&mut elf, 1, 4);
+    put(&mut elf, entry, word);
+    put(&mut elf, header_size, word);
+    put(&mut elf, 0, word);
+    put(&mut elf, 0, 4);
+    put(&mut elf, header_size, 2);
+    put(&mut elf, entry_size, 2);
+    put(&mut elf, segments.len() as u64, 2);
+    put(&mut elf, 0, 6);
+
+    // Program headers.
+    let mut offset = header_size + entry_size * segments.len() as u64;
+    for (vaddr, data, mem_size) in segments {
+        put(&mut elf, 1, 4);
+        if wide {
+            put(&mut elf, 7, 4);
+        }
+        put(&mut elf, offset, word);
+        put(&mut elf, *vaddr, word);
+        put(&mut elf, *vaddr, word);
+        put(&mut elf, data.len() as u64, word);
+        put(&mut elf, *mem_size, word);
+        if !wide {
+            put(&mut elf, 7, 4);
+        }
+        put(&mut elf, 0x1000, word);
+        offset += data.len() as u64;
+    }
+
+    // Segment contents.
+    segments.iter().for_each(|(_, data, _)| elf.extend_from_slice(data));
+    elf
+}
+
+/// Assemblers for MIPS32 instructions.
+pub(crate) mod mips {
+    const fn r(rs: u32, rt: u32, rd: u32, shamt: u32, funct: u32) -> u32 {
+        (rs << 21) | (rt << 16) | (rd << 11) | (shamt << 6) | funct
+    }
+
+    const fn i(op: u32, rs: u32, rt: u32, imm: i32) -> u32 {
+        (op << 26) | (rs << 21) | (rt << 16) | (imm as u32 & 0xffff)
+    }
+
+    pub(crate) const fn addiu(rt: u32, rs: u32, imm: i32) -> u32 {
+        i(0x09, rs, rt, imm)
+    }
+
+    pub(crate) const fn ori(rt: u32, rs: u32, imm: i32) -> u32 {
+        i(0x0d, rs, rt, imm)
+    }
+
+    pub(crate) const fn lui(rt: u32, imm: i32) -> u32 {
+        i(0x0f, 0, rt, imm)
+    }
+
+    pub(crate) const fn addu(rd: u32, rs: u32, rt: u32) -> u32 {
+        r(rs, rt, rd, 0, 0x21)
+    }
+
+    pub(crate) const fn or(rd: u32, rs: u32, rt: u32) -> u32 {
+        r(rs, rt, rd, 0, 0x25)
+    }
+
+    pub(crate) const fn slt(rd: u32, rs: u32, rt: u32) -> u32 {
+        r(rs, rt, rd, 0, 0x2a)
+    }
+
+    pub(crate) const fn sltu(rd: u32, rs: u32, rt: u32) -> u32 {
+        r(rs, rt, rd, 0, 0x2b)
+    }
+
+    pub(crate) const fn srl(rd: u32, rt: u32, shamt: u32) -> u32 {
+        r(0, rt, rd, shamt, 0x02)
+    }
+
+    pub(crate) const fn sra(rd: u32, rt: u32, shamt: u32) -> u32 {
+        r(0, rt, rd, shamt, 0x03)
+    }
+
+    pub(crate) const fn mult(rs: u32, rt: u32) -> u32 {
+        r(rs, rt, 0, 0, 0x18)
+    }
+
+    pub(crate) const fn divu(rs: u32, rt: u32) -> u32 {
+        r(rs, rt, 0, 0, 0x1b)
+    }
+
+    pub(crate) const fn mfhi(rd: u32) -> u32 {
+        r(0, 0, rd, 0, 0x10)
+    }
+
+    pub(crate) const fn mflo(rd: u32) -> u32 {
+        r(0, 0, rd, 0, 0x12)
+    }
+
+    pub(crate) const fn mul(rd: u32, rs: u32, rt: u32) -> u32 {
+        (0x1c << 26) | r(rs, rt, rd, 0, 0x02)
+    }
+
+    pub(crate) const fn clz(rd: u32, rs: u32) -> u32 {
+        (0x1c << 26) | r(rs, rd, rd, 0, 0x20)
+    }
+
+    pub(crate) const fn syscall() -> u32 {
+        r(0, 0, 0, 0, 0x0c)
+    }
+
+    pub(crate) const fn beq(rs: u32, rt: u32, offset: i32) -> u32 {
+        i(0x04, rs, rt, offset)
+    }
+
+    pub(crate) const fn jal(target: u32) -> u32 {
+        (0x03 << 26) | target
+    }
+
+    pub(crate) const fn sw(rt: u32, base: u32, offset: i32) -> u32 {
+        i(0x2b, base, rt, offset)
+    }
+
+    pub(crate) const fn lwl(rt: u32, base: u32, offset: i32) -> u32 {
+        i(0x22, base, rt, offset)
+    }
+
+    pub(crate) const fn lwr(rt: u32, base: u32, offset: i32) -> u32 {
+        i(0x26, base, rt, offset)
+    }
+
+    pub(crate) const fn swl(rt: u32, base: u32, offset: i32) -> u32 {
+        i(0x2a, base, rt, offset)
+    }
+
+    pub(crate) const fn swr(rt: u32, base: u32, offset: i32) -> u32 {
+        i(0x2e, base, rt, offset)
+    }
+}
+
+/// Assemblers for RV64 instructions.
+pub(crate) mod riscv {
+    use crate::riscv::{b_type, i_type, j_type, r_type, s_type, u_type};
+
+    pub(crate) const fn addi(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x13, rd, 0, rs1, imm as u32)
+    }
+
+    pub(crate) const fn addiw(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x1b, rd, 0, rs1, imm as u32)
+    }
+
+    pub(crate) const fn srai(rd: u32, rs1: u32, shamt: u32) -> u32 {
+        i_type(0x13, rd, 5, rs1, 0x400 | shamt)
+    }
+
+    pub(crate) const fn lui(rd: u32, imm: u32) -> u32 {
+        u_type(0x37, rd, imm << 12)
+    }
+
+    pub(crate) const fn add(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 0, rs1, rs2, 0)
+    }
+
+    pub(crate) const fn sub(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 0, rs1, rs2, 0x20)
+    }
+
+    pub(crate) const fn addw(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x3b, rd, 0, rs1, rs2, 0)
+    }
+
+    pub(crate) const fn slt(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 2, rs1, rs2, 0)
+    }
+
+    pub(crate) const fn sltu(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 3, rs1, rs2, 0)
+    }
+
+    pub(crate) const fn mul(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 0, rs1, rs2, 1)
+    }
+
+    pub(crate) const fn div(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 4, rs1, rs2, 1)
+    }
+
+    pub(crate) const fn divu(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 5, rs1, rs2, 1)
+    }
+
+    pub(crate) const fn rem(rd: u32, rs1: u32, rs2: u32) -> u32 {
+        r_type(0x33, rd, 6, rs1, rs2, 1)
+    }
+
+    pub(crate) const fn lw(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x03, rd, 2, rs1, imm as u32)
+    }
+
+    pub(crate) const fn lwu(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x03, rd, 6, rs1, imm as u32)
+    }
+
+    pub(crate) const fn ld(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x03, rd, 3, rs1, imm as u32)
+    }
+
+    pub(crate) const fn sd(rs1: u32, rs2: u32, imm: i32) -> u32 {
+        s_type(0x23, 3, rs1, rs2, imm as u32)
+    }
+
+    pub(crate) const fn amoadd_w(rd: u32, rs2: u32, rs1: u32) -> u32 {
+        r_type(0x2f, rd, 2, rs1, rs2, 0x00)
+    }
+
+    pub(crate) const fn lr_d(rd: u32, rs1: u32) -> u32 {
+        r_type(0x2f, rd, 3, rs1, 0, 0x02 << 2)
+    }
+
+    pub(crate) const fn sc_d(rd: u32, rs2: u32, rs1: u32) -> u32 {
+        r_type(0x2f, rd, 3, rs1, rs2, 0x03 << 2)
+    }
+
+    pub(crate) const fn beq(rs1: u32, rs2: u32, imm: i32) -> u32 {
+        b_type(0x63, 0, rs1, rs2, imm as u32)
+    }
+
+    pub(crate) const fn bne(rs1: u32, rs2: u32, imm: i32) -> u32 {
+        b_type(0x63, 1, rs1, rs2, imm as u32)
+    }
+
+    pub(crate) const fn jal(rd: u32, imm: i32) -> u32 {
+        j_type(0x6f, rd, imm as u32)
+    }
+
+    pub(crate) const fn jalr(rd: u32, rs1: u32, imm: i32) -> u32 {
+        i_type(0x67, rd, 0, rs1, imm as u32)
+    }
+
+    pub(crate) const fn ecall() -> u32 {
+        0x73
+    }
+}
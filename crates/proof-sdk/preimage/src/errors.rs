@@ -22,6 +22,9 @@ pub enum PreimageOracleError {
     /// Buffer length mismatch.
     #[error("Buffer length mismatch. Expected {0}, got {1}.")]
     BufferLengthMismatch(usize, usize),
+    /// The buffer for chunked reads is empty.
+    #[error("Chunk buffer is empty.")]
+    EmptyChunkBuffer,
     /// Other errors.
     #[error("Error in preimage server: {0}")]
     Other(String),
@@ -41,6 +44,9 @@ pub enum ChannelError {
     /// Unexpected EOF.
     #[error("Unexpected EOF in channel read operation.")]
     UnexpectedEOF,
+    /// The state shared with the peer is inconsistent.
+    #[error("Channel state is corrupted.")]
+    Corrupted,
 }
 
 /// A [Result] type for the [ChannelError] enum.
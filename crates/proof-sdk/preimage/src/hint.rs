@@ -3,20 +3,85 @@ use crate::{
     traits::{HintRouter, HintWriterClient},
     Channel, HintReaderServer,
 };
-use alloc::{boxed::Box, format, string::String, vec};
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 use async_trait::async_trait;
 
+/// The length prefix that marks a batched hint frame. A batched frame is laid out as
+/// `u32::MAX ++ body_len (u32 BE) ++ body`, where the body is the concatenation of
+/// `len (u32 BE) ++ hint` for each hint in the batch. The frame is acknowledged once.
+///
+/// The hint routers of Fault Proof VMs, and hosts that predate batching, read the prefix as the
+/// length of a single hint, so a [HintWriter] only writes batched frames after the host has
+/// acknowledged the [HINT_BATCHING_PROBE] with a supported [HINT_BATCHING_VERSION].
+pub const HINT_BATCH_PREFIX: u32 = u32::MAX;
+
+/// The hint that a [HintWriter] sends as a single hint frame to probe the host for support of
+/// batched hint frames. A [HintReader] does not route the probe, and acknowledges it with
+/// [HINT_BATCHING_VERSION] instead of zero.
+///
+/// Hosts that predate batching route the probe as an ordinary hint, which no preimage depends on,
+/// and acknowledge it with zero. Fault Proof VMs forward the probe to the host, but acknowledge
+/// hints themselves without writing the acknowledgement byte, which reads as zero. In both cases,
+/// the writer keeps writing single hint frames.
+pub const HINT_BATCHING_PROBE: &str = "hint-batching-probe";
+
+/// The version of the batched hint frame format, acknowledged by a [HintReader] in response to
+/// the [HINT_BATCHING_PROBE].
+pub const HINT_BATCHING_VERSION: u8 = 1;
+
 /// A [HintWriter] is a high-level interface to the hint channel. It provides a way to write hints
 /// to the host.
 #[derive(Debug, Clone, Copy)]
 pub struct HintWriter<C> {
     channel: C,
+    batching: bool,
 }
 
 impl<C> HintWriter<C> {
-    /// Create a new [HintWriter] from a [Channel].
+    /// Create a new [HintWriter] from a [Channel]. Batches of hints are written one hint at a
+    /// time, unless the host accepts batched frames in [HintWriter::negotiate_batching].
     pub const fn new(channel: C) -> Self {
-        Self { channel }
+        Self { channel, batching: false }
+    }
+
+    /// Returns whether batches of hints are written as a single batched frame.
+    pub const fn batching(&self) -> bool {
+        self.batching
+    }
+}
+
+impl<C> HintWriter<C>
+where
+    C: Channel,
+{
+    /// Probes the host for support of batched hint frames with the [HINT_BATCHING_PROBE], and
+    /// returns the [HintWriter] with batching enabled if the host acknowledges a supported
+    /// [HINT_BATCHING_VERSION]. Otherwise, batches of hints are written one hint at a time.
+    pub async fn negotiate_batching(mut self) -> PreimageOracleResult<Self> {
+        self.write_frame(HINT_BATCHING_PROBE).await?;
+        let version = self.read_ack().await?;
+        self.batching = version >= HINT_BATCHING_VERSION;
+
+        debug!(target: "hint_writer", "Host acknowledged hint batching version {version}");
+        Ok(self)
+    }
+
+    /// Writes a single length-prefixed hint frame, without waiting for the acknowledgement.
+    async fn write_frame(&self, hint: &str) -> PreimageOracleResult<()> {
+        // Form the hint into a byte buffer. The format is a 4-byte big-endian length prefix
+        // followed by the hint string.
+        self.channel.write(u32::to_be_bytes(hint.len() as u32).as_ref()).await?;
+        self.channel.write(hint.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Reads the acknowledgement byte for the last frame from the host.
+    async fn read_ack(&self) -> PreimageOracleResult<u8> {
+        let mut hint_ack = [0u8; 1];
+        self.channel.read_exact(&mut hint_ack).await?;
+
+        trace!(target: "hint_writer", "Received hint acknowledgement");
+        Ok(hint_ack[0])
     }
 }
 
@@ -30,18 +95,42 @@ where
     async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
         trace!(target: "hint_writer", "Writing hint \"{hint}\"");
 
-        // Form the hint into a byte buffer. The format is a 4-byte big-endian length prefix
-        // followed by the hint string.
-        self.channel.write(u32::to_be_bytes(hint.len() as u32).as_ref()).await?;
-        self.channel.write(hint.as_bytes()).await?;
+        self.write_frame(hint).await?;
 
         trace!(target: "hint_writer", "Successfully wrote hint");
 
         // Read the hint acknowledgement from the host.
-        let mut hint_ack = [0u8; 1];
-        self.channel.read_exact(&mut hint_ack).await?;
+        self.read_ack().await?;
 
-        trace!(target: "hint_writer", "Received hint acknowledgement");
+        Ok(())
+    }
+
+    /// Write a batch of hints to the host. If batching is enabled, the hints are sent in a single
+    /// frame and acknowledged once. Otherwise, they are written one at a time.
+    async fn write_batch(&self, hints: &[String]) -> PreimageOracleResult<()> {
+        if !self.batching || hints.len() < 2 {
+            for hint in hints {
+                self.write(hint).await?;
+            }
+            return Ok(());
+        }
+
+        trace!(target: "hint_writer", "Writing batch of {} hints", hints.len());
+
+        // Form the hints into a single body, so that the frame takes a fixed number of writes.
+        let mut body = Vec::with_capacity(hints.iter().map(|hint| 4 + hint.len()).sum());
+        for hint in hints {
+            body.extend_from_slice(&(hint.len() as u32).to_be_bytes());
+            body.extend_from_slice(hint.as_bytes());
+        }
+        self.channel.write(HINT_BATCH_PREFIX.to_be_bytes().as_ref()).await?;
+        self.channel.write(u32::to_be_bytes(body.len() as u32).as_ref()).await?;
+        self.channel.write(&body).await?;
+
+        trace!(target: "hint_writer", "Successfully wrote hint batch");
+
+        // Read the acknowledgement for the whole batch from the host.
+        self.read_ack().await?;
 
         Ok(())
     }
@@ -58,7 +147,8 @@ impl<C> HintReader<C>
 where
     C: Channel,
 {
-    /// Create a new [HintReader] from a [Channel].
+    /// Create a new [HintReader] from a [Channel]. The reader accepts both single and batched
+    /// hint frames, and answers the [HINT_BATCHING_PROBE].
     pub const fn new(channel: C) -> Self {
         Self { channel }
     }
@@ -74,34 +164,59 @@ where
         R: HintRouter + Send + Sync,
     {
         // Read the length of the raw hint payload.
-        let mut len_buf = [0u8; 4];
-        self.channel.read_exact(&mut len_buf).await?;
-        let len = u32::from_be_bytes(len_buf);
-
-        // Read the raw hint payload.
-        let mut raw_payload = vec![0u8; len as usize];
-        self.channel.read_exact(raw_payload.as_mut_slice()).await?;
-        let payload = match String::from_utf8(raw_payload) {
-            Ok(p) => p,
-            Err(e) => {
-                // Write back on error to prevent blocking the client.
-                self.channel.write(&[0x00]).await?;
+        let len = self.read_u32().await?;
+
+        // A batched frame carries a number of hints, which are routed in order and acknowledged
+        // once.
+        let hints = if len == HINT_BATCH_PREFIX {
+            let body_len = self.read_u32().await?;
+            let mut body = vec![0u8; body_len as usize];
+            self.channel.read_exact(&mut body).await?;
+
+            match decode_batch(&body) {
+                Ok(hints) => {
+                    trace!(target: "hint_reader", "Successfully read batch of {} hints", hints.len());
+                    hints
+                }
+                Err(e) => {
+                    // Write back on error to prevent blocking the client.
+                    self.channel.write(&[0x00]).await?;
+                    return Err(e);
+                }
+            }
+        } else {
+            // Read the raw hint payload.
+            let mut raw_payload = vec![0u8; len as usize];
+            self.channel.read_exact(raw_payload.as_mut_slice()).await?;
+            let payload = match decode_hint(raw_payload) {
+                Ok(p) => p,
+                Err(e) => {
+                    // Write back on error to prevent blocking the client.
+                    self.channel.write(&[0x00]).await?;
+                    return Err(e);
+                }
+            };
 
-                return Err(PreimageOracleError::Other(format!(
-                    "Failed to decode hint payload: {e}"
-                )));
+            // The probe is answered with the supported version, rather than routed.
+            if payload == HINT_BATCHING_PROBE {
+                self.channel.write(&[HINT_BATCHING_VERSION]).await?;
+                trace!(target: "hint_reader", "Acknowledged hint batching probe");
+                return Ok(());
             }
-        };
 
-        trace!(target: "hint_reader", "Successfully read hint: \"{payload}\"");
+            trace!(target: "hint_reader", "Successfully read hint: \"{payload}\"");
+            vec![payload]
+        };
 
-        // Route the hint
-        if let Err(e) = hint_router.route_hint(payload).await {
-            // Write back on error to prevent blocking the client.
-            self.channel.write(&[0x00]).await?;
+        // Route the hints
+        for hint in hints {
+            if let Err(e) = hint_router.route_hint(hint).await {
+                // Write back on error to prevent blocking the client.
+                self.channel.write(&[0x00]).await?;
 
-            error!("Failed to route hint: {e}");
-            return Err(e);
+                error!("Failed to route hint: {e}");
+                return Err(e);
+            }
         }
 
         // Write back an acknowledgement to the client to unblock their process.
@@ -113,6 +228,42 @@ where
     }
 }
 
+impl<C> HintReader<C>
+where
+    C: Channel,
+{
+    /// Reads a big-endian [u32] from the channel.
+    async fn read_u32(&self) -> PreimageOracleResult<u32> {
+        let mut buf = [0u8; 4];
+        self.channel.read_exact(&mut buf).await?;
+        Ok(u32::from_be_bytes(buf))
+    }
+}
+
+/// Decodes a hint payload as UTF-8.
+fn decode_hint(raw_payload: Vec<u8>) -> PreimageOracleResult<String> {
+    String::from_utf8(raw_payload)
+        .map_err(|e| PreimageOracleError::Other(format!("Failed to decode hint payload: {e}")))
+}
+
+/// Decodes the body of a batched hint frame into its hints. See [HINT_BATCH_PREFIX] for the
+/// layout.
+fn decode_batch(mut body: &[u8]) -> PreimageOracleResult<Vec<String>> {
+    let truncated = || PreimageOracleError::Other("Truncated hint batch".into());
+
+    let mut hints = Vec::new();
+    while !body.is_empty() {
+        let (len, rest) = body.split_first_chunk::<4>().ok_or_else(truncated)?;
+        let len = u32::from_be_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        hints.push(decode_hint(rest[..len].to_vec())?);
+        body = &rest[len..];
+    }
+    Ok(hints)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -220,4 +371,115 @@ mod test {
         let h = hints.remove(0);
         assert_eq!(h, MOCK_DATA);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_hint_batch_client_and_host() {
+        let mock_hints: Vec<String> =
+            ["test-hint 0xfacade", "test-hint 0xbeef", "other-hint 0x"].map(Into::into).to_vec();
+
+        let incoming_hints = Arc::new(Mutex::new(Vec::new()));
+        let hint_channel = BidirectionalChannel::new().unwrap();
+
+        let client = tokio::task::spawn({
+            let mock_hints = mock_hints.clone();
+            async move {
+                let hint_writer = HintWriter::new(hint_channel.client);
+                assert!(!hint_writer.batching());
+                let hint_writer = hint_writer.negotiate_batching().await?;
+                assert!(hint_writer.batching());
+
+                hint_writer.write_batch(&mock_hints).await
+            }
+        });
+        let host = tokio::task::spawn({
+            let incoming_hints_ref = Arc::clone(&incoming_hints);
+            async move {
+                let router = TestRouter { incoming_hints: incoming_hints_ref };
+
+                // The probe is answered without being routed, and the whole batch takes a single
+                // round-trip.
+                let hint_reader = HintReader::new(hint_channel.host);
+                hint_reader.next_hint(&router).await.unwrap();
+                assert!(router.incoming_hints.lock().await.is_empty());
+                hint_reader.next_hint(&router).await.unwrap();
+            }
+        });
+
+        let (c, h) = tokio::join!(client, host);
+        c.unwrap().unwrap();
+        h.unwrap();
+        assert_eq!(*incoming_hints.lock().await, mock_hints);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_hint_batch_legacy_host() {
+        let mock_hints: Vec<String> =
+            ["test-hint 0xfacade", "test-hint 0xbeef"].map(Into::into).to_vec();
+
+        let hint_channel = BidirectionalChannel::new().unwrap();
+
+        let client = tokio::task::spawn({
+            let mock_hints = mock_hints.clone();
+            async move {
+                let hint_writer = HintWriter::new(hint_channel.client).negotiate_batching().await?;
+                assert!(!hint_writer.batching());
+
+                hint_writer.write_batch(&mock_hints).await
+            }
+        });
+        let host = tokio::task::spawn(async move {
+            // A host that predates batching reads every frame as a single hint, including the
+            // probe, and acknowledges each with zero.
+            let mut hints = Vec::new();
+            for _ in 0..3 {
+                let mut len_buf = [0u8; 4];
+                hint_channel.host.read_exact(&mut len_buf).await.unwrap();
+                let mut payload = vec![0u8; u32::from_be_bytes(len_buf) as usize];
+                hint_channel.host.read_exact(&mut payload).await.unwrap();
+                hint_channel.host.write(&[0x00]).await.unwrap();
+                hints.push(String::from_utf8(payload).unwrap());
+            }
+            hints
+        });
+
+        let (c, h) = tokio::join!(client, host);
+        c.unwrap().unwrap();
+        let hints = h.unwrap();
+        assert_eq!(hints[0], HINT_BATCHING_PROBE);
+        assert_eq!(hints[1..], mock_hints);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_hint_batch_unblock_on_bad_utf8() {
+        let hint_channel = BidirectionalChannel::new().unwrap();
+
+        let client = tokio::task::spawn(async move {
+            let mut body = Vec::new();
+            for hint in [[0xf0, 0x90, 0x28, 0xbc].as_slice(), b"test-hint 0x"] {
+                body.extend_from_slice(&(hint.len() as u32).to_be_bytes());
+                body.extend_from_slice(hint);
+            }
+            hint_channel.client.write(&HINT_BATCH_PREFIX.to_be_bytes()).await.unwrap();
+            hint_channel.client.write(&(body.len() as u32).to_be_bytes()).await.unwrap();
+            hint_channel.client.write(&body).await.unwrap();
+
+            let mut ack = [0xffu8; 1];
+            hint_channel.client.read_exact(&mut ack).await.unwrap();
+            ack[0]
+        });
+        let host = tokio::task::spawn(async move {
+            let router = TestRouter { incoming_hints: Default::default() };
+
+            let hint_reader = HintReader::new(hint_channel.host);
+            let result = hint_reader.next_hint(&router).await;
+            let routed = router.incoming_hints.lock().await.len();
+            (result, routed)
+        });
+
+        let (c, h) = tokio::join!(client, host);
+        assert_eq!(c.unwrap(), 0x00);
+        let (result, routed) = h.unwrap();
+        assert!(result.is_err());
+        assert_eq!(routed, 0);
+    }
 }
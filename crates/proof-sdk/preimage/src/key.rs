@@ -1,7 +1,7 @@
 //! Contains the [PreimageKey] type, which is used to identify preimages that may be fetched from
 //! the preimage oracle.
 
-use alloy_primitives::{B256, U256};
+use alloy_primitives::{Keccak256, B256, U256};
 #[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 #[cfg(feature = "serde")]
@@ -22,7 +22,11 @@ pub enum PreimageKeyType {
     /// low-order 31 bytes of the preimage's `keccak256` digest to the preimage itself.
     #[default]
     Keccak256 = 2,
-    /// GlobalGeneric key types are reserved for future use.
+    /// GlobalGeneric key types are global and context independent, and map preimages under custom
+    /// commitment schemes. GlobalGeneric keys are constructed as `keccak256(namespace ++
+    /// commitment)`, where the namespace identifies the commitment scheme, and then the high-order
+    /// byte of the digest is set to the type byte. The preimage is not verified against the key,
+    /// so consumers must verify it against the commitment.
     GlobalGeneric = 3,
     /// Sha256 key types are global and context independent. Preimages are mapped from the
     /// low-order 31 bytes of the preimage's `sha256` digest to the preimage itself.
@@ -87,6 +91,15 @@ impl PreimageKey {
         Self { data, key_type: PreimageKeyType::Local }
     }
 
+    /// Creates a new global generic [PreimageKey] from the namespace of a commitment scheme and a
+    /// commitment under that scheme. See [PreimageKeyType::GlobalGeneric].
+    pub fn new_global_generic(namespace: B256, commitment: &[u8]) -> Self {
+        let mut hasher = Keccak256::new();
+        hasher.update(namespace);
+        hasher.update(commitment);
+        Self::new(*hasher.finalize(), PreimageKeyType::GlobalGeneric)
+    }
+
     /// Returns the [PreimageKeyType] for the [PreimageKey].
     pub const fn key_type(&self) -> PreimageKeyType {
         self.key_type
@@ -153,6 +166,21 @@ mod test {
         assert_eq!(key.key_value(), U256::from(0xFFu64));
     }
 
+    #[test]
+    fn test_preimage_key_new_global_generic() {
+        let namespace = B256::repeat_byte(0xaa);
+        let key = PreimageKey::new_global_generic(namespace, b"commitment");
+        assert_eq!(key.key_type(), PreimageKeyType::GlobalGeneric);
+
+        let digest = alloy_primitives::keccak256([namespace.as_slice(), b"commitment"].concat());
+        let mut expected = digest.0;
+        expected[0] = PreimageKeyType::GlobalGeneric as u8;
+        assert_eq!(<[u8; 32]>::from(key), expected);
+
+        // Keys are separated by namespace.
+        assert_ne!(key, PreimageKey::new_global_generic(B256::ZERO, b"commitment"));
+    }
+
     #[test]
     fn test_preimage_key_value() {
         let key = PreimageKey::new([0xFFu8; 32], PreimageKeyType::Local);
@@ -21,7 +21,9 @@ mod oracle;
 pub use oracle::{OracleReader, OracleServer};
 
 mod hint;
-pub use hint::{HintReader, HintWriter};
+pub use hint::{
+    HintReader, HintWriter, HINT_BATCHING_PROBE, HINT_BATCHING_VERSION, HINT_BATCH_PREFIX,
+};
 
 mod traits;
 pub use traits::{
@@ -33,3 +35,13 @@ pub use traits::{
 mod native_channel;
 #[cfg(any(test, feature = "std"))]
 pub use native_channel::{BidirectionalChannel, NativeChannel};
+
+#[cfg(all(unix, feature = "unix-socket"))]
+mod unix_channel;
+#[cfg(all(unix, feature = "unix-socket"))]
+pub use unix_channel::{UnixChannel, UnixChannelListener};
+
+#[cfg(feature = "shared-memory")]
+mod shm_channel;
+#[cfg(feature = "shared-memory")]
+pub use shm_channel::{SharedMemoryChannel, SharedMemorySide};
@@ -1,5 +1,8 @@
 //! Native implementation of the [Channel] trait, backed by [async_channel]'s unbounded
 //! channel primitives.
+//!
+//! Reads may consume part of a write, or span several writes, so that the channel behaves like the
+//! file descriptors that the client program reads from on an FPVM.
 
 use crate::{
     errors::{ChannelError, ChannelResult},
@@ -7,7 +10,11 @@ use crate::{
 };
 use async_channel::{unbounded, Receiver, Sender};
 use async_trait::async_trait;
-use std::io::Result;
+use std::{
+    collections::VecDeque,
+    io::Result,
+    sync::{Arc, Mutex},
+};
 
 /// A bidirectional channel, allowing for synchronized communication between two parties.
 #[derive(Debug, Clone)]
@@ -25,8 +32,8 @@ impl BidirectionalChannel {
         let (aw, br) = unbounded();
 
         Ok(Self {
-            client: NativeChannel { read: ar, write: aw },
-            host: NativeChannel { read: br, write: bw },
+            client: NativeChannel { read: ar, write: aw, buffer: Default::default() },
+            host: NativeChannel { read: br, write: bw, buffer: Default::default() },
         })
     }
 }
@@ -38,20 +45,47 @@ pub struct NativeChannel {
     pub(crate) read: Receiver<Vec<u8>>,
     /// The sender of the channel.
     pub(crate) write: Sender<Vec<u8>>,
+    /// The bytes that have been received, but not yet read.
+    pub(crate) buffer: Arc<Mutex<VecDeque<u8>>>,
+}
+
+impl NativeChannel {
+    /// Moves up to `buf.len()` buffered bytes into `buf`, returning the number of bytes moved.
+    fn drain_into(&self, buf: &mut [u8]) -> usize {
+        let mut buffer = self.buffer.lock().expect("channel buffer poisoned");
+        let len = buffer.len().min(buf.len());
+        buffer.drain(..len).zip(buf.iter_mut()).for_each(|(byte, slot)| *slot = byte);
+        len
+    }
+
+    /// Waits for the next write from the counterparty and appends it to the buffer.
+    async fn fill(&self) -> ChannelResult<()> {
+        let data = self.read.recv().await.map_err(|_| ChannelError::Closed)?;
+        self.buffer.lock().expect("channel buffer poisoned").extend(data);
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Channel for NativeChannel {
     async fn read(&self, buf: &mut [u8]) -> ChannelResult<usize> {
-        let data = self.read.recv().await.map_err(|_| ChannelError::Closed)?;
-        let len = data.len().min(buf.len());
-        buf[..len].copy_from_slice(&data[..len]);
-        Ok(len)
+        loop {
+            let len = self.drain_into(buf);
+            if len > 0 || buf.is_empty() {
+                return Ok(len);
+            }
+            self.fill().await?;
+        }
     }
 
     async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
-        let data = self.read.recv().await.map_err(|_| ChannelError::Closed)?;
-        buf[..].copy_from_slice(&data[..]);
+        let mut read = 0;
+        while read < buf.len() {
+            read += self.drain_into(&mut buf[read..]);
+            if read < buf.len() {
+                self.fill().await?;
+            }
+        }
         Ok(buf.len())
     }
 
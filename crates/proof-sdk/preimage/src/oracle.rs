@@ -3,7 +3,7 @@ use crate::{
     traits::{Channel, PreimageFetcher},
     PreimageKey, PreimageOracleClient, PreimageOracleServer,
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 
 /// An [OracleReader] is a high-level interface to the preimage oracle channel.
 #[derive(Debug, Clone, Copy)]
@@ -88,6 +88,87 @@ where
 
         Ok(())
     }
+
+    /// Get the data corresponding to the currently set key from the host. Write the data into the
+    /// provided buffer, resizing it to the length of the preimage.
+    async fn get_into(&self, key: PreimageKey, buf: &mut Vec<u8>) -> PreimageOracleResult<()> {
+        trace!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
+
+        let length = self.write_key(key).await?;
+
+        buf.clear();
+        if length == 0 {
+            return Ok(());
+        }
+        buf.resize(length, 0);
+
+        trace!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
+
+        self.channel.read_exact(buf).await?;
+
+        trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
+
+        Ok(())
+    }
+
+    /// Get the data corresponding to the currently set key from the host. The data is read
+    /// directly into a new `Arc<[u8]>`, without an intermediate `Vec<u8>`.
+    async fn get_shared(&self, key: PreimageKey) -> PreimageOracleResult<Arc<[u8]>> {
+        trace!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
+
+        let length = self.write_key(key).await?;
+
+        // Collecting from an iterator of known length allocates the `Arc` once.
+        let mut data: Arc<[u8]> = (0..length).map(|_| 0).collect();
+        if length == 0 {
+            return Ok(data);
+        }
+
+        trace!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
+
+        let buf = Arc::get_mut(&mut data).expect("Newly allocated Arc is unique");
+        self.channel.read_exact(buf).await?;
+
+        trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
+
+        Ok(data)
+    }
+
+    /// Get the data corresponding to the currently set key from the host, and pass it to `f` in
+    /// chunks of at most `chunk.len()` bytes as it is read from the channel.
+    async fn get_chunked(
+        &self,
+        key: PreimageKey,
+        chunk: &mut [u8],
+        f: &mut (dyn for<'a> FnMut(&'a [u8]) -> PreimageOracleResult<()> + Send),
+    ) -> PreimageOracleResult<usize> {
+        if chunk.is_empty() {
+            return Err(PreimageOracleError::EmptyChunkBuffer);
+        }
+
+        trace!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
+
+        let length = self.write_key(key).await?;
+
+        trace!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
+
+        // The preimage must be read from the channel in full, even if `f` fails, so that the
+        // channel stays in sync with the host.
+        let mut result = Ok(());
+        let mut remaining = length;
+        while remaining > 0 {
+            let len = remaining.min(chunk.len());
+            self.channel.read_exact(&mut chunk[..len]).await?;
+            if result.is_ok() {
+                result = f(&chunk[..len]);
+            }
+            remaining -= len;
+        }
+
+        trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
+
+        result.map(|_| length)
+    }
 }
 
 /// An [OracleServer] is a router for the host to serve data back to the client [OracleReader].
@@ -245,4 +326,126 @@ mod test {
         assert_eq!(contents_a, MOCK_DATA_A);
         assert_eq!(contents_b, MOCK_DATA_B);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_oracle_reader_streaming() {
+        const MOCK_DATA_A: &[u8] = b"1234567890";
+        const MOCK_DATA_B: &[u8] = b"FACADE";
+        let key_a: PreimageKey =
+            PreimageKey::new(*keccak256(MOCK_DATA_A), PreimageKeyType::Keccak256);
+        let key_b: PreimageKey =
+            PreimageKey::new(*keccak256(MOCK_DATA_B), PreimageKeyType::Keccak256);
+
+        let preimages = {
+            let mut preimages = HashMap::default();
+            preimages.insert(key_a, MOCK_DATA_A.to_vec());
+            preimages.insert(key_b, MOCK_DATA_B.to_vec());
+            Arc::new(Mutex::new(preimages))
+        };
+
+        let preimage_channel = BidirectionalChannel::new().unwrap();
+
+        let client = tokio::task::spawn(async move {
+            let oracle_reader = OracleReader::new(preimage_channel.client);
+
+            // Reading into a buffer reuses its allocation.
+            let mut buf = Vec::with_capacity(64);
+            oracle_reader.get_into(key_a, &mut buf).await.unwrap();
+            assert_eq!(buf, MOCK_DATA_A);
+            assert_eq!(buf.capacity(), 64);
+            oracle_reader.get_into(key_b, &mut buf).await.unwrap();
+            assert_eq!(buf, MOCK_DATA_B);
+
+            let shared = oracle_reader.get_shared(key_a).await.unwrap();
+            assert_eq!(&*shared, MOCK_DATA_A);
+
+            // A preimage larger than the chunk buffer is passed on in chunks of at most its size.
+            let mut chunks = Vec::new();
+            let mut chunk = [0u8; 4];
+            let len = oracle_reader
+                .get_chunked(key_a, &mut chunk, &mut |c| {
+                    chunks.push(c.to_vec());
+                    Ok(())
+                })
+                .await
+                .unwrap();
+            assert_eq!(len, MOCK_DATA_A.len());
+            assert_eq!(chunks, [b"1234".to_vec(), b"5678".to_vec(), b"90".to_vec()]);
+
+            // A failing callback still consumes the preimage, keeping the channel in sync.
+            let result = oracle_reader
+                .get_chunked(key_a, &mut chunk, &mut |_| Err(PreimageOracleError::KeyNotFound))
+                .await;
+            assert!(matches!(result, Err(PreimageOracleError::KeyNotFound)));
+            assert!(matches!(
+                oracle_reader.get_chunked(key_a, &mut [], &mut |_| Ok(())).await,
+                Err(PreimageOracleError::EmptyChunkBuffer)
+            ));
+
+            oracle_reader.get(key_b).await.unwrap()
+        });
+        tokio::task::spawn(async move {
+            let oracle_server = OracleServer::new(preimage_channel.host);
+            let test_fetcher = TestFetcher { preimages: Arc::clone(&preimages) };
+
+            loop {
+                match oracle_server.next_preimage_request(&test_fetcher).await {
+                    Err(PreimageOracleError::IOError(_)) => break,
+                    Err(e) => panic!("Unexpected error: {:?}", e),
+                    Ok(_) => {}
+                }
+            }
+        });
+
+        let (c,) = tokio::join!(client);
+        assert_eq!(c.unwrap(), MOCK_DATA_B);
+    }
+
+    /// A [PreimageOracleClient] that only implements the required methods, serving preimages from
+    /// a [TestFetcher].
+    struct MinimalOracle(TestFetcher);
+
+    #[async_trait::async_trait]
+    impl PreimageOracleClient for MinimalOracle {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            self.0.get_preimage(key).await
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            let data = self.get(key).await?;
+            if data.len() != buf.len() {
+                return Err(PreimageOracleError::BufferLengthMismatch(data.len(), buf.len()));
+            }
+            buf.copy_from_slice(&data);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oracle_client_default_reads() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let preimages = HashMap::from_iter([(key, MOCK_DATA.to_vec())]);
+        let oracle = MinimalOracle(TestFetcher { preimages: Arc::new(Mutex::new(preimages)) });
+
+        let mut buf = b"stale contents of the buffer".to_vec();
+        oracle.get_into(key, &mut buf).await.unwrap();
+        assert_eq!(buf, MOCK_DATA);
+        assert_eq!(&*oracle.get_shared(key).await.unwrap(), MOCK_DATA);
+
+        let mut chunks = Vec::new();
+        let len = oracle
+            .get_chunked(key, &mut [0u8; 3], &mut |c| {
+                chunks.push(c.to_vec());
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(len, MOCK_DATA.len());
+        assert_eq!(chunks.concat(), MOCK_DATA);
+        assert_eq!(chunks.len(), 4);
+
+        let missing = PreimageKey::new([0xff; 32], PreimageKeyType::Keccak256);
+        assert!(matches!(oracle.get_shared(missing).await, Err(PreimageOracleError::KeyNotFound)));
+    }
 }
@@ -0,0 +1,534 @@
+//! A [Channel] implementation over a pair of ring buffers in a shared memory mapping.
+//!
+//! Both ends of the channel map the same file, preferably on a memory-backed file system such as
+//! `/dev/shm`, so that the host and the client program can run as separate native processes and
+//! exchange data without a system call per read or write. The file is laid out as a header,
+//! followed by one ring buffer for each direction:
+//!
+//! | Offset                      | Size       | Description                          |
+//! |-----------------------------|------------|--------------------------------------|
+//! | 0                           | 64         | Magic and capacity                   |
+//! | 64                          | 192        | Control block of the host ring       |
+//! | 256                         | `capacity` | Data of the host ring                |
+//! | 256 + `capacity`            | 192        | Control block of the client ring     |
+//! | 448 + `capacity`            | `capacity` | Data of the client ring              |
+//!
+//! The host writes into the host ring and the client writes into the client ring. Each control
+//! block holds the total number of bytes written, the total number of bytes read, and a flag that
+//! is set when either end is dropped, each on its own cache line. The flag is followed by the
+//! process id of the end that writes into the ring, so that a peer which exits without being
+//! dropped is still noticed.
+
+use crate::{
+    errors::{ChannelError, ChannelResult},
+    Channel,
+};
+use async_trait::async_trait;
+use core::{
+    future::Future,
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
+use memmap2::MmapMut;
+use std::{fs::OpenOptions, io, path::Path, sync::Arc};
+
+/// The magic number at the start of a shared memory channel file.
+const MAGIC: u64 = u64::from_be_bytes(*b"KONASHM1");
+
+/// The size of the file header, in bytes.
+const HEADER_SIZE: usize = 64;
+
+/// The size of the control block of a ring, in bytes.
+const CONTROL_SIZE: usize = 192;
+
+/// The number of times to spin before yielding to the executor while waiting on the peer.
+const SPIN_LIMIT: u32 = 64;
+
+/// The number of times to yield to the executor before sleeping while waiting on the peer.
+const YIELD_LIMIT: u32 = 256;
+
+/// The longest time to sleep for while waiting on the peer.
+const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+/// The end of a [SharedMemoryChannel].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedMemorySide {
+    /// The host end, which writes into the host ring and reads from the client ring.
+    Host,
+    /// The client end, which writes into the client ring and reads from the host ring.
+    Client,
+}
+
+/// A [Channel] over a pair of ring buffers in a shared memory mapping. See the
+/// [module documentation](self) for the layout.
+///
+/// Waiting on the peer spins, then yields to the executor, and finally sleeps on the [tokio] timer
+/// for up to a millisecond at a time, so that an idle channel neither occupies a core nor blocks
+/// the executor. While sleeping, a peer whose process has exited is treated as closed, so both
+/// ends must run in the same PID namespace.
+#[derive(Debug, Clone)]
+pub struct SharedMemoryChannel {
+    inner: Arc<Inner>,
+}
+
+impl SharedMemoryChannel {
+    /// Creates the shared memory file at `path` with rings of `capacity` bytes, and opens the
+    /// given side of the channel. An existing file at `path` is truncated.
+    pub fn create(
+        path: impl AsRef<Path>,
+        capacity: usize,
+        side: SharedMemorySide,
+    ) -> io::Result<Self> {
+        if capacity == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "capacity must be non-zero"));
+        }
+        // Keep the control blocks aligned to cache lines.
+        let capacity = capacity.next_multiple_of(64);
+
+        let file =
+            OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len(Self::file_size(capacity) as u64)?;
+
+        // SAFETY: The file was just created with the expected size, and is only modified through
+        // the atomics in the control blocks and the ring protocol.
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[..8].copy_from_slice(&MAGIC.to_le_bytes());
+        mmap[8..16].copy_from_slice(&(capacity as u64).to_le_bytes());
+
+        Ok(Self::from_mmap(mmap, capacity, side))
+    }
+
+    /// Opens the given side of the channel in the existing shared memory file at `path`.
+    pub fn open(path: impl AsRef<Path>, side: SharedMemorySide) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        // SAFETY: The layout of the file is validated below before it is used.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid shared memory file");
+        if mmap.len() < HEADER_SIZE ||
+            u64::from_le_bytes(mmap[..8].try_into().expect("length checked")) != MAGIC
+        {
+            return Err(invalid());
+        }
+        let capacity = u64::from_le_bytes(mmap[8..16].try_into().expect("length checked"));
+        let capacity = usize::try_from(capacity).map_err(|_| invalid())?;
+        if capacity == 0 || capacity % 64 != 0 || mmap.len() != Self::file_size(capacity) {
+            return Err(invalid());
+        }
+
+        Ok(Self::from_mmap(mmap, capacity, side))
+    }
+
+    /// Returns the capacity of each ring, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the size of a shared memory file with rings of `capacity` bytes.
+    const fn file_size(capacity: usize) -> usize {
+        HEADER_SIZE + 2 * (CONTROL_SIZE + capacity)
+    }
+
+    fn from_mmap(mut mmap: MmapMut, capacity: usize, side: SharedMemorySide) -> Self {
+        let base = mmap.as_mut_ptr();
+        // SAFETY: Both rings lie within the mapping, as checked by the callers.
+        let (host, client) = unsafe {
+            let host = base.add(HEADER_SIZE);
+            (host, host.add(CONTROL_SIZE + capacity))
+        };
+        let (tx, rx) = match side {
+            SharedMemorySide::Host => (host, client),
+            SharedMemorySide::Client => (client, host),
+        };
+
+        let inner = Inner {
+            _mmap: mmap,
+            capacity,
+            tx: Ring { base: tx, capacity },
+            rx: Ring { base: rx, capacity },
+            writing: AtomicBool::new(false),
+            reading: AtomicBool::new(false),
+        };
+        inner.tx.pid().store(std::process::id(), Ordering::Release);
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+#[async_trait]
+impl Channel for SharedMemoryChannel {
+    async fn read(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        let _guard = Guard::acquire(&self.inner.reading).await;
+        let rx = &self.inner.rx;
+
+        let mut backoff = Backoff::default();
+        loop {
+            // Check whether the peer is gone before loading the write position, so that data
+            // written before the peer closed the ring or exited is still read.
+            let closed = rx.closed().load(Ordering::Acquire) != 0 ||
+                (backoff.is_sleeping() && !self.inner.peer_alive());
+            let write = rx.write_pos().load(Ordering::Acquire);
+            let read = rx.read_pos().load(Ordering::Relaxed);
+            let available = rx.len(write, read)?;
+
+            if available != 0 || buf.is_empty() {
+                let len = buf.len().min(available);
+                // SAFETY: The bytes in `read..write` have been published by the writer, and are
+                // not overwritten until the read position is advanced.
+                unsafe { rx.copy_out(read, &mut buf[..len]) };
+                rx.read_pos().store(read + len as u64, Ordering::Release);
+                return Ok(len);
+            }
+            if closed {
+                return Err(ChannelError::Closed);
+            }
+            backoff.wait().await;
+        }
+    }
+
+    async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            read += self.read(&mut buf[read..]).await.map_err(|e| match read {
+                0 => e,
+                _ => ChannelError::UnexpectedEOF,
+            })?;
+        }
+        Ok(buf.len())
+    }
+
+    async fn write(&self, buf: &[u8]) -> ChannelResult<usize> {
+        let _guard = Guard::acquire(&self.inner.writing).await;
+        let tx = &self.inner.tx;
+
+        let mut written = 0;
+        let mut backoff = Backoff::default();
+        while written < buf.len() {
+            if tx.closed().load(Ordering::Acquire) != 0 ||
+                (backoff.is_sleeping() && !self.inner.peer_alive())
+            {
+                return Err(ChannelError::Closed);
+            }
+
+            let write = tx.write_pos().load(Ordering::Relaxed);
+            let read = tx.read_pos().load(Ordering::Acquire);
+            let free = tx.capacity - tx.len(write, read)?;
+            if free == 0 {
+                backoff.wait().await;
+                continue;
+            }
+
+            let len = free.min(buf.len() - written);
+            // SAFETY: The bytes in `write..read + capacity` have been consumed by the reader.
+            unsafe { tx.copy_in(write, &buf[written..written + len]) };
+            tx.write_pos().store(write + len as u64, Ordering::Release);
+            written += len;
+            backoff = Backoff::default();
+        }
+        Ok(written)
+    }
+}
+
+/// The shared state of the clones of a [SharedMemoryChannel].
+#[derive(Debug)]
+struct Inner {
+    /// The mapping that the rings point into.
+    _mmap: MmapMut,
+    /// The capacity of each ring, in bytes.
+    capacity: usize,
+    /// The ring that this end writes into.
+    tx: Ring,
+    /// The ring that this end reads from.
+    rx: Ring,
+    /// Whether a clone of this end is writing. Each ring supports a single writer.
+    writing: AtomicBool,
+    /// Whether a clone of this end is reading. Each ring supports a single reader.
+    reading: AtomicBool,
+}
+
+impl Inner {
+    /// Returns whether the process of the peer is still running. A peer that has not opened the
+    /// channel yet is considered alive.
+    fn peer_alive(&self) -> bool {
+        match self.rx.pid().load(Ordering::Acquire) {
+            0 => true,
+            pid => process_alive(pid),
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.tx.closed().store(1, Ordering::Release);
+        self.rx.closed().store(1, Ordering::Release);
+    }
+}
+
+/// A single-producer, single-consumer ring buffer in the shared memory mapping.
+#[derive(Debug)]
+struct Ring {
+    /// The start of the control block of the ring, which is followed by its data.
+    base: *mut u8,
+    /// The capacity of the ring, in bytes.
+    capacity: usize,
+}
+
+// SAFETY: Rings point into the mapping owned by their [Inner], and are only accessed through the
+// atomics in their control blocks, by a single reader and a single writer at a time.
+unsafe impl Send for Ring {}
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    /// The total number of bytes written into the ring.
+    const fn write_pos(&self) -> &AtomicU64 {
+        // SAFETY: The control block is 64-byte aligned and lives as long as the mapping.
+        unsafe { &*(self.base as *const AtomicU64) }
+    }
+
+    /// The total number of bytes read from the ring.
+    const fn read_pos(&self) -> &AtomicU64 {
+        // SAFETY: See [Ring::write_pos].
+        unsafe { &*(self.base.add(64) as *const AtomicU64) }
+    }
+
+    /// Whether either end of the channel has been dropped.
+    const fn closed(&self) -> &AtomicU32 {
+        // SAFETY: See [Ring::write_pos].
+        unsafe { &*(self.base.add(128) as *const AtomicU32) }
+    }
+
+    /// The process id of the end that writes into the ring, or zero if it has not opened the
+    /// channel yet.
+    const fn pid(&self) -> &AtomicU32 {
+        // SAFETY: See [Ring::write_pos].
+        unsafe { &*(self.base.add(132) as *const AtomicU32) }
+    }
+
+    /// Returns the number of unread bytes between the `read` and `write` positions, or
+    /// [ChannelError::Corrupted] if the positions do not describe a valid ring, as the peer can
+    /// write arbitrary values into the control block.
+    fn len(&self, write: u64, read: u64) -> ChannelResult<usize> {
+        write
+            .checked_sub(read)
+            .filter(|len| *len <= self.capacity as u64)
+            .map(|len| len as usize)
+            .ok_or(ChannelError::Corrupted)
+    }
+
+    /// Copies `src` into the ring, starting at position `pos`.
+    ///
+    /// # Safety
+    /// The reader must have consumed the bytes in `pos..pos + src.len()`.
+    unsafe fn copy_in(&self, pos: u64, src: &[u8]) {
+        let data = self.base.add(CONTROL_SIZE);
+        let start = (pos % self.capacity as u64) as usize;
+        let first = src.len().min(self.capacity - start);
+        ptr::copy_nonoverlapping(src.as_ptr(), data.add(start), first);
+        ptr::copy_nonoverlapping(src.as_ptr().add(first), data, src.len() - first);
+    }
+
+    /// Copies bytes out of the ring into `dst`, starting at position `pos`.
+    ///
+    /// # Safety
+    /// The writer must have published the bytes in `pos..pos + dst.len()`.
+    unsafe fn copy_out(&self, pos: u64, dst: &mut [u8]) {
+        let data = self.base.add(CONTROL_SIZE);
+        let start = (pos % self.capacity as u64) as usize;
+        let first = dst.len().min(self.capacity - start);
+        ptr::copy_nonoverlapping(data.add(start), dst.as_mut_ptr(), first);
+        ptr::copy_nonoverlapping(data, dst.as_mut_ptr().add(first), dst.len() - first);
+    }
+}
+
+/// Exclusive access to one direction of a [SharedMemoryChannel], released on drop.
+struct Guard<'a>(&'a AtomicBool);
+
+impl<'a> Guard<'a> {
+    async fn acquire(flag: &'a AtomicBool) -> Self {
+        let mut backoff = Backoff::default();
+        while flag.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            backoff.wait().await;
+        }
+        Self(flag)
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// The strategy for waiting on the peer of a [SharedMemoryChannel].
+#[derive(Debug, Default)]
+struct Backoff(u32);
+
+impl Backoff {
+    async fn wait(&mut self) {
+        if self.0 < SPIN_LIMIT {
+            core::hint::spin_loop();
+        } else if self.0 < YIELD_LIMIT {
+            YieldNow(false).await;
+        } else {
+            let exp = (self.0 - YIELD_LIMIT).min(10);
+            tokio::time::sleep(MAX_SLEEP.min(Duration::from_micros(1 << exp))).await;
+        }
+        self.0 = self.0.saturating_add(1);
+    }
+
+    /// Whether the peer has been waited on for long enough to sleep between checks.
+    const fn is_sleeping(&self) -> bool {
+        self.0 >= YIELD_LIMIT
+    }
+}
+
+/// Returns whether the process with the given id is running.
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // SAFETY: Signal 0 only checks that the process exists and may be signalled.
+    let ret = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    ret == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+/// Returns whether the process with the given id is running.
+#[cfg(not(unix))]
+const fn process_alive(_: u32) -> bool {
+    true
+}
+
+/// A future that yields to the executor once.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        errors::{PreimageOracleError, PreimageOracleResult},
+        OracleReader, OracleServer, PreimageFetcher, PreimageKey, PreimageKeyType,
+        PreimageOracleClient, PreimageOracleServer,
+    };
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use alloy_primitives::keccak256;
+
+    struct TestFetcher(Vec<u8>);
+
+    #[async_trait]
+    impl PreimageFetcher for TestFetcher {
+        async fn get_preimage(&self, _: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("kona-shm-{}-{name}", std::process::id()))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shm_channel_wraparound() {
+        let path = temp_path("wraparound");
+        let host = SharedMemoryChannel::create(&path, 64, SharedMemorySide::Host).unwrap();
+        let client = SharedMemoryChannel::open(&path, SharedMemorySide::Client).unwrap();
+        assert_eq!(client.capacity(), 64);
+
+        // The preimage is much larger than the ring, so the writer must wait on the reader.
+        let preimage = (0..10_000).map(|i| i as u8).collect::<Vec<_>>();
+        let key = PreimageKey::new(*keccak256(&preimage), PreimageKeyType::Keccak256);
+        let server = tokio::task::spawn({
+            let fetcher = TestFetcher(preimage.clone());
+            async move {
+                let server = OracleServer::new(host);
+                loop {
+                    match server.next_preimage_request(&fetcher).await {
+                        Err(PreimageOracleError::IOError(_)) => break,
+                        result => result.unwrap(),
+                    }
+                }
+            }
+        });
+
+        let reader = OracleReader::new(client);
+        assert_eq!(reader.get(key).await.unwrap(), preimage);
+        assert_eq!(reader.get(key).await.unwrap(), preimage);
+
+        // Dropping the client closes the channel for the server.
+        drop(reader);
+        server.await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shm_channel_closed() {
+        let path = temp_path("closed");
+        let host = SharedMemoryChannel::create(&path, 64, SharedMemorySide::Host).unwrap();
+        let client = SharedMemoryChannel::open(&path, SharedMemorySide::Client).unwrap();
+
+        // Data written before the peer is dropped can still be read.
+        host.write(b"bye").await.unwrap();
+        drop(host);
+        let mut buf = [0u8; 3];
+        client.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"bye");
+        assert!(matches!(client.read(&mut buf).await, Err(ChannelError::Closed)));
+        assert!(matches!(client.write(b"hello").await, Err(ChannelError::Closed)));
+        drop(client);
+
+        std::fs::write(&path, vec![0u8; 128]).unwrap();
+        assert!(SharedMemoryChannel::open(&path, SharedMemorySide::Client).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shm_channel_peer_exited() {
+        let path = temp_path("peer-exited");
+        let host = SharedMemoryChannel::create(&path, 64, SharedMemorySide::Host).unwrap();
+        let client = SharedMemoryChannel::open(&path, SharedMemorySide::Client).unwrap();
+
+        // Pretend the host runs in a process that has exited without dropping its end.
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        host.write(b"bye").await.unwrap();
+        host.inner.tx.pid().store(child.id(), Ordering::Release);
+
+        let mut buf = [0u8; 3];
+        client.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"bye");
+        assert!(matches!(client.read(&mut buf).await, Err(ChannelError::Closed)));
+        assert!(matches!(client.write(&[0u8; 128]).await, Err(ChannelError::Closed)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shm_channel_corrupted() {
+        let path = temp_path("corrupted");
+        let host = SharedMemoryChannel::create(&path, 64, SharedMemorySide::Host).unwrap();
+        let client = SharedMemoryChannel::open(&path, SharedMemorySide::Client).unwrap();
+
+        // A read position past the write position must not underflow.
+        host.inner.tx.read_pos().store(1, Ordering::Release);
+        let mut buf = [0u8; 3];
+        assert!(matches!(client.read(&mut buf).await, Err(ChannelError::Corrupted)));
+        assert!(matches!(host.write(b"hello").await, Err(ChannelError::Corrupted)));
+
+        // Neither may a write position more than the capacity ahead of the read position.
+        host.inner.tx.read_pos().store(0, Ordering::Release);
+        host.inner.tx.write_pos().store(65, Ordering::Release);
+        assert!(matches!(client.read(&mut buf).await, Err(ChannelError::Corrupted)));
+        assert!(matches!(host.write(b"hello").await, Err(ChannelError::Corrupted)));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
-    errors::{ChannelResult, PreimageOracleResult},
+    errors::{ChannelResult, PreimageOracleError, PreimageOracleResult},
     PreimageKey,
 };
-use alloc::{boxed::Box, string::String, vec::Vec};
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use async_trait::async_trait;
 
 /// A [PreimageOracleClient] is a high-level interface to read data from the host, keyed by a
@@ -24,6 +24,64 @@ pub trait PreimageOracleClient {
     /// - `Ok(())` if the data was successfully written into the buffer.
     /// - `Err(_)` if the data could not be written into the buffer.
     async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()>;
+
+    /// Get the data corresponding to the currently set key from the host. Writes the data into the
+    /// provided buffer, replacing its contents and reusing its allocation where possible. By
+    /// default, the data is fetched with [PreimageOracleClient::get] and copied into the buffer.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the data was successfully written into the buffer.
+    /// - `Err(_)` if the data could not be fetched from the host.
+    async fn get_into(&self, key: PreimageKey, buf: &mut Vec<u8>) -> PreimageOracleResult<()>
+    where
+        Self: Sync,
+    {
+        let data = self.get(key).await?;
+        buf.clear();
+        buf.extend_from_slice(&data);
+        Ok(())
+    }
+
+    /// Get the data corresponding to the currently set key from the host. Return the data in a
+    /// shared, heap allocated `Arc<[u8]>`, which can be held by a cache without copying. By
+    /// default, the data is fetched with [PreimageOracleClient::get] and moved into the `Arc`.
+    ///
+    /// # Returns
+    /// - `Ok(Arc<[u8]>)` if the data was successfully fetched from the host.
+    /// - `Err(_)` if the data could not be fetched from the host.
+    async fn get_shared(&self, key: PreimageKey) -> PreimageOracleResult<Arc<[u8]>>
+    where
+        Self: Sync,
+    {
+        self.get(key).await.map(Arc::from)
+    }
+
+    /// Get the data corresponding to the currently set key from the host, and pass it to `f` in
+    /// chunks of at most `chunk.len()` bytes. The `chunk` buffer is used as scratch space, so
+    /// that implementations that stream the data can process preimages without holding them in
+    /// memory in full. By default, the data is fetched with [PreimageOracleClient::get] and passed
+    /// to `f` in chunks.
+    ///
+    /// # Returns
+    /// - `Ok(usize)` with the length of the preimage if all chunks were processed.
+    /// - `Err(_)` if the data could not be fetched from the host, or `f` returned an error.
+    async fn get_chunked(
+        &self,
+        key: PreimageKey,
+        chunk: &mut [u8],
+        f: &mut (dyn for<'a> FnMut(&'a [u8]) -> PreimageOracleResult<()> + Send),
+    ) -> PreimageOracleResult<usize>
+    where
+        Self: Sync,
+    {
+        if chunk.is_empty() {
+            return Err(PreimageOracleError::EmptyChunkBuffer);
+        }
+
+        let data = self.get(key).await?;
+        data.chunks(chunk.len()).try_for_each(f)?;
+        Ok(data.len())
+    }
 }
 
 /// A [HintWriterClient] is a high-level interface to the hint pipe. It provides a way to write
@@ -37,6 +95,22 @@ pub trait HintWriterClient {
     /// - `Ok(())` if the hint was successfully written to the host.
     /// - `Err(_)` if the hint could not be written to the host.
     async fn write(&self, hint: &str) -> PreimageOracleResult<()>;
+
+    /// Write a batch of hints to the host, in order. By default, the hints are written one at a
+    /// time. Implementations that support batched hint frames may send them in one round-trip.
+    ///
+    /// # Returns
+    /// - `Ok(())` if all hints were successfully written to the host.
+    /// - `Err(_)` if any hint could not be written to the host.
+    async fn write_batch(&self, hints: &[String]) -> PreimageOracleResult<()>
+    where
+        Self: Sync,
+    {
+        for hint in hints {
+            self.write(hint).await?;
+        }
+        Ok(())
+    }
 }
 
 /// A [CommsClient] is a trait that combines the [PreimageOracleClient] and [HintWriterClient]
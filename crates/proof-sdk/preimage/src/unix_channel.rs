@@ -0,0 +1,148 @@
+//! A [Channel] implementation over Unix domain sockets, backed by [tokio]'s [UnixStream].
+//!
+//! This allows the host and the client program to run as separate native processes, and a single
+//! host to accept connections from many client programs through a [UnixChannelListener].
+
+use crate::{
+    errors::{ChannelError, ChannelResult},
+    Channel,
+};
+use async_trait::async_trait;
+use std::{io, path::Path, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixListener, UnixStream,
+    },
+    sync::Mutex,
+};
+
+/// A [Channel] over a connected Unix domain socket.
+///
+/// The read and write halves of the socket are locked independently, so that one task may read
+/// from the channel while another writes to it.
+#[derive(Debug, Clone)]
+pub struct UnixChannel {
+    /// The read half of the socket.
+    read: Arc<Mutex<OwnedReadHalf>>,
+    /// The write half of the socket.
+    write: Arc<Mutex<OwnedWriteHalf>>,
+}
+
+impl UnixChannel {
+    /// Connects to the Unix domain socket at `path`.
+    pub async fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        UnixStream::connect(path).await.map(Self::from)
+    }
+
+    /// Creates a pair of connected [UnixChannel]s.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = UnixStream::pair()?;
+        Ok((a.into(), b.into()))
+    }
+}
+
+impl From<UnixStream> for UnixChannel {
+    fn from(stream: UnixStream) -> Self {
+        let (read, write) = stream.into_split();
+        Self { read: Arc::new(Mutex::new(read)), write: Arc::new(Mutex::new(write)) }
+    }
+}
+
+#[async_trait]
+impl Channel for UnixChannel {
+    async fn read(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        self.read.lock().await.read(buf).await.map_err(|_| ChannelError::Closed)
+    }
+
+    async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+        self.read.lock().await.read_exact(buf).await.map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => ChannelError::UnexpectedEOF,
+            _ => ChannelError::Closed,
+        })
+    }
+
+    async fn write(&self, buf: &[u8]) -> ChannelResult<usize> {
+        self.write.lock().await.write_all(buf).await.map_err(|_| ChannelError::Closed)?;
+        Ok(buf.len())
+    }
+}
+
+/// A listener for [UnixChannel] connections.
+#[derive(Debug)]
+pub struct UnixChannelListener {
+    listener: UnixListener,
+}
+
+impl UnixChannelListener {
+    /// Binds a listener to the Unix domain socket at `path`.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        UnixListener::bind(path).map(|listener| Self { listener })
+    }
+
+    /// Accepts the next connection to the listener.
+    pub async fn accept(&self) -> io::Result<UnixChannel> {
+        self.listener.accept().await.map(|(stream, _)| stream.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        errors::PreimageOracleResult, HintReader, HintReaderServer, HintRouter, HintWriter,
+        HintWriterClient,
+    };
+    use alloc::{boxed::Box, string::String, vec::Vec};
+
+    struct TestRouter(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl HintRouter for TestRouter {
+        async fn route_hint(&self, hint: String) -> PreimageOracleResult<()> {
+            self.0.lock().await.push(hint);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_unix_channel_partial_reads() {
+        let (a, b) = UnixChannel::pair().unwrap();
+        a.write(b"hello, ").await.unwrap();
+        a.write(b"world").await.unwrap();
+
+        let mut buf = [0u8; 12];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello, world");
+
+        drop(a);
+        assert!(matches!(b.read_exact(&mut buf).await, Err(ChannelError::UnexpectedEOF)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_unix_channel_listener() {
+        let dir = std::env::temp_dir().join(format!("kona-unix-channel-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hint.sock");
+        let _ = std::fs::remove_file(&path);
+
+        let listener = UnixChannelListener::bind(&path).unwrap();
+        let host = tokio::task::spawn(async move {
+            let router = TestRouter(Default::default());
+            let channel = listener.accept().await.unwrap();
+            let hint_reader = HintReader::new(channel);
+            for _ in 0..2 {
+                hint_reader.next_hint(&router).await.unwrap();
+            }
+            router.0.into_inner()
+        });
+
+        let channel = UnixChannel::connect(&path).await.unwrap();
+        let hint_writer = HintWriter::new(channel).negotiate_batching().await.unwrap();
+        hint_writer.write_batch(&["a 0x01".into(), "b 0x02".into()]).await.unwrap();
+
+        assert_eq!(host.await.unwrap(), ["a 0x01", "b 0x02"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -15,34 +15,12 @@ pub fn block_on<T>(f: impl Future<Output = T>) -> T {
     }
 }
 
-/// This function busy waits on a future until it is ready. It uses a no-op waker to poll the future
-/// in a thread-blocking loop.
+/// This function blocks on a future until it is ready, by running it on a new [LocalExecutor].
+///
+/// [LocalExecutor]: crate::LocalExecutor
 #[cfg(not(feature = "std"))]
 pub fn block_on<T>(f: impl Future<Output = T>) -> T {
-    use alloc::boxed::Box;
-    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
-
-    let mut f = Box::pin(f);
-
-    // Construct a no-op waker.
-    fn noop_clone(_: *const ()) -> RawWaker {
-        noop_raw_waker()
-    }
-    const fn noop(_: *const ()) {}
-    fn noop_raw_waker() -> RawWaker {
-        let vtable = &RawWakerVTable::new(noop_clone, noop, noop, noop);
-        RawWaker::new(core::ptr::null(), vtable)
-    }
-    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
-    let mut context = Context::from_waker(&waker);
-
-    loop {
-        // Safety: This is safe because we only poll the future once per loop iteration,
-        // and we do not move the future after pinning it.
-        if let Poll::Ready(v) = f.as_mut().poll(&mut context) {
-            return v;
-        }
-    }
+    crate::LocalExecutor::new().block_on(f)
 }
 
 #[cfg(test)]
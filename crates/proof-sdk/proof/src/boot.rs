@@ -1,7 +1,7 @@
 //! This module contains the prologue phase of the client program, pulling in the boot information
 //! through the `PreimageOracle` ABI as local keys.
 
-use crate::errors::OracleProviderError;
+use crate::{errors::OracleProviderError, rollup_config::rollup_config_hash};
 use alloy_primitives::{B256, U256};
 use kona_preimage::{PreimageKey, PreimageOracleClient};
 use op_alloy_genesis::RollupConfig;
@@ -33,6 +33,9 @@ pub const L2_ROLLUP_CONFIG_KEY: U256 = U256::from_be_slice(&[6]);
 ///   hash.
 /// - `agreed_l2_output_root`:The agreed upon safe L2 output root.
 /// - `chain_id`: The L2 chain ID.
+/// - `rollup_config`: The rollup config of the L2 chain, from the superchain registry. For chains
+///   that are not in it, the config is only verified if it is loaded with
+///   [BootInfo::load_with_rollup_config_hash].
 ///
 /// **User submitted inputs:**
 /// - `claimed_l2_output_root`: The L2 output root claim.
@@ -56,6 +59,9 @@ pub struct BootInfo {
 impl BootInfo {
     /// Load the boot information from the preimage oracle.
     ///
+    /// The rollup config of a chain that is not in the superchain registry is loaded from the
+    /// preimage oracle without verification, which is insecure in production.
+    ///
     /// ## Takes
     /// - `oracle`: The preimage oracle reader.
     ///
@@ -64,7 +70,33 @@ impl BootInfo {
     /// - `Err(_)`: Failed to load the boot information.
     pub async fn load<O>(oracle: &O) -> Result<Self, OracleProviderError>
     where
-        O: PreimageOracleClient + Send,
+        O: PreimageOracleClient + Send + Sync,
+    {
+        Self::load_with_rollup_config_hash(oracle, None).await
+    }
+
+    /// Load the boot information from the preimage oracle, as in [BootInfo::load], verifying the
+    /// rollup config of a chain that is not in the superchain registry against the expected
+    /// [rollup_config_hash], if one is given.
+    ///
+    /// The expected hash must be bound to the program, such as by compiling it in, so that it is
+    /// part of the absolute prestate. A hash that is read from the preimage oracle is chosen by the
+    /// host along with the config, and does not verify anything.
+    ///
+    /// ## Takes
+    /// - `oracle`: The preimage oracle reader.
+    /// - `expected_hash`: The expected hash of the rollup config of a custom chain, if any.
+    ///
+    /// ## Returns
+    /// - `Ok(BootInfo)`: The boot information.
+    /// - `Err(_)`: Failed to load the boot information, or the rollup config does not match the
+    ///   expected hash.
+    pub async fn load_with_rollup_config_hash<O>(
+        oracle: &O,
+        expected_hash: Option<B256>,
+    ) -> Result<Self, OracleProviderError>
+    where
+        O: PreimageOracleClient + Send + Sync,
     {
         let mut l1_head: B256 = B256::ZERO;
         oracle
@@ -104,20 +136,41 @@ impl BootInfo {
         );
 
         // Attempt to load the rollup config from the chain ID. If there is no config for the chain,
-        // fall back to loading the config from the preimage oracle.
+        // fall back to loading the config from the preimage oracle, and verifying it against the
+        // expected config hash if there is one.
         let rollup_config = if let Some(config) = ROLLUP_CONFIGS.get(&chain_id) {
             config.clone()
         } else {
-            warn!(
-                target: "boot-loader",
-                "No rollup config found for chain ID {}, falling back to preimage oracle. This is insecure in production without additional validation!",
-                chain_id
-            );
             let ser_cfg = oracle
-                .get(PreimageKey::new_local(L2_ROLLUP_CONFIG_KEY.to()))
+                .get_shared(PreimageKey::new_local(L2_ROLLUP_CONFIG_KEY.to()))
                 .await
                 .map_err(OracleProviderError::Preimage)?;
-            serde_json::from_slice(&ser_cfg).map_err(OracleProviderError::Serde)?
+            let config = serde_json::from_slice(&ser_cfg).map_err(OracleProviderError::Serde)?;
+
+            match expected_hash {
+                Some(expected_hash) => {
+                    let hash = rollup_config_hash(&config);
+                    if hash != expected_hash {
+                        error!(
+                            target: "boot-loader",
+                            "Rollup config hash {hash} does not match the expected hash {expected_hash}"
+                        );
+                        return Err(OracleProviderError::RollupConfigHash(expected_hash, hash));
+                    }
+                    info!(
+                        target: "boot-loader",
+                        "No rollup config found for chain ID {}, loaded the config with hash {} from the preimage oracle",
+                        chain_id,
+                        hash
+                    );
+                }
+                None => warn!(
+                    target: "boot-loader",
+                    "No rollup config found for chain ID {}, falling back to preimage oracle. This is insecure in production without additional validation!",
+                    chain_id
+                ),
+            }
+            config
         };
 
         Ok(Self {
@@ -130,3 +183,67 @@ impl BootInfo {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_on;
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use async_trait::async_trait;
+    use kona_preimage::errors::{PreimageOracleError, PreimageOracleResult};
+
+    /// A [PreimageOracleClient] that serves the local preimages of a custom chain, whose rollup
+    /// config is the config of OP Mainnet with another chain ID.
+    struct CustomChainOracle {
+        rollup_config: RollupConfig,
+    }
+
+    impl CustomChainOracle {
+        const CHAIN_ID: u64 = 0xdead;
+
+        fn new() -> Self {
+            let mut rollup_config = ROLLUP_CONFIGS.get(&10).unwrap().clone();
+            rollup_config.l2_chain_id = Self::CHAIN_ID;
+            Self { rollup_config }
+        }
+    }
+
+    #[async_trait]
+    impl PreimageOracleClient for CustomChainOracle {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            match key.key_value() {
+                L1_HEAD_KEY | L2_OUTPUT_ROOT_KEY | L2_CLAIM_KEY => Ok(vec![0; 32]),
+                L2_CLAIM_BLOCK_NUMBER_KEY => Ok(1u64.to_be_bytes().to_vec()),
+                L2_CHAIN_ID_KEY => Ok(Self::CHAIN_ID.to_be_bytes().to_vec()),
+                L2_ROLLUP_CONFIG_KEY => Ok(serde_json::to_vec(&self.rollup_config).unwrap()),
+                _ => Err(PreimageOracleError::KeyNotFound),
+            }
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            buf.copy_from_slice(&self.get(key).await?);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_load_with_rollup_config_hash() {
+        let oracle = CustomChainOracle::new();
+        let hash = rollup_config_hash(&oracle.rollup_config);
+
+        // Without an expected hash, any config is accepted.
+        let boot = block_on(BootInfo::load(&oracle)).unwrap();
+        assert_eq!(boot.rollup_config, oracle.rollup_config);
+
+        let boot = block_on(BootInfo::load_with_rollup_config_hash(&oracle, Some(hash))).unwrap();
+        assert_eq!(boot.rollup_config, oracle.rollup_config);
+
+        let wrong = B256::repeat_byte(1);
+        let result = block_on(BootInfo::load_with_rollup_config_hash(&oracle, Some(wrong)));
+        assert!(matches!(
+            result,
+            Err(OracleProviderError::RollupConfigHash(expected, actual))
+                if expected == wrong && actual == hash
+        ));
+    }
+}
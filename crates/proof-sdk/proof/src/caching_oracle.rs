@@ -1,14 +1,18 @@
 //! Contains the [CachingOracle], which is a wrapper around an [OracleReader] and [HintWriter] that
-//! stores a configurable number of responses in an [LruCache] for quick retrieval.
+//! stores a configurable number of responses in an [LruCache] for quick retrieval. Hints are
+//! queued, and written to the host in one batch before the next preimage that misses the cache is
+//! requested. Hints that are queued after the last request must be written with
+//! [CachingOracle::flush_hints] before the program exits.
 //!
 //! [OracleReader]: kona_preimage::OracleReader
 //! [HintWriter]: kona_preimage::HintWriter
 
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use async_trait::async_trait;
 use core::num::NonZeroUsize;
 use kona_preimage::{
-    errors::PreimageOracleResult, HintWriterClient, PreimageKey, PreimageOracleClient,
+    errors::{PreimageOracleError, PreimageOracleResult},
+    HintWriterClient, PreimageKey, PreimageOracleClient,
 };
 use lru::LruCache;
 use spin::Mutex;
@@ -25,12 +29,15 @@ where
     OR: PreimageOracleClient,
     HW: HintWriterClient,
 {
-    /// The spin-locked cache that stores the responses from the oracle.
-    cache: Arc<Mutex<LruCache<PreimageKey, Vec<u8>>>>,
+    /// The spin-locked cache that stores the responses from the oracle. Entries are shared with
+    /// the callers of [PreimageOracleClient::get_shared], rather than copied.
+    cache: Arc<Mutex<LruCache<PreimageKey, Arc<[u8]>>>>,
     /// Oracle reader type.
     oracle_reader: OR,
     /// Hint writer type.
     hint_writer: HW,
+    /// The hints that have not yet been written to the host.
+    pending_hints: Arc<Mutex<Vec<String>>>,
 }
 
 impl<OR, HW> CachingOracle<OR, HW>
@@ -49,10 +56,26 @@ where
             ))),
             oracle_reader,
             hint_writer,
+            pending_hints: Default::default(),
         }
     }
 }
 
+impl<OR, HW> CachingOracle<OR, HW>
+where
+    OR: PreimageOracleClient,
+    HW: HintWriterClient + Sync,
+{
+    /// Writes the queued hints to the host in one batch.
+    pub async fn flush_hints(&self) -> PreimageOracleResult<()> {
+        let hints = core::mem::take(&mut *self.pending_hints.lock());
+        if hints.is_empty() {
+            return Ok(());
+        }
+        self.hint_writer.write_batch(&hints).await
+    }
+}
+
 /// A trait that provides a method to flush a cache.
 pub trait FlushableCache {
     /// Flushes the cache, removing all entries.
@@ -77,29 +100,67 @@ where
     HW: HintWriterClient + Sync,
 {
     async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
-        let mut cache_lock = self.cache.lock();
-        if let Some(value) = cache_lock.get(&key) {
-            Ok(value.clone())
-        } else {
-            let value = self.oracle_reader.get(key).await?;
-            cache_lock.put(key, value.clone());
-            Ok(value)
-        }
+        self.get_shared(key).await.map(|value| value.to_vec())
     }
 
     async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
-        let mut cache_lock = self.cache.lock();
-        if let Some(value) = cache_lock.get(&key) {
-            // SAFETY: The value never enters the cache unless the preimage length matches the
-            // buffer length, due to the checks in the OracleReader.
-            buf.copy_from_slice(value.as_slice());
+        let cached = self.cache.lock().get(&key).cloned();
+        if let Some(value) = cached {
+            if value.len() != buf.len() {
+                return Err(PreimageOracleError::BufferLengthMismatch(value.len(), buf.len()));
+            }
+            buf.copy_from_slice(&value);
             Ok(())
         } else {
+            self.flush_hints().await?;
             self.oracle_reader.get_exact(key, buf).await?;
-            cache_lock.put(key, buf.to_vec());
+            self.cache.lock().put(key, Arc::from(&*buf));
             Ok(())
         }
     }
+
+    async fn get_into(&self, key: PreimageKey, buf: &mut Vec<u8>) -> PreimageOracleResult<()> {
+        let value = self.get_shared(key).await?;
+        buf.clear();
+        buf.extend_from_slice(&value);
+        Ok(())
+    }
+
+    /// Returns the cached preimage, sharing the allocation with the cache. On a cache miss, the
+    /// preimage is read from the host directly into the shared allocation.
+    async fn get_shared(&self, key: PreimageKey) -> PreimageOracleResult<Arc<[u8]>> {
+        let cached = self.cache.lock().get(&key).cloned();
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        self.flush_hints().await?;
+        let value = self.oracle_reader.get_shared(key).await?;
+        self.cache.lock().put(key, Arc::clone(&value));
+        Ok(value)
+    }
+
+    /// Passes the cached preimage to `f` in chunks. On a cache miss, the preimage is streamed from
+    /// the host and is not cached, so that it is never held in memory in full.
+    async fn get_chunked(
+        &self,
+        key: PreimageKey,
+        chunk: &mut [u8],
+        f: &mut (dyn for<'a> FnMut(&'a [u8]) -> PreimageOracleResult<()> + Send),
+    ) -> PreimageOracleResult<usize> {
+        if chunk.is_empty() {
+            return Err(PreimageOracleError::EmptyChunkBuffer);
+        }
+
+        let cached = self.cache.lock().get(&key).cloned();
+        if let Some(value) = cached {
+            value.chunks(chunk.len()).try_for_each(f)?;
+            return Ok(value.len());
+        }
+
+        self.flush_hints().await?;
+        self.oracle_reader.get_chunked(key, chunk, f).await
+    }
 }
 
 #[async_trait]
@@ -108,7 +169,15 @@ where
     OR: PreimageOracleClient + Sync,
     HW: HintWriterClient + Sync,
 {
+    /// Queues a hint, to be written to the host before the next preimage that misses the cache
+    /// is requested.
     async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
-        self.hint_writer.write(hint).await
+        self.pending_hints.lock().push(hint.into());
+        Ok(())
+    }
+
+    async fn write_batch(&self, hints: &[String]) -> PreimageOracleResult<()> {
+        self.pending_hints.lock().extend_from_slice(hints);
+        Ok(())
     }
 }
@@ -1,6 +1,7 @@
 //! Error types for the proof program.
 
 use alloc::string::{String, ToString};
+use alloy_primitives::B256;
 use kona_derive::errors::{PipelineError, PipelineErrorKind};
 use kona_mpt::OrderedListWalkerError;
 use kona_preimage::errors::PreimageOracleError;
@@ -34,6 +35,12 @@ pub enum OracleProviderError {
     /// Serde error.
     #[error("Serde error: {0}")]
     Serde(serde_json::Error),
+    /// A global generic preimage does not match its commitment.
+    #[error("Invalid preimage for commitment in namespace {0}")]
+    InvalidGenericPreimage(B256),
+    /// The hash of the rollup config does not match the expected hash.
+    #[error("Rollup config hash mismatch. Expected {0}, actual {1}")]
+    RollupConfigHash(B256, B256),
 }
 
 impl From<OracleProviderError> for PipelineErrorKind {
@@ -49,3 +56,48 @@ impl From<OracleProviderError> for PipelineErrorKind {
 #[derive(Error, Debug)]
 #[error("Hint parsing error: {_0}")]
 pub struct HintParsingError(pub String);
+
+/// An internal inconsistency in a rollup config.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RollupConfigError {
+    /// A hardfork is scheduled, but the hardfork before it is not.
+    #[error("Hardfork {fork} is scheduled at {time}, but the prior hardfork {prior} is not")]
+    MissingPriorHardfork {
+        /// The name of the unscheduled prior hardfork.
+        prior: &'static str,
+        /// The name of the scheduled hardfork.
+        fork: &'static str,
+        /// The activation time of the scheduled hardfork.
+        time: u64,
+    },
+    /// A hardfork activates before the hardfork before it.
+    #[error(
+        "Hardfork {fork} activates at {time}, before the prior hardfork {prior} at {prior_time}"
+    )]
+    HardforkOrder {
+        /// The name of the prior hardfork.
+        prior: &'static str,
+        /// The activation time of the prior hardfork.
+        prior_time: u64,
+        /// The name of the hardfork.
+        fork: &'static str,
+        /// The activation time of the hardfork.
+        time: u64,
+    },
+    /// The block time is zero.
+    #[error("Block time is zero")]
+    ZeroBlockTime,
+    /// A channel timeout is outside of its valid range.
+    #[error("Channel timeout `{field}` of {timeout} L1 blocks is not between 1 and {max}")]
+    ChannelTimeoutRange {
+        /// The name of the channel timeout field.
+        field: &'static str,
+        /// The channel timeout, in L1 blocks.
+        timeout: u64,
+        /// The maximum channel timeout, in L1 blocks.
+        max: u64,
+    },
+    /// The genesis has no system config.
+    #[error("Genesis system config is missing")]
+    MissingSystemConfig,
+}
@@ -0,0 +1,103 @@
+//! Client-side support for [PreimageKeyType::GlobalGeneric] preimages, which are committed to by
+//! custom commitment schemes rather than by one of the built-in preimage key types.
+//!
+//! [PreimageKeyType::GlobalGeneric]: kona_preimage::PreimageKeyType::GlobalGeneric
+
+use crate::{errors::OracleProviderError, Hint};
+use alloc::vec::Vec;
+use alloy_primitives::{b256, keccak256, Bytes, B256};
+use kona_preimage::{CommsClient, PreimageKey};
+
+/// A commitment scheme for [PreimageKeyType::GlobalGeneric] preimages.
+///
+/// The namespace of a scheme separates its keys from the keys of other schemes, and its
+/// verification hook checks preimages served by the host against their commitments. The host is
+/// not trusted to serve the correct preimage for a global generic key, so [fetch_global_generic]
+/// only returns preimages that the scheme has verified.
+///
+/// [PreimageKeyType::GlobalGeneric]: kona_preimage::PreimageKeyType::GlobalGeneric
+pub trait CommitmentScheme {
+    /// The namespace of the commitment scheme.
+    const NAMESPACE: B256;
+
+    /// Returns whether `preimage` is the preimage of `commitment` under the scheme.
+    fn verify(commitment: &[u8], preimage: &[u8]) -> bool;
+
+    /// Returns the [PreimageKey] of the preimage of `commitment` under the scheme.
+    fn key(commitment: &[u8]) -> PreimageKey {
+        PreimageKey::new_global_generic(Self::NAMESPACE, commitment)
+    }
+
+    /// Returns the [Hint] that requests the preimage of `commitment` under the scheme.
+    fn hint(commitment: &[u8]) -> Hint {
+        Hint::GlobalGeneric {
+            namespace: Self::NAMESPACE,
+            commitment: Bytes::copy_from_slice(commitment),
+        }
+    }
+}
+
+/// The keccak256 commitment scheme, where the commitment is the `keccak256` digest of the
+/// preimage, as used by keccak256 alt-DA commitments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256Commitment;
+
+impl CommitmentScheme for Keccak256Commitment {
+    /// `keccak256("kona.global-generic.keccak256")`
+    const NAMESPACE: B256 =
+        b256!("7e792fe7c2340be3a81c09633fb9c38ce372e7018f2a799910c2f0e4caef121c");
+
+    fn verify(commitment: &[u8], preimage: &[u8]) -> bool {
+        commitment == keccak256(preimage).as_slice()
+    }
+}
+
+/// Fetches the preimage of `commitment` under the commitment scheme `S` from the oracle, and
+/// verifies it with [CommitmentScheme::verify].
+///
+/// ## Returns
+/// - `Ok(preimage)`: The verified preimage.
+/// - `Err(_)`: The preimage could not be fetched, or does not match the commitment.
+pub async fn fetch_global_generic<S, O>(
+    oracle: &O,
+    commitment: &[u8],
+) -> Result<Vec<u8>, OracleProviderError>
+where
+    S: CommitmentScheme,
+    O: CommsClient + Send + Sync,
+{
+    oracle.write(&S::hint(commitment).encode()).await.map_err(OracleProviderError::Preimage)?;
+    let preimage = oracle.get(S::key(commitment)).await.map_err(OracleProviderError::Preimage)?;
+
+    if !S::verify(commitment, &preimage) {
+        return Err(OracleProviderError::InvalidGenericPreimage(S::NAMESPACE));
+    }
+    Ok(preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_preimage::PreimageKeyType;
+
+    #[test]
+    fn test_keccak256_commitment() {
+        assert_eq!(Keccak256Commitment::NAMESPACE, keccak256("kona.global-generic.keccak256"));
+
+        let preimage = b"alt-da input";
+        let commitment = keccak256(preimage);
+        assert!(Keccak256Commitment::verify(commitment.as_slice(), preimage));
+        assert!(!Keccak256Commitment::verify(commitment.as_slice(), b"other input"));
+
+        let key = Keccak256Commitment::key(commitment.as_slice());
+        assert_eq!(key.key_type(), PreimageKeyType::GlobalGeneric);
+        assert_eq!(
+            key,
+            PreimageKey::new_global_generic(Keccak256Commitment::NAMESPACE, commitment.as_slice())
+        );
+        assert_eq!(
+            Keccak256Commitment::hint(commitment.as_slice()).payload(),
+            [Keccak256Commitment::NAMESPACE.as_slice(), commitment.as_slice()].concat()
+        );
+    }
+}
@@ -1,26 +1,247 @@
-//! This module contains the [HintType] enum.
+//! This module contains the [Hint] and [HintType] enums.
 
 use crate::errors::HintParsingError;
 use alloc::{
+    boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use alloy_primitives::{hex, Bytes};
+use alloy_primitives::{hex, Address, Bytes, B256};
 use core::fmt::Display;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
 
-/// A [Hint] is parsed in the format `<hint_type> <hint_data>`, where `<hint_type>` is a string that
-/// represents the type of hint, and `<hint_data>` is the data associated with the hint (bytes
-/// encoded as hex UTF-8).
+/// A [Hint] is a typed request for the host to prefetch a set of preimages.
+///
+/// Hints are sent over the wire in the format `<hint_type> <hint_data>`, where `<hint_type>` is
+/// the [HintType] of the hint, and `<hint_data>` is the hex encoded payload of the hint. The layout
+/// of each payload is documented on its variant, and is shared between the client and the host
+/// through [Hint::encode] and [Hint::parse].
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Hint {
-    /// The type of hint.
-    pub hint_type: HintType,
-    /// The data associated with the hint.
-    pub hint_data: Bytes,
+pub enum Hint {
+    /// Requests the block header of a layer 1 block.
+    ///
+    /// Payload: `block_hash (32)`.
+    L1BlockHeader(B256),
+    /// Requests the transactions of a layer 1 block.
+    ///
+    /// Payload: `block_hash (32)`.
+    L1Transactions(B256),
+    /// Requests the receipts of a layer 1 block.
+    ///
+    /// Payload: `block_hash (32)`.
+    L1Receipts(B256),
+    /// Requests a blob in the layer 1 beacon chain.
+    ///
+    /// Payload: `versioned_hash (32) ‖ index (8, big-endian) ‖ timestamp (8, big-endian)`.
+    L1Blob {
+        /// The versioned hash of the blob.
+        hash: B256,
+        /// The index of the blob within its block.
+        index: u64,
+        /// The timestamp of the block that contains the blob.
+        timestamp: u64,
+    },
+    /// Requests the result of a precompile call on layer 1.
+    ///
+    /// Payload: `address (20) ‖ input (variable)`.
+    L1Precompile {
+        /// The address of the precompile.
+        address: Address,
+        /// The input to the precompile call.
+        input: Bytes,
+    },
+    /// Requests the block header of a layer 2 block.
+    ///
+    /// Payload: `block_hash (32)`.
+    L2BlockHeader(B256),
+    /// Requests the transactions of a layer 2 block.
+    ///
+    /// Payload: `block_hash (32)`.
+    L2Transactions(B256),
+    /// Requests the code of a contract on layer 2.
+    ///
+    /// Payload: `code_hash (32)`.
+    L2Code(B256),
+    /// Requests the preimage of the starting L2 output root.
+    ///
+    /// Payload: `output_root (32)`.
+    StartingL2Output(B256),
+    /// Requests a node in the L2 state trie.
+    ///
+    /// Payload: `node_hash (32)`.
+    L2StateNode(B256),
+    /// Requests the proof on the path to an account in the L2 state trie.
+    ///
+    /// Payload: `block_number (8, big-endian) ‖ address (20)`.
+    L2AccountProof {
+        /// The number of the block whose state is proven.
+        block_number: u64,
+        /// The address of the account.
+        address: Address,
+    },
+    /// Requests the proof on the path to a storage slot in an account within the L2 state trie.
+    ///
+    /// Payload: `block_number (8, big-endian) ‖ address (20) ‖ slot (32)`.
+    L2AccountStorageProof {
+        /// The number of the block whose state is proven.
+        block_number: u64,
+        /// The address of the account.
+        address: Address,
+        /// The storage slot.
+        slot: B256,
+    },
+    /// Requests bulk storage of all the code, state and keys generated by an execution witness.
+    ///
+    /// Payload: `parent_block_hash (32) ‖ payload_attributes (variable, JSON)`.
+    L2PayloadWitness {
+        /// The hash of the parent of the block to execute.
+        parent_block_hash: B256,
+        /// The attributes of the payload to execute.
+        payload_attributes: Box<OpPayloadAttributes>,
+    },
+    /// Requests a preimage under a custom commitment scheme, keyed by a
+    /// [PreimageKeyType::GlobalGeneric] key.
+    ///
+    /// Payload: `namespace (32) ‖ commitment (variable)`.
+    ///
+    /// [PreimageKeyType::GlobalGeneric]: kona_preimage::PreimageKeyType::GlobalGeneric
+    GlobalGeneric {
+        /// The namespace of the commitment scheme.
+        namespace: B256,
+        /// The commitment to the preimage.
+        commitment: Bytes,
+    },
 }
 
 impl Hint {
-    /// Parses a hint from a string.
+    /// Returns the [HintType] of the hint.
+    pub const fn hint_type(&self) -> HintType {
+        match self {
+            Self::L1BlockHeader(_) => HintType::L1BlockHeader,
+            Self::L1Transactions(_) => HintType::L1Transactions,
+            Self::L1Receipts(_) => HintType::L1Receipts,
+            Self::L1Blob { .. } => HintType::L1Blob,
+            Self::L1Precompile { .. } => HintType::L1Precompile,
+            Self::L2BlockHeader(_) => HintType::L2BlockHeader,
+            Self::L2Transactions(_) => HintType::L2Transactions,
+            Self::L2Code(_) => HintType::L2Code,
+            Self::StartingL2Output(_) => HintType::StartingL2Output,
+            Self::L2StateNode(_) => HintType::L2StateNode,
+            Self::L2AccountProof { .. } => HintType::L2AccountProof,
+            Self::L2AccountStorageProof { .. } => HintType::L2AccountStorageProof,
+            Self::L2PayloadWitness { .. } => HintType::L2PayloadWitness,
+            Self::GlobalGeneric { .. } => HintType::GlobalGeneric,
+        }
+    }
+
+    /// Returns the raw payload of the hint.
+    pub fn payload(&self) -> Vec<u8> {
+        match self {
+            Self::L1BlockHeader(hash) |
+            Self::L1Transactions(hash) |
+            Self::L1Receipts(hash) |
+            Self::L2BlockHeader(hash) |
+            Self::L2Transactions(hash) |
+            Self::L2Code(hash) |
+            Self::StartingL2Output(hash) |
+            Self::L2StateNode(hash) => hash.to_vec(),
+            Self::L1Blob { hash, index, timestamp } => {
+                [hash.as_slice(), &index.to_be_bytes(), &timestamp.to_be_bytes()].concat()
+            }
+            Self::L1Precompile { address, input } => [address.as_slice(), input.as_ref()].concat(),
+            Self::L2AccountProof { block_number, address } => {
+                [&block_number.to_be_bytes(), address.as_slice()].concat()
+            }
+            Self::L2AccountStorageProof { block_number, address, slot } => {
+                [&block_number.to_be_bytes(), address.as_slice(), slot.as_slice()].concat()
+            }
+            Self::L2PayloadWitness { parent_block_hash, payload_attributes } => {
+                let attributes = serde_json::to_vec(payload_attributes.as_ref())
+                    .expect("Payload attributes are serializable");
+                [parent_block_hash.as_slice(), &attributes].concat()
+            }
+            Self::GlobalGeneric { namespace, commitment } => {
+                [namespace.as_slice(), commitment.as_ref()].concat()
+            }
+        }
+    }
+
+    /// Encodes the hint in the wire format, `<hint_type> <hint_data>`.
+    pub fn encode(&self) -> String {
+        alloc::format!("{} {}", self.hint_type(), hex::encode(self.payload()))
+    }
+
+    /// Decodes a hint of the given [HintType] from its raw payload.
+    pub fn decode(hint_type: HintType, data: &[u8]) -> Result<Self, HintParsingError> {
+        let hint = match hint_type {
+            HintType::L1BlockHeader => Self::L1BlockHeader(Self::fixed(hint_type, data)?.into()),
+            HintType::L1Transactions => Self::L1Transactions(Self::fixed(hint_type, data)?.into()),
+            HintType::L1Receipts => Self::L1Receipts(Self::fixed(hint_type, data)?.into()),
+            HintType::L1Blob => {
+                let data = Self::fixed::<48>(hint_type, data)?;
+                Self::L1Blob {
+                    hash: B256::from_slice(&data[..32]),
+                    index: u64::from_be_bytes(data[32..40].try_into().expect("length checked")),
+                    timestamp: u64::from_be_bytes(data[40..].try_into().expect("length checked")),
+                }
+            }
+            HintType::L1Precompile => {
+                if data.len() < 20 {
+                    return Err(Self::length_error(hint_type, "at least 20", data.len()));
+                }
+                Self::L1Precompile {
+                    address: Address::from_slice(&data[..20]),
+                    input: Bytes::copy_from_slice(&data[20..]),
+                }
+            }
+            HintType::L2BlockHeader => Self::L2BlockHeader(Self::fixed(hint_type, data)?.into()),
+            HintType::L2Transactions => Self::L2Transactions(Self::fixed(hint_type, data)?.into()),
+            HintType::L2Code => Self::L2Code(Self::fixed(hint_type, data)?.into()),
+            HintType::StartingL2Output => {
+                Self::StartingL2Output(Self::fixed(hint_type, data)?.into())
+            }
+            HintType::L2StateNode => Self::L2StateNode(Self::fixed(hint_type, data)?.into()),
+            HintType::L2AccountProof => {
+                let data = Self::fixed::<28>(hint_type, data)?;
+                Self::L2AccountProof {
+                    block_number: u64::from_be_bytes(data[..8].try_into().expect("length checked")),
+                    address: Address::from_slice(&data[8..]),
+                }
+            }
+            HintType::L2AccountStorageProof => {
+                let data = Self::fixed::<60>(hint_type, data)?;
+                Self::L2AccountStorageProof {
+                    block_number: u64::from_be_bytes(data[..8].try_into().expect("length checked")),
+                    address: Address::from_slice(&data[8..28]),
+                    slot: B256::from_slice(&data[28..]),
+                }
+            }
+            HintType::L2PayloadWitness => {
+                if data.len() < 32 {
+                    return Err(Self::length_error(hint_type, "at least 32", data.len()));
+                }
+                let payload_attributes = serde_json::from_slice(&data[32..]).map_err(|e| {
+                    HintParsingError(alloc::format!("Invalid payload attributes: {e}"))
+                })?;
+                Self::L2PayloadWitness {
+                    parent_block_hash: B256::from_slice(&data[..32]),
+                    payload_attributes: Box::new(payload_attributes),
+                }
+            }
+            HintType::GlobalGeneric => {
+                if data.len() < 32 {
+                    return Err(Self::length_error(hint_type, "at least 32", data.len()));
+                }
+                Self::GlobalGeneric {
+                    namespace: B256::from_slice(&data[..32]),
+                    commitment: Bytes::copy_from_slice(&data[32..]),
+                }
+            }
+        };
+        Ok(hint)
+    }
+
+    /// Parses a hint from its wire format, `<hint_type> <hint_data>`.
     pub fn parse(s: &str) -> Result<Self, HintParsingError> {
         let mut parts = s.split(' ').collect::<Vec<_>>();
 
@@ -30,14 +251,30 @@ impl Hint {
 
         let hint_type = HintType::try_from(parts.remove(0))?;
         let hint_data =
-            hex::decode(parts.remove(0)).map_err(|e| HintParsingError(e.to_string()))?.into();
+            hex::decode(parts.remove(0)).map_err(|e| HintParsingError(e.to_string()))?;
+
+        Self::decode(hint_type, &hint_data)
+    }
 
-        Ok(Self { hint_type, hint_data })
+    /// Checks that the payload of a fixed-size hint has the expected length.
+    fn fixed<const N: usize>(
+        hint_type: HintType,
+        data: &[u8],
+    ) -> Result<[u8; N], HintParsingError> {
+        data.try_into().map_err(|_| Self::length_error(hint_type, &N.to_string(), data.len()))
     }
 
-    /// Splits the [Hint] into its components.
-    pub fn split(self) -> (HintType, Bytes) {
-        (self.hint_type, self.hint_data)
+    /// Constructs the error for a payload of an invalid length.
+    fn length_error(hint_type: HintType, expected: &str, actual: usize) -> HintParsingError {
+        HintParsingError(alloc::format!(
+            "Invalid hint data length for {hint_type}: expected {expected} bytes, got {actual}"
+        ))
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.encode())
     }
 }
 
@@ -72,10 +309,14 @@ pub enum HintType {
     /// A hint that specifies bulk storage of all the code, state and keys generated by an
     /// execution witness.
     L2PayloadWitness,
+    /// A hint that specifies a preimage under a custom commitment scheme.
+    GlobalGeneric,
 }
 
 impl HintType {
-    /// Encodes the hint type as a string.
+    /// Encodes the hint type with the concatenation of the given raw data as its payload.
+    ///
+    /// Prefer [Hint::encode], which enforces the payload layout of each [HintType].
     pub fn encode_with(&self, data: &[&[u8]]) -> String {
         let concatenated = hex::encode(data.iter().copied().flatten().copied().collect::<Vec<_>>());
         alloc::format!("{} {}", self, concatenated)
@@ -100,6 +341,7 @@ impl TryFrom<&str> for HintType {
             "l2-account-proof" => Ok(Self::L2AccountProof),
             "l2-account-storage-proof" => Ok(Self::L2AccountStorageProof),
             "l2-payload-witness" => Ok(Self::L2PayloadWitness),
+            "global-generic" => Ok(Self::GlobalGeneric),
             _ => Err(HintParsingError(value.to_string())),
         }
     }
@@ -121,6 +363,7 @@ impl From<HintType> for &str {
             HintType::L2AccountProof => "l2-account-proof",
             HintType::L2AccountStorageProof => "l2-account-storage-proof",
             HintType::L2PayloadWitness => "l2-payload-witness",
+            HintType::GlobalGeneric => "global-generic",
         }
     }
 }
@@ -131,3 +374,89 @@ impl Display for HintType {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::{address, b256, bytes};
+
+    fn hints() -> Vec<Hint> {
+        let hash = b256!("0102030405060708091011121314151617181920212223242526272829303132");
+        let address = address!("4200000000000000000000000000000000000016");
+        vec![
+            Hint::L1BlockHeader(hash),
+            Hint::L1Transactions(hash),
+            Hint::L1Receipts(hash),
+            Hint::L1Blob { hash, index: 3, timestamp: 1_700_000_000 },
+            Hint::L1Precompile { address, input: bytes!("deadbeef") },
+            Hint::L1Precompile { address, input: Bytes::new() },
+            Hint::L2BlockHeader(hash),
+            Hint::L2Transactions(hash),
+            Hint::L2Code(hash),
+            Hint::StartingL2Output(hash),
+            Hint::L2StateNode(hash),
+            Hint::L2AccountProof { block_number: 42, address },
+            Hint::L2AccountStorageProof { block_number: 42, address, slot: hash },
+            Hint::L2PayloadWitness {
+                parent_block_hash: hash,
+                payload_attributes: Box::new(OpPayloadAttributes {
+                    gas_limit: Some(30_000_000),
+                    no_tx_pool: Some(true),
+                    ..Default::default()
+                }),
+            },
+            Hint::GlobalGeneric { namespace: hash, commitment: bytes!("c0ffee") },
+        ]
+    }
+
+    #[test]
+    fn test_hint_round_trip() {
+        for hint in hints() {
+            let encoded = hint.encode();
+            assert!(encoded.starts_with(&alloc::format!("{} ", hint.hint_type())));
+            assert_eq!(Hint::parse(&encoded).unwrap(), hint);
+            assert_eq!(Hint::decode(hint.hint_type(), &hint.payload()).unwrap(), hint);
+        }
+    }
+
+    #[test]
+    fn test_hint_payload_layout() {
+        let hash = B256::repeat_byte(0xaa);
+        let hint = Hint::L1Blob { hash, index: 1, timestamp: 2 };
+        assert_eq!(
+            hint.encode(),
+            HintType::L1Blob.encode_with(&[
+                hash.as_ref(),
+                &1u64.to_be_bytes(),
+                &2u64.to_be_bytes()
+            ])
+        );
+
+        let address = Address::repeat_byte(0xbb);
+        let hint = Hint::L2AccountStorageProof { block_number: 7, address, slot: hash };
+        let payload = hint.payload();
+        assert_eq!(payload.len(), 60);
+        assert_eq!(&payload[..8], &7u64.to_be_bytes());
+        assert_eq!(&payload[8..28], address.as_slice());
+        assert_eq!(&payload[28..], hash.as_slice());
+    }
+
+    #[test]
+    fn test_hint_parse_invalid() {
+        assert!(Hint::parse("l1-block-header").is_err());
+        assert!(Hint::parse("l1-block-header 00 00").is_err());
+        assert!(Hint::parse("l1-block-headers 00").is_err());
+        assert!(Hint::parse("l1-block-header zz").is_err());
+
+        let err = Hint::parse("l1-block-header 0011").unwrap_err();
+        assert_eq!(err.0, "Invalid hint data length for l1-block-header: expected 32 bytes, got 2");
+        let err = Hint::decode(HintType::L1Precompile, &[0u8; 19]).unwrap_err();
+        assert_eq!(
+            err.0,
+            "Invalid hint data length for l1-precompile: expected at least 20 bytes, got 19"
+        );
+        assert!(Hint::decode(HintType::L1Blob, &[0u8; 40]).is_err());
+        assert!(Hint::decode(HintType::L2PayloadWitness, &[0u8; 33]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! Contains the concrete implementation of the [BlobProvider] trait for the client program.
 
-use crate::{errors::OracleProviderError, HintType};
+use crate::{errors::OracleProviderError, Hint};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::Blob;
 use alloy_eips::eip4844::{IndexedBlobHash, FIELD_ELEMENTS_PER_BLOB};
@@ -36,16 +36,13 @@ impl<T: CommsClient> OracleBlobProvider<T> {
         block_ref: &BlockInfo,
         blob_hash: &IndexedBlobHash,
     ) -> Result<Blob, OracleProviderError> {
-        let mut blob_req_meta = [0u8; 48];
-        blob_req_meta[0..32].copy_from_slice(blob_hash.hash.as_ref());
-        blob_req_meta[32..40].copy_from_slice((blob_hash.index).to_be_bytes().as_ref());
-        blob_req_meta[40..48].copy_from_slice(block_ref.timestamp.to_be_bytes().as_ref());
-
         // Send a hint for the blob commitment and field elements.
-        self.oracle
-            .write(&HintType::L1Blob.encode_with(&[blob_req_meta.as_ref()]))
-            .await
-            .map_err(OracleProviderError::Preimage)?;
+        let hint = Hint::L1Blob {
+            hash: blob_hash.hash,
+            index: blob_hash.index,
+            timestamp: block_ref.timestamp,
+        };
+        self.oracle.write(&hint.encode()).await.map_err(OracleProviderError::Preimage)?;
 
         // Fetch the blob commitment.
         let mut commitment = [0u8; 48];
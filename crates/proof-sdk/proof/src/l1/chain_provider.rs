@@ -1,6 +1,6 @@
 //! Contains the concrete implementation of the [ChainProvider] trait for the proof.
 
-use crate::{errors::OracleProviderError, BootInfo, HintType};
+use crate::{errors::OracleProviderError, BootInfo, Hint};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{Header, Receipt, ReceiptEnvelope, TxEnvelope};
 use alloy_eips::eip2718::Decodable2718;
@@ -35,19 +35,19 @@ impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
     async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
         // Send a hint for the block header.
         self.oracle
-            .write(&HintType::L1BlockHeader.encode_with(&[hash.as_ref()]))
+            .write(&Hint::L1BlockHeader(hash).encode())
             .await
             .map_err(OracleProviderError::Preimage)?;
 
         // Fetch the header RLP from the oracle.
         let header_rlp = self
             .oracle
-            .get(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
+            .get_shared(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
             .await
             .map_err(OracleProviderError::Preimage)?;
 
         // Decode the header RLP into a Header.
-        Header::decode(&mut header_rlp.as_slice()).map_err(OracleProviderError::Rlp)
+        Header::decode(&mut header_rlp.as_ref()).map_err(OracleProviderError::Rlp)
     }
 
     async fn block_info_by_number(&mut self, block_number: u64) -> Result<BlockInfo, Self::Error> {
@@ -79,7 +79,7 @@ impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
         // Send a hint for the block's receipts, and walk through the receipts trie in the header to
         // verify them.
         self.oracle
-            .write(&HintType::L1Receipts.encode_with(&[hash.as_ref()]))
+            .write(&Hint::L1Receipts(hash).encode())
             .await
             .map_err(OracleProviderError::Preimage)?;
         let trie_walker = OrderedListWalker::try_new_hydrated(header.receipts_root, self)
@@ -114,7 +114,7 @@ impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
         // Send a hint for the block's transactions, and walk through the transactions trie in the
         // header to verify them.
         self.oracle
-            .write(&HintType::L1Transactions.encode_with(&[hash.as_ref()]))
+            .write(&Hint::L1Transactions(hash).encode())
             .await
             .map_err(OracleProviderError::Preimage)?;
         let trie_walker = OrderedListWalker::try_new_hydrated(header.transactions_root, self)
@@ -135,7 +135,7 @@ impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
     }
 }
 
-impl<T: CommsClient> TrieProvider for OracleL1ChainProvider<T> {
+impl<T: CommsClient + Sync> TrieProvider for OracleL1ChainProvider<T> {
     type Error = OracleProviderError;
 
     fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, Self::Error> {
@@ -145,7 +145,7 @@ impl<T: CommsClient> TrieProvider for OracleL1ChainProvider<T> {
             TrieNode::decode(
                 &mut self
                     .oracle
-                    .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
+                    .get_shared(PreimageKey::new(*key, PreimageKeyType::Keccak256))
                     .await
                     .map_err(OracleProviderError::Preimage)?
                     .as_ref(),
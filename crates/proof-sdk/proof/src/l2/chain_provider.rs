@@ -1,6 +1,6 @@
 //! Contains the concrete implementation of the [L2ChainProvider] trait for the client program.
 
-use crate::{errors::OracleProviderError, BootInfo, HintType};
+use crate::{errors::OracleProviderError, BootInfo, Hint};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{BlockBody, Header};
 use alloy_eips::eip2718::Decodable2718;
@@ -31,21 +31,18 @@ impl<T: CommsClient> OracleL2ChainProvider<T> {
     }
 }
 
-impl<T: CommsClient> OracleL2ChainProvider<T> {
+impl<T: CommsClient + Sync> OracleL2ChainProvider<T> {
     /// Returns a [Header] corresponding to the given L2 block number, by walking back from the
     /// L2 safe head.
     async fn header_by_number(&mut self, block_number: u64) -> Result<Header, OracleProviderError> {
         // Fetch the starting L2 output preimage.
         self.oracle
-            .write(
-                &HintType::StartingL2Output
-                    .encode_with(&[self.boot_info.agreed_l2_output_root.as_ref()]),
-            )
+            .write(&Hint::StartingL2Output(self.boot_info.agreed_l2_output_root).encode())
             .await
             .map_err(OracleProviderError::Preimage)?;
         let output_preimage = self
             .oracle
-            .get(PreimageKey::new(
+            .get_shared(PreimageKey::new(
                 *self.boot_info.agreed_l2_output_root,
                 PreimageKeyType::Keccak256,
             ))
@@ -92,7 +89,7 @@ impl<T: CommsClient + Send + Sync> BatchValidationProvider for OracleL2ChainProv
 
         // Fetch the transactions in the block.
         self.oracle
-            .write(&HintType::L2Transactions.encode_with(&[header_hash.as_ref()]))
+            .write(&Hint::L2Transactions(header_hash).encode())
             .await
             .map_err(OracleProviderError::Preimage)?;
         let trie_walker = OrderedListWalker::try_new_hydrated(transactions_root, self)
@@ -142,7 +139,7 @@ impl<T: CommsClient + Send + Sync> L2ChainProvider for OracleL2ChainProvider<T>
     }
 }
 
-impl<T: CommsClient> TrieProvider for OracleL2ChainProvider<T> {
+impl<T: CommsClient + Sync> TrieProvider for OracleL2ChainProvider<T> {
     type Error = OracleProviderError;
 
     fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, OracleProviderError> {
@@ -152,7 +149,7 @@ impl<T: CommsClient> TrieProvider for OracleL2ChainProvider<T> {
             TrieNode::decode(
                 &mut self
                     .oracle
-                    .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
+                    .get_shared(PreimageKey::new(*key, PreimageKeyType::Keccak256))
                     .await
                     .map_err(OracleProviderError::Preimage)?
                     .as_ref(),
@@ -162,12 +159,12 @@ impl<T: CommsClient> TrieProvider for OracleL2ChainProvider<T> {
     }
 }
 
-impl<T: CommsClient> TrieDBProvider for OracleL2ChainProvider<T> {
+impl<T: CommsClient + Sync> TrieDBProvider for OracleL2ChainProvider<T> {
     fn bytecode_by_hash(&self, hash: B256) -> Result<Bytes, OracleProviderError> {
         // Fetch the bytecode preimage from the caching oracle.
         crate::block_on(async move {
             self.oracle
-                .write(&HintType::L2Code.encode_with(&[hash.as_ref()]))
+                .write(&Hint::L2Code(hash).encode())
                 .await
                 .map_err(OracleProviderError::Preimage)?;
 
@@ -183,16 +180,16 @@ impl<T: CommsClient> TrieDBProvider for OracleL2ChainProvider<T> {
         // Fetch the header from the caching oracle.
         crate::block_on(async move {
             self.oracle
-                .write(&HintType::L2BlockHeader.encode_with(&[hash.as_ref()]))
+                .write(&Hint::L2BlockHeader(hash).encode())
                 .await
                 .map_err(OracleProviderError::Preimage)?;
 
             let header_bytes = self
                 .oracle
-                .get(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
+                .get_shared(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
                 .await
                 .map_err(OracleProviderError::Preimage)?;
-            Header::decode(&mut header_bytes.as_slice()).map_err(OracleProviderError::Rlp)
+            Header::decode(&mut header_bytes.as_ref()).map_err(OracleProviderError::Rlp)
         })
     }
 }
@@ -203,7 +200,7 @@ impl<T: CommsClient> TrieHinter for OracleL2ChainProvider<T> {
     fn hint_trie_node(&self, hash: B256) -> Result<(), Self::Error> {
         crate::block_on(async move {
             self.oracle
-                .write(&HintType::L2StateNode.encode_with(&[hash.as_slice()]))
+                .write(&Hint::L2StateNode(hash).encode())
                 .await
                 .map_err(OracleProviderError::Preimage)
         })
@@ -212,10 +209,7 @@ impl<T: CommsClient> TrieHinter for OracleL2ChainProvider<T> {
     fn hint_account_proof(&self, address: Address, block_number: u64) -> Result<(), Self::Error> {
         crate::block_on(async move {
             self.oracle
-                .write(
-                    &HintType::L2AccountProof
-                        .encode_with(&[block_number.to_be_bytes().as_ref(), address.as_slice()]),
-                )
+                .write(&Hint::L2AccountProof { block_number, address }.encode())
                 .await
                 .map_err(OracleProviderError::Preimage)
         })
@@ -229,11 +223,10 @@ impl<T: CommsClient> TrieHinter for OracleL2ChainProvider<T> {
     ) -> Result<(), Self::Error> {
         crate::block_on(async move {
             self.oracle
-                .write(&HintType::L2AccountStorageProof.encode_with(&[
-                    block_number.to_be_bytes().as_ref(),
-                    address.as_slice(),
-                    slot.to_be_bytes::<32>().as_ref(),
-                ]))
+                .write(
+                    &Hint::L2AccountStorageProof { block_number, address, slot: slot.into() }
+                        .encode(),
+                )
                 .await
                 .map_err(OracleProviderError::Preimage)
         })
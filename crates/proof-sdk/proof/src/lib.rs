@@ -26,8 +26,15 @@ pub use hint::{Hint, HintType};
 pub mod boot;
 pub use boot::BootInfo;
 
+pub mod rollup_config;
+
+pub mod generic;
+
 mod caching_oracle;
 pub use caching_oracle::{CachingOracle, FlushableCache};
 
 mod blocking_runtime;
 pub use blocking_runtime::block_on;
+
+mod local_executor;
+pub use local_executor::{yield_now, JoinHandle, LocalExecutor, Spawner};
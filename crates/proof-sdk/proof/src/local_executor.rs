@@ -0,0 +1,371 @@
+//! This module contains a single-threaded async executor for embedded environments, with real
+//! wakers and a task queue.
+//!
+//! The executor is deterministic: tasks are polled one at a time, in the order that they were
+//! woken, and woken tasks are only queued once until they are polled again. Given the same
+//! sequence of I/O results, the executor always polls the same futures in the same order, which
+//! keeps the execution trace of a client program reproducible inside of an FPVM.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+    sync::Arc,
+    task::Wake,
+};
+use core::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    pin::{pin, Pin},
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll, Waker},
+};
+use spin::Mutex;
+
+/// The identifier of the future passed to [LocalExecutor::block_on].
+const MAIN_TASK: usize = 0;
+
+/// The queue of woken tasks, shared between a [LocalExecutor] and the wakers of its tasks.
+type ReadyQueue = Arc<Mutex<VecDeque<usize>>>;
+
+/// A single-threaded executor that runs a future to completion, along with any tasks spawned
+/// onto it through a [Spawner].
+///
+/// Tasks are woken through real [Waker]s that push them onto the executor's ready queue, so that
+/// pending futures are only polled again once they can make progress. If no task has been woken,
+/// the executor polls every pending task again, in order. Nothing outside of the executor can
+/// wake a task in a single-threaded FPVM, so this keeps futures that return [Poll::Pending]
+/// without registering their waker working, as they did with a busy-polling executor.
+pub struct LocalExecutor<'a> {
+    /// The tasks spawned onto the executor.
+    tasks: Rc<RefCell<TaskSet<'a>>>,
+    /// The queue of woken tasks.
+    ready: ReadyQueue,
+}
+
+impl<'a> LocalExecutor<'a> {
+    /// Creates a new [LocalExecutor] without any tasks.
+    pub fn new() -> Self {
+        Self { tasks: Default::default(), ready: Default::default() }
+    }
+
+    /// Returns a [Spawner] that spawns tasks onto the executor.
+    pub fn spawner(&self) -> Spawner<'a> {
+        Spawner { tasks: Rc::clone(&self.tasks), ready: Arc::clone(&self.ready) }
+    }
+
+    /// Spawns a task onto the executor. See [Spawner::spawn].
+    pub fn spawn<T: 'a>(&self, future: impl Future<Output = T> + 'a) -> JoinHandle<T> {
+        self.spawner().spawn(future)
+    }
+
+    /// Runs the executor until `future` completes, polling spawned tasks as they are woken.
+    /// Spawned tasks that have not completed by then are left on the executor.
+    pub fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
+        let mut future = pin!(future);
+        let main_waker = TaskWaker::new(MAIN_TASK, &self.ready);
+        let waker = Waker::from(Arc::clone(&main_waker));
+        main_waker.wake_by_ref();
+
+        loop {
+            let next = self.ready.lock().pop_front();
+            match next {
+                Some(MAIN_TASK) => {
+                    main_waker.queued.store(false, Ordering::Release);
+                    if let Poll::Ready(output) =
+                        future.as_mut().poll(&mut Context::from_waker(&waker))
+                    {
+                        return output;
+                    }
+                }
+                Some(id) => {
+                    // Take the task out of the set while it is polled, so that it may spawn
+                    // other tasks.
+                    let Some(mut task) = self.tasks.borrow_mut().tasks.remove(&id) else {
+                        continue;
+                    };
+                    task.waker.queued.store(false, Ordering::Release);
+                    let task_waker = Waker::from(Arc::clone(&task.waker));
+                    if task.future.as_mut().poll(&mut Context::from_waker(&task_waker)).is_pending()
+                    {
+                        self.tasks.borrow_mut().tasks.insert(id, task);
+                    }
+                }
+                None => {
+                    main_waker.wake_by_ref();
+                    self.tasks.borrow().tasks.values().for_each(|task| task.waker.wake_by_ref());
+                }
+            }
+        }
+    }
+}
+
+impl Default for LocalExecutor<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LocalExecutor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalExecutor")
+            .field("tasks", &self.tasks.borrow().tasks.len())
+            .field("ready", &self.ready.lock().len())
+            .finish()
+    }
+}
+
+/// A handle that spawns tasks onto a [LocalExecutor]. Spawners can be cloned and moved into the
+/// futures run by the executor, so that they can spawn work that runs while they wait.
+#[derive(Clone)]
+pub struct Spawner<'a> {
+    /// The tasks spawned onto the executor.
+    tasks: Rc<RefCell<TaskSet<'a>>>,
+    /// The queue of woken tasks.
+    ready: ReadyQueue,
+}
+
+impl<'a> Spawner<'a> {
+    /// Spawns a task onto the executor. The task is first polled after the tasks that are already
+    /// queued, and runs while the executor is blocked on another future.
+    ///
+    /// ## Returns
+    /// A [JoinHandle] that resolves to the output of the task once it completes.
+    pub fn spawn<T: 'a>(&self, future: impl Future<Output = T> + 'a) -> JoinHandle<T> {
+        let state = Rc::new(RefCell::new(JoinState { output: None, waker: None }));
+        let join_state = Rc::clone(&state);
+        let task = async move {
+            let output = future.await;
+            let mut state = join_state.borrow_mut();
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+
+        let mut tasks = self.tasks.borrow_mut();
+        tasks.next_id += 1;
+        let id = tasks.next_id;
+        let waker = TaskWaker::new(id, &self.ready);
+        waker.wake_by_ref();
+        tasks.tasks.insert(id, Task { future: Box::pin(task), waker });
+
+        JoinHandle { state }
+    }
+}
+
+impl fmt::Debug for Spawner<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spawner").field("tasks", &self.tasks.borrow().tasks.len()).finish()
+    }
+}
+
+/// A future that resolves to the output of a task spawned onto a [LocalExecutor].
+pub struct JoinHandle<T> {
+    /// The state shared with the task.
+    state: Rc<RefCell<JoinState<T>>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Returns `true` if the task has completed, and its output has not been taken yet.
+    pub fn is_finished(&self) -> bool {
+        self.state.borrow().output.is_some()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle").field("finished", &self.is_finished()).finish()
+    }
+}
+
+/// Yields to the executor once, so that other woken tasks are polled before the current task
+/// continues.
+pub async fn yield_now() {
+    let mut yielded = false;
+    core::future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+/// The tasks spawned onto a [LocalExecutor].
+#[derive(Default)]
+struct TaskSet<'a> {
+    /// The pending tasks, by identifier.
+    tasks: BTreeMap<usize, Task<'a>>,
+    /// The identifier of the most recently spawned task.
+    next_id: usize,
+}
+
+/// A task spawned onto a [LocalExecutor].
+struct Task<'a> {
+    /// The future of the task.
+    future: Pin<Box<dyn Future<Output = ()> + 'a>>,
+    /// The waker of the task.
+    waker: Arc<TaskWaker>,
+}
+
+/// The state shared between a spawned task and its [JoinHandle].
+struct JoinState<T> {
+    /// The output of the task, once it has completed.
+    output: Option<T>,
+    /// The waker of the future awaiting the [JoinHandle].
+    waker: Option<Waker>,
+}
+
+/// The waker of a task, which queues the task on the ready queue of its executor.
+struct TaskWaker {
+    /// The identifier of the task.
+    id: usize,
+    /// Whether the task is already on the ready queue.
+    queued: AtomicBool,
+    /// The ready queue of the executor.
+    ready: ReadyQueue,
+}
+
+impl TaskWaker {
+    /// Creates a new [TaskWaker] for the task with the given identifier. The task is not queued
+    /// until it is woken.
+    fn new(id: usize, ready: &ReadyQueue) -> Arc<Self> {
+        Arc::new(Self { id, queued: AtomicBool::new(false), ready: Arc::clone(ready) })
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.ready.lock().push_back(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn test_local_executor_interleaves_tasks() {
+        let log = RefCell::new(Vec::new());
+        let executor = LocalExecutor::new();
+
+        let output = executor.block_on(async {
+            let spawner = executor.spawner();
+            let (a, b) = (
+                spawner.spawn(async {
+                    for i in 0..3 {
+                        log.borrow_mut().push(("a", i));
+                        yield_now().await;
+                    }
+                    "a"
+                }),
+                spawner.spawn(async {
+                    for i in 0..3 {
+                        log.borrow_mut().push(("b", i));
+                        yield_now().await;
+                    }
+                    "b"
+                }),
+            );
+            (b.await, a.await)
+        });
+
+        assert_eq!(output, ("b", "a"));
+        assert_eq!(*log.borrow(), vec![("a", 0), ("b", 0), ("a", 1), ("b", 1), ("a", 2), ("b", 2)]);
+    }
+
+    #[test]
+    fn test_local_executor_wakes_task() {
+        // The first task can only make progress once the second task has set the flag and woken
+        // it, so it is polled exactly twice: once before, and once after the wakeup.
+        let flag = RefCell::new((false, None::<Waker>));
+        let polls = RefCell::new(0);
+        let executor = LocalExecutor::new();
+
+        let output = executor.block_on(async {
+            let spawner = executor.spawner();
+            let waiter = spawner.spawn(core::future::poll_fn(|cx| {
+                *polls.borrow_mut() += 1;
+                let mut flag = flag.borrow_mut();
+                if flag.0 {
+                    Poll::Ready("woken")
+                } else {
+                    flag.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }));
+            let waker = spawner.spawn(async {
+                let mut flag = flag.borrow_mut();
+                flag.0 = true;
+                flag.1.take().expect("waiter is polled first").wake();
+            });
+            waker.await;
+            waiter.await
+        });
+
+        assert_eq!(output, "woken");
+        assert_eq!(*polls.borrow(), 2);
+    }
+
+    #[test]
+    fn test_local_executor_nested_spawn() {
+        let executor = LocalExecutor::new();
+        let spawner = executor.spawner();
+        let output = executor.block_on(async {
+            let inner = spawner.clone();
+            spawner.spawn(async move { inner.spawn(async { 21 }).await * 2 }).await
+        });
+        assert_eq!(output, 42);
+    }
+
+    #[test]
+    fn test_local_executor_repolls_without_wakeup() {
+        // A future that returns `Pending` without registering its waker is polled again once
+        // no task has been woken.
+        let mut polls = 0;
+        let output = LocalExecutor::new().block_on(core::future::poll_fn(|_| {
+            polls += 1;
+            if polls == 3 {
+                Poll::Ready(polls)
+            } else {
+                Poll::Pending
+            }
+        }));
+        assert_eq!(output, 3);
+    }
+
+    #[test]
+    fn test_local_executor_unfinished_tasks() {
+        let executor = LocalExecutor::new();
+        let handle = executor.spawn(core::future::pending::<()>());
+        assert_eq!(executor.block_on(async { 7 }), 7);
+        assert!(!handle.is_finished());
+        assert_eq!(executor.tasks.borrow().tasks.len(), 1);
+    }
+}
@@ -0,0 +1,415 @@
+//! Validation and hashing of [RollupConfig]s, for the configs of chains outside of the superchain
+//! registry that are loaded from the preimage oracle.
+
+use crate::errors::RollupConfigError;
+use alloc::{format, string::String, vec, vec::Vec};
+use alloy_eips::{eip1559::BaseFeeParams, BlockNumHash};
+use alloy_primitives::{keccak256, B256};
+use op_alloy_genesis::{ChainGenesis, RollupConfig, SystemConfig};
+use serde_json::Value;
+
+/// The version of the [encoding](encode_rollup_config) of [RollupConfig]s, which is its first
+/// byte. It must be bumped whenever the encoding changes, such as when a field is added.
+pub const ROLLUP_CONFIG_ENCODING_VERSION: u8 = 1;
+
+/// Returns the encoding of a [RollupConfig] that is hashed by [rollup_config_hash]: the
+/// [ROLLUP_CONFIG_ENCODING_VERSION], followed by every field of the config in declaration order,
+/// with the fields of nested structs inlined.
+///
+/// Integers are encoded big-endian at their full width, and addresses and hashes as their raw
+/// bytes. An optional field is encoded as a `0` byte if it is absent, and otherwise as a `1` byte
+/// followed by its value.
+pub fn encode_rollup_config(config: &RollupConfig) -> Vec<u8> {
+    // Destructure every struct exhaustively, so that a new field fails to compile until the
+    // encoding, and its version, are updated.
+    let RollupConfig {
+        genesis: ChainGenesis { l1, l2, l2_time, system_config },
+        block_time,
+        max_sequencer_drift,
+        seq_window_size,
+        channel_timeout,
+        granite_channel_timeout,
+        l1_chain_id,
+        l2_chain_id,
+        base_fee_params,
+        canyon_base_fee_params,
+        regolith_time,
+        canyon_time,
+        delta_time,
+        ecotone_time,
+        fjord_time,
+        granite_time,
+        holocene_time,
+        isthmus_time,
+        batch_inbox_address,
+        deposit_contract_address,
+        l1_system_config_address,
+        protocol_versions_address,
+        superchain_config_address,
+        blobs_enabled_l1_timestamp,
+        da_challenge_address,
+    } = config;
+
+    let mut out = vec![ROLLUP_CONFIG_ENCODING_VERSION];
+    for BlockNumHash { number, hash } in [l1, l2] {
+        out.extend(number.to_be_bytes());
+        out.extend(hash.as_slice());
+    }
+    out.extend(l2_time.to_be_bytes());
+    encode_option(&mut out, system_config.as_ref(), |out, system_config| {
+        let SystemConfig {
+            batcher_address,
+            overhead,
+            scalar,
+            gas_limit,
+            base_fee_scalar,
+            blob_base_fee_scalar,
+            eip1559_denominator,
+            eip1559_elasticity,
+        } = system_config;
+        out.extend(batcher_address.as_slice());
+        out.extend(overhead.to_be_bytes::<32>());
+        out.extend(scalar.to_be_bytes::<32>());
+        out.extend(gas_limit.to_be_bytes());
+        for value in [base_fee_scalar, blob_base_fee_scalar] {
+            encode_option(out, *value, |out, value| out.extend(value.to_be_bytes()));
+        }
+        for value in [eip1559_denominator, eip1559_elasticity] {
+            encode_option(out, *value, |out, value| out.extend(value.to_be_bytes()));
+        }
+    });
+    for value in [
+        block_time,
+        max_sequencer_drift,
+        seq_window_size,
+        channel_timeout,
+        granite_channel_timeout,
+        l1_chain_id,
+        l2_chain_id,
+    ] {
+        out.extend(value.to_be_bytes());
+    }
+    for BaseFeeParams { max_change_denominator, elasticity_multiplier } in
+        [base_fee_params, canyon_base_fee_params]
+    {
+        out.extend(max_change_denominator.to_be_bytes());
+        out.extend(elasticity_multiplier.to_be_bytes());
+    }
+    for time in [
+        regolith_time,
+        canyon_time,
+        delta_time,
+        ecotone_time,
+        fjord_time,
+        granite_time,
+        holocene_time,
+        isthmus_time,
+    ] {
+        encode_option(&mut out, *time, |out, time| out.extend(time.to_be_bytes()));
+    }
+    for address in [
+        batch_inbox_address,
+        deposit_contract_address,
+        l1_system_config_address,
+        protocol_versions_address,
+    ] {
+        out.extend(address.as_slice());
+    }
+    encode_option(&mut out, *superchain_config_address, |out, address| {
+        out.extend(address.as_slice())
+    });
+    encode_option(&mut out, *blobs_enabled_l1_timestamp, |out, time| {
+        out.extend(time.to_be_bytes())
+    });
+    encode_option(&mut out, *da_challenge_address, |out, address| out.extend(address.as_slice()));
+    out
+}
+
+/// Returns the hash of a [RollupConfig], the `keccak256` digest of its
+/// [encoding](encode_rollup_config).
+pub fn rollup_config_hash(config: &RollupConfig) -> B256 {
+    keccak256(encode_rollup_config(config))
+}
+
+/// Appends an optional field to `out`, encoding its value with `encode` if it is present.
+fn encode_option<T>(out: &mut Vec<u8>, value: Option<T>, encode: impl FnOnce(&mut Vec<u8>, T)) {
+    match value {
+        Some(value) => {
+            out.push(1);
+            encode(out, value);
+        }
+        None => out.push(0),
+    }
+}
+
+/// Checks a [RollupConfig] for internal consistency, returning every problem found:
+/// - Each hardfork must be scheduled at or after the hardfork before it, and only if the hardfork
+///   before it is scheduled.
+/// - The block time must not be zero.
+/// - The channel timeouts must be at least one L1 block, and at most the sequencing window. The
+///   Granite channel timeout must also be at most the channel timeout before Granite.
+/// - The genesis must have a system config.
+pub fn check_rollup_config(config: &RollupConfig) -> Vec<RollupConfigError> {
+    let mut errors = Vec::new();
+
+    let hardforks = [
+        ("regolith", config.regolith_time),
+        ("canyon", config.canyon_time),
+        ("delta", config.delta_time),
+        ("ecotone", config.ecotone_time),
+        ("fjord", config.fjord_time),
+        ("granite", config.granite_time),
+        ("holocene", config.holocene_time),
+        ("isthmus", config.isthmus_time),
+    ];
+    for pair in hardforks.windows(2) {
+        let [(prior, prior_time), (fork, time)] = pair else { continue };
+        match (prior_time, time) {
+            (None, Some(time)) => {
+                errors.push(RollupConfigError::MissingPriorHardfork { prior, fork, time: *time })
+            }
+            (Some(prior_time), Some(time)) if prior_time > time => {
+                errors.push(RollupConfigError::HardforkOrder {
+                    prior,
+                    prior_time: *prior_time,
+                    fork,
+                    time: *time,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    if config.block_time == 0 {
+        errors.push(RollupConfigError::ZeroBlockTime);
+    }
+
+    // A channel can only carry batches that are still within their sequencing window, and Granite
+    // only ever shortens the channel timeout.
+    let channel_timeouts = [
+        ("channel_timeout", config.channel_timeout, config.seq_window_size),
+        (
+            "granite_channel_timeout",
+            config.granite_channel_timeout,
+            config.channel_timeout.min(config.seq_window_size),
+        ),
+    ];
+    for (field, timeout, max) in channel_timeouts {
+        if !(1..=max).contains(&timeout) {
+            errors.push(RollupConfigError::ChannelTimeoutRange { field, timeout, max });
+        }
+    }
+    if config.genesis.system_config.is_none() {
+        errors.push(RollupConfigError::MissingSystemConfig);
+    }
+
+    errors
+}
+
+/// A field that differs between two [RollupConfig]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollupConfigDiff {
+    /// The path of the field in the serialized config, such as `genesis.l1.hash`.
+    pub field: String,
+    /// The value of the field in the expected config, or [Value::Null] if it is absent.
+    pub expected: Value,
+    /// The value of the field in the actual config, or [Value::Null] if it is absent.
+    pub actual: Value,
+}
+
+/// Returns the fields that differ between the `expected` and the `actual` [RollupConfig], such as
+/// a config loaded from a file and the config of the same chain in the superchain registry, in the
+/// order of their paths.
+pub fn diff_rollup_configs(
+    expected: &RollupConfig,
+    actual: &RollupConfig,
+) -> Vec<RollupConfigDiff> {
+    let mut diffs = Vec::new();
+    diff_values(
+        String::new(),
+        &serde_json::to_value(expected).unwrap_or_default(),
+        &serde_json::to_value(actual).unwrap_or_default(),
+        &mut diffs,
+    );
+    diffs.sort_by(|a, b| a.field.cmp(&b.field));
+    diffs
+}
+
+/// Appends the leaves that differ between `expected` and `actual`, at `path`, to `diffs`.
+fn diff_values(path: String, expected: &Value, actual: &Value, diffs: &mut Vec<RollupConfigDiff>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for key in
+                expected.keys().chain(actual.keys().filter(|key| !expected.contains_key(*key)))
+            {
+                let field = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+                diff_values(
+                    field,
+                    expected.get(key).unwrap_or(&Value::Null),
+                    actual.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        _ if expected != actual => diffs.push(RollupConfigDiff {
+            field: path,
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{b256, Address, U256};
+    use op_alloy_registry::ROLLUP_CONFIGS;
+
+    #[test]
+    fn test_check_rollup_config() {
+        let mainnet = ROLLUP_CONFIGS.get(&10).unwrap();
+        assert_eq!(check_rollup_config(mainnet), Vec::new());
+
+        let mut config = mainnet.clone();
+        config.block_time = 0;
+        config.channel_timeout = 0;
+        config.genesis.system_config = None;
+        config.delta_time = None;
+        config.fjord_time = Some(1);
+        assert_eq!(
+            check_rollup_config(&config),
+            [
+                RollupConfigError::MissingPriorHardfork {
+                    prior: "delta",
+                    fork: "ecotone",
+                    time: mainnet.ecotone_time.unwrap(),
+                },
+                RollupConfigError::HardforkOrder {
+                    prior: "ecotone",
+                    prior_time: mainnet.ecotone_time.unwrap(),
+                    fork: "fjord",
+                    time: 1,
+                },
+                RollupConfigError::ZeroBlockTime,
+                RollupConfigError::ChannelTimeoutRange {
+                    field: "channel_timeout",
+                    timeout: 0,
+                    max: mainnet.seq_window_size,
+                },
+                RollupConfigError::ChannelTimeoutRange {
+                    field: "granite_channel_timeout",
+                    timeout: mainnet.granite_channel_timeout,
+                    max: 0,
+                },
+                RollupConfigError::MissingSystemConfig,
+            ]
+        );
+
+        // The channel timeouts are bounded by the sequencing window.
+        let mut config = mainnet.clone();
+        config.channel_timeout = mainnet.seq_window_size + 1;
+        config.granite_channel_timeout = mainnet.seq_window_size;
+        assert_eq!(
+            check_rollup_config(&config),
+            [RollupConfigError::ChannelTimeoutRange {
+                field: "channel_timeout",
+                timeout: mainnet.seq_window_size + 1,
+                max: mainnet.seq_window_size,
+            }]
+        );
+
+        // Every chain in the superchain registry has valid channel timeouts.
+        for config in ROLLUP_CONFIGS.values() {
+            assert!(!check_rollup_config(config)
+                .iter()
+                .any(|e| matches!(e, RollupConfigError::ChannelTimeoutRange { .. })));
+        }
+    }
+
+    /// Returns a [RollupConfig] with every field set to a distinct value, and the optional fields
+    /// of its system config absent.
+    fn known_answer_config() -> RollupConfig {
+        RollupConfig {
+            genesis: ChainGenesis {
+                l1: BlockNumHash { number: 1, hash: B256::repeat_byte(0x11) },
+                l2: BlockNumHash { number: 2, hash: B256::repeat_byte(0x22) },
+                l2_time: 3,
+                system_config: Some(SystemConfig {
+                    batcher_address: Address::repeat_byte(0x44),
+                    overhead: U256::from(5),
+                    scalar: U256::from(6),
+                    gas_limit: 7,
+                    base_fee_scalar: None,
+                    blob_base_fee_scalar: None,
+                    eip1559_denominator: None,
+                    eip1559_elasticity: None,
+                }),
+            },
+            block_time: 8,
+            max_sequencer_drift: 9,
+            seq_window_size: 10,
+            channel_timeout: 11,
+            granite_channel_timeout: 12,
+            l1_chain_id: 13,
+            l2_chain_id: 14,
+            base_fee_params: BaseFeeParams::new(15, 16),
+            canyon_base_fee_params: BaseFeeParams::new(17, 18),
+            regolith_time: Some(19),
+            canyon_time: Some(20),
+            delta_time: Some(21),
+            ecotone_time: Some(22),
+            fjord_time: Some(23),
+            granite_time: Some(24),
+            holocene_time: Some(25),
+            isthmus_time: None,
+            batch_inbox_address: Address::repeat_byte(0x1a),
+            deposit_contract_address: Address::repeat_byte(0x1b),
+            l1_system_config_address: Address::repeat_byte(0x1c),
+            protocol_versions_address: Address::repeat_byte(0x1d),
+            superchain_config_address: Some(Address::repeat_byte(0x1e)),
+            blobs_enabled_l1_timestamp: None,
+            da_challenge_address: None,
+        }
+    }
+
+    #[test]
+    fn test_rollup_config_hash() {
+        let config = known_answer_config();
+        let encoded = encode_rollup_config(&config);
+        assert_eq!(encoded[0], ROLLUP_CONFIG_ENCODING_VERSION);
+        // The version, the genesis with its system config, the integers, the base fee params, the
+        // hardfork times, and the addresses.
+        let len = 1 + 2 * 40 + 8 + (1 + 20 + 2 * 32 + 8 + 4) + 7 * 8 + 4 * 16 + (7 * 9 + 1);
+        assert_eq!(encoded.len(), len + 4 * 20 + 21 + 2);
+        assert_eq!(
+            rollup_config_hash(&config),
+            b256!("bf38d8b9195d2308ad20f3abc86ffd8386f841ca36fce7c1491ec02468994288")
+        );
+
+        // The hash depends on every field, and on whether an optional field is present.
+        let mut changed = config.clone();
+        changed.genesis.l2_time += 1;
+        assert_ne!(rollup_config_hash(&changed), rollup_config_hash(&config));
+        let mut changed = config.clone();
+        changed.isthmus_time = Some(0);
+        assert_ne!(rollup_config_hash(&changed), rollup_config_hash(&config));
+    }
+
+    #[test]
+    fn test_diff_rollup_configs() {
+        let mainnet = ROLLUP_CONFIGS.get(&10).unwrap();
+        assert_eq!(diff_rollup_configs(mainnet, mainnet), Vec::new());
+
+        let mut config = mainnet.clone();
+        config.block_time = 1;
+        config.genesis.l2.number += 1;
+        let diffs = diff_rollup_configs(mainnet, &config);
+        assert_eq!(
+            diffs.iter().map(|diff| diff.field.as_str()).collect::<Vec<_>>(),
+            ["block_time", "genesis.l2.number"]
+        );
+        assert_eq!(diffs[0].expected, Value::from(2));
+        assert_eq!(diffs[0].actual, Value::from(1));
+    }
+}
@@ -0,0 +1,131 @@
+#![allow(missing_docs)]
+//! Contains benchmarks comparing the client program allocators on a synthetic block workload.
+//!
+//! These run natively and measure wall-clock time, which tracks the relative cost of each
+//! allocator. The instruction counts on an FPVM can be compared with the `bench-allocators`
+//! recipe in `bin/client/justfile`.
+
+use core::alloc::{GlobalAlloc, Layout};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use kona_std_fpvm::malloc::{BumpAllocator, SlabAllocator};
+use linked_list_allocator::LockedHeap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The size of the heap region given to each allocator.
+const HEAP_SIZE: usize = 128 * 1024 * 1024;
+
+/// An operation in the allocation workload.
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    /// Allocate a block of the given size and alignment.
+    Alloc(usize, usize),
+    /// Free the block allocated by the operation at the given index, if it is still live.
+    Free(usize),
+    /// Grow the block allocated by the operation at the given index to the given size.
+    Grow(usize, usize),
+}
+
+/// Generates an allocation trace that resembles executing a block: a long tail of short-lived
+/// trie nodes and RLP buffers, a smaller number of longer-lived account and storage values, and
+/// buffers that grow while transactions and receipts are encoded.
+fn block_workload() -> Vec<Op> {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut ops = Vec::new();
+    let mut allocs = Vec::new();
+
+    for _ in 0..100_000 {
+        let roll = rng.gen_range(0..100);
+        if roll < 55 {
+            allocs.push(ops.len());
+            ops.push(Op::Alloc(rng.gen_range(16..160), 8));
+        } else if roll < 65 {
+            allocs.push(ops.len());
+            ops.push(Op::Alloc(rng.gen_range(512..4096), 8));
+        } else if roll < 67 && !allocs.is_empty() {
+            let index = allocs[rng.gen_range(0..allocs.len())];
+            ops.push(Op::Grow(index, rng.gen_range(4096..32768)));
+        } else if !allocs.is_empty() {
+            let index = allocs.swap_remove(rng.gen_range(0..allocs.len()));
+            ops.push(Op::Free(index));
+        }
+    }
+    ops
+}
+
+/// Replays the workload against the given allocator.
+fn replay<A: GlobalAlloc>(allocator: &A, ops: &[Op]) {
+    let mut live: Vec<Option<(*mut u8, Layout)>> = vec![None; ops.len()];
+    for (i, op) in ops.iter().enumerate() {
+        unsafe {
+            match *op {
+                Op::Alloc(size, align) => {
+                    let layout = Layout::from_size_align_unchecked(size, align);
+                    let ptr = allocator.alloc(layout);
+                    assert!(!ptr.is_null(), "heap exhausted");
+                    live[i] = Some((ptr, layout));
+                }
+                Op::Free(index) => {
+                    if let Some((ptr, layout)) = live[index].take() {
+                        allocator.dealloc(ptr, layout);
+                    }
+                }
+                Op::Grow(index, size) => {
+                    if let Some((ptr, layout)) = live[index] {
+                        if size > layout.size() {
+                            let ptr = allocator.realloc(ptr, layout, size);
+                            assert!(!ptr.is_null(), "heap exhausted");
+                            live[index] = Some((
+                                ptr,
+                                Layout::from_size_align_unchecked(size, layout.align()),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn allocators(c: &mut Criterion) {
+    let mut g = c.benchmark_group("allocators");
+    g.sample_size(10);
+
+    let ops = block_workload();
+    let mut region = vec![0u8; HEAP_SIZE];
+    let heap_start = region.as_mut_ptr();
+
+    g.bench_function("Linked list - block workload", |b| {
+        b.iter_batched(
+            || unsafe { LockedHeap::new(heap_start, HEAP_SIZE) },
+            |allocator| replay(&allocator, &ops),
+            BatchSize::LargeInput,
+        );
+    });
+
+    g.bench_function("Bump - block workload", |b| {
+        b.iter_batched(
+            || {
+                let allocator = BumpAllocator::empty();
+                unsafe { allocator.init(heap_start, HEAP_SIZE) };
+                allocator
+            },
+            |allocator| replay(&allocator, &ops),
+            BatchSize::LargeInput,
+        );
+    });
+
+    g.bench_function("Slab - block workload", |b| {
+        b.iter_batched(
+            || {
+                let allocator = SlabAllocator::empty();
+                unsafe { allocator.init(heap_start, HEAP_SIZE) };
+                allocator
+            },
+            |allocator| replay(&allocator, &ops),
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+criterion_group!(allocator_benches, allocators);
+criterion_main!(allocator_benches);
@@ -67,17 +67,28 @@ pub fn print_err(s: &str) {
 /// Write the passed buffer to the given [FileDescriptor].
 #[inline]
 pub fn write(fd: FileDescriptor, buf: &[u8]) -> IOResult<usize> {
-    ClientIO::write(fd, buf)
+    let written = ClientIO::write(fd, buf)?;
+    #[cfg(feature = "profiling")]
+    crate::profile::record_write(fd, written);
+    Ok(written)
 }
 
 /// Write the passed buffer to the given [FileDescriptor].
 #[inline]
 pub fn read(fd: FileDescriptor, buf: &mut [u8]) -> IOResult<usize> {
-    ClientIO::read(fd, buf)
+    let read = ClientIO::read(fd, buf)?;
+    #[cfg(feature = "profiling")]
+    crate::profile::record_read(fd, read);
+    Ok(read)
 }
 
 /// Exit the process with the given exit code.
+///
+/// With the `profiling` feature enabled, a summary of the heap and syscall statistics is emitted
+/// over [FileDescriptor::StdErr] before exiting.
 #[inline]
 pub fn exit(code: usize) -> ! {
+    #[cfg(feature = "profiling")]
+    crate::profile::checkpoint("exit");
     ClientIO::exit(code)
 }
@@ -18,6 +18,9 @@ pub mod tracing;
 
 pub mod malloc;
 
+#[cfg(feature = "profiling")]
+pub mod profile;
+
 mod traits;
 pub use traits::BasicKernelInterface;
 
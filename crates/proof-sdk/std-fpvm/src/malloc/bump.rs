@@ -0,0 +1,190 @@
+//! A bump allocator for short-lived client programs.
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    ptr,
+};
+use spin::Mutex;
+
+/// A bump allocator that hands out memory from a single region and never reuses freed memory.
+///
+/// Allocation is a pointer increment, which makes it the cheapest allocator in instruction count.
+/// This is sound for client programs because they are short-lived, but it requires the heap to be
+/// large enough for every allocation the program makes. Two cases are special-cased to limit the
+/// waste of growing buffers:
+/// * Deallocating the most recent allocation rolls the bump pointer back.
+/// * Reallocating the most recent allocation grows or shrinks it in place.
+#[derive(Debug)]
+pub struct BumpAllocator {
+    state: Mutex<BumpState>,
+}
+
+/// The mutable state of a [BumpAllocator].
+#[derive(Debug)]
+struct BumpState {
+    /// The start of the heap region.
+    start: usize,
+    /// The next free address.
+    next: usize,
+    /// The end of the heap region.
+    end: usize,
+    /// The address of the most recent allocation.
+    last: usize,
+}
+
+impl BumpAllocator {
+    /// Creates an empty [BumpAllocator]. All allocations fail until it is initialized with
+    /// [BumpAllocator::init].
+    pub const fn empty() -> Self {
+        Self { state: Mutex::new(BumpState { start: 0, next: 0, end: 0, last: usize::MAX }) }
+    }
+
+    /// Initializes the allocator with the given memory region.
+    ///
+    /// # Safety
+    /// The memory region must be valid, unused by anything else, and live for as long as the
+    /// allocator is in use.
+    pub unsafe fn init(&self, heap_start_addr: *mut u8, heap_size: usize) {
+        let start = heap_start_addr as usize;
+        *self.state.lock() = BumpState {
+            start,
+            next: start,
+            end: start.saturating_add(heap_size),
+            last: usize::MAX,
+        };
+    }
+
+    /// Returns the size of the heap region, in bytes.
+    pub fn size(&self) -> usize {
+        let state = self.state.lock();
+        state.end - state.start
+    }
+
+    /// Returns the number of bytes that have been handed out, including alignment padding.
+    pub fn used(&self) -> usize {
+        let state = self.state.lock();
+        state.next - state.start
+    }
+}
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let mut state = self.state.lock();
+        let Some(addr) =
+            state.next.checked_add(layout.align() - 1).map(|a| a & !(layout.align() - 1))
+        else {
+            return ptr::null_mut();
+        };
+        match addr.checked_add(layout.size()) {
+            Some(next) if next <= state.end => {
+                state.next = next;
+                state.last = addr;
+                addr as *mut u8
+            }
+            _ => ptr::null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let mut state = self.state.lock();
+        if ptr as usize == state.last && state.last + layout.size() == state.next {
+            state.next = state.last;
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        {
+            let mut state = self.state.lock();
+            let addr = ptr as usize;
+            if addr == state.last && addr + layout.size() == state.next {
+                if let Some(next) = addr.checked_add(new_size).filter(|next| *next <= state.end) {
+                    state.next = next;
+                    return ptr;
+                }
+            }
+        }
+
+        let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl crate::profile::HeapUsage for BumpAllocator {
+    fn size(&self) -> usize {
+        Self::size(self)
+    }
+
+    fn used(&self) -> usize {
+        Self::used(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    fn allocator(size: usize) -> (Vec<u8>, BumpAllocator) {
+        let mut region = vec![0u8; size];
+        let allocator = BumpAllocator::empty();
+        unsafe { allocator.init(region.as_mut_ptr(), size) };
+        (region, allocator)
+    }
+
+    #[test]
+    fn test_bump_alloc_aligned() {
+        let (_region, allocator) = allocator(1024);
+        unsafe {
+            let a = allocator.alloc(Layout::from_size_align(3, 1).unwrap());
+            let b = allocator.alloc(Layout::from_size_align(16, 16).unwrap());
+            assert!(!a.is_null());
+            assert_eq!(b as usize % 16, 0);
+            assert!(b as usize >= a as usize + 3);
+        }
+    }
+
+    #[test]
+    fn test_bump_alloc_exhausted() {
+        let (_region, allocator) = allocator(64);
+        assert!(unsafe { allocator.alloc(Layout::from_size_align(128, 1).unwrap()) }.is_null());
+        assert_eq!(allocator.used(), 0);
+
+        let empty = BumpAllocator::empty();
+        assert!(unsafe { empty.alloc(Layout::from_size_align(1, 1).unwrap()) }.is_null());
+    }
+
+    #[test]
+    fn test_bump_reuses_last_allocation() {
+        let (_region, allocator) = allocator(1024);
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(layout);
+            let b = allocator.alloc(layout);
+            let used = allocator.used();
+
+            // Freeing an earlier allocation leaks it, freeing the latest rolls back.
+            allocator.dealloc(a, layout);
+            assert_eq!(allocator.used(), used);
+            allocator.dealloc(b, layout);
+            assert_eq!(allocator.used(), used - 32);
+
+            // The latest allocation grows in place.
+            let c = allocator.alloc(layout);
+            c.write_bytes(0xab, 32);
+            let grown = allocator.realloc(c, layout, 256);
+            assert_eq!(grown, c);
+            assert_eq!(allocator.used(), used - 32 + 256);
+
+            // Others are moved.
+            let d = allocator.alloc(layout);
+            let moved = allocator.realloc(grown, Layout::from_size_align(256, 8).unwrap(), 512);
+            assert_ne!(moved, grown);
+            assert_eq!(*moved.add(31), 0xab);
+            assert!(!d.is_null());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! This module contains the memory allocators for client programs running on an embedded device.
+//!
+//! The default allocator is a linked list allocator based on the `dlmalloc` algorithm, which is a
+//! well-known and widely used allocator software such as OS Kernels. Its allocation cost grows with
+//! the length of its free list, so two cheaper strategies can be selected with cargo features:
+//! * `bump-allocator` selects the [BumpAllocator], which never reuses freed memory.
+//! * `slab-allocator` selects the [SlabAllocator], which serves small allocations from power-of-two
+//!   size classes.
+//!
+//! If both features are enabled, the bump allocator takes precedence.
+
+mod bump;
+pub use bump::BumpAllocator;
+
+mod slab;
+pub use slab::SlabAllocator;
+
+/// The global allocator for the program in embedded environments.
+#[cfg(any(target_arch = "mips", target_arch = "riscv64"))]
+pub mod global_allocator {
+    /// The allocator selected by the enabled cargo features.
+    #[cfg(feature = "bump-allocator")]
+    type Heap = super::BumpAllocator;
+    /// The allocator selected by the enabled cargo features.
+    #[cfg(all(feature = "slab-allocator", not(feature = "bump-allocator")))]
+    type Heap = super::SlabAllocator;
+    /// The allocator selected by the enabled cargo features.
+    #[cfg(not(any(feature = "bump-allocator", feature = "slab-allocator")))]
+    type Heap = linked_list_allocator::LockedHeap;
+
+    /// The global allocator for the program.
+    #[cfg(not(feature = "profiling"))]
+    #[global_allocator]
+    static ALLOCATOR: Heap = Heap::empty();
+
+    /// With the `profiling` feature enabled, the global allocator is wrapped in a
+    /// [ProfilingAllocator](crate::profile::ProfilingAllocator) to track heap usage.
+    #[cfg(feature = "profiling")]
+    #[global_allocator]
+    static ALLOCATOR: crate::profile::ProfilingAllocator<Heap> =
+        crate::profile::ProfilingAllocator::new(Heap::empty());
+
+    /// Returns the selected allocator, unwrapped from the profiler if it is enabled.
+    fn heap() -> &'static Heap {
+        #[cfg(not(feature = "profiling"))]
+        return &ALLOCATOR;
+        #[cfg(feature = "profiling")]
+        return ALLOCATOR.inner();
+    }
+
+    /// Initialize the global allocator with the following parameters:
+    /// * `heap_start_addr` is the starting address of the heap memory region,
+    /// * `heap_size` is the size of the heap memory region in bytes.
+    ///
+    /// # Safety
+    /// This function is unsafe because the caller must ensure:
+    /// * The allocator has not already been initialized.
+    /// * The provided memory region must be valid, non-null, and not used by anything else.
+    /// * After aligning the start and end addresses, the size of the heap must be > 0, or the
+    ///   function will panic.
+    pub unsafe fn init_allocator(heap_start_addr: *mut u8, heap_size: usize) {
+        #[cfg(any(feature = "bump-allocator", feature = "slab-allocator"))]
+        heap().init(heap_start_addr, heap_size);
+        #[cfg(not(any(feature = "bump-allocator", feature = "slab-allocator")))]
+        heap().lock().init(heap_start_addr, heap_size);
+    }
+
+    /// Returns a snapshot of the global allocator's statistics.
+    #[cfg(feature = "profiling")]
+    pub fn stats() -> crate::profile::HeapStats {
+        ALLOCATOR.stats()
+    }
+
+    /// Resets the phase peak of the global allocator.
+    #[cfg(feature = "profiling")]
+    pub fn reset_phase_peak() {
+        ALLOCATOR.reset_phase_peak()
+    }
+}
+
+/// Initialize heap memory for the `client` program with the given size.
+///
+/// # Safety
+#[cfg_attr(
+    any(target_arch = "mips", target_arch = "riscv64"),
+    doc = "See [global_allocator::init_allocator] safety comment."
+)]
+#[cfg_attr(
+    not(any(target_arch = "mips", target_arch = "riscv64")),
+    doc = "This macro is entirely safe to invoke in non-MIPS and non-RISC-V64 profiles, and functions as a no-op."
+)]
+#[macro_export]
+macro_rules! alloc_heap {
+    ($size:expr) => {{
+        #[cfg(any(target_arch = "mips", target_arch = "riscv64"))]
+        {
+            use $crate::malloc::global_allocator::init_allocator;
+
+            static mut HEAP: [u8; $size] = [0u8; $size];
+            unsafe { init_allocator(HEAP.as_mut_ptr(), $size) }
+        }
+    }};
+}
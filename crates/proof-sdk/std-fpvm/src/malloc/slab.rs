@@ -0,0 +1,241 @@
+//! A size-class slab allocator for client programs.
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    fmt,
+    ptr::{self, NonNull},
+};
+use linked_list_allocator::Heap;
+use spin::Mutex;
+
+/// The size of the smallest size class, in bytes. Free blocks store the address of the next free
+/// block, so this must be at least the size of a pointer.
+const MIN_CLASS_SIZE: usize = 8;
+
+/// The size of the largest size class, in bytes. Larger allocations are served by the backing heap.
+const MAX_CLASS_SIZE: usize = 4096;
+
+/// The number of size classes, one per power of two in `MIN_CLASS_SIZE..=MAX_CLASS_SIZE`.
+const SIZE_CLASSES: usize =
+    (MAX_CLASS_SIZE.trailing_zeros() - MIN_CLASS_SIZE.trailing_zeros()) as usize + 1;
+
+/// The size of the chunks that are carved into blocks when a size class runs out of free blocks.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// A slab allocator with power-of-two size classes between 8 bytes and 4 KiB.
+///
+/// Each size class keeps an intrusive free list of blocks, so that allocating and freeing small
+/// objects is a constant-time list operation instead of a walk over the free list of the heap.
+/// When a size class is empty, a chunk is taken from a backing [Heap] and split into blocks.
+/// Freed blocks are kept by their size class and never returned to the backing heap. Allocations
+/// larger than the largest size class are served by the backing heap directly.
+#[derive(Debug)]
+pub struct SlabAllocator {
+    state: Mutex<SlabState>,
+}
+
+/// The mutable state of a [SlabAllocator].
+struct SlabState {
+    /// The head of the free list of each size class, or zero if the list is empty.
+    free: [usize; SIZE_CLASSES],
+    /// The heap that chunks and large allocations are taken from.
+    heap: Heap,
+}
+
+impl SlabAllocator {
+    /// Creates an empty [SlabAllocator]. All allocations fail until it is initialized with
+    /// [SlabAllocator::init].
+    pub const fn empty() -> Self {
+        Self { state: Mutex::new(SlabState { free: [0; SIZE_CLASSES], heap: Heap::empty() }) }
+    }
+
+    /// Initializes the allocator with the given memory region.
+    ///
+    /// # Safety
+    /// See [Heap::init].
+    pub unsafe fn init(&self, heap_start_addr: *mut u8, heap_size: usize) {
+        self.state.lock().heap.init(heap_start_addr, heap_size)
+    }
+
+    /// Returns the size of the heap region, in bytes.
+    pub fn size(&self) -> usize {
+        self.state.lock().heap.size()
+    }
+
+    /// Returns the number of bytes taken from the backing heap, including blocks that are free
+    /// within a size class.
+    pub fn used(&self) -> usize {
+        self.state.lock().heap.used()
+    }
+
+    /// Returns the index of the size class that serves `layout`, if it is small enough for one.
+    fn size_class(layout: &Layout) -> Option<usize> {
+        let size = layout.size().max(layout.align()).max(MIN_CLASS_SIZE).next_power_of_two();
+        (size <= MAX_CLASS_SIZE)
+            .then(|| (size.trailing_zeros() - MIN_CLASS_SIZE.trailing_zeros()) as usize)
+    }
+}
+
+impl fmt::Debug for SlabState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlabState")
+            .field("free", &self.free)
+            .field("heap_size", &self.heap.size())
+            .field("heap_used", &self.heap.used())
+            .finish()
+    }
+}
+
+impl SlabState {
+    /// Pops a block off the free list of the size class at `index`, refilling it from the
+    /// backing heap if it is empty.
+    unsafe fn pop(&mut self, index: usize) -> *mut u8 {
+        if self.free[index] == 0 {
+            let block_size = MIN_CLASS_SIZE << index;
+            let chunk = Layout::from_size_align_unchecked(CHUNK_SIZE, block_size);
+            let Ok(chunk) = self.heap.allocate_first_fit(chunk) else {
+                return ptr::null_mut();
+            };
+
+            // Thread the blocks of the chunk into a free list, in address order.
+            let base = chunk.as_ptr() as usize;
+            let blocks = CHUNK_SIZE / block_size;
+            for i in 0..blocks {
+                let block = base + i * block_size;
+                let next = if i + 1 < blocks { block + block_size } else { 0 };
+                (block as *mut usize).write(next);
+            }
+            self.free[index] = base;
+        }
+
+        let block = self.free[index];
+        self.free[index] = (block as *const usize).read();
+        block as *mut u8
+    }
+
+    /// Pushes a block onto the free list of the size class at `index`.
+    unsafe fn push(&mut self, index: usize, block: *mut u8) {
+        (block as *mut usize).write(self.free[index]);
+        self.free[index] = block as usize;
+    }
+}
+
+unsafe impl GlobalAlloc for SlabAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let mut state = self.state.lock();
+        match Self::size_class(&layout) {
+            Some(index) => state.pop(index),
+            None => state.heap.allocate_first_fit(layout).map_or(ptr::null_mut(), NonNull::as_ptr),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let mut state = self.state.lock();
+        match Self::size_class(&layout) {
+            Some(index) => state.push(index, ptr),
+            None => state.heap.deallocate(NonNull::new_unchecked(ptr), layout),
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let class = Self::size_class(&layout);
+        if class.is_some() && class == Self::size_class(&new_layout) {
+            return ptr;
+        }
+
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl crate::profile::HeapUsage for SlabAllocator {
+    fn size(&self) -> usize {
+        Self::size(self)
+    }
+
+    fn used(&self) -> usize {
+        Self::used(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    fn allocator(size: usize) -> (Vec<u8>, SlabAllocator) {
+        let mut region = vec![0u8; size];
+        let allocator = SlabAllocator::empty();
+        unsafe { allocator.init(region.as_mut_ptr(), size) };
+        (region, allocator)
+    }
+
+    #[test]
+    fn test_size_class() {
+        let class =
+            |size, align| SlabAllocator::size_class(&Layout::from_size_align(size, align).unwrap());
+        assert_eq!(SIZE_CLASSES, 10);
+        assert_eq!(class(0, 1), Some(0));
+        assert_eq!(class(8, 8), Some(0));
+        assert_eq!(class(9, 1), Some(1));
+        assert_eq!(class(1, 64), Some(3));
+        assert_eq!(class(4096, 8), Some(9));
+        assert_eq!(class(4097, 8), None);
+    }
+
+    #[test]
+    fn test_slab_reuses_blocks() {
+        let (_region, allocator) = allocator(64 * 1024);
+        let layout = Layout::from_size_align(24, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(layout);
+            let b = allocator.alloc(layout);
+            assert_eq!(b as usize - a as usize, 32);
+            assert_eq!(allocator.used(), CHUNK_SIZE);
+
+            allocator.dealloc(a, layout);
+            assert_eq!(allocator.alloc(layout), a);
+
+            // Reallocating within the size class keeps the block.
+            assert_eq!(allocator.realloc(b, layout, 30), b);
+        }
+    }
+
+    #[test]
+    fn test_slab_alignment() {
+        let (_region, allocator) = allocator(64 * 1024);
+        unsafe {
+            for align in [1, 8, 64, 4096] {
+                let layout = Layout::from_size_align(align, align).unwrap();
+                let ptr = allocator.alloc(layout);
+                assert!(!ptr.is_null());
+                assert_eq!(ptr as usize % align, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_slab_large_allocations() {
+        let (_region, allocator) = allocator(64 * 1024);
+        let small = Layout::from_size_align(100, 8).unwrap();
+        let large = Layout::from_size_align(8192, 8).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(small);
+            ptr.write_bytes(0xcd, 100);
+            let grown = allocator.realloc(ptr, small, 8192);
+            assert_ne!(grown, ptr);
+            assert_eq!(*grown.add(99), 0xcd);
+            assert_eq!(allocator.used(), CHUNK_SIZE + 8192);
+
+            allocator.dealloc(grown, large);
+            assert_eq!(allocator.used(), CHUNK_SIZE);
+            assert!(allocator.alloc(Layout::from_size_align(128 * 1024, 8).unwrap()).is_null());
+        }
+    }
+}
@@ -0,0 +1,526 @@
+//! Heap and syscall profiling for client programs.
+//!
+//! When the `profiling` feature is enabled, the global allocator on FPVM targets is wrapped in a
+//! [ProfilingAllocator], and every call to [crate::io::read] and [crate::io::write] is counted.
+//! A summary of both is emitted over [FileDescriptor::StdErr] when the program exits through
+//! [crate::io::exit], and may be emitted at any point with [checkpoint] to attribute heap usage to
+//! a phase of the program.
+//!
+//! Nothing in this module allocates, so that a summary can still be emitted after the heap has
+//! been exhausted.
+
+use crate::{io, FileDescriptor};
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    fmt::{self, Write},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use linked_list_allocator::LockedHeap;
+
+/// A heap that can report how much of its memory region is in use.
+pub trait HeapUsage {
+    /// Returns the size of the heap's memory region, in bytes.
+    fn size(&self) -> usize;
+
+    /// Returns the number of bytes of the heap's memory region that are in use, including the
+    /// padding and block overhead of the allocator.
+    fn used(&self) -> usize;
+
+    /// Returns the number of bytes of the heap's memory region that are free.
+    fn free(&self) -> usize {
+        self.size().saturating_sub(self.used())
+    }
+}
+
+impl HeapUsage for LockedHeap {
+    fn size(&self) -> usize {
+        self.lock().size()
+    }
+
+    fn used(&self) -> usize {
+        self.lock().used()
+    }
+
+    fn free(&self) -> usize {
+        self.lock().free()
+    }
+}
+
+/// A snapshot of the statistics recorded by a [ProfilingAllocator].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    /// The size of the heap's memory region, in bytes.
+    pub heap_size: usize,
+    /// The number of bytes of the heap in use, including allocator overhead.
+    pub heap_used: usize,
+    /// The number of successful allocations.
+    pub allocations: usize,
+    /// The number of deallocations.
+    pub deallocations: usize,
+    /// The number of reallocations.
+    pub reallocations: usize,
+    /// The number of allocations that have not yet been deallocated.
+    pub live_allocations: usize,
+    /// The number of bytes requested by allocations that have not yet been deallocated.
+    pub live_bytes: usize,
+    /// The highest value of `live_bytes` over the lifetime of the program.
+    pub peak_bytes: usize,
+    /// The highest value of `live_bytes` since the last [checkpoint].
+    pub phase_peak_bytes: usize,
+    /// The number of allocations that the heap could not satisfy.
+    pub failed_allocations: usize,
+    /// The size of the largest allocation that the heap could not satisfy.
+    pub largest_failed_bytes: usize,
+    /// The free bytes of the heap when the largest failed allocation was requested.
+    pub free_at_failure: usize,
+}
+
+impl HeapStats {
+    /// Returns the number of heap bytes in use that do not belong to a live allocation, i.e. the
+    /// padding and block overhead of the allocator.
+    pub const fn overhead(&self) -> usize {
+        self.heap_used.saturating_sub(self.live_bytes)
+    }
+
+    /// Returns the fragmentation of the heap as a percentage. This is the share of used heap
+    /// memory that is not backing a live allocation.
+    pub const fn fragmentation_percent(&self) -> usize {
+        if self.heap_used == 0 {
+            return 0;
+        }
+        self.overhead() * 100 / self.heap_used
+    }
+}
+
+/// A [GlobalAlloc] wrapper that tracks the peak heap usage, live allocations, and failed
+/// allocations of the inner allocator.
+#[derive(Debug)]
+pub struct ProfilingAllocator<A> {
+    /// The wrapped allocator.
+    inner: A,
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    reallocations: AtomicUsize,
+    live_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    phase_peak_bytes: AtomicUsize,
+    failed_allocations: AtomicUsize,
+    largest_failed_bytes: AtomicUsize,
+    free_at_failure: AtomicUsize,
+}
+
+impl<A> ProfilingAllocator<A> {
+    /// Wraps the given allocator.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            allocations: AtomicUsize::new(0),
+            deallocations: AtomicUsize::new(0),
+            reallocations: AtomicUsize::new(0),
+            live_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+            phase_peak_bytes: AtomicUsize::new(0),
+            failed_allocations: AtomicUsize::new(0),
+            largest_failed_bytes: AtomicUsize::new(0),
+            free_at_failure: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns a reference to the wrapped allocator.
+    pub const fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Resets the phase peak to the current number of live bytes.
+    pub fn reset_phase_peak(&self) {
+        self.phase_peak_bytes.store(self.live_bytes.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    fn record_alloc(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        let live = self.live_bytes.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+        self.phase_peak_bytes.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.live_bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+impl<A: HeapUsage> ProfilingAllocator<A> {
+    /// Returns a snapshot of the recorded statistics.
+    pub fn stats(&self) -> HeapStats {
+        let allocations = self.allocations.load(Ordering::Relaxed);
+        let deallocations = self.deallocations.load(Ordering::Relaxed);
+        HeapStats {
+            heap_size: self.inner.size(),
+            heap_used: self.inner.used(),
+            allocations,
+            deallocations,
+            reallocations: self.reallocations.load(Ordering::Relaxed),
+            live_allocations: allocations.saturating_sub(deallocations),
+            live_bytes: self.live_bytes.load(Ordering::Relaxed),
+            peak_bytes: self.peak_bytes.load(Ordering::Relaxed),
+            phase_peak_bytes: self.phase_peak_bytes.load(Ordering::Relaxed),
+            failed_allocations: self.failed_allocations.load(Ordering::Relaxed),
+            largest_failed_bytes: self.largest_failed_bytes.load(Ordering::Relaxed),
+            free_at_failure: self.free_at_failure.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_failure(&self, size: usize) {
+        self.failed_allocations.fetch_add(1, Ordering::Relaxed);
+        if self.largest_failed_bytes.fetch_max(size, Ordering::Relaxed) < size {
+            self.free_at_failure.store(self.inner.free(), Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl<A: GlobalAlloc + HeapUsage> GlobalAlloc for ProfilingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if ptr.is_null() {
+            self.record_failure(layout.size());
+        } else {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if ptr.is_null() {
+            self.record_failure(layout.size());
+        } else {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if new_ptr.is_null() {
+            self.record_failure(new_size);
+            return new_ptr;
+        }
+
+        self.reallocations.fetch_add(1, Ordering::Relaxed);
+        if new_size >= layout.size() {
+            let grown = new_size - layout.size();
+            let live = self.live_bytes.fetch_add(grown, Ordering::Relaxed) + grown;
+            self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+            self.phase_peak_bytes.fetch_max(live, Ordering::Relaxed);
+        } else {
+            self.live_bytes.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+/// A snapshot of the syscall counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyscallStats {
+    /// The number of `read` syscalls.
+    pub reads: usize,
+    /// The number of bytes read.
+    pub bytes_read: usize,
+    /// The number of `write` syscalls.
+    pub writes: usize,
+    /// The number of bytes written.
+    pub bytes_written: usize,
+    /// The number of writes to [FileDescriptor::HintWrite].
+    pub hint_writes: usize,
+    /// The number of writes to [FileDescriptor::PreimageWrite], i.e. preimage requests.
+    pub preimage_requests: usize,
+    /// The number of reads from [FileDescriptor::PreimageRead].
+    pub preimage_reads: usize,
+    /// The number of bytes read from [FileDescriptor::PreimageRead].
+    pub preimage_bytes_read: usize,
+}
+
+/// The global syscall counters.
+#[derive(Debug)]
+struct SyscallCounters {
+    reads: AtomicUsize,
+    bytes_read: AtomicUsize,
+    writes: AtomicUsize,
+    bytes_written: AtomicUsize,
+    hint_writes: AtomicUsize,
+    preimage_requests: AtomicUsize,
+    preimage_reads: AtomicUsize,
+    preimage_bytes_read: AtomicUsize,
+}
+
+static SYSCALLS: SyscallCounters = SyscallCounters {
+    reads: AtomicUsize::new(0),
+    bytes_read: AtomicUsize::new(0),
+    writes: AtomicUsize::new(0),
+    bytes_written: AtomicUsize::new(0),
+    hint_writes: AtomicUsize::new(0),
+    preimage_requests: AtomicUsize::new(0),
+    preimage_reads: AtomicUsize::new(0),
+    preimage_bytes_read: AtomicUsize::new(0),
+};
+
+/// Records a `read` syscall of `len` bytes from `fd`.
+pub(crate) fn record_read(fd: FileDescriptor, len: usize) {
+    SYSCALLS.reads.fetch_add(1, Ordering::Relaxed);
+    SYSCALLS.bytes_read.fetch_add(len, Ordering::Relaxed);
+    if fd == FileDescriptor::PreimageRead {
+        SYSCALLS.preimage_reads.fetch_add(1, Ordering::Relaxed);
+        SYSCALLS.preimage_bytes_read.fetch_add(len, Ordering::Relaxed);
+    }
+}
+
+/// Records a `write` syscall of `len` bytes to `fd`.
+pub(crate) fn record_write(fd: FileDescriptor, len: usize) {
+    SYSCALLS.writes.fetch_add(1, Ordering::Relaxed);
+    SYSCALLS.bytes_written.fetch_add(len, Ordering::Relaxed);
+    match fd {
+        FileDescriptor::HintWrite => SYSCALLS.hint_writes.fetch_add(1, Ordering::Relaxed),
+        FileDescriptor::PreimageWrite => SYSCALLS.preimage_requests.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+/// Returns a snapshot of the syscall counters.
+pub fn syscall_stats() -> SyscallStats {
+    SyscallStats {
+        reads: SYSCALLS.reads.load(Ordering::Relaxed),
+        bytes_read: SYSCALLS.bytes_read.load(Ordering::Relaxed),
+        writes: SYSCALLS.writes.load(Ordering::Relaxed),
+        bytes_written: SYSCALLS.bytes_written.load(Ordering::Relaxed),
+        hint_writes: SYSCALLS.hint_writes.load(Ordering::Relaxed),
+        preimage_requests: SYSCALLS.preimage_requests.load(Ordering::Relaxed),
+        preimage_reads: SYSCALLS.preimage_reads.load(Ordering::Relaxed),
+        preimage_bytes_read: SYSCALLS.preimage_bytes_read.load(Ordering::Relaxed),
+    }
+}
+
+/// Returns a snapshot of the global allocator's statistics, if the program is running on an FPVM
+/// target. On other targets, the global allocator is not managed by this crate.
+#[cfg(any(target_arch = "mips", target_arch = "riscv64"))]
+pub fn heap_stats() -> Option<HeapStats> {
+    Some(crate::malloc::global_allocator::stats())
+}
+
+/// Returns a snapshot of the global allocator's statistics, if the program is running on an FPVM
+/// target. On other targets, the global allocator is not managed by this crate.
+#[cfg(not(any(target_arch = "mips", target_arch = "riscv64")))]
+pub const fn heap_stats() -> Option<HeapStats> {
+    None
+}
+
+/// Emits a summary of the heap and syscall statistics over [FileDescriptor::StdErr], labelled
+/// with the given phase, and resets the phase peak of the global allocator.
+pub fn checkpoint(label: &str) {
+    let heap = heap_stats();
+    let syscalls = syscall_stats();
+    let mut writer = StderrWriter::default();
+    let _ = write_summary(&mut writer, label, heap.as_ref(), &syscalls);
+    writer.flush();
+
+    #[cfg(any(target_arch = "mips", target_arch = "riscv64"))]
+    crate::malloc::global_allocator::reset_phase_peak();
+}
+
+/// Writes a summary of the given statistics to `w`.
+pub fn write_summary<W: Write>(
+    w: &mut W,
+    label: &str,
+    heap: Option<&HeapStats>,
+    syscalls: &SyscallStats,
+) -> fmt::Result {
+    if let Some(heap) = heap {
+        writeln!(
+            w,
+            "[profile:{label}] heap: used={}/{} live={} ({} allocs) peak={} phase_peak={} \
+             fragmentation={}% allocs={} deallocs={} reallocs={}",
+            heap.heap_used,
+            heap.heap_size,
+            heap.live_bytes,
+            heap.live_allocations,
+            heap.peak_bytes,
+            heap.phase_peak_bytes,
+            heap.fragmentation_percent(),
+            heap.allocations,
+            heap.deallocations,
+            heap.reallocations,
+        )?;
+        if heap.failed_allocations > 0 {
+            writeln!(
+                w,
+                "[profile:{label}] heap: failed_allocs={} largest_failed={} free_at_failure={}",
+                heap.failed_allocations, heap.largest_failed_bytes, heap.free_at_failure,
+            )?;
+        }
+    }
+    writeln!(
+        w,
+        "[profile:{label}] syscalls: reads={} ({} bytes) writes={} ({} bytes) hints={} \
+         preimage_requests={} preimage_reads={} ({} bytes)",
+        syscalls.reads,
+        syscalls.bytes_read,
+        syscalls.writes,
+        syscalls.bytes_written,
+        syscalls.hint_writes,
+        syscalls.preimage_requests,
+        syscalls.preimage_reads,
+        syscalls.preimage_bytes_read,
+    )
+}
+
+/// A [Write] implementation that buffers on the stack and writes to [FileDescriptor::StdErr].
+#[derive(Debug)]
+struct StderrWriter {
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl Default for StderrWriter {
+    fn default() -> Self {
+        Self { buf: [0; 256], len: 0 }
+    }
+}
+
+impl StderrWriter {
+    fn flush(&mut self) {
+        if self.len > 0 {
+            let _ = io::write(FileDescriptor::StdErr, &self.buf[..self.len]);
+            self.len = 0;
+        }
+    }
+}
+
+impl Write for StderrWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut bytes = s.as_bytes();
+        while !bytes.is_empty() {
+            if self.len == self.buf.len() {
+                self.flush();
+            }
+            let n = bytes.len().min(self.buf.len() - self.len);
+            self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+            self.len += n;
+            bytes = &bytes[n..];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec, vec::Vec};
+
+    fn heap(size: usize) -> (Vec<u8>, ProfilingAllocator<LockedHeap>) {
+        let mut region = vec![0u8; size];
+        let allocator = ProfilingAllocator::new(LockedHeap::empty());
+        unsafe { allocator.inner().lock().init(region.as_mut_ptr(), size) };
+        (region, allocator)
+    }
+
+    #[test]
+    fn test_profiling_allocator_tracks_live_and_peak() {
+        let (_region, allocator) = heap(4096);
+        let a = Layout::from_size_align(100, 8).unwrap();
+        let b = Layout::from_size_align(200, 8).unwrap();
+
+        unsafe {
+            let pa = allocator.alloc(a);
+            let pb = allocator.alloc_zeroed(b);
+            allocator.dealloc(pa, a);
+            let pb = allocator.realloc(pb, b, 300);
+            allocator.reset_phase_peak();
+
+            let stats = allocator.stats();
+            assert_eq!(stats.heap_size, 4096);
+            assert_eq!(stats.allocations, 2);
+            assert_eq!(stats.deallocations, 1);
+            assert_eq!(stats.reallocations, 1);
+            assert_eq!(stats.live_allocations, 1);
+            assert_eq!(stats.live_bytes, 300);
+            assert_eq!(stats.peak_bytes, 300);
+            assert_eq!(stats.phase_peak_bytes, 300);
+            assert!(stats.heap_used >= 300);
+
+            allocator.dealloc(pb, Layout::from_size_align(300, 8).unwrap());
+        }
+        let stats = allocator.stats();
+        assert_eq!(stats.live_bytes, 0);
+        assert_eq!(stats.heap_used, 0);
+        assert_eq!(stats.phase_peak_bytes, 300);
+    }
+
+    #[test]
+    fn test_profiling_allocator_records_failures() {
+        let (_region, allocator) = heap(1024);
+        let small = Layout::from_size_align(600, 8).unwrap();
+        let large = Layout::from_size_align(2048, 8).unwrap();
+
+        unsafe {
+            let ptr = allocator.alloc(small);
+            assert!(allocator.alloc(large).is_null());
+            assert!(allocator.alloc(small).is_null());
+            allocator.dealloc(ptr, small);
+        }
+
+        let stats = allocator.stats();
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.failed_allocations, 2);
+        assert_eq!(stats.largest_failed_bytes, 2048);
+        assert_eq!(stats.free_at_failure, 1024 - 600);
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let stats = HeapStats { heap_used: 1000, live_bytes: 750, ..Default::default() };
+        assert_eq!(stats.overhead(), 250);
+        assert_eq!(stats.fragmentation_percent(), 25);
+        assert_eq!(HeapStats::default().fragmentation_percent(), 0);
+    }
+
+    #[test]
+    fn test_write_summary() {
+        let heap = HeapStats {
+            heap_size: 1024,
+            heap_used: 512,
+            allocations: 3,
+            live_allocations: 1,
+            live_bytes: 256,
+            peak_bytes: 768,
+            failed_allocations: 1,
+            largest_failed_bytes: 2048,
+            free_at_failure: 512,
+            ..Default::default()
+        };
+        let syscalls = SyscallStats {
+            reads: 2,
+            bytes_read: 40,
+            preimage_reads: 1,
+            preimage_bytes_read: 32,
+            ..Default::default()
+        };
+
+        let mut out = String::new();
+        write_summary(&mut out, "exit", Some(&heap), &syscalls).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("[profile:exit] heap: used=512/1024 live=256 (1 allocs)"));
+        assert!(lines[0].contains("fragmentation=50%"));
+        assert!(lines[1].contains("largest_failed=2048 free_at_failure=512"));
+        assert!(lines[2].contains("reads=2 (40 bytes)"));
+        assert!(lines[2].contains("preimage_reads=1 (32 bytes)"));
+
+        out.clear();
+        write_summary(&mut out, "boot", None, &SyscallStats::default()).unwrap();
+        assert_eq!(out.lines().count(), 1);
+    }
+}